@@ -0,0 +1,89 @@
+// Shapes: a fill color with an outline that glows, and pulses while `pulse` is above zero. The
+// rim attribute runs from 0 at the shape's center to 1 on its outline, see `game_shapes::generator`.
+// `pattern` draws stripes, dots or a grid over the fill, see `game_shapes::palette::Pattern`.
+
+#import bevy_sprite::{
+    mesh2d_functions as mesh_functions,
+    mesh2d_view_bindings::{globals, view},
+}
+
+#ifdef TONEMAP_IN_SHADER
+#import bevy_core_pipeline::tonemapping
+#endif
+
+struct ShapeMaterial {
+    color: vec4<f32>,
+    outline: vec4<f32>,
+    outline_width: f32,
+    pulse: f32,
+    pattern: u32,
+};
+
+@group(1) @binding(0) var<uniform> material: ShapeMaterial;
+
+// Pulses per second.
+const PULSE_RATE: f32 = 1.5;
+const TAU: f32 = 6.28318530718;
+// Pattern period, in mesh units (a shape's radius is 80).
+const PATTERN_SCALE: f32 = 20.0;
+// How far the pattern darkens the fill.
+const PATTERN_SHADE: f32 = 0.45;
+
+struct Vertex {
+    @builtin(instance_index) instance_index: u32,
+    @location(0) position: vec3<f32>,
+    @location(1) rim: f32,
+};
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) rim: f32,
+    @location(1) local: vec2<f32>,
+};
+
+@vertex
+fn vertex(vertex: Vertex) -> VertexOutput {
+    var out: VertexOutput;
+    let model = mesh_functions::get_model_matrix(vertex.instance_index);
+    out.position = mesh_functions::mesh2d_position_local_to_clip(
+        model,
+        vec4<f32>(vertex.position, 1.0)
+    );
+    out.rim = vertex.rim;
+    out.local = vertex.position.xy;
+    return out;
+}
+
+// 1 where the pattern is drawn, 0 elsewhere.
+fn pattern_mask(local: vec2<f32>) -> f32 {
+    let cell = fract(local / PATTERN_SCALE);
+    switch material.pattern {
+        case 1u: {
+            return step(0.5, fract((local.x + local.y) / PATTERN_SCALE));
+        }
+        case 2u: {
+            return 1.0 - step(0.25, length(cell - vec2<f32>(0.5)));
+        }
+        case 3u: {
+            return max(step(0.8, cell.x), step(0.8, cell.y));
+        }
+        default: {
+            return 0.0;
+        }
+    }
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    let wave = 0.5 + 0.5 * sin(globals.time * PULSE_RATE * TAU);
+    let glow = material.pulse * wave;
+    let width = material.outline_width * (1.0 + glow);
+    let edge = smoothstep(1.0 - width, 1.0, in.rim);
+    let fill = material.color.rgb * (1.0 - PATTERN_SHADE * pattern_mask(in.local));
+    let rgb = mix(fill, material.outline.rgb, edge) + material.outline.rgb * glow * edge;
+    var output_color = vec4<f32>(rgb, material.color.a);
+#ifdef TONEMAP_IN_SHADER
+    output_color = tonemapping::tone_mapping(output_color, view.color_grading);
+#endif
+    return output_color;
+}
@@ -0,0 +1,129 @@
+use bevy::{ecs::system::RunSystemOnce, prelude::*};
+use bevy_xpbd_2d::prelude::{CollisionEnded, CollisionStarted, LinearVelocity, Position};
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use shapecraft::{
+    board::{rebuild_rule_lookup, shape_collisions},
+    prelude::*,
+};
+
+const SHAPES: usize = 300;
+const CONTACTS: usize = 500;
+
+const POLYGONS: [GamePolygon; 3] = [
+    GamePolygon::Triangle,
+    GamePolygon::Square,
+    GamePolygon::Pentagon,
+];
+const COLORS: [GameColor; 3] = [GameColor::Red, GameColor::Green, GameColor::Blue];
+
+fn shape(i: usize) -> Shape {
+    Shape {
+        polygon: POLYGONS[i % POLYGONS.len()],
+        color: COLORS[(i / POLYGONS.len()) % COLORS.len()],
+    }
+}
+
+/// Ten rules combining shapes that are on the board.
+fn mixed_rules() -> Vec<Operation> {
+    (0..10)
+        .map(|i| match i % 2 {
+            0 => Operation::Add(shape(i), shape(i + 1)),
+            _ => Operation::Sub(shape(i), shape(i + 4)),
+        })
+        .collect()
+}
+
+/// Ten rules that only involve polygons which never appear on the board.
+fn unmatched_rules() -> Vec<Operation> {
+    (0..10)
+        .map(|i| {
+            let s = Shape {
+                polygon: GamePolygon::Heptagon,
+                color: COLORS[i % COLORS.len()],
+            };
+            Operation::Add(s, s)
+        })
+        .collect()
+}
+
+/// Builds a board of overlapping shapes with `CONTACTS` contact-begin events queued up.
+fn board(rules: Vec<Operation>, shape_of: fn(usize) -> Shape) -> World {
+    let mut world = World::new();
+    world.init_resource::<Events<CollisionStarted>>();
+    world.init_resource::<Events<CollisionEnded>>();
+    world.init_resource::<Events<SpawnBody>>();
+    world.init_resource::<Events<ShapesCombined>>();
+    world.init_resource::<Events<ShapesSplit>>();
+    world.init_resource::<Events<RuleApplied>>();
+    world.init_resource::<ProcessedContacts>();
+    world.init_resource::<CombinedShapes>();
+    world.init_resource::<RuleLookup>();
+    world.init_resource::<ColorInteraction>();
+    world.init_resource::<RuleResolution>();
+    world.init_resource::<CardDefinitions>();
+    world.insert_resource(GameRng::from_seed(Seed(0)));
+    world.spawn(Rule(rules));
+    world.run_system_once(rebuild_rule_lookup);
+
+    let shapes: Vec<Entity> = (0..SHAPES)
+        .map(|i| {
+            world
+                .spawn((
+                    shape_of(i),
+                    Position::from_xy(i as f32, 0.),
+                    Transform::from_xyz(i as f32, 0., 0.),
+                    LinearVelocity::ZERO,
+                    IsOnBoard,
+                ))
+                .id()
+        })
+        .collect();
+
+    let mut started = world.resource_mut::<Events<CollisionStarted>>();
+    for i in 0..CONTACTS {
+        let a = i % SHAPES;
+        let b = (a + 1 + (i * 37) % (SHAPES - 1)) % SHAPES;
+        started.send(CollisionStarted(shapes[a], shapes[b]));
+    }
+    world
+}
+
+fn bench_shape_collisions(c: &mut Criterion) {
+    let mut group = c.benchmark_group("shape_collisions");
+
+    group.bench_function("mixed", |b| {
+        b.iter_batched(
+            || board(mixed_rules(), shape),
+            |mut world| world.run_system_once(shape_collisions),
+            BatchSize::LargeInput,
+        )
+    });
+    group.bench_function("no_matching_rules", |b| {
+        b.iter_batched(
+            || board(unmatched_rules(), shape),
+            |mut world| world.run_system_once(shape_collisions),
+            BatchSize::LargeInput,
+        )
+    });
+    group.bench_function("all_matching", |b| {
+        // every contact is between two red triangles, matched only by the last rule
+        let red_triangle = |_: usize| Shape {
+            polygon: GamePolygon::Triangle,
+            color: GameColor::Red,
+        };
+        b.iter_batched(
+            || {
+                let mut rules = unmatched_rules();
+                rules[9] = Operation::Add(red_triangle(0), red_triangle(0));
+                board(rules, red_triangle)
+            },
+            |mut world| world.run_system_once(shape_collisions),
+            BatchSize::LargeInput,
+        )
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_shape_collisions);
+criterion_main!(benches);
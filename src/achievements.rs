@@ -0,0 +1,347 @@
+//! Achievements. What there is to unlock is read from `assets/achievements/default.achievements.ron`,
+//! each entry a name, a description and an `AchievementGoal`. Gameplay systems turn what happens
+//! into `AchievementSignal`s, which move the goals along; reaching a goal sends
+//! `AchievementUnlocked` for the toast. Progress and unlocks are kept in `achievements.ron`, so
+//! goals like "merge 100 hexagons" add up over every run.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io,
+};
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+    utils::BoxedFuture,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    board::ShapesCombined,
+    cards::{
+        zones::{ActivePlayer, ZoneKind, Zones},
+        GameState,
+    },
+    game_over::GameWon,
+    game_shapes::GamePolygon,
+    level::LevelCleared,
+    loading::AchievementAssets,
+    platform::Storage,
+    score::Score,
+    AppState,
+};
+
+const PROGRESS_KEY: &str = "achievements.ron";
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum AchievementGoal {
+    /// Make `count` shapes of `polygon` by combining, over every run. `None` counts any polygon.
+    Merge {
+        polygon: Option<GamePolygon>,
+        count: u32,
+    },
+    /// A chain of combines this deep.
+    Combo(u32),
+    /// Play this many cards, over every run.
+    CardsPlayed(u32),
+    /// Finish this many runs.
+    Runs(u32),
+    /// Clear this level, or a later one.
+    ClearLevel(u32),
+    /// Win a run by making the target shape.
+    Win,
+    /// Finish a run with nothing left in the library.
+    EmptyDeck,
+}
+
+/// How a signal moves a goal along.
+enum Step {
+    Add(u32),
+    /// Raises the progress to at least this.
+    Reach(u32),
+}
+
+impl AchievementGoal {
+    pub fn target(self) -> u32 {
+        match self {
+            AchievementGoal::Merge { count, .. } => count,
+            AchievementGoal::Combo(depth) => depth,
+            AchievementGoal::CardsPlayed(count) | AchievementGoal::Runs(count) => count,
+            AchievementGoal::ClearLevel(level) => level,
+            AchievementGoal::Win | AchievementGoal::EmptyDeck => 1,
+        }
+    }
+
+    fn step(self, signal: AchievementSignal) -> Option<Step> {
+        match (self, signal) {
+            (AchievementGoal::Merge { polygon, .. }, AchievementSignal::Merged(merged)) => polygon
+                .is_none_or(|polygon| polygon == merged)
+                .then_some(Step::Add(1)),
+            (AchievementGoal::Combo(_), AchievementSignal::Combo(depth)) => {
+                Some(Step::Reach(depth))
+            },
+            (AchievementGoal::CardsPlayed(_), AchievementSignal::CardsPlayed(count)) => {
+                Some(Step::Add(count))
+            },
+            (AchievementGoal::Runs(_), AchievementSignal::RunFinished) => Some(Step::Add(1)),
+            (AchievementGoal::ClearLevel(_), AchievementSignal::LevelCleared(level)) => {
+                Some(Step::Reach(level))
+            },
+            (AchievementGoal::Win, AchievementSignal::Won)
+            | (AchievementGoal::EmptyDeck, AchievementSignal::EmptyDeck) => Some(Step::Reach(1)),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AchievementDef {
+    /// Keys the saved progress, so it shouldn't change once released.
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub goal: AchievementGoal,
+}
+
+/// Every achievement there is, in the order they're listed.
+#[derive(Asset, Resource, TypePath, Serialize, Deserialize, Default, Clone, Debug)]
+pub struct Achievements(pub Vec<AchievementDef>);
+
+/// Saved between sessions. Progress is kept by achievement id.
+#[derive(Resource, Serialize, Deserialize, Default, Clone, Debug)]
+#[serde(default)]
+pub struct AchievementProgress {
+    pub progress: BTreeMap<String, u32>,
+    pub unlocked: BTreeSet<String>,
+}
+
+impl AchievementProgress {
+    pub fn is_unlocked(&self, id: &str) -> bool {
+        self.unlocked.contains(id)
+    }
+
+    pub fn get(&self, id: &str) -> u32 {
+        self.progress.get(id).copied().unwrap_or(0)
+    }
+}
+
+/// Something that happened that achievements may count.
+#[derive(Event, Clone, Copy, PartialEq, Debug)]
+pub enum AchievementSignal {
+    Merged(GamePolygon),
+    Combo(u32),
+    CardsPlayed(u32),
+    LevelCleared(u32),
+    Won,
+    EmptyDeck,
+    RunFinished,
+}
+
+#[derive(Event, Clone, Debug)]
+pub struct AchievementUnlocked {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+}
+
+#[derive(Default)]
+pub struct AchievementsLoader;
+
+impl AssetLoader for AchievementsLoader {
+    type Asset = Achievements;
+    type Settings = ();
+    type Error = io::Error;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Achievements, io::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            ron::de::from_bytes(&bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["achievements.ron"]
+    }
+}
+
+pub struct AchievementsPlugin;
+impl Plugin for AchievementsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<Achievements>()
+            .init_asset_loader::<AchievementsLoader>()
+            .init_resource::<Achievements>()
+            .init_resource::<AchievementProgress>()
+            .add_event::<AchievementSignal>()
+            .add_event::<AchievementUnlocked>()
+            .add_systems(OnEnter(AppState::Loading), load_progress)
+            .add_systems(
+                Update,
+                (
+                    apply_achievements,
+                    (signal_combines, signal_cards, signal_run_events)
+                        .run_if(in_state(AppState::Playing))
+                        .run_if(not(in_state(GameState::Scoring))),
+                    advance_achievements,
+                )
+                    .chain(),
+            )
+            .add_systems(OnEnter(GameState::Scoring), signal_run_finished)
+            .add_systems(OnExit(AppState::Playing), save_progress);
+    }
+}
+
+fn load_progress(mut progress: ResMut<AchievementProgress>, storage: Res<Storage>) {
+    let Some(text) = storage.load(PROGRESS_KEY) else {
+        return;
+    };
+    match ron::from_str(&text) {
+        Ok(loaded) => *progress = loaded,
+        Err(err) => warn!(
+            "couldn't read {}, starting the achievements over: {err}",
+            storage.location(PROGRESS_KEY)
+        ),
+    }
+}
+
+fn save_progress(progress: Res<AchievementProgress>, storage: Res<Storage>) {
+    write_progress(&progress, &storage);
+}
+
+fn write_progress(progress: &AchievementProgress, storage: &Storage) {
+    let result = ron::ser::to_string_pretty(progress, default())
+        .map_err(|e| e.to_string())
+        .and_then(|ron| storage.save(PROGRESS_KEY, &ron).map_err(|e| e.to_string()));
+    if let Err(err) = result {
+        error!("couldn't save the achievements: {err}");
+    }
+}
+
+/// Copies the loaded list into the resource, again whenever the file changes.
+fn apply_achievements(
+    mut reader: EventReader<AssetEvent<Achievements>>,
+    assets: Res<Assets<Achievements>>,
+    collection: Option<Res<AchievementAssets>>,
+    mut achievements: ResMut<Achievements>,
+) {
+    // NOTE: the collection is only inserted once loading finishes, so the first load is read then
+    let Some(collection) = collection else {
+        return;
+    };
+    if collection.is_added() {
+        if let Some(loaded) = assets.get(&collection.achievements) {
+            *achievements = loaded.clone();
+        }
+    }
+    for event in reader.read() {
+        if let AssetEvent::Modified { id } = event {
+            if *id != collection.achievements.id() {
+                continue;
+            }
+            if let Some(loaded) = assets.get(*id) {
+                *achievements = loaded.clone();
+                info!("achievements updated");
+            }
+        }
+    }
+}
+
+fn signal_combines(
+    mut reader: EventReader<ShapesCombined>,
+    mut signals: EventWriter<AchievementSignal>,
+) {
+    for combined in reader.read() {
+        signals.send(AchievementSignal::Merged(combined.result.polygon));
+        signals.send(AchievementSignal::Combo(combined.depth));
+    }
+}
+
+fn signal_cards(
+    score: Res<Score>,
+    mut counted: Local<u32>,
+    mut signals: EventWriter<AchievementSignal>,
+) {
+    // NOTE: the count starts over with each run
+    if score.cards_played > *counted {
+        signals.send(AchievementSignal::CardsPlayed(
+            score.cards_played - *counted,
+        ));
+    }
+    *counted = score.cards_played;
+}
+
+fn signal_run_events(
+    mut cleared: EventReader<LevelCleared>,
+    mut won: EventReader<GameWon>,
+    mut signals: EventWriter<AchievementSignal>,
+) {
+    for event in cleared.read() {
+        signals.send(AchievementSignal::LevelCleared(event.level));
+    }
+    for _ in won.read() {
+        signals.send(AchievementSignal::Won);
+    }
+}
+
+fn signal_run_finished(
+    zones: Res<Zones>,
+    active: Res<ActivePlayer>,
+    q_children: Query<&Children>,
+    mut signals: EventWriter<AchievementSignal>,
+) {
+    signals.send(AchievementSignal::RunFinished);
+    let library_empty = zones
+        .get(active.0, ZoneKind::Library)
+        .is_some_and(|library| q_children.get(library).map_or(true, |c| c.is_empty()));
+    if library_empty {
+        signals.send(AchievementSignal::EmptyDeck);
+    }
+}
+
+fn advance_achievements(
+    mut signals: EventReader<AchievementSignal>,
+    achievements: Res<Achievements>,
+    mut progress: ResMut<AchievementProgress>,
+    mut unlocked: EventWriter<AchievementUnlocked>,
+    storage: Res<Storage>,
+) {
+    if signals.is_empty() {
+        return;
+    }
+    let mut any_unlocked = false;
+    for &signal in signals.read() {
+        for def in achievements.0.iter() {
+            if progress.is_unlocked(&def.id) {
+                continue;
+            }
+            let Some(step) = def.goal.step(signal) else {
+                continue;
+            };
+            let current = progress.progress.entry(def.id.clone()).or_default();
+            *current = match step {
+                Step::Add(n) => *current + n,
+                Step::Reach(n) => (*current).max(n),
+            };
+            if *current < def.goal.target() {
+                continue;
+            }
+            info!("achievement unlocked: {}", def.name);
+            progress.unlocked.insert(def.id.clone());
+            unlocked.send(AchievementUnlocked {
+                id: def.id.clone(),
+                name: def.name.clone(),
+                description: def.description.clone(),
+            });
+            any_unlocked = true;
+        }
+    }
+    // NOTE: an unlock is saved straight away, the rest of the progress when the run ends
+    if any_unlocked {
+        write_progress(&progress, &storage);
+    }
+}
@@ -0,0 +1,132 @@
+use bevy::prelude::*;
+
+use crate::{platform, AppState};
+
+const USAGE: &str = "\
+usage: shapecraft [options]
+
+  --seed <n>                seed for the game's random numbers
+  --skip-to playing|menu    state to enter once assets are loaded
+  --windowed <W>x<H>        window size, e.g. 1280x720
+  --mute                    start with the volume at zero
+  --dev                     enable the dev tools' runtime toggles, and reload edited assets
+                            (built with `--features dev`)
+  --deck <path>             deck list to play with
+  --board <name>            board layout to play on
+  --replay <path>           replay to play back
+  --load-scene <path>       board snapshot (saved with F9) to start from
+
+On the web the same options are read from the query string, e.g. ?seed=3&mute";
+
+/// Options the game was launched with. Parsed before any plugin is built, so plugins can read it
+/// from the world while building.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct LaunchArgs {
+    pub seed: Option<u64>,
+    pub skip_to: Option<AppState>,
+    pub windowed: Option<Vec2>,
+    pub mute: bool,
+    pub dev: bool,
+    pub deck: Option<String>,
+    pub board: Option<String>,
+    pub replay: Option<String>,
+    pub load_scene: Option<String>,
+}
+
+impl LaunchArgs {
+    /// Reads the command line (or the page's query string on the web). Invalid options print the
+    /// usage and exit on native; on the web they're logged and the defaults are used.
+    pub fn from_env() -> Self {
+        match Self::parse(raw_args()) {
+            Ok(args) => args,
+            #[cfg(not(target_arch = "wasm32"))]
+            Err(err) if err.is_empty() => {
+                println!("{USAGE}");
+                std::process::exit(0);
+            },
+            #[cfg(not(target_arch = "wasm32"))]
+            Err(err) => {
+                eprintln!("{err}\n\n{USAGE}");
+                std::process::exit(2);
+            },
+            #[cfg(target_arch = "wasm32")]
+            Err(err) => {
+                error!("{err}\n\n{USAGE}");
+                Self::default()
+            },
+        }
+    }
+
+    /// The seed for this run: `--seed` if given, otherwise a fresh one.
+    pub fn run_seed(&self) -> u64 {
+        self.seed.unwrap_or_else(platform::entropy_seed)
+    }
+
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut parsed = Self::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| format!("{arg} expects a value"));
+            match arg.as_str() {
+                "--seed" => {
+                    let seed = value()?;
+                    parsed.seed = Some(seed.parse().map_err(|_| format!("invalid seed `{seed}`"))?);
+                },
+                "--skip-to" => {
+                    parsed.skip_to = Some(match value()?.as_str() {
+                        "playing" => AppState::Playing,
+                        "menu" => AppState::Menu,
+                        other => return Err(format!("can't skip to `{other}`")),
+                    });
+                },
+                "--windowed" => {
+                    let size = value()?;
+                    parsed.windowed = Some(
+                        parse_size(&size).ok_or_else(|| format!("invalid window size `{size}`"))?,
+                    );
+                },
+                "--mute" => parsed.mute = true,
+                "--dev" => parsed.dev = true,
+                "--deck" => parsed.deck = Some(value()?),
+                "--board" => parsed.board = Some(value()?),
+                "--replay" => parsed.replay = Some(value()?),
+                "--load-scene" => parsed.load_scene = Some(value()?),
+                // NOTE: an empty error asks for the usage without complaining
+                "--help" | "-h" => return Err(String::new()),
+                other => return Err(format!("unknown option `{other}`")),
+            }
+        }
+
+        Ok(parsed)
+    }
+}
+
+fn parse_size(size: &str) -> Option<Vec2> {
+    let (w, h) = size.split_once('x')?;
+    let size = Vec2::new(w.parse().ok()?, h.parse().ok()?);
+    (size.x > 0. && size.y > 0.).then_some(size)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn raw_args() -> Vec<String> {
+    std::env::args().skip(1).collect()
+}
+
+/// Turns `?seed=3&mute` into `--seed 3 --mute`, so both platforms share one parser.
+#[cfg(target_arch = "wasm32")]
+fn raw_args() -> Vec<String> {
+    let search = web_sys::window()
+        .and_then(|window| window.location().search().ok())
+        .unwrap_or_default();
+
+    search
+        .trim_start_matches('?')
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .flat_map(|pair| match pair.split_once('=') {
+            Some((key, value)) => vec![format!("--{key}"), value.to_string()],
+            None => vec![format!("--{pair}")],
+        })
+        .collect()
+}
@@ -0,0 +1,221 @@
+//! Sound effects and volume. Gameplay code sends `PlaySound` instead of spawning audio itself, so
+//! every sound goes through one place that applies the `Volume` settings and keeps a pile-up of
+//! shapes from playing hundreds of pops in one frame. Music is handled by `music`.
+
+pub mod music;
+
+use bevy::{audio::PlaybackMode, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use self::music::{MusicFade, MusicPlugin};
+use crate::{
+    board::{combo::ComboEvent, ShapesCombined},
+    loading::SoundAssets,
+};
+
+/// The most sounds of one kind started in a single frame.
+const MAX_SOUNDS_PER_FRAME: usize = 6;
+const VOLUME_STEP: f32 = 0.1;
+
+/// Every playing sound is tagged with the channel whose volume it follows.
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SoundChannel {
+    Music,
+    Sfx,
+    Ui,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Sfx {
+    Spawn,
+    Combine,
+    Combo,
+    DeckSpawn,
+    Draw,
+    Flip,
+    Discard,
+    Click,
+    Reject,
+}
+
+impl Sfx {
+    pub fn channel(self) -> SoundChannel {
+        match self {
+            Sfx::Click => SoundChannel::Ui,
+            _ => SoundChannel::Sfx,
+        }
+    }
+
+    // NOTE: there are only a few recordings, so some sounds are the same one played faster or
+    // slower
+    fn source(self, sounds: &SoundAssets) -> (Handle<AudioSource>, f32, f32) {
+        match self {
+            Sfx::Spawn => (sounds.spawn.clone_weak(), 1., 1.),
+            Sfx::Combine => (sounds.spawn.clone_weak(), 1.5, 1.),
+            Sfx::Combo => (sounds.spawn.clone_weak(), 2.2, 1.),
+            Sfx::DeckSpawn => (sounds.spawn_deck.clone_weak(), 1., 1.),
+            Sfx::Draw => (sounds.draw_card.clone_weak(), 1., 1.),
+            Sfx::Flip => (sounds.draw_card.clone_weak(), 1.4, 0.5),
+            Sfx::Discard => (sounds.draw_card.clone_weak(), 0.8, 1.),
+            Sfx::Click => (sounds.spawn.clone_weak(), 2., 0.6),
+            Sfx::Reject => (sounds.draw_card.clone_weak(), 0.5, 1.),
+        }
+    }
+}
+
+#[derive(Event, Clone, Copy, Debug)]
+pub struct PlaySound(pub Sfx);
+
+/// The volume sliders, each from 0 to 1. `--mute` is applied on top through bevy's
+/// `GlobalVolume`. Saved with the rest of the `Settings`.
+#[derive(Resource, Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(default)]
+pub struct Volume {
+    pub master: f32,
+    pub music: f32,
+    pub sfx: f32,
+}
+
+impl Default for Volume {
+    fn default() -> Self {
+        Self {
+            master: 1.,
+            music: 0.6,
+            sfx: 1.,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum VolumeSlider {
+    Master,
+    Music,
+    Sfx,
+}
+
+impl VolumeSlider {
+    pub const ALL: [VolumeSlider; 3] =
+        [VolumeSlider::Master, VolumeSlider::Music, VolumeSlider::Sfx];
+}
+
+impl Volume {
+    /// How loud a sound on `channel` plays, with the master volume applied.
+    pub fn level(&self, channel: SoundChannel) -> f32 {
+        let channel = match channel {
+            SoundChannel::Music => self.music,
+            SoundChannel::Sfx | SoundChannel::Ui => self.sfx,
+        };
+        self.master * channel
+    }
+
+    pub fn get(&self, slider: VolumeSlider) -> f32 {
+        match slider {
+            VolumeSlider::Master => self.master,
+            VolumeSlider::Music => self.music,
+            VolumeSlider::Sfx => self.sfx,
+        }
+    }
+
+    /// Moves a slider up (`steps > 0`) or down by whole steps.
+    pub fn step(&mut self, slider: VolumeSlider, steps: i32) {
+        let value = match slider {
+            VolumeSlider::Master => &mut self.master,
+            VolumeSlider::Music => &mut self.music,
+            VolumeSlider::Sfx => &mut self.sfx,
+        };
+        // NOTE: round to the step so repeated presses land back on exact values
+        let stepped = ((*value / VOLUME_STEP).round() as i32 + steps) as f32 * VOLUME_STEP;
+        *value = stepped.clamp(0., 1.);
+    }
+
+    pub fn playback(&self, channel: SoundChannel, mode: PlaybackMode) -> PlaybackSettings {
+        PlaybackSettings {
+            mode,
+            volume: bevy::audio::Volume::new_relative(self.level(channel)),
+            ..default()
+        }
+    }
+}
+
+pub struct AudioPlugin;
+impl Plugin for AudioPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Volume>()
+            .add_event::<PlaySound>()
+            .add_plugins(MusicPlugin)
+            .add_systems(
+                Update,
+                (
+                    (combine_sounds, combo_sounds, click_sounds),
+                    play_sounds.run_if(resource_exists::<SoundAssets>()),
+                    apply_volume.run_if(resource_changed::<Volume>()),
+                )
+                    .chain(),
+            );
+    }
+}
+
+fn combine_sounds(mut reader: EventReader<ShapesCombined>, mut writer: EventWriter<PlaySound>) {
+    for _ in reader.read() {
+        writer.send(PlaySound(Sfx::Combine));
+    }
+}
+
+fn combo_sounds(mut reader: EventReader<ComboEvent>, mut writer: EventWriter<PlaySound>) {
+    for _ in reader.read() {
+        writer.send(PlaySound(Sfx::Combo));
+    }
+}
+
+fn click_sounds(
+    q_buttons: Query<&Interaction, (Changed<Interaction>, With<Button>)>,
+    mut writer: EventWriter<PlaySound>,
+) {
+    for interaction in q_buttons.iter() {
+        if *interaction == Interaction::Pressed {
+            writer.send(PlaySound(Sfx::Click));
+        }
+    }
+}
+
+fn play_sounds(
+    mut cmd: Commands,
+    mut reader: EventReader<PlaySound>,
+    sounds: Res<SoundAssets>,
+    volume: Res<Volume>,
+) {
+    let mut started: Vec<Sfx> = Vec::new();
+    for PlaySound(sfx) in reader.read() {
+        if started.iter().filter(|s| *s == sfx).count() >= MAX_SOUNDS_PER_FRAME {
+            continue;
+        }
+        started.push(*sfx);
+
+        let (source, speed, gain) = sfx.source(&sounds);
+        let channel = sfx.channel();
+        cmd.spawn((
+            AudioBundle {
+                source,
+                settings: PlaybackSettings {
+                    speed,
+                    volume: bevy::audio::Volume::new_relative(volume.level(channel) * gain),
+                    ..volume.playback(channel, PlaybackMode::Despawn)
+                },
+            },
+            channel,
+        ));
+    }
+}
+
+/// Sounds already playing follow the sliders as they move. Short effects keep their per-sound
+/// gain only until the next change, which they rarely outlive. Music that is fading follows them
+/// through its fade instead.
+fn apply_volume(
+    volume: Res<Volume>,
+    global: Res<GlobalVolume>,
+    q_sinks: Query<(&AudioSink, &SoundChannel), Without<MusicFade>>,
+) {
+    for (sink, channel) in q_sinks.iter() {
+        sink.set_volume(volume.level(*channel) * global.volume.get());
+    }
+}
@@ -0,0 +1,161 @@
+//! Looping background music. Each app state asks for its track with `MusicEvent::Play`, and the
+//! old track fades out while the new one fades in.
+
+use bevy::{audio::PlaybackMode, prelude::*};
+
+use super::{SoundChannel, Volume};
+use crate::{cards::GameState, loading::SoundAssets, AppState};
+
+const CROSSFADE: f32 = 1.5;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum TrackId {
+    Menu,
+    Playing,
+    GameOver,
+}
+
+impl TrackId {
+    // NOTE: there's only one recording so far, so the tracks are it played at different speeds
+    fn source(self, sounds: &SoundAssets) -> (Handle<AudioSource>, f32) {
+        match self {
+            TrackId::Menu => (sounds.bg_music.clone_weak(), 0.9),
+            TrackId::Playing => (sounds.bg_music.clone_weak(), 1.),
+            TrackId::GameOver => (sounds.bg_music.clone_weak(), 0.75),
+        }
+    }
+}
+
+/// Asks for a different track. Asking for the track that's already playing does nothing.
+#[derive(Event, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MusicEvent {
+    Play(TrackId),
+    Stop,
+}
+
+/// The track playing or fading in, if any.
+#[derive(Resource, Default, Deref)]
+pub struct CurrentTrack(Option<TrackId>);
+
+#[derive(Component)]
+pub struct MusicTrack(pub TrackId);
+
+/// Moves a track's level from `from` to `to` (as a fraction of the music volume). A track that
+/// fades to zero is despawned.
+#[derive(Component)]
+pub struct MusicFade {
+    timer: Timer,
+    from: f32,
+    to: f32,
+}
+
+impl MusicFade {
+    fn new(from: f32, to: f32) -> Self {
+        Self {
+            timer: Timer::from_seconds(CROSSFADE, TimerMode::Once),
+            from,
+            to,
+        }
+    }
+
+    fn level(&self) -> f32 {
+        self.from + (self.to - self.from) * self.timer.percent()
+    }
+}
+
+pub struct MusicPlugin;
+impl Plugin for MusicPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CurrentTrack>()
+            .add_event::<MusicEvent>()
+            .add_systems(OnEnter(AppState::Menu), play(TrackId::Menu))
+            .add_systems(OnEnter(AppState::Playing), play(TrackId::Playing))
+            .add_systems(OnEnter(GameState::Scoring), play(TrackId::GameOver))
+            .add_systems(
+                Update,
+                (
+                    change_track.run_if(resource_exists::<SoundAssets>()),
+                    fade_music,
+                )
+                    .chain(),
+            );
+    }
+}
+
+fn play(track: TrackId) -> impl FnMut(EventWriter<MusicEvent>) {
+    move |mut writer: EventWriter<MusicEvent>| writer.send(MusicEvent::Play(track))
+}
+
+fn change_track(
+    mut cmd: Commands,
+    mut reader: EventReader<MusicEvent>,
+    mut current: ResMut<CurrentTrack>,
+    q_tracks: Query<(Entity, Option<&MusicFade>), With<MusicTrack>>,
+    sounds: Res<SoundAssets>,
+) {
+    // NOTE: only the last request in a frame matters, the others would never be heard
+    let Some(&event) = reader.read().last() else {
+        return;
+    };
+    let next = match event {
+        MusicEvent::Play(track) => Some(track),
+        MusicEvent::Stop => None,
+    };
+    if next == current.0 {
+        return;
+    }
+    current.0 = next;
+
+    for (entity, fade) in q_tracks.iter() {
+        if fade.is_some_and(|fade| fade.to == 0.) {
+            continue;
+        }
+        // NOTE: a track that was still fading in fades out from where it got to
+        let level = fade.map_or(1., MusicFade::level);
+        cmd.entity(entity).insert(MusicFade::new(level, 0.));
+    }
+
+    if let Some(track) = next {
+        let (source, speed) = track.source(&sounds);
+        cmd.spawn((
+            AudioBundle {
+                source,
+                settings: PlaybackSettings {
+                    mode: PlaybackMode::Loop,
+                    volume: bevy::audio::Volume::new_relative(0.),
+                    speed,
+                    ..default()
+                },
+            },
+            SoundChannel::Music,
+            MusicTrack(track),
+            MusicFade::new(0., 1.),
+        ));
+    }
+}
+
+/// Runs on real time, so pausing a run doesn't hold a crossfade halfway.
+fn fade_music(
+    mut cmd: Commands,
+    time: Res<Time<Real>>,
+    volume: Res<Volume>,
+    global: Res<GlobalVolume>,
+    mut q_fading: Query<(Entity, &mut MusicFade, Option<&AudioSink>)>,
+) {
+    for (entity, mut fade, sink) in q_fading.iter_mut() {
+        fade.timer.tick(time.delta());
+        // NOTE: the sink only shows up once the track has started, so a fade can't finish before
+        let Some(sink) = sink else {
+            continue;
+        };
+        sink.set_volume(fade.level() * volume.level(SoundChannel::Music) * global.volume.get());
+        if !fade.timer.finished() {
+            continue;
+        }
+        if fade.to == 0. {
+            cmd.entity(entity).despawn_recursive();
+        } else {
+            cmd.entity(entity).remove::<MusicFade>();
+        }
+    }
+}
@@ -0,0 +1,305 @@
+//! The backdrop behind the board: a few layers of the starfield, tiled and tinted, each following
+//! the board camera at its own fraction of the camera's movement and zoom so the far ones barely
+//! move. They drift slowly on their own, and flare up for a moment on big combines and long
+//! chains. Each level picks a `BackgroundTheme`, cycling through `THEMES`.
+
+use bevy::{
+    prelude::*,
+    render::{
+        mesh::{Indices, VertexAttributeValues},
+        render_resource::PrimitiveTopology,
+        texture::{ImageAddressMode, ImageSampler, ImageSamplerDescriptor},
+    },
+    sprite::MaterialMesh2dBundle,
+};
+
+use crate::{
+    board::ShapesCombined,
+    camera::{BoardCamera, CameraSet},
+    juice::{BIG_CHAIN_DEPTH, BIG_COMBINE_VERTICES},
+    level::CurrentLevel,
+    loading::TextureAssets,
+    AppState, GameplayEntity,
+};
+
+/// Behind everything on the board.
+const BACKGROUND_Z: f32 = -30.;
+/// Seconds a flare takes to die down.
+const FLARE_DURATION: f32 = 0.8;
+/// How far toward white a fresh flare takes the layers.
+const FLARE_BRIGHTEN: f32 = 0.35;
+/// How much faster the layers drift at the start of a flare.
+const FLARE_DRIFT: f32 = 6.;
+
+#[derive(Clone, Copy, Debug)]
+pub struct LayerDef {
+    pub tint: Color,
+    /// World units one tile of the texture covers.
+    pub tile_size: f32,
+    /// How much of the camera's movement and zoom the layer follows: 0 stays put, 1 moves with
+    /// the board.
+    pub parallax: f32,
+    /// World units per second.
+    pub drift: Vec2,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct BackgroundTheme {
+    pub name: &'static str,
+    /// Back to front.
+    pub layers: [LayerDef; 3],
+}
+
+impl BackgroundTheme {
+    pub fn for_level(number: u32) -> &'static BackgroundTheme {
+        &THEMES[(number.max(1) - 1) as usize % THEMES.len()]
+    }
+}
+
+pub const THEMES: [BackgroundTheme; 3] = [
+    BackgroundTheme {
+        name: "Nebula",
+        layers: [
+            LayerDef {
+                tint: Color::rgb(0.55, 0.5, 0.7),
+                tile_size: 2048.,
+                parallax: 0.1,
+                drift: Vec2::new(4., 1.),
+            },
+            LayerDef {
+                tint: Color::rgba(0.8, 0.6, 1., 0.45),
+                tile_size: 1200.,
+                parallax: 0.3,
+                drift: Vec2::new(-6., 3.),
+            },
+            LayerDef {
+                tint: Color::rgba(1., 1., 1., 0.35),
+                tile_size: 600.,
+                parallax: 0.55,
+                drift: Vec2::new(10., -4.),
+            },
+        ],
+    },
+    BackgroundTheme {
+        name: "Deep Sea",
+        layers: [
+            LayerDef {
+                tint: Color::rgb(0.2, 0.45, 0.6),
+                tile_size: 2400.,
+                parallax: 0.1,
+                drift: Vec2::new(0., 3.),
+            },
+            LayerDef {
+                tint: Color::rgba(0.3, 0.8, 0.9, 0.4),
+                tile_size: 1400.,
+                parallax: 0.25,
+                drift: Vec2::new(2., 6.),
+            },
+            LayerDef {
+                tint: Color::rgba(0.7, 1., 1., 0.3),
+                tile_size: 700.,
+                parallax: 0.5,
+                drift: Vec2::new(-3., 12.),
+            },
+        ],
+    },
+    BackgroundTheme {
+        name: "Ember",
+        layers: [
+            LayerDef {
+                tint: Color::rgb(0.6, 0.3, 0.25),
+                tile_size: 2048.,
+                parallax: 0.1,
+                drift: Vec2::new(-3., -2.),
+            },
+            LayerDef {
+                tint: Color::rgba(1., 0.55, 0.3, 0.45),
+                tile_size: 1100.,
+                parallax: 0.3,
+                drift: Vec2::new(5., 5.),
+            },
+            LayerDef {
+                tint: Color::rgba(1., 0.9, 0.6, 0.35),
+                tile_size: 550.,
+                parallax: 0.6,
+                drift: Vec2::new(0., 14.),
+            },
+        ],
+    },
+];
+
+/// One layer of the backdrop. `offset` is how far it has drifted, in world units.
+#[derive(Component, Debug)]
+pub struct BackgroundLayer {
+    pub def: LayerDef,
+    offset: Vec2,
+}
+
+/// Counts down after a big combine, brightening the layers and speeding up their drift.
+#[derive(Resource, Default, Debug)]
+struct Flare(Timer);
+
+impl Flare {
+    fn strength(&self) -> f32 {
+        if self.0.duration().is_zero() {
+            return 0.;
+        }
+        self.0.percent_left()
+    }
+}
+
+pub struct BackgroundPlugin;
+impl Plugin for BackgroundPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Flare>()
+            .add_systems(OnEnter(AppState::Playing), spawn_background)
+            .add_systems(
+                Update,
+                (
+                    flare_on_combine,
+                    (drift_layers, follow_camera).chain().after(CameraSet),
+                )
+                    .run_if(in_state(AppState::Playing)),
+            )
+            .add_systems(OnExit(AppState::Playing), reset_flare);
+    }
+}
+
+fn spawn_background(
+    mut cmd: Commands,
+    level: Res<CurrentLevel>,
+    textures: Res<TextureAssets>,
+    mut images: ResMut<Assets<Image>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    // NOTE: the layers tile the texture by running their UVs past 1
+    if let Some(image) = images.get_mut(&textures.bg) {
+        if !matches!(image.sampler, ImageSampler::Descriptor(_)) {
+            image.sampler = ImageSampler::Descriptor(ImageSamplerDescriptor {
+                address_mode_u: ImageAddressMode::Repeat,
+                address_mode_v: ImageAddressMode::Repeat,
+                ..ImageSamplerDescriptor::linear()
+            });
+        }
+    }
+
+    let theme = BackgroundTheme::for_level(level.number);
+    info!("background: {}", theme.name);
+    for (i, def) in theme.layers.iter().enumerate() {
+        cmd.spawn((
+            MaterialMesh2dBundle {
+                // NOTE: each layer rewrites its own UVs, so it can't share the quad
+                mesh: meshes.add(unit_quad()).into(),
+                material: materials.add(ColorMaterial {
+                    color: def.tint,
+                    texture: Some(textures.bg.clone()),
+                }),
+                transform: Transform::from_xyz(0., 0., BACKGROUND_Z + i as f32),
+                ..default()
+            },
+            BackgroundLayer {
+                def: *def,
+                offset: Vec2::ZERO,
+            },
+            GameplayEntity,
+        ));
+    }
+}
+
+/// A 1 by 1 quad around the origin, its corners in the order `follow_camera` writes UVs.
+fn unit_quad() -> Mesh {
+    let corners = [[-0.5, -0.5], [0.5, -0.5], [0.5, 0.5], [-0.5, 0.5]];
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+    mesh.insert_attribute(
+        Mesh::ATTRIBUTE_POSITION,
+        corners.map(|[x, y]| [x, y, 0.]).to_vec(),
+    );
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, vec![[0., 0., 1.]; 4]);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, vec![[0., 0.]; 4]);
+    mesh.set_indices(Some(Indices::U32(vec![0, 1, 2, 0, 2, 3])));
+    mesh
+}
+
+fn flare_on_combine(
+    mut reader: EventReader<ShapesCombined>,
+    time: Res<Time>,
+    mut flare: ResMut<Flare>,
+) {
+    let big = reader
+        .read()
+        .filter(|combined| {
+            combined.result.polygon.vertices() >= BIG_COMBINE_VERTICES
+                || combined.depth >= BIG_CHAIN_DEPTH
+        })
+        .count();
+    if big > 0 {
+        flare.0 = Timer::from_seconds(FLARE_DURATION, TimerMode::Once);
+    } else {
+        flare.0.tick(time.delta());
+    }
+}
+
+fn drift_layers(
+    time: Res<Time>,
+    flare: Res<Flare>,
+    mut q_layers: Query<(&mut BackgroundLayer, &Handle<ColorMaterial>)>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let flare = flare.strength();
+    for (mut layer, material) in q_layers.iter_mut() {
+        let drift = layer.def.drift * (1. + FLARE_DRIFT * flare);
+        layer.offset += drift * time.delta_seconds();
+        let Some(material) = materials.get_mut(material.id()) else {
+            continue;
+        };
+        let [r, g, b, a] = layer.def.tint.as_rgba_f32();
+        let by = FLARE_BRIGHTEN * flare;
+        material.color = Color::rgba(r + (1. - r) * by, g + (1. - g) * by, b + (1. - b) * by, a);
+    }
+}
+
+/// Keeps each layer covering the board camera's view, and lays its texture so the layer seems to
+/// sit behind the board at its parallax.
+fn follow_camera(
+    q_camera: Query<(&Transform, &OrthographicProjection), With<BoardCamera>>,
+    mut q_layers: Query<(&BackgroundLayer, &mut Transform, &Handle<Mesh>), Without<BoardCamera>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    let Ok((camera, projection)) = q_camera.get_single() else {
+        return;
+    };
+    let camera_pos = camera.translation.truncate();
+    let view = projection.area;
+    // NOTE: the area is already scaled, undo it to get the unzoomed view
+    let unzoomed = view.size() / projection.scale.max(f32::EPSILON);
+    for (layer, mut transform, mesh) in q_layers.iter_mut() {
+        let z = transform.translation.z;
+        transform.translation = (camera_pos + view.center()).extend(z);
+        transform.scale = view.size().extend(1.);
+
+        let Some(mesh) = meshes.get_mut(mesh) else {
+            continue;
+        };
+        let def = layer.def;
+        let extent = unzoomed * projection.scale.powf(def.parallax) / def.tile_size;
+        let center = (camera_pos * def.parallax + layer.offset) / def.tile_size;
+        // NOTE: UV space points y down
+        let (min, max) = (center - extent / 2., center + extent / 2.);
+        let uvs = vec![
+            [min.x, -min.y],
+            [max.x, -min.y],
+            [max.x, -max.y],
+            [min.x, -max.y],
+        ];
+        if let Some(VertexAttributeValues::Float32x2(current)) =
+            mesh.attribute_mut(Mesh::ATTRIBUTE_UV_0)
+        {
+            *current = uvs;
+        }
+    }
+}
+
+fn reset_flare(mut flare: ResMut<Flare>) {
+    *flare = Flare::default();
+}
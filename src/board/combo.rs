@@ -0,0 +1,92 @@
+//! Chain reactions. A shape made by a combine remembers how deep in a chain it is, and if it
+//! combines again within `COMBO_WINDOW` of being able to touch other shapes, the new shape is one
+//! deeper. Combines are scored times their depth, and chains of two or more send `ComboEvent`.
+
+use bevy::prelude::*;
+
+use super::{AwaitNoCollision, ShapesCombined};
+use crate::AppState;
+
+/// Seconds a combine's result has, once it can collide, to combine again and continue the chain.
+pub const COMBO_WINDOW: f32 = 2.;
+
+/// Where in a chain the shape was made. Removed once its window runs out.
+#[derive(Component, Clone, Debug)]
+pub struct ComboDepth {
+    pub depth: u32,
+    window: Timer,
+}
+
+impl ComboDepth {
+    pub fn new(depth: u32) -> Self {
+        Self {
+            depth,
+            window: Timer::from_seconds(COMBO_WINDOW, TimerMode::Once),
+        }
+    }
+}
+
+#[derive(Resource, Default, Debug)]
+pub struct ComboTracker {
+    /// The deepest chain that can still continue, 0 when there's none.
+    pub depth: u32,
+    /// The deepest chain of the run.
+    pub best: u32,
+}
+
+/// A combine continued a chain. `depth` is 2 for the first combine that did.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct ComboEvent {
+    pub depth: u32,
+    pub position: Vec2,
+}
+
+pub struct ComboPlugin;
+impl Plugin for ComboPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ComboTracker>()
+            .add_event::<ComboEvent>()
+            .add_systems(
+                Update,
+                (expire_combos, track_combos)
+                    .chain()
+                    .run_if(in_state(AppState::Playing)),
+            )
+            .add_systems(OnExit(AppState::Playing), reset_combos);
+    }
+}
+
+/// The window only runs once the shape has phased into the board.
+fn expire_combos(
+    mut cmd: Commands,
+    time: Res<Time>,
+    mut q_combos: Query<(Entity, &mut ComboDepth), Without<AwaitNoCollision>>,
+) {
+    for (entity, mut combo) in q_combos.iter_mut() {
+        if combo.window.tick(time.delta()).finished() {
+            cmd.entity(entity).remove::<ComboDepth>();
+        }
+    }
+}
+
+fn track_combos(
+    mut reader: EventReader<ShapesCombined>,
+    q_combos: Query<&ComboDepth>,
+    mut tracker: ResMut<ComboTracker>,
+    mut writer: EventWriter<ComboEvent>,
+) {
+    for combined in reader.read() {
+        tracker.best = tracker.best.max(combined.depth);
+        if combined.depth >= 2 {
+            writer.send(ComboEvent {
+                depth: combined.depth,
+                position: combined.position,
+            });
+        }
+    }
+    tracker.depth = q_combos.iter().map(|combo| combo.depth).max().unwrap_or(0);
+}
+
+fn reset_combos(mut tracker: ResMut<ComboTracker>) {
+    *tracker = ComboTracker::default();
+}
@@ -0,0 +1,137 @@
+//! Gravity shifts. Shapes are normally pulled toward the center of the board by `world_gravity`,
+//! with xpbd's own `Gravity` left at zero. A `ShiftGravity` turns that pull by an angle for a
+//! while, half a turn pushing shapes outward instead, and sets xpbd's `Gravity` to a gentle drift
+//! toward the turned "down". Cards and gravity zones shift it, and `GravityChanged` tells the
+//! indicator when it turns or settles back.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_xpbd_2d::resources::Gravity;
+
+use super::status::BoardStatus;
+use crate::{cards::GameState, pause::PauseState, AppState};
+
+/// Acceleration of the drift toward "down" while gravity is shifted.
+const SHIFT_DRIFT: f32 = 60.;
+
+/// Turns gravity by `angle` radians, counterclockwise, for `duration` seconds. A shift by the same
+/// angle as the one running keeps whichever would last longer; any other replaces it.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct ShiftGravity {
+    pub angle: f32,
+    pub duration: f32,
+}
+
+/// Gravity turned to `angle`, 0 once it settles back.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct GravityChanged {
+    pub angle: f32,
+}
+
+/// How far gravity is turned, and for how much longer.
+#[derive(Resource, Default, Debug)]
+pub struct BoardGravity {
+    pub angle: f32,
+    pub timer: Option<Timer>,
+}
+
+impl BoardGravity {
+    /// The pull on a shape `to_center` away from the center: the square of the distance, toward
+    /// the center, turned by the shift and scaled by the `BoardStatus`.
+    pub fn pull(&self, to_center: Vec2, status: &BoardStatus) -> Vec2 {
+        Vec2::from_angle(self.angle).rotate(
+            status.gravity_scale() * to_center.length_squared() * to_center.normalize_or_zero(),
+        )
+    }
+
+    /// Which way is "down", turned with the shift.
+    pub fn down(&self) -> Vec2 {
+        Vec2::from_angle(self.angle).rotate(Vec2::NEG_Y)
+    }
+
+    /// What xpbd's `Gravity` should be: the drift while shifted, nothing otherwise.
+    pub fn drift(&self, status: &BoardStatus) -> Vec2 {
+        if self.timer.is_some() {
+            status.gravity_scale() * SHIFT_DRIFT * self.down()
+        } else {
+            Vec2::ZERO
+        }
+    }
+
+    pub fn remaining_secs(&self) -> f32 {
+        self.timer.as_ref().map_or(0., Timer::remaining_secs)
+    }
+}
+
+pub struct GravityPlugin;
+impl Plugin for GravityPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ShiftGravity>()
+            .add_event::<GravityChanged>()
+            .init_resource::<BoardGravity>()
+            .add_systems(
+                Update,
+                (shift_gravity, settle_gravity, set_drift)
+                    .chain()
+                    .run_if(in_state(PauseState::Running))
+                    .run_if(in_state(AppState::Playing))
+                    .run_if(not(in_state(GameState::Scoring))),
+            )
+            .add_systems(OnExit(AppState::Playing), reset_gravity);
+    }
+}
+
+fn shift_gravity(
+    mut reader: EventReader<ShiftGravity>,
+    mut gravity: ResMut<BoardGravity>,
+    mut changed: EventWriter<GravityChanged>,
+) {
+    for event in reader.read() {
+        let duration = Duration::from_secs_f32(event.duration);
+        if gravity.angle == event.angle {
+            if let Some(timer) = gravity.timer.as_mut() {
+                if timer.remaining() < duration {
+                    *timer = Timer::new(duration, TimerMode::Once);
+                }
+                continue;
+            }
+        }
+        gravity.angle = event.angle;
+        gravity.timer = Some(Timer::new(duration, TimerMode::Once));
+        changed.send(GravityChanged { angle: event.angle });
+    }
+}
+
+fn settle_gravity(
+    time: Res<Time>,
+    mut gravity: ResMut<BoardGravity>,
+    mut changed: EventWriter<GravityChanged>,
+) {
+    let Some(timer) = gravity.timer.as_mut() else {
+        return;
+    };
+    if timer.tick(time.delta()).finished() {
+        *gravity = BoardGravity::default();
+        changed.send(GravityChanged { angle: 0. });
+    }
+}
+
+fn set_drift(gravity: Res<BoardGravity>, status: Res<BoardStatus>, mut xpbd: ResMut<Gravity>) {
+    let drift = gravity.drift(&status);
+    if xpbd.0 != drift {
+        xpbd.0 = drift;
+    }
+}
+
+fn reset_gravity(
+    mut gravity: ResMut<BoardGravity>,
+    mut xpbd: ResMut<Gravity>,
+    mut shift: ResMut<Events<ShiftGravity>>,
+    mut changed: EventWriter<GravityChanged>,
+) {
+    *gravity = BoardGravity::default();
+    xpbd.0 = Vec2::ZERO;
+    shift.clear();
+    changed.send(GravityChanged { angle: 0. });
+}
@@ -0,0 +1,309 @@
+//! A launcher at the edge of the board that fires shapes in, like a pinball plunger with aim. It
+//! points at the pointer (or the gamepad's board cursor), and `GameAction::Launch` fires the next
+//! shape of the `LaunchQueue`. A line of dots shows where the shot will go, worked out from the
+//! same pull toward the center and speed limit the board's physics use. Boards that want one
+//! put it in their `BoardLayout`.
+
+use std::collections::VecDeque;
+
+use bevy::{
+    prelude::*,
+    sprite::{MaterialMesh2dBundle, Mesh2dHandle},
+};
+use bevy_xpbd_2d::prelude::LinearVelocity;
+use leafwing_input_manager::prelude::ActionState;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    config, gravity::BoardGravity, spawner::run_spawners, status::BoardStatus, SpawnBody, SpawnSet,
+    SpawnSource,
+};
+use crate::{
+    cards::{focus::BoardCursor, GameState},
+    game_shapes::{
+        get_color_material, get_polygon_mesh, material::ShapeMaterial, stats::ShapeStats,
+        PolygonColliders, Shape, ShapeAssets, ShapeMaterialAssets,
+    },
+    input::GameAction,
+    level::CurrentLevel,
+    pause::PauseState,
+    pointer::Pointer,
+    rng::GameRng,
+    AppState, GameplayEntity,
+};
+
+/// Shapes waiting to be fired, the next one included.
+const QUEUE_LEN: usize = 3;
+/// Anything faster is capped by the board's speed limit anyway.
+const LAUNCH_SPEED: f32 = config::MAX_SPEED;
+const PREVIEW_DOTS: usize = 10;
+/// Seconds of flight between two preview dots.
+const DOT_INTERVAL: f32 = 0.25;
+/// Simulation steps between two preview dots.
+const DOT_SUBSTEPS: usize = 15;
+const BARREL_SIZE: Vec2 = Vec2::new(16., 60.);
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct LauncherDef {
+    /// Relative to the center of the board.
+    pub position: Vec2,
+    /// Seconds between two shots.
+    #[serde(default = "default_cooldown")]
+    pub cooldown: f32,
+}
+
+fn default_cooldown() -> f32 {
+    0.6
+}
+
+#[derive(Component, Debug)]
+pub struct Launcher {
+    /// The unit vector it's pointing along.
+    pub aim: Vec2,
+    cooldown: Timer,
+}
+
+/// The shapes the launcher fires, in order. Topped up with random shapes the level allows.
+#[derive(Resource, Default, Debug)]
+pub struct LaunchQueue(pub VecDeque<Shape>);
+
+#[derive(Component)]
+struct LauncherBarrel;
+
+/// Shows the front of the `LaunchQueue` in the launcher.
+#[derive(Component)]
+struct NextShape;
+
+/// The `n`th dot along the predicted path.
+#[derive(Component)]
+struct TrajectoryDot(usize);
+
+pub struct LauncherPlugin;
+impl Plugin for LauncherPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LaunchQueue>()
+            .add_systems(
+                Update,
+                (
+                    fill_launch_queue,
+                    aim_launcher,
+                    fire_launcher.run_if(in_state(PauseState::Running)),
+                    (show_next_shape, preview_trajectory),
+                )
+                    .chain()
+                    .before(SpawnSet)
+                    .after(run_spawners)
+                    .run_if(in_state(AppState::Playing))
+                    .run_if(not(in_state(GameState::Setup)))
+                    .run_if(not(in_state(GameState::Start)))
+                    .run_if(not(in_state(GameState::Scoring))),
+            )
+            .add_systems(OnExit(AppState::Playing), clear_launch_queue);
+    }
+}
+
+pub fn spawn_launcher(cmd: &mut Commands, def: &LauncherDef) {
+    let mut cooldown = Timer::from_seconds(def.cooldown, TimerMode::Once);
+    cooldown.tick(cooldown.duration());
+    cmd.spawn((
+        SpatialBundle::from_transform(Transform::from_translation(
+            (config::CENTER + def.position).extend(6.),
+        )),
+        Launcher {
+            aim: Vec2::Y,
+            cooldown,
+        },
+        GameplayEntity,
+    ))
+    .with_children(|parent| {
+        parent.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::GRAY,
+                    custom_size: Some(BARREL_SIZE),
+                    anchor: bevy::sprite::Anchor::BottomCenter,
+                    ..default()
+                },
+                ..default()
+            },
+            LauncherBarrel,
+        ));
+        parent.spawn((
+            MaterialMesh2dBundle::<ShapeMaterial> {
+                transform: Transform::from_xyz(0., 0., 1.).with_scale(Vec3::new(
+                    config::SHAPE_SCALE,
+                    config::SHAPE_SCALE,
+                    1.,
+                )),
+                ..default()
+            },
+            NextShape,
+        ));
+        for n in 0..PREVIEW_DOTS {
+            parent.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: Color::rgba(1., 1., 1., 0.8 - 0.06 * n as f32),
+                        custom_size: Some(Vec2::splat(6.)),
+                        ..default()
+                    },
+                    ..default()
+                },
+                TrajectoryDot(n),
+            ));
+        }
+    });
+}
+
+pub fn fill_launch_queue(
+    mut queue: ResMut<LaunchQueue>,
+    level: Res<CurrentLevel>,
+    mut rng: ResMut<GameRng>,
+) {
+    while queue.0.len() < QUEUE_LEN {
+        // NOTE: a level that allows no polygon at all gets whatever comes up
+        let shape = std::iter::repeat_with(|| Shape::random_shape_with(&mut rng.0))
+            .take(32)
+            .find(|shape| level.allows(shape.polygon))
+            .unwrap_or_else(|| Shape::random_shape_with(&mut rng.0));
+        queue.0.push_back(shape);
+    }
+}
+
+fn aim_launcher(
+    pointer: Res<Pointer>,
+    q_cursor: Query<(&Transform, &Visibility), With<BoardCursor>>,
+    mut q_launchers: Query<(&mut Launcher, &Transform, &Children)>,
+    mut q_barrels: Query<
+        &mut Transform,
+        (
+            With<LauncherBarrel>,
+            Without<Launcher>,
+            Without<BoardCursor>,
+        ),
+    >,
+) {
+    let cursor = q_cursor
+        .iter()
+        .find(|(_, visibility)| **visibility != Visibility::Hidden)
+        .map(|(transform, _)| transform.translation.truncate());
+    let Some(target) = pointer.board.or(cursor) else {
+        return;
+    };
+    for (mut launcher, transform, children) in q_launchers.iter_mut() {
+        let aim = (target - transform.translation.truncate()).normalize_or_zero();
+        if aim == Vec2::ZERO {
+            continue;
+        }
+        launcher.aim = aim;
+        for &child in children.iter() {
+            if let Ok(mut barrel) = q_barrels.get_mut(child) {
+                barrel.rotation = Quat::from_rotation_arc_2d(Vec2::Y, aim);
+            }
+        }
+    }
+}
+
+fn fire_launcher(
+    time: Res<Time>,
+    actions: Res<ActionState<GameAction>>,
+    mut queue: ResMut<LaunchQueue>,
+    mut q_launchers: Query<(&mut Launcher, &Transform)>,
+    mut writer: EventWriter<SpawnBody>,
+) {
+    let fire = actions.just_pressed(GameAction::Launch);
+    for (mut launcher, transform) in q_launchers.iter_mut() {
+        if !launcher.cooldown.tick(time.delta()).finished() || !fire {
+            continue;
+        }
+        let Some(shape) = queue.0.pop_front() else {
+            continue;
+        };
+        launcher.cooldown.reset();
+        writer.send(SpawnBody {
+            shape,
+            transform: Transform::from_translation(transform.translation.truncate().extend(10.)),
+            velocity: Some(LinearVelocity(launcher.aim * LAUNCH_SPEED)),
+            combo: 0,
+            element: None,
+            source: SpawnSource::Board,
+        });
+    }
+}
+
+fn show_next_shape(
+    queue: Res<LaunchQueue>,
+    shape_assets: Res<ShapeAssets>,
+    color_mat: Res<ShapeMaterialAssets>,
+    mut q_next: Query<(&mut Mesh2dHandle, &mut Handle<ShapeMaterial>), With<NextShape>>,
+) {
+    let Some(shape) = queue.0.front() else {
+        return;
+    };
+    let mesh = get_polygon_mesh(&shape.polygon, &shape_assets);
+    let material = get_color_material(&shape.color, &color_mat);
+    for (mut mesh_handle, mut material_handle) in q_next.iter_mut() {
+        if mesh_handle.0 != mesh {
+            mesh_handle.0 = mesh.clone();
+        }
+        if *material_handle != material {
+            *material_handle = material.clone();
+        }
+    }
+}
+
+/// Steps the next shot the way the physics would: `world_gravity` pulls it toward the center
+/// with the square of its distance, turned by the `BoardGravity` and scaled by the `BoardStatus`,
+/// over its mass, the drift of a gravity shift speeds it along, and `clamp_vel` caps its speed.
+/// Collisions and Frozen's damping are left out.
+fn preview_trajectory(
+    queue: Res<LaunchQueue>,
+    poly_colliders: Res<PolygonColliders>,
+    shape_stats: Res<ShapeStats>,
+    status: Res<BoardStatus>,
+    gravity: Res<BoardGravity>,
+    q_launchers: Query<(&Launcher, &Transform, &Children)>,
+    mut q_dots: Query<(&TrajectoryDot, &mut Transform), Without<Launcher>>,
+) {
+    let Some(shape) = queue.0.front() else {
+        return;
+    };
+    let Some(collider) = poly_colliders.get(&shape.polygon) else {
+        return;
+    };
+    let mut collider = collider.clone();
+    collider.set_scale(Vec2::splat(config::SHAPE_SCALE), 10);
+    let mass = collider
+        .mass_properties(shape_stats.get(shape.polygon).density)
+        .mass();
+    if mass <= 0. {
+        return;
+    }
+
+    let dt = DOT_INTERVAL / DOT_SUBSTEPS as f32;
+    let drift = gravity.drift(&status);
+    for (launcher, transform, children) in q_launchers.iter() {
+        let start = transform.translation.truncate();
+        let mut position = start;
+        let mut velocity = launcher.aim * LAUNCH_SPEED;
+        let mut path = [Vec2::ZERO; PREVIEW_DOTS];
+        for point in path.iter_mut() {
+            for _ in 0..DOT_SUBSTEPS {
+                let to_center = config::CENTER - position;
+                velocity += (gravity.pull(to_center, &status) / mass + drift) * dt;
+                velocity = velocity.clamp_length_max(config::MAX_SPEED);
+                position += velocity * dt;
+            }
+            *point = position - start;
+        }
+        for &child in children.iter() {
+            if let Ok((dot, mut dot_transform)) = q_dots.get_mut(child) {
+                dot_transform.translation = path[dot.0].extend(dot_transform.translation.z);
+            }
+        }
+    }
+}
+
+fn clear_launch_queue(mut queue: ResMut<LaunchQueue>) {
+    queue.0.clear();
+}
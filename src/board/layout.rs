@@ -0,0 +1,271 @@
+//! Board layouts: the walls, obstacles, spawn zones, sensor zones, spawners, launcher and
+//! scheduled statuses of an arena, read from the `.board.ron`
+//! files in `assets/boards/`. The layout picked on the level select screen is built when a run
+//! starts, and edits to its file rebuild it on the spot.
+
+use std::io;
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+    utils::BoxedFuture,
+};
+use bevy_xpbd_2d::prelude::{Collider, Restitution, RigidBody};
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    config,
+    launcher::{spawn_launcher, Launcher, LauncherDef},
+    obstacle::{spawn_obstacle, BoardObstacle, ObstacleDef},
+    spawner::{spawn_spawner, Spawner, SpawnerDef},
+    status::StatusDef,
+    zone::{spawn_zone, BoardZone, BoardZoneDef},
+    Layer,
+};
+use crate::{args::LaunchArgs, loading::LayoutAssets, AppState, GameplayEntity};
+
+const WALL_COLOR: Color = Color::WHITE;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct WallDef {
+    /// Relative to the center of the board.
+    pub position: Vec2,
+    pub size: Vec2,
+    /// In radians.
+    #[serde(default)]
+    pub rotation: f32,
+}
+
+/// A rectangle timed spawns can land in.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct SpawnZone {
+    /// Relative to the center of the board.
+    pub center: Vec2,
+    pub half_size: Vec2,
+}
+
+#[derive(Asset, TypePath, Serialize, Deserialize, Clone, Debug)]
+pub struct BoardLayout {
+    /// Shown on the level select screen.
+    pub name: String,
+    /// Shapes further from the center than its longer side are taken off the board.
+    #[serde(default = "default_size")]
+    pub size: Vec2,
+    #[serde(default)]
+    pub walls: Vec<WallDef>,
+    /// Empty spawns shapes around the middle of the board.
+    #[serde(default)]
+    pub spawn_zones: Vec<SpawnZone>,
+    #[serde(default)]
+    pub obstacles: Vec<ObstacleDef>,
+    #[serde(default)]
+    pub spawners: Vec<SpawnerDef>,
+    /// Goals, voids and color fields. See `zone`.
+    #[serde(default)]
+    pub zones: Vec<BoardZoneDef>,
+    /// Lets the player fire shapes in. See `launcher`.
+    #[serde(default)]
+    pub launcher: Option<LauncherDef>,
+    /// Board statuses applied as the run goes on. See `status`.
+    #[serde(default)]
+    pub statuses: Vec<StatusDef>,
+}
+
+fn default_size() -> Vec2 {
+    config::SIZE
+}
+
+impl Default for BoardLayout {
+    fn default() -> Self {
+        Self {
+            name: "Open".to_string(),
+            size: default_size(),
+            walls: Vec::new(),
+            spawn_zones: Vec::new(),
+            obstacles: Vec::new(),
+            spawners: Vec::new(),
+            zones: Vec::new(),
+            launcher: None,
+            statuses: Vec::new(),
+        }
+    }
+}
+
+impl BoardLayout {
+    /// A random point, in world space, inside one of the spawn zones.
+    pub fn spawn_point(&self, rng: &mut impl Rng) -> Vec2 {
+        let zone = self.spawn_zones.choose(rng).copied().unwrap_or(SpawnZone {
+            center: Vec2::ZERO,
+            half_size: 0.3 * self.size,
+        });
+        let offset = Vec2::new(
+            rng.gen_range(-zone.half_size.x..=zone.half_size.x),
+            rng.gen_range(-zone.half_size.y..=zone.half_size.y),
+        );
+        config::CENTER + zone.center + offset
+    }
+
+    pub fn max_radius(&self) -> f32 {
+        self.size.max_element()
+    }
+}
+
+/// The name of the layout to play on. Names that aren't loaded fall back to the first layout.
+#[derive(Resource, Clone, Default, Debug)]
+pub struct SelectedLayout(pub String);
+
+/// The layout of the run in progress. Present only during `AppState::Playing`.
+#[derive(Resource, Deref, Debug)]
+pub struct ActiveLayout {
+    pub handle: Handle<BoardLayout>,
+    #[deref]
+    pub layout: BoardLayout,
+}
+
+#[derive(Component)]
+pub struct BoardWall;
+
+#[derive(Default)]
+pub struct BoardLayoutLoader;
+
+impl AssetLoader for BoardLayoutLoader {
+    type Asset = BoardLayout;
+    type Settings = ();
+    type Error = io::Error;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<BoardLayout, io::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            ron::de::from_bytes(&bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["board.ron"]
+    }
+}
+
+pub struct LayoutPlugin;
+impl Plugin for LayoutPlugin {
+    fn build(&self, app: &mut App) {
+        let selected = app
+            .world
+            .get_resource::<LaunchArgs>()
+            .and_then(|args| args.board.clone())
+            .unwrap_or_default();
+
+        app.init_asset::<BoardLayout>()
+            .init_asset_loader::<BoardLayoutLoader>()
+            .insert_resource(SelectedLayout(selected))
+            .add_systems(
+                Update,
+                rebuild_changed_layout.run_if(in_state(AppState::Playing)),
+            )
+            .add_systems(OnExit(AppState::Playing), clear_active_layout);
+    }
+}
+
+/// The selected layout, or the first one if it isn't loaded.
+pub fn find_layout(
+    selected: &SelectedLayout,
+    handles: &LayoutAssets,
+    layouts: &Assets<BoardLayout>,
+) -> Option<ActiveLayout> {
+    let loaded = || {
+        handles
+            .layouts
+            .iter()
+            .filter_map(|handle| Some((handle, layouts.get(handle)?)))
+    };
+    let (handle, layout) = loaded()
+        .find(|(_, layout)| layout.name == selected.0)
+        .or_else(|| loaded().next())?;
+    Some(ActiveLayout {
+        handle: handle.clone(),
+        layout: layout.clone(),
+    })
+}
+
+pub fn build_arena(
+    cmd: &mut Commands,
+    layout: &BoardLayout,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<ColorMaterial>,
+) {
+    for wall in layout.walls.iter() {
+        cmd.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: WALL_COLOR,
+                    custom_size: Some(wall.size),
+                    ..default()
+                },
+                transform: Transform::from_translation((config::CENTER + wall.position).extend(5.))
+                    .with_rotation(Quat::from_rotation_z(wall.rotation)),
+                ..default()
+            },
+            RigidBody::Static,
+            Collider::cuboid(wall.size.x, wall.size.y),
+            Restitution::PERFECTLY_ELASTIC,
+            Layer::wall(),
+            BoardWall,
+            GameplayEntity,
+        ));
+    }
+    for obstacle in layout.obstacles.iter() {
+        spawn_obstacle(cmd, obstacle, meshes, materials);
+    }
+    for spawner in layout.spawners.iter() {
+        spawn_spawner(cmd, spawner);
+    }
+    for zone in layout.zones.iter() {
+        spawn_zone(cmd, zone);
+    }
+    if let Some(launcher) = &layout.launcher {
+        spawn_launcher(cmd, launcher);
+    }
+}
+
+fn rebuild_changed_layout(
+    mut cmd: Commands,
+    mut reader: EventReader<AssetEvent<BoardLayout>>,
+    mut active: ResMut<ActiveLayout>,
+    layouts: Res<Assets<BoardLayout>>,
+    q_arena: Query<
+        Entity,
+        Or<(
+            With<BoardWall>,
+            With<BoardObstacle>,
+            With<Spawner>,
+            With<BoardZone>,
+            With<Launcher>,
+        )>,
+    >,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let id = active.handle.id();
+    let modified = reader
+        .read()
+        .any(|event| *event == AssetEvent::Modified { id });
+    let Some(layout) = layouts.get(id).filter(|_| modified) else {
+        return;
+    };
+    for entity in q_arena.iter() {
+        cmd.entity(entity).despawn_recursive();
+    }
+    active.layout = layout.clone();
+    build_arena(&mut cmd, layout, &mut meshes, &mut materials);
+    info!("board layout {} updated", layout.name);
+}
+
+fn clear_active_layout(mut cmd: Commands) {
+    cmd.remove_resource::<ActiveLayout>();
+}
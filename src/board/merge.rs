@@ -0,0 +1,88 @@
+//! The blend when two shapes combine. The two inputs leave the physics and shrink into the
+//! point where they met, while the result grows out of it, held in place, and only starts
+//! moving once the blend is over.
+
+use std::time::Duration;
+
+use bevy::{ecs::system::EntityCommands, prelude::*};
+use bevy_tweening::{lens::TransformScaleLens, Animator, EaseFunction, Tween};
+use bevy_xpbd_2d::prelude::{Collider, LinearVelocity, RigidBody};
+
+use super::{AwaitNoCollision, IsOnBoard};
+use crate::{cards::hand::TransformLens, tween_ids::DESPAWN_SHAPE, AppState};
+
+/// Seconds the inputs take to shrink away and the result to grow in.
+pub const MERGE_DURATION: f32 = 0.2;
+/// How big the result starts, relative to its full size.
+const GROW_FROM: f32 = 0.4;
+
+/// A combine's result, kinematic and still until `timer` finishes, then set moving at `velocity`.
+#[derive(Component, Debug)]
+pub struct MergeAnimation {
+    timer: Timer,
+    velocity: Vec2,
+}
+
+pub struct MergePlugin;
+impl Plugin for MergePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, finish_merges.run_if(in_state(AppState::Playing)));
+    }
+}
+
+/// Takes a combine's input off the board and shrinks it into `midpoint`, then despawns it.
+pub fn merge_out(cmd: &mut Commands, entity: Entity, from: Transform, midpoint: Vec2) {
+    let to = Transform {
+        translation: midpoint.extend(from.translation.z),
+        scale: Vec3::ZERO,
+        ..from
+    };
+    let tween = Tween::new(
+        EaseFunction::QuadraticIn,
+        Duration::from_secs_f32(MERGE_DURATION),
+        TransformLens {
+            start: from,
+            end: to,
+        },
+    )
+    .with_completed_event(DESPAWN_SHAPE);
+
+    cmd.entity(entity)
+        .remove::<(IsOnBoard, AwaitNoCollision, RigidBody, Collider)>()
+        .insert(Animator::new(tween));
+}
+
+/// Holds a combine's freshly spawned result still while it grows to `scale`.
+pub fn merge_in(body: &mut EntityCommands<'_, '_, '_>, scale: Vec3, velocity: Vec2) {
+    let tween = Tween::new(
+        EaseFunction::BackOut,
+        Duration::from_secs_f32(MERGE_DURATION),
+        TransformScaleLens {
+            start: scale * GROW_FROM,
+            end: scale,
+        },
+    );
+    body.insert((
+        RigidBody::Kinematic,
+        LinearVelocity::ZERO,
+        MergeAnimation {
+            timer: Timer::from_seconds(MERGE_DURATION, TimerMode::Once),
+            velocity,
+        },
+        Animator::new(tween),
+    ));
+}
+
+fn finish_merges(
+    mut cmd: Commands,
+    time: Res<Time>,
+    mut q_merging: Query<(Entity, &mut MergeAnimation)>,
+) {
+    for (entity, mut merge) in q_merging.iter_mut() {
+        if merge.timer.tick(time.delta()).finished() {
+            cmd.entity(entity)
+                .remove::<MergeAnimation>()
+                .insert((RigidBody::Dynamic, LinearVelocity(merge.velocity)));
+        }
+    }
+}
@@ -1,48 +1,180 @@
-use std::ops::{Deref, Sub};
+use std::f32::consts::TAU;
 
-use bevy::{ecs::system::Command, prelude::*, render::texture::ImageSampler};
-use bevy_xpbd_2d::prelude::{
-    Collider, CollidingEntities, Collision, CollisionLayers, ExternalAngularImpulse, ExternalForce,
-    ExternalImpulse, LinearVelocity, MassPropertiesBundle, PhysicsLayer, Restitution, RigidBody,
-    SpatialQuery, SpatialQueryFilter,
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use bevy_xpbd_2d::{
+    prelude::{
+        CollisionEnded, CollisionLayers, CollisionStarted, ExternalForce, LinearDamping,
+        LinearVelocity, PhysicsLayer, Position, RigidBody,
+    },
+    PhysicsSchedule, PhysicsStepSet,
+};
+use rand::{
+    seq::{IteratorRandom, SliceRandom},
+    Rng,
+};
+use serde::{Deserialize, Serialize};
+
+use self::{
+    combo::{ComboDepth, ComboPlugin},
+    gravity::{BoardGravity, GravityPlugin},
+    launcher::LauncherPlugin,
+    layout::{build_arena, find_layout, ActiveLayout, BoardLayout, LayoutPlugin, SelectedLayout},
+    merge::{merge_in, merge_out, MergePlugin},
+    obstacle::ObstaclePlugin,
+    population::{DespawnShape, Lifetime, PopulationPlugin},
+    prime::PrimePlugin,
+    pulse::PulsePlugin,
+    reaction::ReactionPlugin,
+    snapshot::SnapshotPlugin,
+    spawner::SpawnerPlugin,
+    status::{BoardStatus, StatusPlugin},
+    transmute::TransmutePlugin,
+    zone::ZonePlugin,
 };
-use rand::{seq::IteratorRandom, Rng};
-
 use crate::{
-    cards::{self, deck::reset_deck, rules::Rule, GameState},
+    audio::{PlaySound, Sfx},
+    cards::{definition::CardDefinitions, rules::Rule, zones::PlayerId, GameState},
     game_shapes::{
-        self, config::POLYGON_RADIUS, ColorMaterialAssets, GameColor, GamePolygon,
-        PolygonColliders, Shape, ShapeAssets,
+        config::POLYGON_RADIUS,
+        element::{Element, ElementReactions},
+        interaction::ColorInteraction,
+        stats::ShapeStats,
+        PolygonColliders, Shape, ShapeAssets, ShapeMaterialAssets,
     },
-    loading::{SoundAssets, TextureAssets},
+    level::CurrentLevel,
+    loading::LayoutAssets,
     operation::Operation,
-    utils::{average, vec3_to_vec2},
-    AppState,
+    replay::ReplaySet,
+    rng::GameRng,
+    utils::{average, ThrottledWarn},
+    AppState, GameplayEntity,
 };
 
+pub mod combo;
+pub mod gravity;
+pub mod launcher;
+pub mod layout;
+pub mod merge;
+pub mod obstacle;
+pub mod population;
+pub mod prime;
+pub mod pulse;
+pub mod reaction;
+pub mod snapshot;
+pub mod spawner;
+pub mod status;
+pub mod transmute;
+pub mod zone;
+
 pub mod config {
     use super::Vec2;
 
     pub const SIZE: Vec2 = Vec2::new(1000., 1000.);
     pub const CENTER: Vec2 = Vec2::new(0., 0.2 * SIZE.x);
-    pub const WALL_THICKNESS: f32 = 100.;
     pub const SHAPE_SCALE: f32 = 0.25;
+    /// Smallest size, relative to `SHAPE_SCALE`, that `Mul` will shrink a shape to.
+    pub const MIN_SHAPE_SCALE: f32 = 0.35;
     pub const MAX_SPEED: f32 = 100.;
     pub const MAX_SHAPES: u32 = 20_000;
-    pub const MAX_RADIUS: f32 = 1_000.;
 }
 
-#[derive(PhysicsLayer)]
-enum Layer {
+/// What collides with what. Build an entity's `CollisionLayers` with the functions below rather
+/// than by hand, so the pairs stay symmetric: xpbd only collides two entities when each one's
+/// filter includes the other's layer.
+#[derive(PhysicsLayer, Clone, Copy, Debug)]
+pub enum Layer {
     Shape,
+    /// Walls and obstacles. They never move, so they don't collide with each other.
     Wall,
+    /// Things thrown at shapes. They hit shapes and walls, but pass through each other.
+    Projectile,
+    /// Areas that notice shapes in them without pushing them. Give them xpbd's `Sensor` too.
+    Sensor,
+}
+
+impl Layer {
+    pub fn shape() -> CollisionLayers {
+        CollisionLayers::new(
+            [Layer::Shape],
+            [Layer::Shape, Layer::Wall, Layer::Projectile, Layer::Sensor],
+        )
+    }
+
+    /// A shape that just spawned, and might overlap another. See `AwaitNoCollision`.
+    pub fn spawning_shape() -> CollisionLayers {
+        CollisionLayers::new([Layer::Shape], [Layer::Wall])
+    }
+
+    pub fn wall() -> CollisionLayers {
+        CollisionLayers::new([Layer::Wall], [Layer::Shape, Layer::Projectile])
+    }
+
+    pub fn projectile() -> CollisionLayers {
+        CollisionLayers::new([Layer::Projectile], [Layer::Shape, Layer::Wall])
+    }
+
+    pub fn sensor() -> CollisionLayers {
+        CollisionLayers::new([Layer::Sensor], [Layer::Shape])
+    }
 }
 
 #[derive(Event, Clone, Copy)]
 pub struct SpawnBody {
-    shape: Shape,
-    transform: Transform,
-    velocity: Option<LinearVelocity>,
+    pub shape: Shape,
+    /// The scale is relative to `config::SHAPE_SCALE`.
+    pub transform: Transform,
+    /// A random velocity is picked when `None`.
+    pub velocity: Option<LinearVelocity>,
+    /// The chain depth of the combine that made the shape, 0 when it didn't come from one.
+    pub combo: u32,
+    /// See `reaction`.
+    pub element: Option<Element>,
+    pub source: SpawnSource,
+}
+
+/// What put a shape on the board. Shapes a player's card spawned carry `Card` with its owner, and
+/// so do the shapes they combine into, so the combine can be credited to that player.
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum SpawnSource {
+    #[default]
+    Board,
+    Card(PlayerId),
+}
+
+impl SpawnSource {
+    pub fn owner(&self) -> Option<PlayerId> {
+        match *self {
+            SpawnSource::Board => None,
+            SpawnSource::Card(owner) => Some(owner),
+        }
+    }
+}
+
+/// Sent for every `SpawnBody` that became a body, with the velocity it was given.
+#[derive(Event, Clone, Copy)]
+pub struct BodySpawned(pub SpawnBody);
+
+/// Two shapes combined by a rule, sent alongside the `SpawnBody` for the result.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct ShapesCombined {
+    pub inputs: [Shape; 2],
+    pub result: Shape,
+    pub position: Vec2,
+    /// 1 for a combine of two shapes that weren't part of a chain.
+    pub depth: u32,
+    /// The player whose card spawned one of the inputs, see `SpawnSource`.
+    pub credit: Option<PlayerId>,
+}
+
+/// A shape broken into `pieces` by a `Div` rule, sent alongside the `SpawnBody`s for them.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct ShapesSplit {
+    pub shape: Shape,
+    pub pieces: usize,
+    pub position: Vec2,
 }
 
 #[derive(Resource, Deref, DerefMut)]
@@ -54,25 +186,151 @@ pub struct Board;
 #[derive(Component, Clone, Copy)]
 pub struct AwaitNoCollision(usize);
 
-#[derive(Component)]
+/// Contact pairs (ordered by entity) that already triggered a rule, kept until the contact ends.
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct ProcessedContacts(HashSet<(Entity, Entity)>);
+
+/// Shapes a rule used up during the current physics step. Their despawn or blend only lands once
+/// the step's commands are applied, so until then anything else reading the step's contacts should
+/// skip them. Cleared at the start of every step.
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct CombinedShapes(HashSet<Entity>);
+
+#[derive(Component, Default, Reflect)]
+#[reflect(Component)]
 pub struct IsOnBoard;
 
+/// A shape held by the player instead of left to the physics. Board-wide effects leave it alone.
+#[derive(Component)]
+pub struct Dragged;
+
+/// Which rule a contact uses when more than one in the `Rule` matches it.
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum RuleResolution {
+    /// The newest matching rule.
+    #[default]
+    First,
+    /// The oldest matching rule.
+    Last,
+    /// The matching rule whose card has the highest `priority`, the newest of those on a tie.
+    HighestPriority,
+    /// Any matching rule, drawn from `GameRng` so seeded runs replay the same.
+    Random,
+}
+
+/// One rule that applies to a pair of shapes.
+#[derive(Clone, Debug)]
+pub struct RuleMatch {
+    /// Where the operation sits in the `Rule`, newest first.
+    pub rule_index: usize,
+    pub priority: i32,
+    pub operation: Operation,
+}
+
+/// The `Add`/`Sub` rules for each (ordered) pair of shapes, and `Mul`/`Div` rules for a shape
+/// meeting itself, in `Rule` order. Rebuilt whenever the `Rule` changes so contacts don't have to
+/// scan the rule list.
+#[derive(Resource, Default)]
+pub struct RuleLookup(HashMap<(Shape, Shape), Vec<RuleMatch>>);
+
+impl RuleLookup {
+    pub fn get(&self, s1: Shape, s2: Shape) -> &[RuleMatch] {
+        self.0.get(&(s1, s2)).map_or(&[], Vec::as_slice)
+    }
+
+    /// The rule a contact between `s1` and `s2` uses under `resolution`.
+    pub fn resolve(
+        &self,
+        s1: Shape,
+        s2: Shape,
+        resolution: RuleResolution,
+        rng: &mut impl Rng,
+    ) -> Option<&RuleMatch> {
+        let matches = self.get(s1, s2);
+        match resolution {
+            RuleResolution::First => matches.first(),
+            RuleResolution::Last => matches.last(),
+            RuleResolution::HighestPriority => matches
+                .iter()
+                .rev()
+                .max_by_key(|candidate| candidate.priority),
+            RuleResolution::Random => matches.choose(rng),
+        }
+    }
+}
+
+/// A contact was resolved by the operation at `rule_index` in the `Rule`.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct RuleApplied {
+    pub rule_index: usize,
+}
+
+/// Runs inside xpbd's `PhysicsSchedule`, right after each step reports its contacts, so every
+/// contact is handled exactly once no matter how many steps a frame takes. Sends `SpawnBody`
+/// events for the bodies that should replace the combined shapes.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CollisionSet;
+
+/// Turns `SpawnBody` events into bodies in `Update`. Combinations found during last frame's
+/// physics steps are spawned here, before this frame's steps run.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SpawnSet;
+
 pub struct BoardPlugin;
 impl Plugin for BoardPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<SpawnBody>()
+            .add_event::<BodySpawned>()
+            .add_event::<ShapesCombined>()
+            .add_event::<ShapesSplit>()
+            .add_event::<RuleApplied>()
+            .add_plugins((
+                SnapshotPlugin,
+                TransmutePlugin,
+                PopulationPlugin,
+                LayoutPlugin,
+                ObstaclePlugin,
+                SpawnerPlugin,
+                ComboPlugin,
+                ZonePlugin,
+                LauncherPlugin,
+                MergePlugin,
+                StatusPlugin,
+                GravityPlugin,
+                ReactionPlugin,
+                PrimePlugin,
+                PulsePlugin,
+            ))
+            .init_resource::<ProcessedContacts>()
+            .init_resource::<CombinedShapes>()
+            .init_resource::<RuleLookup>()
+            .init_resource::<RuleResolution>()
             .insert_resource(BoardTick(Timer::from_seconds(1.25, TimerMode::Repeating)))
             .add_systems(OnEnter(AppState::Playing), setup)
-            .add_systems(OnExit(AppState::Playing), despawn_shapes)
+            .add_systems(OnExit(AppState::Playing), reset_board)
+            .configure_sets(
+                PhysicsSchedule,
+                CollisionSet
+                    .after(PhysicsStepSet::ReportContacts)
+                    .before(PhysicsStepSet::Sleeping),
+            )
+            .add_systems(
+                PhysicsSchedule,
+                (
+                    (world_gravity, clear_combined_shapes).before(PhysicsStepSet::BroadPhase),
+                    (rebuild_rule_lookup, shape_collisions, clamp_vel)
+                        .chain()
+                        .in_set(CollisionSet),
+                )
+                    .run_if(in_state(AppState::Playing))
+                    .run_if(not(in_state(GameState::Scoring))),
+            )
             .add_systems(
                 Update,
                 (
-                    spawn_bodies,
-                    spawn_on_timer,
-                    shape_collisions.after(spawn_on_timer),
+                    spawn_bodies.in_set(SpawnSet).after(ReplaySet::SpawnBody),
+                    spawn_on_timer.before(SpawnSet),
                     handle_delay,
-                    clamp_vel,
-                    world_gravity,
                 )
                     .run_if(in_state(AppState::Playing))
                     .run_if(not(in_state(GameState::Scoring))),
@@ -80,96 +338,67 @@ impl Plugin for BoardPlugin {
     }
 }
 
-fn setup(mut cmd: Commands, textures: Res<TextureAssets>) {
+fn setup(
+    mut cmd: Commands,
+    selected: Res<SelectedLayout>,
+    handles: Res<LayoutAssets>,
+    layouts: Res<Assets<BoardLayout>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    // NOTE: the backdrop behind the board is drawn by the `BackgroundPlugin`
     cmd.spawn((
-        SpriteBundle {
-            texture: textures.bg.clone(),
-            transform: Transform::from_translation(config::CENTER.extend(-10.))
-                .with_scale(Vec3::splat(9999.)),
-            ..Default::default()
-        },
+        SpatialBundle::from_transform(Transform::from_translation(config::CENTER.extend(-10.))),
         Board,
+        GameplayEntity,
     ));
 
-    let side_length = config::SIZE.x;
-
-    // Wall positions (assuming the center of the square is at the origin)
-    let positions = [
-        Vec3::new(0.0, side_length / 2.0 + config::WALL_THICKNESS / 2.0, 0.0), // Top wall
-        Vec3::new(0.0, -side_length / 2.0 - config::WALL_THICKNESS / 2.0, 0.0), // Bottom wall
-        Vec3::new(side_length / 2.0 + config::WALL_THICKNESS / 2.0, 0.0, 0.0), // Right wall
-        Vec3::new(-side_length / 2.0 - config::WALL_THICKNESS / 2.0, 0.0, 0.0), // Left wall
-    ];
-
-    // Wall sizes
-    let sizes = [
-        Vec3::new(side_length, config::WALL_THICKNESS, 1.0), // Horizontal walls
-        Vec3::new(config::WALL_THICKNESS, side_length, 1.0), // Vertical walls
-    ];
-
-    for (i, &position) in positions.iter().enumerate() {
-        let size = if i < 2 { sizes[0] } else { sizes[1] };
-        let position = position + config::CENTER.extend(0.);
-
-        // cmd.spawn((
-        //     RigidBody::Static,
-        //     Collider::cuboid(size.x, size.y),
-        //     Restitution::PERFECTLY_ELASTIC,
-        //     SpriteBundle {
-        //         sprite: Sprite {
-        //             color: Color::WHITE,
-        //             custom_size: Some(Vec2::new(size.x, size.y)),
-        //             ..Default::default()
-        //         },
-        //         transform: Transform::from_translation(position),
-        //         ..Default::default()
-        //     },
-        //     CollisionLayers::new([Layer::Wall], [Layer::Wall]),
-        // ));
-    }
+    let active = find_layout(&selected, &handles, &layouts).unwrap_or_else(|| {
+        warn!("no board layouts loaded, playing on an open board");
+        ActiveLayout {
+            handle: Handle::default(),
+            layout: BoardLayout::default(),
+        }
+    });
+    build_arena(&mut cmd, &active, &mut meshes, &mut materials);
+    info!("playing on board layout {}", active.name);
+    cmd.insert_resource(active);
 }
 
 fn spawn_on_timer(
-    mut cmd: Commands,
     mut board_tick: ResMut<BoardTick>,
     t: Res<Time>,
     rules: Query<&Rule>,
     q_board_shapes: Query<(Entity, &Shape), With<IsOnBoard>>,
+    layout: Res<ActiveLayout>,
+    level: Res<CurrentLevel>,
+    status: Res<BoardStatus>,
+    reactions: Res<ElementReactions>,
+    mut rng: ResMut<GameRng>,
     mut e: EventWriter<SpawnBody>,
+    mut despawn: EventWriter<DespawnShape>,
+    mut missing_rule: Local<ThrottledWarn>,
 ) {
-    if board_tick.tick(t.delta()).finished() {
-        let mut rng_thread = rand::thread_rng();
+    if board_tick
+        .tick(t.delta().mul_f32(status.spawn_scale()))
+        .finished()
+    {
+        let rng = &mut rng.0;
 
         let Ok(rule_ops) = rules.get_single() else {
+            missing_rule.warn("spawn_on_timer: expected exactly one Rule entity, spawning nothing");
             return;
         };
 
         let spawn_event: Vec<SpawnBody> = rule_ops
             .iter()
             .filter(|op| match op {
-                Operation::Mul(_, _) => true,
                 // Operation::Sqr(_) => true,
-                Operation::Inc(_) => true,
+                Operation::Inc(shape) => level.allows(shape.polygon),
                 Operation::Dec(_) => true,
                 _ => false,
             })
-            .map(|op| match op {
-                Operation::Mul(shape, x) => (0..((*x as usize - 1)
-                    * q_board_shapes.iter().filter(|(_, s)| s == &shape).count()))
-                    .into_iter()
-                    .map(|_| SpawnBody {
-                        shape: shape.clone(),
-                        transform: Transform::from_translation(
-                            config::CENTER.extend(0.)
-                                + Vec3::new(
-                                    rng_thread.gen_range(-300..=300) as f32,
-                                    rng_thread.gen_range(-300..=300) as f32,
-                                    10.,
-                                ),
-                        ),
-                        velocity: None,
-                    })
-                    .collect(),
+            .flat_map(|op| match op {
                 // Operation::Sqr(shape) => std::iter::repeat(SpawnBody {
                 //     shape: shape.clone(),
                 //     transform: Transform::from_translation(
@@ -189,54 +418,29 @@ fn spawn_on_timer(
                 // })
                 // .collect(),
                 Operation::Inc(shape) => vec![SpawnBody {
-                    shape: shape.clone(),
-                    transform: Transform::from_translation(
-                        config::CENTER.extend(0.)
-                            + Vec3::new(
-                                rng_thread.gen_range(-300..=300) as f32,
-                                rng_thread.gen_range(-300..=300) as f32,
-                                10.,
-                            ),
-                    ),
+                    shape: *shape,
+                    transform: Transform::from_translation(layout.spawn_point(rng).extend(10.)),
                     velocity: None,
+                    combo: 0,
+                    element: reactions.roll(rng),
+                    source: SpawnSource::Board,
                 }],
                 Operation::Dec(shape) => {
                     if let Some((e, _)) = q_board_shapes
                         .iter()
                         .filter(|(_, s)| s == &shape)
-                        .choose(&mut rng_thread)
+                        .choose(rng)
                     {
-                        cmd.entity(e).despawn_recursive();
+                        despawn.send(DespawnShape { entity: e });
                     }
                     vec![]
                 },
                 _ => unreachable!(),
             })
-            .flatten()
             .collect();
 
         e.send_batch(spawn_event);
-        // for ev in spawn_event {
-        //     e.send(ev);
-        // }
-        // e.send_batch(spawn_event);
     }
-
-    // let mut rng_thread = rand::thread_rng();
-    // for i in (0..100) {
-    //     e.send(SpawnBody {
-    //         shape: Shape::random_shape(),
-    //         transform: Transform::from_translation(
-    //             config::CENTER.extend(0.)
-    //                 + Vec3::new(
-    //                     rng_thread.gen_range(-300..=300) as f32,
-    //                     rng_thread.gen_range(-300..=300) as f32,
-    //                     10.,
-    //                 ),
-    //         ),
-    //         velocity: None,
-    //     });
-    // }
 }
 
 fn spawn_bodies(
@@ -244,13 +448,18 @@ fn spawn_bodies(
     mut reader: EventReader<SpawnBody>,
     q_board: Query<(), With<IsOnBoard>>,
     poly_colliders: Res<PolygonColliders>,
+    shape_stats: Res<ShapeStats>,
     mesh: Res<ShapeAssets>,
-    color_mat: Res<ColorMaterialAssets>,
-    r_sound: Res<SoundAssets>,
+    color_mat: Res<ShapeMaterialAssets>,
+    status: Res<BoardStatus>,
+    mut rng: ResMut<GameRng>,
+    mut sound: EventWriter<PlaySound>,
+    mut spawned: EventWriter<BodySpawned>,
 ) {
-    let frame_num = q_board.iter().collect::<Vec<()>>().len();
+    #[cfg(feature = "trace")]
+    let _span = info_span!("spawn_bodies", bodies = reader.len()).entered();
 
-    let mut rng_thread = rand::thread_rng();
+    let frame_num = q_board.iter().collect::<Vec<()>>().len();
 
     for (i, event) in reader.read().enumerate() {
         if (i + frame_num) as u32 >= config::MAX_SHAPES {
@@ -258,51 +467,76 @@ fn spawn_bodies(
             return;
         };
 
-        if i <= 5 {
-            cmd.spawn(AudioBundle {
-                source: r_sound.spawn.clone_weak(),
-                settings: PlaybackSettings {
-                    mode: bevy::audio::PlaybackMode::Remove,
-                    ..default()
-                },
-            });
-        }
+        sound.send(PlaySound(Sfx::Spawn));
+
+        let velocity = event.velocity.unwrap_or(LinearVelocity(Vec2::new(
+            rng.gen_range(-config::MAX_SPEED..=config::MAX_SPEED),
+            rng.gen_range(-config::MAX_SPEED..=config::MAX_SPEED),
+        )));
+        spawned.send(BodySpawned(SpawnBody {
+            velocity: Some(velocity),
+            ..*event
+        }));
 
-        cmd.spawn((
+        let stats = shape_stats.get(event.shape.polygon);
+        let mut body = cmd.spawn((
             event.shape.get_bundle(&mesh, &color_mat),
             poly_colliders.get(&event.shape.polygon).unwrap().clone(),
-            event.shape.clone(),
+            event.shape,
             RigidBody::Dynamic,
-            event.velocity.unwrap_or(LinearVelocity(Vec2::new(
-                rng_thread.gen_range(-config::MAX_SPEED..=config::MAX_SPEED),
-                rng_thread.gen_range(-config::MAX_SPEED..=config::MAX_SPEED),
-            ))),
-            Restitution::PERFECTLY_ELASTIC,
+            velocity,
+            stats.physics(),
             IsOnBoard,
-            CollisionLayers::new([Layer::Shape], [Layer::Wall]),
+            Layer::spawning_shape(),
             AwaitNoCollision(300),
             ExternalForce::ZERO,
-        ))
-        .insert(event.transform.with_scale(Vec3::splat(config::SHAPE_SCALE)));
+            LinearDamping(status.damping()),
+            GameplayEntity,
+        ));
+        let scale = event.transform.scale * config::SHAPE_SCALE;
+        body.insert(event.transform.with_scale(scale));
+        if let Some(seconds) = stats.lifetime {
+            body.insert(Lifetime::new(seconds));
+        }
+        if let Some(element) = event.element {
+            body.insert(element);
+        }
+        if event.source != SpawnSource::Board {
+            body.insert(event.source);
+        }
+        if event.combo > 0 {
+            body.insert(ComboDepth::new(event.combo));
+            merge_in(&mut body, scale, velocity.0);
+        }
     }
 }
 
-fn despawn_shapes(mut c: Commands, q_board_shapes: Query<(Entity, &Shape), With<IsOnBoard>>) {
-    for (e, _) in q_board_shapes.iter() {
-        c.entity(e).despawn_recursive();
-    }
+fn reset_board(
+    mut processed: ResMut<ProcessedContacts>,
+    mut lookup: ResMut<RuleLookup>,
+    mut board_tick: ResMut<BoardTick>,
+    mut spawn_events: ResMut<Events<SpawnBody>>,
+) {
+    processed.clear();
+    lookup.0.clear();
+    board_tick.reset();
+    spawn_events.clear();
 }
 
-fn world_gravity(mut q_central_force: Query<(&mut ExternalForce, &Transform), With<IsOnBoard>>) {
-    for (mut f, t) in q_central_force.iter_mut() {
-        let v_to_center = config::CENTER - t.translation.truncate();
-
-        let distance_to_center = t.translation.truncate().distance_squared(config::CENTER);
-
-        f.set_force(distance_to_center * v_to_center.normalize_or_zero());
+fn world_gravity(
+    status: Res<BoardStatus>,
+    gravity: Res<BoardGravity>,
+    mut q_central_force: Query<(&mut ExternalForce, &Position), With<IsOnBoard>>,
+) {
+    for (mut f, p) in q_central_force.iter_mut() {
+        f.set_force(gravity.pull(config::CENTER - p.0, &status));
     }
 }
 
+fn clear_combined_shapes(mut combined: ResMut<CombinedShapes>) {
+    combined.clear();
+}
+
 fn clamp_vel(mut q_vel: Query<&mut LinearVelocity, With<IsOnBoard>>) {
     for mut v in q_vel
         .iter_mut()
@@ -333,151 +567,275 @@ fn handle_delay(
 
         if intersected && awa.0 == 1 {
             c.entity(ent).remove::<AwaitNoCollision>();
-            c.entity(ent).insert(CollisionLayers::new(
-                [Layer::Shape],
-                [Layer::Shape, Layer::Wall],
-            ));
+            c.entity(ent).insert(Layer::shape());
         } else if intersected {
             // println!("overlapped");
         }
     }
 }
 
-fn shape_collisions(
-    mut cmd: Commands,
-    rules: Query<&Rule>,
-    q_shape: Query<(Entity, &Shape, &Transform, &LinearVelocity), With<IsOnBoard>>,
-    // mut collision_event_reader: EventReader<Collision>,
-    mut s_event: EventWriter<SpawnBody>,
+pub fn rebuild_rule_lookup(
+    q_rule: Query<&Rule, Changed<Rule>>,
+    definitions: Res<CardDefinitions>,
+    mut lookup: ResMut<RuleLookup>,
 ) {
-    let Ok(rule_ops) = rules.get_single() else {
+    let Ok(rule) = q_rule.get_single() else {
         return;
     };
 
-    let mut combined: Vec<&Entity> = Vec::new();
+    lookup.0.clear();
+    for (rule_index, op) in rule.iter().enumerate() {
+        let keys = match op {
+            // NOTE: `Add(s, s)` has the same key twice, and only counts once
+            Operation::Add(s1, s2) | Operation::Sub(s1, s2) if s1 == s2 => vec![(*s1, *s2)],
+            Operation::Add(s1, s2) | Operation::Sub(s1, s2) => vec![(*s1, *s2), (*s2, *s1)],
+            Operation::Mul(s, _) | Operation::Div(s) => vec![(*s, *s)],
+            _ => continue,
+        };
+        let candidate = RuleMatch {
+            rule_index,
+            priority: definitions.priority_of(op),
+            operation: op.clone(),
+        };
+        for key in keys {
+            lookup.0.entry(key).or_default().push(candidate.clone());
+        }
+    }
+}
+
+pub fn shape_collisions(
+    mut cmd: Commands,
+    lookup: Res<RuleLookup>,
+    resolution: Res<RuleResolution>,
+    mut rng: ResMut<GameRng>,
+    colors: Res<ColorInteraction>,
+    q_rule: Query<(), With<Rule>>,
+    q_shape: Query<
+        (
+            &Shape,
+            &Position,
+            &LinearVelocity,
+            &Transform,
+            Option<&ComboDepth>,
+            Option<&Element>,
+            Option<&SpawnSource>,
+        ),
+        With<IsOnBoard>,
+    >,
+    mut started_reader: EventReader<CollisionStarted>,
+    mut ended_reader: EventReader<CollisionEnded>,
+    mut processed: ResMut<ProcessedContacts>,
+    mut combined: ResMut<CombinedShapes>,
+    mut s_event: EventWriter<SpawnBody>,
+    mut combined_event: EventWriter<ShapesCombined>,
+    mut split_event: EventWriter<ShapesSplit>,
+    mut applied_event: EventWriter<RuleApplied>,
+    mut missing_rule: Local<ThrottledWarn>,
+) {
+    #[cfg(feature = "trace")]
+    let _span = info_span!("shape_collisions", contacts = started_reader.len()).entered();
+
+    for CollisionEnded(e1, e2) in ended_reader.read() {
+        processed.remove(&contact_pair(*e1, *e2));
+    }
 
-    let translations: Vec<(Entity, &Shape, Vec3, Vec2)> = q_shape
-        .iter()
-        .map(|(e, s, t, v)| (e, s, t.translation, v.0))
-        .collect();
+    if q_rule.is_empty() && !started_reader.is_empty() {
+        missing_rule.warn("shape_collisions: no Rule entity, contacts won't combine shapes");
+    }
 
-    for (ent, s, t, v) in translations.iter() {
-        if combined.contains(&&ent) {
+    // NOTE:
+    // xpbd keeps reporting `Collision` for as long as two bodies overlap, so only the
+    // contact-begin event is reacted to, and each pair is remembered until its contact ends.
+    for CollisionStarted(e1, e2) in started_reader.read() {
+        if !processed.insert(contact_pair(*e1, *e2)) {
+            continue;
+        }
+        if combined.contains(e1) || combined.contains(e2) {
+            continue;
+        }
+        // NOTE: `Transform` is only synced once all of this frame's steps ran, so use `Position`
+        let Ok([(s, p, v, t, c, el, src), (o_s, o_p, o_v, o_t, o_c, o_el, o_src)]) =
+            q_shape.get_many([*e1, *e2])
+        else {
             continue;
         };
+        let Some(RuleMatch {
+            rule_index,
+            operation: op,
+            ..
+        }) = lookup.resolve(*s, *o_s, *resolution, &mut rng.0)
+        else {
+            continue;
+        };
+        // NOTE: a combine's inputs blend into its result, the shapes a split leaves just vanish
+        let mut midpoint = None;
+        let sources = [
+            (
+                *s,
+                p.0,
+                v.0,
+                t.scale.x / config::SHAPE_SCALE,
+                el.copied(),
+                src.copied().unwrap_or_default(),
+            ),
+            (
+                *o_s,
+                o_p.0,
+                o_v.0,
+                o_t.scale.x / config::SHAPE_SCALE,
+                o_el.copied(),
+                o_src.copied().unwrap_or_default(),
+            ),
+        ];
+
+        match op {
+            Operation::Add(s1, s2) | Operation::Sub(s1, s2) => {
+                let polygon = match op {
+                    Operation::Add(..) => s1.polygon + s2.polygon,
+                    _ => s1.polygon - s2.polygon,
+                };
+                let shape = Shape {
+                    polygon,
+                    color: colors.fight(s1.color, s2.color),
+                };
+                let position = average(&[p.0, o_p.0]);
+                let depth = [c, o_c]
+                    .into_iter()
+                    .flatten()
+                    .map(|combo| combo.depth)
+                    .max()
+                    .unwrap_or(0)
+                    + 1;
+                // NOTE: like the element, the credit goes to the first input a card spawned
+                let credit = [src, o_src]
+                    .into_iter()
+                    .flatten()
+                    .find_map(SpawnSource::owner);
+                s_event.send(SpawnBody {
+                    shape,
+                    transform: Transform::from_translation(position.extend(0.)),
+                    velocity: Some(LinearVelocity(average(&[v.0, o_v.0]))),
+                    combo: depth,
+                    // NOTE: the result carries on the first element among its inputs
+                    element: el.or(o_el).copied(),
+                    source: credit.map_or(SpawnSource::Board, SpawnSource::Card),
+                });
+                combined_event.send(ShapesCombined {
+                    inputs: [*s, *o_s],
+                    result: shape,
+                    position,
+                    depth,
+                    credit,
+                });
+                midpoint = Some(position);
+            },
+            Operation::Mul(shape, n) => {
+                // NOTE: the copies share the original's area, and stop at `MIN_SHAPE_SCALE` so
+                // two small shapes touching don't keep multiplying into dust
+                let shrink = (*n as f32).sqrt().recip();
+                if *n < 2
+                    || sources
+                        .iter()
+                        .any(|&(.., scale, _, _)| scale * shrink < config::MIN_SHAPE_SCALE)
+                {
+                    continue;
+                }
+                for (_, position, velocity, scale, element, source) in sources {
+                    let copies = vec![*shape; *n as usize];
+                    s_event.send_batch(scatter(
+                        &copies,
+                        position,
+                        velocity,
+                        scale * shrink,
+                        element,
+                        source,
+                    ));
+                }
+            },
+            Operation::Div(shape) => {
+                let Some(pieces) = shape.polygon.divide(2) else {
+                    continue;
+                };
+                let pieces: Vec<Shape> = pieces
+                    .into_iter()
+                    .map(|polygon| Shape {
+                        polygon,
+                        color: shape.color,
+                    })
+                    .collect();
+                for (_, position, velocity, scale, element, source) in sources {
+                    s_event
+                        .send_batch(scatter(&pieces, position, velocity, scale, element, source));
+                    split_event.send(ShapesSplit {
+                        shape: *shape,
+                        pieces: pieces.len(),
+                        position,
+                    });
+                }
+            },
+            _ => unreachable!(),
+        }
+        applied_event.send(RuleApplied {
+            rule_index: *rule_index,
+        });
+        combined.extend([*e1, *e2]);
+
+        if let Some(midpoint) = midpoint {
+            for (entity, position, transform) in [(*e1, p, t), (*e2, o_p, o_t)] {
+                let from = Transform {
+                    translation: position.0.extend(transform.translation.z),
+                    ..*transform
+                };
+                merge_out(&mut cmd, entity, from, midpoint);
+            }
+        } else {
+            cmd.entity(*e1).despawn_recursive();
+            cmd.entity(*e2).despawn_recursive();
+        }
+    }
 
-        if let Some((o_ent, o_s, o_t, o_v)) = translations
-            .iter()
-            .filter(|(o_ent, _, o_t, _)| {
-                !combined.contains(&o_ent)
-                    && (ent != o_ent)
-                    && (t.distance_squared(*o_t)
-                        <= (2. * config::SHAPE_SCALE * POLYGON_RADIUS).powi(2))
-            })
-            .take(1)
-            .collect::<Vec<&(Entity, &Shape, Vec3, Vec2)>>()
-            .first()
-        {
-            let shapes_slc = [*s, *o_s];
+    // NOTE:
+    // a despawned body never reports its contact ending, so drop any pair that lost a shape
+    processed.retain(|(e1, e2)| {
+        !combined.contains(e1)
+            && !combined.contains(e2)
+            && q_shape.contains(*e1)
+            && q_shape.contains(*e2)
+    });
+}
 
-            if let Some(spawn_event) = rule_ops
-                .iter()
-                .filter(|op| match op {
-                    Operation::Add(s1, s2) => shapes_slc.contains(&s1) && shapes_slc.contains(&s2),
-                    Operation::Sub(s1, s2) => shapes_slc.contains(&s1) && shapes_slc.contains(&s2),
-                    _ => false,
-                })
-                .take(1)
-                .collect::<Vec<&Operation>>()
-                .first()
-                .map(|op| match op {
-                    Operation::Add(s1, s2) => SpawnBody {
-                        shape: Shape {
-                            polygon: s1.polygon + s2.polygon,
-                            color: s1.color.fight(s2.color),
-                        },
-                        transform: Transform::from_translation(average(&[*t, *o_t])),
-                        velocity: Some(LinearVelocity(average(&[*v, *o_v]))),
-                    },
-                    Operation::Sub(s1, s2) => SpawnBody {
-                        shape: Shape {
-                            polygon: s1.polygon - s2.polygon,
-                            color: s1.color.fight(s2.color),
-                        },
-                        transform: Transform::from_translation(average(&[*t, *o_t])),
-                        velocity: Some(LinearVelocity(average(&[*v, *o_v]))),
-                    },
-                    _ => unreachable!(),
-                })
-            {
-                // dbg!("combined", s, o_s, "into shape", spawn_event.shape);
-                s_event.send(spawn_event);
-                combined.append(&mut vec![ent, o_ent]);
-
-                cmd.entity(*ent).despawn_recursive();
-                cmd.entity(*o_ent).despawn_recursive();
-            }
+/// Spreads `shapes` evenly around `position`, each moving outwards so they don't start on top of
+/// each other. They all keep the `element` and `source` of the shape they came from.
+fn scatter(
+    shapes: &[Shape],
+    position: Vec2,
+    velocity: Vec2,
+    scale: f32,
+    element: Option<Element>,
+    source: SpawnSource,
+) -> impl Iterator<Item = SpawnBody> + '_ {
+    let count = shapes.len() as f32;
+    shapes.iter().enumerate().map(move |(i, &shape)| {
+        let dir = Vec2::from_angle(i as f32 / count * TAU);
+        let offset = dir * POLYGON_RADIUS * config::SHAPE_SCALE * scale;
+        SpawnBody {
+            shape,
+            transform: Transform::from_translation((position + offset).extend(0.))
+                .with_scale(Vec3::splat(scale)),
+            velocity: Some(LinearVelocity(velocity + dir * config::MAX_SPEED * 0.25)),
+            combo: 0,
+            element,
+            source,
         }
+    })
+}
+
+fn contact_pair(e1: Entity, e2: Entity) -> (Entity, Entity) {
+    if e1 <= e2 {
+        (e1, e2)
+    } else {
+        (e2, e1)
     }
-    //
-    // // dbg!(rule_ops.deref());
-    //
-    //
-    // for Collision(contacts) in collision_event_reader.read() {
-    //     // TODO:
-    //     // Combinations / interactions occur based on the 'Rules'
-    //     //
-    //     if combined.contains(&&contacts.entity1) || combined.contains(&&contacts.entity2) {
-    //         continue;
-    //     };
-    //
-    //     let polygons_slc = [c_s1.polygon, c_s2.polygon];
-    //
-    //     if let Some(spawn_event) = rule_ops
-    //         .iter()
-    //         .filter(|op| match op {
-    //             Operation::Add(s1, s2) => {
-    //                 polygons_slc.contains(&s1.polygon) && polygons_slc.contains(&s2.polygon)
-    //             },
-    //             Operation::Sub(s1, s2) => {
-    //                 polygons_slc.contains(&s1.polygon) && polygons_slc.contains(&s2.polygon)
-    //             },
-    //             _ => false,
-    //         })
-    //         .last()
-    //         .map(|op| match op {
-    //             Operation::Add(s1, s2) => SpawnBody {
-    //                 shape: Shape {
-    //                     polygon: s1.polygon + s2.polygon,
-    //                     color: s1.color.fight(s2.color),
-    //                 },
-    //                 transform: Transform::from_translation(average(&[
-    //                     transform1.translation,
-    //                     transform2.translation,
-    //                 ])),
-    //                 velocity: Some(LinearVelocity(average(&[*lin_v1.deref(), *lin_v2.deref()]))),
-    //             },
-    //             Operation::Sub(s1, s2) => SpawnBody {
-    //                 shape: Shape {
-    //                     polygon: s1.polygon - s2.polygon,
-    //                     color: s1.color.fight(s2.color),
-    //                 },
-    //                 transform: Transform::from_translation(average(&[
-    //                     transform1.translation,
-    //                     transform2.translation,
-    //                 ])),
-    //                 velocity: Some(LinearVelocity(average(&[*lin_v1.deref(), *lin_v2.deref()]))),
-    //             },
-    //             _ => unreachable!(),
-    //         })
-    //     {
-    //         println!("big collision");
-    //         s_event.send(spawn_event);
-    //         combined.append(&mut vec![&contacts.entity1, &contacts.entity2]);
-    //     }
-    // }
-
-    // dbg!(combined.deref());
 }
 
 // NOTE:
@@ -489,11 +847,3 @@ fn shape_collisions(
 // } else {
 //     println!("Image not loaded yet");
 // }
-
-pub fn get_deck_transform(board_size: Vec2, card_height: f32) -> Transform {
-    Transform::from_xyz(-board_size.x / 2., -(board_size.y + card_height) / 2., 20.)
-}
-
-pub fn get_discard_transform(board_size: Vec2, card_height: f32) -> Transform {
-    Transform::from_xyz(board_size.x / 2., -(board_size.y + card_height) / 2., 20.)
-}
@@ -0,0 +1,137 @@
+//! Obstacles inside the arena: pegs shapes bounce off, spinners that sweep them along and bumpers
+//! that kick them away. Where they go is part of the `BoardLayout`.
+
+use bevy::prelude::*;
+use bevy_xpbd_2d::{
+    prelude::{
+        AngularVelocity, Collider, CollisionStarted, LinearVelocity, Position, Restitution,
+        RigidBody,
+    },
+    PhysicsSchedule,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{clamp_vel, config, shape_collisions, CollisionSet, CombinedShapes, IsOnBoard, Layer};
+use crate::{AppState, GameplayEntity};
+
+const OBSTACLE_COLOR: Color = Color::rgb(0.85, 0.85, 0.9);
+const BUMPER_COLOR: Color = Color::rgb(1., 0.6, 0.2);
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub enum ObstacleKind {
+    /// A fixed round peg.
+    Peg { radius: f32 },
+    /// A paddle turning about its center, in radians per second.
+    Spinner { length: f32, width: f32, speed: f32 },
+    /// A fixed round bumper that sends shapes touching it away at `speed`, which is capped like
+    /// any shape speed by `config::MAX_SPEED`.
+    Bumper { radius: f32, speed: f32 },
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct ObstacleDef {
+    pub kind: ObstacleKind,
+    /// Relative to the center of the board.
+    pub position: Vec2,
+    /// Starting angle, in radians.
+    #[serde(default)]
+    pub rotation: f32,
+}
+
+#[derive(Component)]
+pub struct BoardObstacle;
+
+/// Sends shapes that touch it away from its center.
+#[derive(Component, Clone, Copy)]
+pub struct Bumper {
+    pub speed: f32,
+}
+
+pub struct ObstaclePlugin;
+impl Plugin for ObstaclePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PhysicsSchedule,
+            bump_shapes
+                .in_set(CollisionSet)
+                .after(shape_collisions)
+                .before(clamp_vel)
+                .run_if(in_state(AppState::Playing)),
+        );
+    }
+}
+
+pub fn spawn_obstacle(
+    cmd: &mut Commands,
+    obstacle: &ObstacleDef,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<ColorMaterial>,
+) {
+    let (mesh, collider, color) = match obstacle.kind {
+        ObstacleKind::Peg { radius } => (
+            shape::Circle::new(radius).into(),
+            Collider::ball(radius),
+            OBSTACLE_COLOR,
+        ),
+        ObstacleKind::Spinner { length, width, .. } => (
+            shape::Quad::new(Vec2::new(length, width)).into(),
+            Collider::cuboid(length, width),
+            OBSTACLE_COLOR,
+        ),
+        ObstacleKind::Bumper { radius, .. } => (
+            shape::Circle::new(radius).into(),
+            Collider::ball(radius),
+            BUMPER_COLOR,
+        ),
+    };
+    let mut entity = cmd.spawn((
+        ColorMesh2dBundle {
+            mesh: meshes.add(mesh).into(),
+            material: materials.add(ColorMaterial::from(color)),
+            transform: Transform::from_translation((config::CENTER + obstacle.position).extend(5.))
+                .with_rotation(Quat::from_rotation_z(obstacle.rotation)),
+            ..default()
+        },
+        collider,
+        Restitution::PERFECTLY_ELASTIC,
+        Layer::wall(),
+        BoardObstacle,
+        GameplayEntity,
+    ));
+    match obstacle.kind {
+        ObstacleKind::Peg { .. } => {
+            entity.insert(RigidBody::Static);
+        },
+        ObstacleKind::Spinner { speed, .. } => {
+            entity.insert((RigidBody::Kinematic, AngularVelocity(speed)));
+        },
+        ObstacleKind::Bumper { speed, .. } => {
+            entity.insert((RigidBody::Static, Bumper { speed }));
+        },
+    }
+}
+
+fn bump_shapes(
+    mut reader: EventReader<CollisionStarted>,
+    q_bumpers: Query<(&Bumper, &Position)>,
+    mut q_shapes: Query<(&mut LinearVelocity, &Position), With<IsOnBoard>>,
+    combined: Res<CombinedShapes>,
+) {
+    for CollisionStarted(e1, e2) in reader.read() {
+        let (bumper, shape) = if q_bumpers.contains(*e1) {
+            (*e1, *e2)
+        } else {
+            (*e2, *e1)
+        };
+        let Ok((bumper, bumper_pos)) = q_bumpers.get(bumper) else {
+            continue;
+        };
+        if combined.contains(&shape) {
+            continue;
+        }
+        let Ok((mut velocity, shape_pos)) = q_shapes.get_mut(shape) else {
+            continue;
+        };
+        velocity.0 = (shape_pos.0 - bumper_pos.0).normalize_or_zero() * bumper.speed;
+    }
+}
@@ -0,0 +1,226 @@
+//! Keeps the board from filling up for good. Shapes past the population cap, and shapes that
+//! drift too far from the board, shrink away and are despawned. So do shapes whose `Lifetime`
+//! runs out, after fading.
+
+use bevy::prelude::*;
+use bevy_tweening::{lens::TransformScaleLens, Animator, EaseFunction, Tween, TweenCompleted};
+use bevy_xpbd_2d::prelude::{Collider, RigidBody};
+
+use super::{config, layout::ActiveLayout, AwaitNoCollision, IsOnBoard, SpawnSet};
+use crate::{
+    cards::GameState,
+    game_shapes::{material::ShapeMaterial, AnimatedMaterial, MaterialPool, Shape},
+    platform::Duration,
+    tween_ids::DESPAWN_SHAPE,
+    AppState,
+};
+
+const SHRINK_DURATION: f32 = 0.25;
+/// The last seconds of a `Lifetime`, over which the shape fades out.
+const FADE_DURATION: f32 = 2.;
+
+/// Which shapes go first once the board is over its cap.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum DespawnPolicy {
+    #[default]
+    OldestFirst,
+    /// Fewest vertices first, oldest first among equals.
+    SmallestFirst,
+}
+
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct BoardPopulation {
+    /// Shapes on the board, updated every frame.
+    pub count: usize,
+    pub cap: usize,
+    pub policy: DespawnPolicy,
+}
+
+impl Default for BoardPopulation {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            cap: 1_000,
+            policy: DespawnPolicy::default(),
+        }
+    }
+}
+
+/// Takes a shape off the board and shrinks it away before despawning it.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct DespawnShape {
+    pub entity: Entity,
+}
+
+/// Time the shape has left on the board. Set from `ShapeStats` when it spawns.
+#[derive(Component, Clone, Debug)]
+pub struct Lifetime(pub Timer);
+
+impl Lifetime {
+    pub fn new(seconds: f32) -> Self {
+        Self(Timer::from_seconds(seconds, TimerMode::Once))
+    }
+}
+
+/// When the shape arrived on the board, in seconds since startup.
+#[derive(Component, Clone, Copy)]
+pub struct SpawnedAt(pub f32);
+
+pub struct PopulationPlugin;
+impl Plugin for PopulationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BoardPopulation>()
+            .add_event::<DespawnShape>()
+            .add_systems(
+                Update,
+                (
+                    (
+                        stamp_spawn_time,
+                        expire_shapes,
+                        cleanup_off_board,
+                        enforce_cap,
+                    )
+                        .chain()
+                        .after(SpawnSet)
+                        .run_if(not(in_state(GameState::Scoring))),
+                    shrink_despawned,
+                    despawn_shrunk,
+                )
+                    .chain()
+                    .run_if(in_state(AppState::Playing)),
+            );
+    }
+}
+
+fn stamp_spawn_time(
+    mut cmd: Commands,
+    time: Res<Time>,
+    q_new: Query<Entity, (With<Shape>, With<IsOnBoard>, Without<SpawnedAt>)>,
+) {
+    for entity in q_new.iter() {
+        cmd.entity(entity).insert(SpawnedAt(time.elapsed_seconds()));
+    }
+}
+
+fn expire_shapes(
+    mut cmd: Commands,
+    time: Res<Time>,
+    mut q_shapes: Query<
+        (
+            Entity,
+            &mut Lifetime,
+            &mut Handle<ShapeMaterial>,
+            Has<AnimatedMaterial>,
+        ),
+        With<IsOnBoard>,
+    >,
+    mut materials: ResMut<Assets<ShapeMaterial>>,
+    mut pool: ResMut<MaterialPool>,
+    mut writer: EventWriter<DespawnShape>,
+) {
+    for (entity, mut lifetime, mut material, animated) in q_shapes.iter_mut() {
+        if lifetime.0.tick(time.delta()).finished() {
+            writer.send(DespawnShape { entity });
+            continue;
+        }
+        let left = lifetime.0.remaining_secs();
+        if left > FADE_DURATION {
+            continue;
+        }
+        if !animated {
+            *material = pool.take(entity, &material, &mut materials);
+            cmd.entity(entity).insert(AnimatedMaterial);
+        }
+        if let Some(material) = materials.get_mut(material.id()) {
+            material.color.set_a(left / FADE_DURATION);
+        }
+    }
+}
+
+fn cleanup_off_board(
+    layout: Res<ActiveLayout>,
+    q_shapes: Query<(Entity, &Transform), (With<Shape>, With<IsOnBoard>)>,
+    mut writer: EventWriter<DespawnShape>,
+) {
+    for (entity, transform) in q_shapes.iter() {
+        if transform.translation.truncate().distance(config::CENTER) > layout.max_radius() {
+            writer.send(DespawnShape { entity });
+        }
+    }
+}
+
+fn enforce_cap(
+    mut population: ResMut<BoardPopulation>,
+    q_shapes: Query<(Entity, &Shape, Option<&SpawnedAt>), With<IsOnBoard>>,
+    mut writer: EventWriter<DespawnShape>,
+) {
+    population.count = q_shapes.iter().len();
+    if population.count <= population.cap {
+        return;
+    }
+    let excess = population.count - population.cap;
+
+    // NOTE: shapes stamped this frame don't have `SpawnedAt` yet and count as the newest
+    let age = |spawned: Option<&SpawnedAt>| spawned.map_or(f32::INFINITY, |s| s.0);
+    let mut shapes: Vec<_> = q_shapes.iter().collect();
+    match population.policy {
+        DespawnPolicy::OldestFirst => shapes.sort_by(|a, b| age(a.2).total_cmp(&age(b.2))),
+        DespawnPolicy::SmallestFirst => shapes.sort_by(|a, b| {
+            a.1.polygon
+                .vertices()
+                .cmp(&b.1.polygon.vertices())
+                .then(age(a.2).total_cmp(&age(b.2)))
+        }),
+    }
+    writer.send_batch(
+        shapes
+            .into_iter()
+            .take(excess)
+            .map(|(entity, ..)| DespawnShape { entity }),
+    );
+}
+
+/// Shapes leave the board straight away, so rules, goals and the population no longer see them,
+/// and only the shrink animation is left.
+fn shrink_despawned(
+    mut cmd: Commands,
+    mut reader: EventReader<DespawnShape>,
+    q_shapes: Query<&Transform, With<IsOnBoard>>,
+) {
+    for DespawnShape { entity } in reader.read() {
+        // NOTE: the same shape can be picked twice in a frame, e.g. off the board and over the cap
+        let Ok(transform) = q_shapes.get(*entity) else {
+            continue;
+        };
+        let shrink = Tween::new(
+            EaseFunction::QuadraticIn,
+            Duration::from_secs_f32(SHRINK_DURATION),
+            TransformScaleLens {
+                start: transform.scale,
+                end: Vec3::ZERO,
+            },
+        )
+        .with_completed_event(DESPAWN_SHAPE);
+
+        cmd.entity(*entity)
+            .remove::<(IsOnBoard, AwaitNoCollision, RigidBody, Collider)>()
+            .insert(Animator::new(shrink));
+    }
+}
+
+fn despawn_shrunk(
+    mut cmd: Commands,
+    mut reader: EventReader<TweenCompleted>,
+    q_shapes: Query<(), With<Shape>>,
+) {
+    for event in reader.read() {
+        // NOTE: other tweens complete with their own ids, only shapes are ours to despawn
+        if event.user_data != DESPAWN_SHAPE || !q_shapes.contains(event.entity) {
+            continue;
+        }
+        // NOTE: the run may have been torn down while the shape was shrinking
+        if let Some(entity) = cmd.get_entity(event.entity) {
+            entity.despawn_recursive();
+        }
+    }
+}
@@ -0,0 +1,63 @@
+//! The `Prime`, what a combine past the top of the polygon chain makes. It doesn't last, see
+//! `ShapeStats`, but while it's on the board it pushes the shapes around it away, harder the
+//! closer they are, breaking up the crowd it formed in.
+
+use bevy::prelude::*;
+use bevy_xpbd_2d::{
+    prelude::{ExternalForce, Position},
+    PhysicsSchedule, PhysicsStepSet,
+};
+
+use super::{world_gravity, IsOnBoard};
+use crate::{
+    cards::GameState,
+    game_shapes::{GamePolygon, Shape},
+    AppState,
+};
+
+/// How far from a `Prime` shapes are pushed.
+const PRIME_REACH: f32 = 250.;
+/// The push on a shape touching a `Prime`, fading to nothing at `PRIME_REACH`.
+const PRIME_PUSH: f32 = 150_000.;
+
+pub struct PrimePlugin;
+impl Plugin for PrimePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PhysicsSchedule,
+            repel_from_primes
+                .after(world_gravity)
+                .before(PhysicsStepSet::BroadPhase)
+                .run_if(in_state(AppState::Playing))
+                .run_if(not(in_state(GameState::Scoring))),
+        );
+    }
+}
+
+/// Adds each `Prime`'s push on top of the pull `world_gravity` set this step.
+fn repel_from_primes(
+    mut q_shapes: Query<(&Shape, &Position, &mut ExternalForce), With<IsOnBoard>>,
+) {
+    let primes: Vec<Vec2> = q_shapes
+        .iter()
+        .filter(|(shape, ..)| shape.polygon == GamePolygon::Prime)
+        .map(|(_, position, _)| position.0)
+        .collect();
+    if primes.is_empty() {
+        return;
+    }
+    for (shape, position, mut force) in q_shapes.iter_mut() {
+        if shape.polygon == GamePolygon::Prime {
+            continue;
+        }
+        let push: Vec2 = primes
+            .iter()
+            .map(|&prime| position.0 - prime)
+            .filter(|away| away.length_squared() < PRIME_REACH.powi(2))
+            .map(|away| (1. - away.length() / PRIME_REACH) * away.normalize_or_zero())
+            .sum();
+        if push != Vec2::ZERO {
+            force.apply_force(PRIME_PUSH * push);
+        }
+    }
+}
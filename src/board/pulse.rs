@@ -0,0 +1,175 @@
+//! Pulses the shapes that are about to matter: the ones close to a shape the current rule would
+//! combine them with, and the ones the card being dragged would hit if it were dropped here. A
+//! pulsing shape swaps to its color's pulsing `ShapeMaterial`, or has the pulse set on its own copy
+//! when it has one.
+
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use bevy_xpbd_2d::prelude::Position;
+
+use super::{IsOnBoard, RuleLookup};
+use crate::{
+    cards::{
+        effect::CardEffect,
+        hand::Hand,
+        zones::{ActivePlayer, ZoneKind, Zones},
+    },
+    game_shapes::{
+        config::{POLYGON_RADIUS, PULSE_STRENGTH},
+        get_color_material, get_pulsing_material,
+        material::ShapeMaterial,
+        AnimatedMaterial, Shape, ShapeMaterialAssets,
+    },
+    operation::Operation,
+    pointer::Pointer,
+    AppState,
+};
+
+/// How close, edge to edge, two shapes a rule would combine have to be to pulse.
+const NEAR_GAP: f32 = POLYGON_RADIUS;
+
+/// The shapes pulsing this frame.
+#[derive(Resource, Default)]
+pub struct PulseTargets(HashSet<Entity>);
+
+impl PulseTargets {
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.0.contains(&entity)
+    }
+}
+
+pub struct PulsePlugin;
+impl Plugin for PulsePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PulseTargets>()
+            .add_systems(
+                Update,
+                (find_pulse_targets, pulse_shapes)
+                    .chain()
+                    .run_if(in_state(AppState::Playing)),
+            )
+            .add_systems(OnExit(AppState::Playing), reset_pulse);
+    }
+}
+
+fn find_pulse_targets(
+    q_shapes: Query<(Entity, &Shape, &Position, &Transform), With<IsOnBoard>>,
+    lookup: Res<RuleLookup>,
+    q_hand: Query<&Hand>,
+    q_effects: Query<&CardEffect>,
+    zones: Res<Zones>,
+    active: Res<ActivePlayer>,
+    pointer: Res<Pointer>,
+    mut targets: ResMut<PulseTargets>,
+) {
+    targets.0.clear();
+
+    // NOTE: bucket the shapes so each only looks at its neighbours, boards can get crowded
+    let reach = 2. * POLYGON_RADIUS + NEAR_GAP;
+    let cell = |p: Vec2| (p / reach).floor().as_ivec2();
+    let mut grid: HashMap<IVec2, Vec<(Entity, Shape, Vec2, f32)>> = HashMap::new();
+    for (entity, shape, position, transform) in q_shapes.iter() {
+        grid.entry(cell(position.0)).or_default().push((
+            entity,
+            *shape,
+            position.0,
+            POLYGON_RADIUS * transform.scale.x,
+        ));
+    }
+    for (&key, shapes) in grid.iter() {
+        for &(entity, shape, position, radius) in shapes {
+            let near = (-1..=1)
+                .flat_map(|x| (-1..=1).map(move |y| key + IVec2::new(x, y)))
+                .filter_map(|key| grid.get(&key))
+                .flatten()
+                .any(|&(other, o_shape, o_position, o_radius)| {
+                    other != entity
+                        && position.distance(o_position) < radius + o_radius + NEAR_GAP
+                        && (!lookup.get(shape, o_shape).is_empty()
+                            || !lookup.get(o_shape, shape).is_empty())
+                });
+            if near {
+                targets.0.insert(entity);
+            }
+        }
+    }
+
+    let Some(effect) = zones
+        .get(active.0, ZoneKind::Hand)
+        .and_then(|hand| q_hand.get(hand).ok())
+        .and_then(|hand| hand.selected)
+        .and_then(|card| q_effects.get(card).ok())
+    else {
+        return;
+    };
+    let drop = pointer.over_board();
+    for (entity, shape, position, _) in q_shapes.iter() {
+        let hit = match effect {
+            CardEffect::Transmute(from, _) => shape.polygon == *from,
+            CardEffect::ClearRegion(radius) | CardEffect::Impulse { radius, .. } => {
+                drop.is_some_and(|drop| drop.distance(position.0) < *radius)
+            },
+            CardEffect::Rule(operation) => match operation {
+                Operation::Mul(s, _)
+                | Operation::Div(s)
+                | Operation::Inc(s)
+                | Operation::Dec(s) => shape == s,
+                Operation::Add(s1, s2) | Operation::Sub(s1, s2) => shape == s1 || shape == s2,
+                Operation::Transmute(from, _) => shape.polygon == *from,
+                Operation::None => false,
+            },
+            _ => false,
+        };
+        if hit {
+            targets.0.insert(entity);
+        }
+    }
+}
+
+/// Swaps shapes between their color's still and pulsing material as they start and stop pulsing.
+/// Shapes with a copy of their own keep it and have the pulse set on it instead.
+fn pulse_shapes(
+    mut q_shapes: Query<
+        (
+            Entity,
+            &Shape,
+            &mut Handle<ShapeMaterial>,
+            Has<AnimatedMaterial>,
+        ),
+        With<IsOnBoard>,
+    >,
+    targets: Res<PulseTargets>,
+    c_m: Res<ShapeMaterialAssets>,
+    mut materials: ResMut<Assets<ShapeMaterial>>,
+) {
+    for (entity, shape, mut material, animated) in q_shapes.iter_mut() {
+        let pulsing = targets.contains(entity);
+        if animated {
+            let pulse = if pulsing { PULSE_STRENGTH } else { 0. };
+            // NOTE: only touch the copy when it changes, a mutable borrow re-uploads it
+            if materials
+                .get(material.id())
+                .is_some_and(|m| m.pulse != pulse)
+            {
+                if let Some(m) = materials.get_mut(material.id()) {
+                    m.pulse = pulse;
+                }
+            }
+            continue;
+        }
+        let wanted = if pulsing {
+            get_pulsing_material(&shape.color, &c_m)
+        } else {
+            get_color_material(&shape.color, &c_m)
+        };
+        if material.id() != wanted.id() {
+            *material = wanted;
+        }
+    }
+}
+
+fn reset_pulse(mut targets: ResMut<PulseTargets>) {
+    targets.0.clear();
+}
@@ -0,0 +1,298 @@
+//! Element reactions on the board. When two shapes whose elements react touch, Steam takes both
+//! elements away and leaves a cloud that slows every shape drifting through it, and Burn sets a
+//! shape on fire, draining its color until it burns out. What reacts with what comes from the
+//! `ElementReactions` table, see `game_shapes::element`. Shapes with an element carry a small mark
+//! in its color.
+
+use bevy::{prelude::*, sprite::MaterialMesh2dBundle, utils::HashMap};
+use bevy_xpbd_2d::{
+    prelude::{CollisionStarted, LinearVelocity, Position},
+    PhysicsSchedule,
+};
+use strum::IntoEnumIterator;
+
+use super::{population::DespawnShape, shape_collisions, CollisionSet, CombinedShapes, IsOnBoard};
+use crate::{
+    cards::GameState,
+    game_shapes::{
+        config::POLYGON_RADIUS,
+        element::{Element, ElementReactions, Reaction},
+        material::ShapeMaterial,
+        AnimatedMaterial, MaterialPool, Shape,
+    },
+    particles::{ParticlePreset, SpawnParticles},
+    pause::PauseState,
+    AppState, GameplayEntity,
+};
+
+/// How much of its speed a shape in steam loses each second.
+const STEAM_DRAG: f32 = 1.5;
+const STEAM_COLOR: Color = Color::rgba(1., 1., 1., STEAM_ALPHA);
+const STEAM_ALPHA: f32 = 0.3;
+/// The color a burning shape drains to.
+const ASH: Color = Color::rgb(0.25, 0.22, 0.2);
+/// The element mark's radius, relative to the shape's.
+const MARK_SCALE: f32 = 0.3;
+
+/// Two touching shapes reacted. `target` is the one the reaction acts on: the one that burns, or
+/// either for Steam.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct ElementsReacted {
+    pub reaction: Reaction,
+    pub source: Entity,
+    pub target: Entity,
+    pub position: Vec2,
+}
+
+/// A cloud of steam, slowing the shapes within `radius` of it until `timer` runs out.
+#[derive(Component, Debug)]
+pub struct SteamCloud {
+    pub radius: f32,
+    pub timer: Timer,
+}
+
+/// The shape is on fire, and burns out when `timer` runs out.
+#[derive(Component, Debug)]
+pub struct Burning(pub Timer);
+
+/// The mark on a shape with an element.
+#[derive(Component)]
+pub struct ElementMark;
+
+#[derive(Resource, Default)]
+struct ReactionAssets {
+    circle: Handle<Mesh>,
+    marks: HashMap<Element, Handle<ColorMaterial>>,
+}
+
+pub struct ReactionPlugin;
+impl Plugin for ReactionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ElementsReacted>()
+            .init_resource::<ReactionAssets>()
+            .add_systems(Startup, setup_reaction_assets)
+            .add_systems(
+                PhysicsSchedule,
+                element_contacts
+                    .in_set(CollisionSet)
+                    .after(shape_collisions)
+                    .run_if(in_state(AppState::Playing)),
+            )
+            .add_systems(
+                Update,
+                (
+                    (mark_elements, unmark_elements).run_if(in_state(AppState::Playing)),
+                    (apply_reactions, drift_steam, burn_shapes)
+                        .chain()
+                        .run_if(in_state(PauseState::Running))
+                        .run_if(in_state(AppState::Playing))
+                        .run_if(not(in_state(GameState::Scoring))),
+                ),
+            )
+            .add_systems(OnExit(AppState::Playing), reset_reactions);
+    }
+}
+
+fn setup_reaction_assets(
+    mut assets: ResMut<ReactionAssets>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    assets.circle = meshes.add(shape::Circle::new(1.).into());
+    assets.marks = Element::iter()
+        .map(|element| (element, materials.add(ColorMaterial::from(element.color()))))
+        .collect();
+}
+
+fn element_contacts(
+    mut started: EventReader<CollisionStarted>,
+    q_shapes: Query<(&Element, &Position), (With<IsOnBoard>, Without<Burning>)>,
+    combined: Res<CombinedShapes>,
+    reactions: Res<ElementReactions>,
+    mut writer: EventWriter<ElementsReacted>,
+) {
+    for CollisionStarted(e1, e2) in started.read() {
+        // NOTE: a shape a rule just used up is already gone, as far as gameplay is concerned
+        if combined.contains(e1) || combined.contains(e2) {
+            continue;
+        }
+        let Ok([(el, p), (o_el, o_p)]) = q_shapes.get_many([*e1, *e2]) else {
+            continue;
+        };
+        let Some((reaction, swapped)) = reactions.react(*el, *o_el) else {
+            continue;
+        };
+        let (source, target) = if swapped { (*e2, *e1) } else { (*e1, *e2) };
+        writer.send(ElementsReacted {
+            reaction,
+            source,
+            target,
+            position: (p.0 + o_p.0) / 2.,
+        });
+    }
+}
+
+fn apply_reactions(
+    mut cmd: Commands,
+    mut reader: EventReader<ElementsReacted>,
+    q_shapes: Query<(), (With<Element>, With<IsOnBoard>)>,
+    assets: Res<ReactionAssets>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut particles: EventWriter<SpawnParticles>,
+) {
+    for event in reader.read() {
+        // NOTE: either shape may have reacted with a third one earlier in the frame
+        if !q_shapes.contains(event.source) || !q_shapes.contains(event.target) {
+            continue;
+        }
+        match event.reaction {
+            Reaction::Steam { radius, duration } => {
+                cmd.entity(event.source).remove::<Element>();
+                cmd.entity(event.target).remove::<Element>();
+                // NOTE: each cloud fades on its own, and its material goes with it
+                cmd.spawn((
+                    MaterialMesh2dBundle {
+                        mesh: assets.circle.clone().into(),
+                        material: materials.add(ColorMaterial::from(STEAM_COLOR)),
+                        transform: Transform::from_translation(event.position.extend(5.))
+                            .with_scale(Vec3::splat(radius)),
+                        ..default()
+                    },
+                    SteamCloud {
+                        radius,
+                        timer: Timer::from_seconds(duration, TimerMode::Once),
+                    },
+                    GameplayEntity,
+                ));
+                particles.send(SpawnParticles {
+                    preset: ParticlePreset::Despawn,
+                    position: event.position,
+                    color: Color::WHITE,
+                });
+            },
+            Reaction::Burn { duration } => {
+                cmd.entity(event.target)
+                    .remove::<Element>()
+                    .insert(Burning(Timer::from_seconds(duration, TimerMode::Once)));
+                particles.send(SpawnParticles {
+                    preset: ParticlePreset::Split,
+                    position: event.position,
+                    color: Element::Fire.color(),
+                });
+            },
+        }
+    }
+}
+
+/// Slows the shapes in each cloud, and fades the cloud out as it runs out.
+fn drift_steam(
+    mut cmd: Commands,
+    time: Res<Time>,
+    mut q_clouds: Query<(Entity, &mut SteamCloud, &Transform, &Handle<ColorMaterial>)>,
+    mut q_shapes: Query<(&Position, &mut LinearVelocity), With<IsOnBoard>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let drag = (1. - STEAM_DRAG * time.delta_seconds()).max(0.);
+    for (entity, mut cloud, transform, material) in q_clouds.iter_mut() {
+        if cloud.timer.tick(time.delta()).finished() {
+            cmd.entity(entity).despawn_recursive();
+            continue;
+        }
+        let center = transform.translation.truncate();
+        for (position, mut velocity) in q_shapes.iter_mut() {
+            if position.0.distance_squared(center) < cloud.radius.powi(2) {
+                velocity.0 *= drag;
+            }
+        }
+        if let Some(material) = materials.get_mut(material.id()) {
+            material
+                .color
+                .set_a(STEAM_ALPHA * cloud.timer.percent_left());
+        }
+    }
+}
+
+/// Drains a burning shape's color toward ash, and takes it off the board once it burns out.
+fn burn_shapes(
+    mut cmd: Commands,
+    time: Res<Time>,
+    mut q_shapes: Query<
+        (
+            Entity,
+            &Shape,
+            &mut Burning,
+            &mut Handle<ShapeMaterial>,
+            Has<AnimatedMaterial>,
+        ),
+        With<IsOnBoard>,
+    >,
+    mut materials: ResMut<Assets<ShapeMaterial>>,
+    mut pool: ResMut<MaterialPool>,
+    mut despawn: EventWriter<DespawnShape>,
+) {
+    for (entity, shape, mut burning, mut material, animated) in q_shapes.iter_mut() {
+        if burning.0.tick(time.delta()).finished() {
+            despawn.send(DespawnShape { entity });
+            continue;
+        }
+        if !animated {
+            *material = pool.take(entity, &material, &mut materials);
+            cmd.entity(entity).insert(AnimatedMaterial);
+        }
+        if let Some(material) = materials.get_mut(material.id()) {
+            let [r, g, b, _] = Into::<Color>::into(shape.color).as_rgba_f32();
+            let [ash_r, ash_g, ash_b, _] = ASH.as_rgba_f32();
+            let burnt = burning.0.percent();
+            material.color = Color::rgba(
+                r + (ash_r - r) * burnt,
+                g + (ash_g - g) * burnt,
+                b + (ash_b - b) * burnt,
+                material.color.a(),
+            );
+        }
+    }
+}
+
+fn mark_elements(
+    mut cmd: Commands,
+    q_added: Query<(Entity, &Element), Added<Element>>,
+    assets: Res<ReactionAssets>,
+) {
+    for (entity, element) in q_added.iter() {
+        let mark = cmd
+            .spawn((
+                MaterialMesh2dBundle {
+                    mesh: assets.circle.clone().into(),
+                    material: assets.marks[element].clone(),
+                    transform: Transform::from_xyz(0., 0., 0.5)
+                        .with_scale(Vec3::splat(POLYGON_RADIUS * MARK_SCALE)),
+                    ..default()
+                },
+                ElementMark,
+            ))
+            .id();
+        cmd.entity(entity).add_child(mark);
+    }
+}
+
+fn unmark_elements(
+    mut cmd: Commands,
+    mut removed: RemovedComponents<Element>,
+    q_children: Query<&Children>,
+    q_marks: Query<(), With<ElementMark>>,
+) {
+    for entity in removed.read() {
+        // NOTE: a despawned shape took its mark with it
+        let Ok(children) = q_children.get(entity) else {
+            continue;
+        };
+        for &child in children.iter().filter(|&&child| q_marks.contains(child)) {
+            cmd.entity(entity).remove_children(&[child]);
+            cmd.entity(child).despawn_recursive();
+        }
+    }
+}
+
+fn reset_reactions(mut events: ResMut<Events<ElementsReacted>>) {
+    events.clear();
+}
@@ -0,0 +1,157 @@
+//! Board snapshots for debugging: F9 (or whatever `GameAction::Snapshot` is bound to) saves every shape on the board as a `DynamicScene`, and
+//! `--load-scene <path>` replaces the board with a saved one. Only `Shape`, `Transform` and
+//! `LinearVelocity` are saved; `attach_bodies` rebuilds the mesh, material and physics body
+//! from the `Shape` when the scene is loaded.
+
+use bevy::{prelude::*, scene::serde::SceneDeserializer, utils::HashMap};
+use bevy_xpbd_2d::prelude::{ExternalForce, LinearVelocity, Restitution, RigidBody};
+use leafwing_input_manager::common_conditions::action_just_pressed;
+use serde::de::DeserializeSeed;
+
+use super::{IsOnBoard, Layer, SpawnSet};
+use crate::{
+    args::LaunchArgs,
+    game_shapes::{
+        GameColor, GamePolygon, PolygonColliders, Shape, ShapeAssets, ShapeMaterialAssets,
+    },
+    input::GameAction,
+    platform::{self, Storage},
+    AppState, GameplayEntity,
+};
+
+/// A scene from `--load-scene`, spawned onto the board once a run starts.
+#[derive(Resource)]
+pub struct PendingScene(DynamicScene);
+
+pub struct SnapshotPlugin;
+impl Plugin for SnapshotPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Shape>()
+            .register_type::<GamePolygon>()
+            .register_type::<GameColor>()
+            .register_type::<IsOnBoard>()
+            .register_type::<LinearVelocity>()
+            .add_systems(Startup, read_scene_arg)
+            .add_systems(
+                Update,
+                (
+                    save_snapshot.run_if(action_just_pressed(GameAction::Snapshot)),
+                    load_pending_scene.run_if(resource_exists::<PendingScene>()),
+                    attach_bodies.before(SpawnSet),
+                )
+                    .run_if(in_state(AppState::Playing)),
+            );
+    }
+}
+
+/// Serializes every shape on the board into a scene in RON.
+pub fn export_board(world: &mut World) -> Result<String, String> {
+    let shapes: Vec<Entity> = world
+        .query_filtered::<Entity, (With<Shape>, With<IsOnBoard>)>()
+        .iter(world)
+        .collect();
+    let scene = DynamicSceneBuilder::from_world(world)
+        .deny_all()
+        .allow::<Shape>()
+        .allow::<IsOnBoard>()
+        .allow::<Transform>()
+        .allow::<LinearVelocity>()
+        .extract_entities(shapes.into_iter())
+        .build();
+
+    let registry = world.resource::<AppTypeRegistry>();
+    scene.serialize_ron(registry).map_err(|e| e.to_string())
+}
+
+pub fn parse_scene(world: &World, text: &str) -> Result<DynamicScene, String> {
+    let registry = world.resource::<AppTypeRegistry>().read();
+    let mut deserializer = ron::Deserializer::from_str(text).map_err(|e| e.to_string())?;
+    SceneDeserializer {
+        type_registry: &registry,
+    }
+    .deserialize(&mut deserializer)
+    .map_err(|e| e.to_string())
+}
+
+/// Despawns every shape on the board and spawns the scene's shapes in their place. Returns how
+/// many shapes were spawned.
+pub fn import_board(world: &mut World, scene: &DynamicScene) -> Result<usize, String> {
+    let shapes: Vec<Entity> = world
+        .query_filtered::<Entity, With<IsOnBoard>>()
+        .iter(world)
+        .collect();
+    for entity in shapes {
+        despawn_with_children_recursive(world, entity);
+    }
+
+    let mut entity_map = HashMap::default();
+    scene
+        .write_to_world(world, &mut entity_map)
+        .map_err(|e| e.to_string())?;
+    Ok(entity_map.len())
+}
+
+fn save_snapshot(world: &mut World) {
+    let key = format!("board-{}.scn.ron", platform::unix_millis());
+    let saved = export_board(world).and_then(|ron| {
+        let storage = world.resource::<Storage>();
+        storage.save(&key, &ron).map_err(|e| e.to_string())?;
+        Ok(storage.location(&key))
+    });
+    match saved {
+        Ok(location) => info!("saved board snapshot to {location}"),
+        Err(err) => error!("couldn't save board snapshot: {err}"),
+    }
+}
+
+fn read_scene_arg(world: &mut World) {
+    let Some(path) = world
+        .get_resource::<LaunchArgs>()
+        .and_then(|args| args.load_scene.clone())
+    else {
+        return;
+    };
+    let scene = platform::read_file(&path).and_then(|text| parse_scene(world, &text));
+    match scene {
+        Ok(scene) => world.insert_resource(PendingScene(scene)),
+        Err(err) => error!("couldn't load scene {path}: {err}"),
+    }
+}
+
+fn load_pending_scene(world: &mut World) {
+    let Some(PendingScene(scene)) = world.remove_resource::<PendingScene>() else {
+        return;
+    };
+    match import_board(world, &scene) {
+        Ok(count) => info!("loaded {count} shapes from scene"),
+        Err(err) => error!("couldn't spawn scene: {err}"),
+    }
+}
+
+/// Gives shapes spawned from a scene everything `spawn_bodies` would have, keeping the saved
+/// transform. They collide with each other straight away, since a snapshot is usually taken
+/// mid pile-up.
+fn attach_bodies(
+    mut cmd: Commands,
+    q_loaded: Query<(Entity, &Shape, &Transform), (With<IsOnBoard>, Without<RigidBody>)>,
+    poly_colliders: Res<PolygonColliders>,
+    mesh: Res<ShapeAssets>,
+    color_mat: Res<ShapeMaterialAssets>,
+) {
+    for (entity, shape, transform) in q_loaded.iter() {
+        let Some(collider) = poly_colliders.get(&shape.polygon) else {
+            continue;
+        };
+        let mut bundle = shape.get_bundle(&mesh, &color_mat);
+        bundle.transform = *transform;
+        cmd.entity(entity).insert((
+            bundle,
+            collider.clone(),
+            RigidBody::Dynamic,
+            Restitution::PERFECTLY_ELASTIC,
+            Layer::shape(),
+            ExternalForce::ZERO,
+            GameplayEntity,
+        ));
+    }
+}
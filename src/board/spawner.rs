@@ -0,0 +1,173 @@
+//! Spawners drop shapes into an area of the board on their own, apart from the shapes rules
+//! spawn. How often they do follows a rate curve over the level's time, so a board can start
+//! calm and ramp up, and what they drop is picked from a weighted table. They're placed by the
+//! `BoardLayout`.
+
+use bevy::prelude::*;
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+
+use super::{config, status::BoardStatus, SpawnBody, SpawnSet, SpawnSource};
+use crate::{
+    cards::{GameState, GameTimer},
+    content::{ContentSelection, CustomContent},
+    daily::DailyChallenge,
+    game_shapes::{
+        element::{Element, ElementReactions},
+        GameColor, GamePolygon, Shape,
+    },
+    level::CurrentLevel,
+    rng::GameRng,
+    AppState, GameplayEntity,
+};
+
+/// Spawns per second at points in the level's time, in seconds. Linear in between, and flat
+/// before the first point and after the last.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(transparent)]
+pub struct RateCurve(pub Vec<(f32, f32)>);
+
+impl RateCurve {
+    pub fn sample(&self, time: f32) -> f32 {
+        let Some(&(first_time, first_rate)) = self.0.first() else {
+            return 0.;
+        };
+        if time <= first_time {
+            return first_rate;
+        }
+        for pair in self.0.windows(2) {
+            let ((t0, r0), (t1, r1)) = (pair[0], pair[1]);
+            if time <= t1 {
+                let ratio = if t1 > t0 { (time - t0) / (t1 - t0) } else { 1. };
+                return r0 + (r1 - r0) * ratio;
+            }
+        }
+        self.0.last().map_or(0., |&(_, rate)| rate)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct WeightedShape {
+    pub polygon: GamePolygon,
+    /// A random color when left out.
+    #[serde(default)]
+    pub color: Option<GameColor>,
+    /// Rolled from the `ElementReactions` when left out.
+    #[serde(default)]
+    pub element: Option<Element>,
+    pub weight: u32,
+}
+
+impl WeightedShape {
+    pub fn shape_with(&self, rng: &mut impl Rng) -> Shape {
+        Shape {
+            polygon: self.polygon,
+            color: self
+                .color
+                .unwrap_or_else(|| GameColor::random_color_with(rng)),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SpawnerDef {
+    /// Relative to the center of the board.
+    pub position: Vec2,
+    pub half_size: Vec2,
+    pub rate: RateCurve,
+    pub shapes: Vec<WeightedShape>,
+}
+
+#[derive(Component, Clone, Debug)]
+pub struct Spawner {
+    /// In world space.
+    pub position: Vec2,
+    pub half_size: Vec2,
+    pub rate: RateCurve,
+    pub shapes: Vec<WeightedShape>,
+    /// Spawns owed but not made yet, carried between frames.
+    pending: f32,
+}
+
+impl From<&SpawnerDef> for Spawner {
+    fn from(def: &SpawnerDef) -> Self {
+        Self {
+            position: config::CENTER + def.position,
+            half_size: def.half_size,
+            rate: def.rate.clone(),
+            shapes: def.shapes.clone(),
+            pending: 0.,
+        }
+    }
+}
+
+pub struct SpawnerPlugin;
+impl Plugin for SpawnerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            // NOTE: spawns draw from `GameRng` in a fixed order, so a seed places the same board
+            run_spawners
+                .before(SpawnSet)
+                .after(super::spawn_on_timer)
+                .run_if(in_state(AppState::Playing))
+                .run_if(not(in_state(GameState::Setup)))
+                .run_if(not(in_state(GameState::Start)))
+                .run_if(not(in_state(GameState::Scoring))),
+        );
+    }
+}
+
+pub fn spawn_spawner(cmd: &mut Commands, def: &SpawnerDef) {
+    cmd.spawn((Spawner::from(def), GameplayEntity));
+}
+
+/// Polygons the level doesn't allow are left out of the table. A custom `ShapeDistribution`
+/// picked in the menu stands in for every spawner's own table, outside the daily challenge.
+pub fn run_spawners(
+    time: Res<Time>,
+    game_timer: Res<GameTimer>,
+    level: Res<CurrentLevel>,
+    status: Res<BoardStatus>,
+    reactions: Res<ElementReactions>,
+    selection: Res<ContentSelection>,
+    content: Res<CustomContent>,
+    daily: Option<Res<DailyChallenge>>,
+    mut rng: ResMut<GameRng>,
+    mut q_spawners: Query<&mut Spawner>,
+    mut writer: EventWriter<SpawnBody>,
+) {
+    let rng = &mut rng.0;
+    let elapsed = game_timer.timer.elapsed_secs();
+    let custom = selection.shapes(&content).filter(|_| daily.is_none());
+
+    for mut spawner in q_spawners.iter_mut() {
+        spawner.pending +=
+            spawner.rate.sample(elapsed) * status.spawn_scale() * time.delta_seconds();
+        let allowed: Vec<_> = custom
+            .unwrap_or(&spawner.shapes)
+            .iter()
+            .filter(|entry| level.allows(entry.polygon))
+            .copied()
+            .collect();
+
+        while spawner.pending >= 1. {
+            spawner.pending -= 1.;
+            let Ok(entry) = allowed.choose_weighted(rng, |entry| entry.weight) else {
+                continue;
+            };
+            let offset = Vec2::new(
+                rng.gen_range(-spawner.half_size.x..=spawner.half_size.x),
+                rng.gen_range(-spawner.half_size.y..=spawner.half_size.y),
+            );
+            writer.send(SpawnBody {
+                shape: entry.shape_with(rng),
+                transform: Transform::from_translation((spawner.position + offset).extend(10.)),
+                velocity: None,
+                combo: 0,
+                element: entry.element.or_else(|| reactions.roll(rng)),
+                source: SpawnSource::Board,
+            });
+        }
+    }
+}
@@ -0,0 +1,231 @@
+//! Board statuses: modifiers on the whole board that wear off after a while. Low Gravity weakens
+//! the pull toward the center, Frenzy speeds up every spawn, and Frozen damps the shapes' speed.
+//! A card effect applies one with `ApplyStatus`, and a layout can schedule them over the run with
+//! its `statuses`. Applying a status that's already running keeps whichever would last longer.
+//! While Frozen runs, every shape on the board carries `Frozen`.
+
+use bevy::prelude::*;
+use bevy_xpbd_2d::prelude::LinearDamping;
+use serde::{Deserialize, Serialize};
+
+use super::{layout::ActiveLayout, IsOnBoard};
+use crate::{
+    cards::{GameState, GameTimer},
+    pause::PauseState,
+    AppState,
+};
+
+/// How much of the pull toward the center is left under Low Gravity.
+const LOW_GRAVITY_SCALE: f32 = 0.35;
+/// How much faster shapes spawn during a Frenzy.
+const FRENZY_SPAWN_SCALE: f32 = 2.;
+/// The `LinearDamping` shapes get while Frozen.
+const FROZEN_DAMPING: f32 = 2.5;
+
+/// On every shape on the board while the Frozen status runs. Board-wide effects leave frozen
+/// shapes as they are.
+#[derive(Component)]
+pub struct Frozen;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum StatusKind {
+    LowGravity,
+    Frenzy,
+    Frozen,
+}
+
+impl StatusKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            StatusKind::LowGravity => "Low Gravity",
+            StatusKind::Frenzy => "Frenzy",
+            StatusKind::Frozen => "Frozen",
+        }
+    }
+
+    /// What the status does, for tooltips.
+    pub fn describe(self) -> &'static str {
+        match self {
+            StatusKind::LowGravity => "shapes drift toward the middle far more slowly",
+            StatusKind::Frenzy => "shapes spawn twice as fast",
+            StatusKind::Frozen => "shapes slow to a crawl",
+        }
+    }
+
+    /// The status bar's icon color.
+    pub fn color(self) -> Color {
+        match self {
+            StatusKind::LowGravity => Color::rgb(0.55, 0.45, 0.95),
+            StatusKind::Frenzy => Color::rgb(0.95, 0.4, 0.2),
+            StatusKind::Frozen => Color::rgb(0.45, 0.85, 1.),
+        }
+    }
+}
+
+/// A status a layout applies `at` seconds into the run, for `duration` seconds.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct StatusDef {
+    pub kind: StatusKind,
+    pub at: f32,
+    pub duration: f32,
+}
+
+/// Starts `kind` for `duration` seconds.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct ApplyStatus {
+    pub kind: StatusKind,
+    pub duration: f32,
+}
+
+#[derive(Clone, Debug)]
+pub struct ActiveStatus {
+    pub kind: StatusKind,
+    pub timer: Timer,
+}
+
+/// The statuses running on the board, in the order they started.
+#[derive(Resource, Default, Debug)]
+pub struct BoardStatus {
+    active: Vec<ActiveStatus>,
+    /// How far into the run the layout's statuses have been applied.
+    scheduled_until: f32,
+}
+
+impl BoardStatus {
+    pub fn iter(&self) -> impl Iterator<Item = &ActiveStatus> {
+        self.active.iter()
+    }
+
+    pub fn has(&self, kind: StatusKind) -> bool {
+        self.active.iter().any(|status| status.kind == kind)
+    }
+
+    /// Scales the pull toward the center.
+    pub fn gravity_scale(&self) -> f32 {
+        if self.has(StatusKind::LowGravity) {
+            LOW_GRAVITY_SCALE
+        } else {
+            1.
+        }
+    }
+
+    /// Scales how fast timed spawns and spawners go.
+    pub fn spawn_scale(&self) -> f32 {
+        if self.has(StatusKind::Frenzy) {
+            FRENZY_SPAWN_SCALE
+        } else {
+            1.
+        }
+    }
+
+    pub fn damping(&self) -> f32 {
+        if self.has(StatusKind::Frozen) {
+            FROZEN_DAMPING
+        } else {
+            0.
+        }
+    }
+
+    fn apply(&mut self, kind: StatusKind, duration: f32) {
+        match self.active.iter_mut().find(|status| status.kind == kind) {
+            Some(status) if status.timer.remaining_secs() < duration => {
+                status.timer = Timer::from_seconds(duration, TimerMode::Once);
+            },
+            Some(_) => {},
+            None => self.active.push(ActiveStatus {
+                kind,
+                timer: Timer::from_seconds(duration, TimerMode::Once),
+            }),
+        }
+    }
+}
+
+pub struct StatusPlugin;
+impl Plugin for StatusPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ApplyStatus>()
+            .init_resource::<BoardStatus>()
+            .add_systems(
+                Update,
+                (
+                    schedule_statuses.run_if(resource_exists::<ActiveLayout>()),
+                    apply_statuses,
+                    tick_statuses,
+                    (damp_shapes, freeze_shapes),
+                )
+                    .chain()
+                    .run_if(in_state(PauseState::Running))
+                    .run_if(in_state(AppState::Playing))
+                    .run_if(not(in_state(GameState::Scoring))),
+            )
+            .add_systems(OnExit(AppState::Playing), reset_statuses);
+    }
+}
+
+/// Applies the layout's statuses as the run's clock passes their start.
+fn schedule_statuses(
+    layout: Res<ActiveLayout>,
+    game_timer: Res<GameTimer>,
+    mut status: ResMut<BoardStatus>,
+    mut writer: EventWriter<ApplyStatus>,
+) {
+    let elapsed = game_timer.timer.elapsed_secs();
+    if elapsed <= status.scheduled_until {
+        return;
+    }
+    let from = status.scheduled_until;
+    writer.send_batch(
+        layout
+            .statuses
+            .iter()
+            .filter(|def| def.at >= from && def.at < elapsed)
+            .map(|def| ApplyStatus {
+                kind: def.kind,
+                duration: def.duration,
+            }),
+    );
+    status.scheduled_until = elapsed;
+}
+
+fn apply_statuses(mut reader: EventReader<ApplyStatus>, mut status: ResMut<BoardStatus>) {
+    for event in reader.read() {
+        status.apply(event.kind, event.duration);
+    }
+}
+
+fn tick_statuses(time: Res<Time>, mut status: ResMut<BoardStatus>) {
+    for active in status.active.iter_mut() {
+        active.timer.tick(time.delta());
+    }
+    status.active.retain(|active| !active.timer.finished());
+}
+
+/// Gives every shape the current damping. Shapes spawn with it already, see `spawn_bodies`.
+fn damp_shapes(status: Res<BoardStatus>, mut q_shapes: Query<&mut LinearDamping, With<IsOnBoard>>) {
+    let damping = status.damping();
+    for mut shape in q_shapes.iter_mut() {
+        if shape.0 != damping {
+            shape.0 = damping;
+        }
+    }
+}
+
+fn freeze_shapes(
+    mut cmd: Commands,
+    status: Res<BoardStatus>,
+    q_shapes: Query<(Entity, Has<Frozen>), With<IsOnBoard>>,
+) {
+    let frozen = status.has(StatusKind::Frozen);
+    for (entity, was_frozen) in q_shapes.iter() {
+        if frozen && !was_frozen {
+            cmd.entity(entity).insert(Frozen);
+        } else if !frozen && was_frozen {
+            cmd.entity(entity).remove::<Frozen>();
+        }
+    }
+}
+
+fn reset_statuses(mut status: ResMut<BoardStatus>, mut events: ResMut<Events<ApplyStatus>>) {
+    *status = BoardStatus::default();
+    events.clear();
+}
@@ -0,0 +1,122 @@
+//! Board-wide effects: every shape of one polygon on the board turns into another at once, in a
+//! quick wave, instead of waiting for collisions.
+
+use bevy::{prelude::*, sprite::Mesh2dHandle};
+use bevy_tweening::{lens::TransformScaleLens, Animator, Delay, EaseFunction, Tween};
+use bevy_xpbd_2d::prelude::Collider;
+
+use super::{config, status::Frozen, AwaitNoCollision, Dragged, IsOnBoard, SpawnSet};
+use crate::{
+    cards::GameState,
+    game_shapes::{
+        get_polygon_mesh, stats::ShapeStats, GamePolygon, PolygonColliders, Shape, ShapeAssets,
+    },
+    platform::Duration,
+    AppState,
+};
+
+/// How long the morph wave takes to pass over every transformed shape.
+const WAVE_DURATION: f32 = 0.5;
+const MORPH_DURATION: f32 = 0.15;
+
+/// Turns every `from` shape on the board into a `to` shape, keeping its color.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct TransmuteShapes {
+    pub from: GamePolygon,
+    pub to: GamePolygon,
+}
+
+/// Sent once per `TransmuteShapes`, with how many shapes actually changed.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct BoardTransformed {
+    pub from: GamePolygon,
+    pub to: GamePolygon,
+    pub count: usize,
+}
+
+pub struct TransmutePlugin;
+impl Plugin for TransmutePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<TransmuteShapes>()
+            .add_event::<BoardTransformed>()
+            .add_systems(
+                Update,
+                transmute_shapes
+                    .before(SpawnSet)
+                    .run_if(in_state(AppState::Playing))
+                    .run_if(not(in_state(GameState::Scoring))),
+            );
+    }
+}
+
+/// Shapes that just spawned (including the results of a combine) are still phasing into the
+/// board, so they're left as they are, and so are `Frozen` and `Dragged` ones. Shapes blending
+/// into a combine are already off the board.
+fn transmute_shapes(
+    mut cmd: Commands,
+    mut reader: EventReader<TransmuteShapes>,
+    mut writer: EventWriter<BoardTransformed>,
+    mut q_shapes: Query<
+        (
+            Entity,
+            &mut Shape,
+            &mut Mesh2dHandle,
+            &mut Collider,
+            &Transform,
+        ),
+        (
+            With<IsOnBoard>,
+            Without<AwaitNoCollision>,
+            Without<Frozen>,
+            Without<Dragged>,
+        ),
+    >,
+    poly_colliders: Res<PolygonColliders>,
+    shape_stats: Res<ShapeStats>,
+    mesh: Res<ShapeAssets>,
+) {
+    for event in reader.read() {
+        let Some(collider) = poly_colliders.get(&event.to) else {
+            continue;
+        };
+        let mut matching: Vec<_> = q_shapes
+            .iter_mut()
+            .filter(|(_, shape, ..)| shape.polygon == event.from)
+            .collect();
+        let count = matching.len();
+
+        // NOTE: sort left to right so the wave sweeps across the board
+        matching.sort_by(|a, b| a.4.translation.x.total_cmp(&b.4.translation.x));
+        for (i, (entity, mut shape, mut mesh_handle, mut shape_collider, transform)) in
+            matching.into_iter().enumerate()
+        {
+            shape.polygon = event.to;
+            *mesh_handle = Mesh2dHandle(get_polygon_mesh(&event.to, &mesh));
+            // NOTE: xpbd recomputes the body's mass when its collider changes
+            *shape_collider = collider.clone();
+
+            let morph = Tween::new(
+                EaseFunction::BackOut,
+                Duration::from_secs_f32(MORPH_DURATION),
+                TransformScaleLens {
+                    start: transform.scale * 0.4,
+                    end: Vec3::splat(config::SHAPE_SCALE),
+                },
+            );
+            let delay = WAVE_DURATION * i as f32 / count as f32;
+            let animator = if delay > 0. {
+                Animator::new(Delay::new(Duration::from_secs_f32(delay)).then(morph))
+            } else {
+                Animator::new(morph)
+            };
+            cmd.entity(entity)
+                .insert((animator, shape_stats.get(event.to).physics()));
+        }
+
+        writer.send(BoardTransformed {
+            from: event.from,
+            to: event.to,
+            count,
+        });
+    }
+}
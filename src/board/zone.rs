@@ -0,0 +1,215 @@
+//! Areas of the board that do something to the shapes drifting into them: a goal that scores and
+//! takes them, a void that swallows them, a field that changes their color, or one that turns the
+//! board's gravity. They're sensors,
+//! so shapes pass through without bouncing, and are part of the `BoardLayout` like obstacles.
+//! Other gameplay can react to `ZoneEntered` and `ZoneExited` too.
+
+use bevy::prelude::*;
+use bevy_xpbd_2d::{
+    prelude::{Collider, CollisionEnded, CollisionStarted, RigidBody, Sensor},
+    PhysicsSchedule,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    config, gravity::ShiftGravity, population::DespawnShape, shape_collisions, CollisionSet,
+    CombinedShapes, IsOnBoard, Layer,
+};
+use crate::{
+    game_shapes::{
+        get_color_material, material::ShapeMaterial, palette::ColorOptions, AnimatedMaterial,
+        GameColor, Shape, ShapeMaterialAssets,
+    },
+    score::{Score, ScoreChanged},
+    AppState, GameplayEntity,
+};
+
+const ZONE_ALPHA: f32 = 0.15;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum BoardZoneKind {
+    /// Takes shapes off the board for `points` each.
+    Goal { points: u32 },
+    /// Takes shapes off the board for nothing.
+    Void,
+    /// Turns shapes `color`.
+    Recolor { color: GameColor },
+    /// Turns gravity by `angle` radians for `duration` seconds whenever a shape drifts in. See
+    /// `gravity`.
+    Gravity { angle: f32, duration: f32 },
+}
+
+impl BoardZoneKind {
+    fn color(self, colors: ColorOptions) -> Color {
+        match self {
+            BoardZoneKind::Goal { .. } => Color::GOLD,
+            BoardZoneKind::Void => Color::BLACK,
+            BoardZoneKind::Recolor { color } => colors.color(color),
+            BoardZoneKind::Gravity { .. } => Color::PURPLE,
+        }
+        .with_a(ZONE_ALPHA)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct BoardZoneDef {
+    pub kind: BoardZoneKind,
+    /// Relative to the center of the board.
+    pub position: Vec2,
+    pub half_size: Vec2,
+}
+
+#[derive(Component, Clone, Copy, Debug)]
+pub struct BoardZone(pub BoardZoneKind);
+
+/// A shape on the board started overlapping a zone.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct ZoneEntered {
+    pub zone: Entity,
+    pub kind: BoardZoneKind,
+    pub shape: Entity,
+}
+
+/// A shape stopped overlapping a zone, including by being taken off the board.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct ZoneExited {
+    pub zone: Entity,
+    pub kind: BoardZoneKind,
+    pub shape: Entity,
+}
+
+pub struct ZonePlugin;
+impl Plugin for ZonePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ZoneEntered>()
+            .add_event::<ZoneExited>()
+            .add_systems(
+                PhysicsSchedule,
+                zone_contacts
+                    .in_set(CollisionSet)
+                    .after(shape_collisions)
+                    .run_if(in_state(AppState::Playing)),
+            )
+            .add_systems(Update, apply_zones.run_if(in_state(AppState::Playing)))
+            .add_systems(PostUpdate, tint_zones);
+    }
+}
+
+/// Colors new zones, and every zone when the `ColorOptions` change. Runs in `PostUpdate`, so a
+/// zone spawned this frame is colored before it's drawn.
+fn tint_zones(colors: Res<ColorOptions>, mut q_zones: Query<(Ref<BoardZone>, &mut Sprite)>) {
+    for (zone, mut sprite) in q_zones.iter_mut() {
+        if colors.is_changed() || zone.is_added() {
+            sprite.color = zone.0.color(*colors);
+        }
+    }
+}
+
+pub fn spawn_zone(cmd: &mut Commands, zone: &BoardZoneDef) {
+    let size = 2. * zone.half_size;
+    cmd.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                custom_size: Some(size),
+                ..default()
+            },
+            transform: Transform::from_translation((config::CENTER + zone.position).extend(1.)),
+            ..default()
+        },
+        RigidBody::Static,
+        Collider::cuboid(size.x, size.y),
+        Sensor,
+        Layer::sensor(),
+        BoardZone(zone.kind),
+        GameplayEntity,
+    ));
+}
+
+fn zone_contacts(
+    mut started: EventReader<CollisionStarted>,
+    mut ended: EventReader<CollisionEnded>,
+    q_zones: Query<&BoardZone>,
+    q_on_board: Query<(), (With<Shape>, With<IsOnBoard>)>,
+    q_shapes: Query<(), With<Shape>>,
+    combined: Res<CombinedShapes>,
+    mut entered: EventWriter<ZoneEntered>,
+    mut exited: EventWriter<ZoneExited>,
+) {
+    let split = |e1: Entity, e2: Entity| {
+        if q_zones.contains(e1) {
+            (e1, e2)
+        } else {
+            (e2, e1)
+        }
+    };
+    for CollisionStarted(e1, e2) in started.read() {
+        let (zone, shape) = split(*e1, *e2);
+        // NOTE: a shape a rule just used up is already gone, as far as gameplay is concerned
+        if combined.contains(&shape) {
+            continue;
+        }
+        if let (Ok(BoardZone(kind)), true) = (q_zones.get(zone), q_on_board.contains(shape)) {
+            entered.send(ZoneEntered {
+                zone,
+                kind: *kind,
+                shape,
+            });
+        }
+    }
+    for CollisionEnded(e1, e2) in ended.read() {
+        let (zone, shape) = split(*e1, *e2);
+        if let (Ok(BoardZone(kind)), true) = (q_zones.get(zone), q_shapes.contains(shape)) {
+            exited.send(ZoneExited {
+                zone,
+                kind: *kind,
+                shape,
+            });
+        }
+    }
+}
+
+fn apply_zones(
+    mut reader: EventReader<ZoneEntered>,
+    mut q_shapes: Query<
+        (&mut Shape, &mut Handle<ShapeMaterial>),
+        (With<IsOnBoard>, Without<AnimatedMaterial>),
+    >,
+    color_mat: Res<ShapeMaterialAssets>,
+    mut score: ResMut<Score>,
+    mut score_changed: EventWriter<ScoreChanged>,
+    mut despawn: EventWriter<DespawnShape>,
+    mut shift: EventWriter<ShiftGravity>,
+) {
+    for event in reader.read() {
+        match event.kind {
+            BoardZoneKind::Goal { points } => {
+                score.combine_points += points;
+                score_changed.send(ScoreChanged {
+                    total: score.combine_points,
+                    delta: points,
+                });
+                despawn.send(DespawnShape {
+                    entity: event.shape,
+                });
+            },
+            BoardZoneKind::Void => {
+                despawn.send(DespawnShape {
+                    entity: event.shape,
+                });
+            },
+            BoardZoneKind::Recolor { color } => {
+                // NOTE: a shape mid tint or fade has its own material copy, and keeps its color
+                let Ok((mut shape, mut material)) = q_shapes.get_mut(event.shape) else {
+                    continue;
+                };
+                if shape.color != color {
+                    shape.color = color;
+                    *material = get_color_material(&color, &color_mat);
+                }
+            },
+            BoardZoneKind::Gravity { angle, duration } => {
+                shift.send(ShiftGravity { angle, duration });
+            },
+        }
+    }
+}
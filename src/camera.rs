@@ -1,28 +1,113 @@
+//! The board and card cameras. The board camera frames the board and the hand at any window size,
+//! and can be zoomed with the mouse wheel or a pinch and panned with the arrow keys or a right
+//! drag, within the framed area. `GameAction::FrameCamera` goes back to the framed view.
+
 use bevy::{
     core_pipeline::clear_color::ClearColorConfig,
+    input::mouse::{MouseMotion, MouseScrollUnit, MouseWheel},
     prelude::*,
     render::{camera::ScalingMode, view::RenderLayers},
     window::PrimaryWindow,
 };
-use bevy_pancam::{PanCam, PanCamPlugin};
+use leafwing_input_manager::{common_conditions::action_just_pressed, prelude::ActionState};
+
+use crate::{board, input::GameAction, ui::PileBrowserOpen, AppState};
 
-use crate::{board, AppState};
+/// The area the framed view shows: the board, with the deck, discard and hand below it. Panning
+/// keeps the camera's center inside it.
+pub const FRAME: Rect = Rect {
+    min: Vec2::new(-0.75 * board::config::SIZE.x, -0.75 * board::config::SIZE.y),
+    max: Vec2::new(0.75 * board::config::SIZE.x, 0.75 * board::config::SIZE.y),
+};
+/// Closest the camera zooms in, as a projection scale. 1 is the framed view, and the furthest out.
+const MIN_SCALE: f32 = 0.25;
+/// Fraction of the way to its target the camera would still have left after a second.
+const SMOOTHING: f32 = 0.0001;
+const PAN_SPEED: f32 = 800.;
+/// How much holding a zoom action zooms in a second.
+const ZOOM_SPEED: f32 = 2.;
 #[derive(Debug, Component)]
 pub struct BoardCamera;
 
 #[derive(Debug, Component)]
 pub struct CardCamera;
 
-#[derive(Component)]
-pub struct CameraFollow;
+/// Runs the board camera to where it's headed this frame. Anything nudging the camera for a frame,
+/// like screen shake, goes after it.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CameraSet;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum CameraMode {
+    /// The whole board and hand, whatever the window size.
+    #[default]
+    Framed,
+    /// Zoomed or panned by the player.
+    Free,
+}
+
+/// Where the board camera is headed, and where it is on the way there.
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct CameraRig {
+    pub mode: CameraMode,
+    pub target: Vec2,
+    pub target_scale: f32,
+    position: Vec2,
+    scale: f32,
+}
+
+impl Default for CameraRig {
+    fn default() -> Self {
+        Self {
+            mode: CameraMode::Framed,
+            target: FRAME.center(),
+            target_scale: 1.,
+            position: FRAME.center(),
+            scale: 1.,
+        }
+    }
+}
+
+impl CameraRig {
+    pub fn frame(&mut self) {
+        self.mode = CameraMode::Framed;
+        self.target = FRAME.center();
+        self.target_scale = 1.;
+    }
+
+    /// Zooms by `factor`, below 1 being in.
+    pub fn zoom(&mut self, factor: f32) {
+        self.mode = CameraMode::Free;
+        self.target_scale = (self.target_scale * factor).clamp(MIN_SCALE, 1.);
+    }
+
+    pub fn pan(&mut self, delta: Vec2) {
+        self.mode = CameraMode::Free;
+        self.target = (self.target + delta).clamp(FRAME.min, FRAME.max);
+    }
+}
 
 pub struct CameraPlugin;
 
 impl Plugin for CameraPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(AppState::Loading), setup);
-        app.add_systems(Update, free_cam_movement);
-        app.add_plugins(PanCamPlugin::default());
+        app.init_resource::<CameraRig>()
+            .add_systems(OnEnter(AppState::Loading), setup)
+            .add_systems(
+                Update,
+                (
+                    (
+                        frame_camera.run_if(action_just_pressed(GameAction::FrameCamera)),
+                        zoom_camera.run_if(not(resource_exists::<PileBrowserOpen>())),
+                        pan_camera,
+                    )
+                        .run_if(in_state(AppState::Playing)),
+                    move_camera,
+                )
+                    .chain()
+                    .in_set(CameraSet),
+            )
+            .add_systems(OnExit(AppState::Playing), frame_camera);
     }
 }
 
@@ -32,7 +117,6 @@ fn setup(mut cmd: Commands) {
             camera_2d: Camera2d {
                 // no "background color", we need to see the main camera's output
                 clear_color: ClearColorConfig::Custom(Color::BLACK),
-                ..default()
             },
             camera: Camera {
                 order: 0,
@@ -41,8 +125,8 @@ fn setup(mut cmd: Commands) {
             projection: OrthographicProjection {
                 near: -1000.,
                 scaling_mode: ScalingMode::AutoMin {
-                    min_width: 1.5 * board::config::SIZE.x,
-                    min_height: 1.5 * board::config::SIZE.y,
+                    min_width: FRAME.width(),
+                    min_height: FRAME.height(),
                 },
                 // scale: 2.,
                 ..Default::default()
@@ -57,7 +141,6 @@ fn setup(mut cmd: Commands) {
             camera_2d: Camera2d {
                 // no "background color", we need to see the main camera's output
                 clear_color: ClearColorConfig::None,
-                ..default()
             },
             camera: Camera {
                 order: 1,
@@ -67,8 +150,8 @@ fn setup(mut cmd: Commands) {
             projection: OrthographicProjection {
                 near: -1000.,
                 scaling_mode: ScalingMode::AutoMin {
-                    min_width: 1.5 * board::config::SIZE.x,
-                    min_height: 1.5 * board::config::SIZE.y,
+                    min_width: FRAME.width(),
+                    min_height: FRAME.height(),
                 },
                 // scale: 2.,
                 ..Default::default()
@@ -80,52 +163,114 @@ fn setup(mut cmd: Commands) {
     ));
 }
 
-pub fn lerp(x: f32, y: f32, by: f32) -> f32 {
-    x * (1. - by) + y * by
+fn frame_camera(mut rig: ResMut<CameraRig>) {
+    rig.frame();
 }
 
-// A simple camera system for moving and zooming the camera.
-#[allow(dead_code)]
-pub fn free_cam_movement(
-    time: Res<Time>,
-    keyboard_input: Res<Input<KeyCode>>,
-    mut query: Query<(&mut Transform, &mut OrthographicProjection), With<BoardCamera>>,
+/// The mouse wheel, the zoom actions, or a pinch when two fingers are down.
+fn zoom_camera(
+    time: Res<Time<Real>>,
+    mut wheel: EventReader<MouseWheel>,
+    actions: Res<ActionState<GameAction>>,
+    touches: Res<Touches>,
+    mut rig: ResMut<CameraRig>,
 ) {
-    for (mut transform, mut ortho) in query.iter_mut() {
-        let mut direction = Vec3::ZERO;
-
-        if keyboard_input.pressed(KeyCode::Left) {
-            direction -= Vec3::new(1.0, 0.0, 0.0);
-        }
-
-        if keyboard_input.pressed(KeyCode::Right) {
-            direction += Vec3::new(1.0, 0.0, 0.0);
-        }
-
-        if keyboard_input.pressed(KeyCode::Up) {
-            direction += Vec3::new(0.0, 1.0, 0.0);
-        }
-
-        if keyboard_input.pressed(KeyCode::Down) {
-            direction -= Vec3::new(0.0, 1.0, 0.0);
+    let mut factor = 1.;
+    if actions.pressed(GameAction::ZoomIn) {
+        factor /= ZOOM_SPEED.powf(time.delta_seconds());
+    }
+    if actions.pressed(GameAction::ZoomOut) {
+        factor *= ZOOM_SPEED.powf(time.delta_seconds());
+    }
+    for event in wheel.read() {
+        let steps = match event.unit {
+            MouseScrollUnit::Line => event.y,
+            MouseScrollUnit::Pixel => event.y / 50.,
+        };
+        factor *= 0.9_f32.powf(steps);
+    }
+    let fingers: Vec<_> = touches.iter().take(3).collect();
+    if let [a, b] = fingers[..] {
+        let before = a.previous_position().distance(b.previous_position());
+        let after = a.position().distance(b.position());
+        if before > 0. && after > 0. {
+            factor *= before / after;
         }
+    }
+    if factor != 1. {
+        rig.zoom(factor);
+    }
+}
 
-        if keyboard_input.pressed(KeyCode::Z) {
-            ortho.scale += 0.1;
+/// Arrow keys, or dragging with the right mouse button.
+fn pan_camera(
+    time: Res<Time<Real>>,
+    keys: Res<Input<KeyCode>>,
+    buttons: Res<Input<MouseButton>>,
+    mut motion: EventReader<MouseMotion>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    q_camera: Query<&OrthographicProjection, With<BoardCamera>>,
+    mut rig: ResMut<CameraRig>,
+) {
+    let mut direction = Vec2::ZERO;
+    for (key, dir) in [
+        (KeyCode::Left, Vec2::NEG_X),
+        (KeyCode::Right, Vec2::X),
+        (KeyCode::Up, Vec2::Y),
+        (KeyCode::Down, Vec2::NEG_Y),
+    ] {
+        if keys.pressed(key) {
+            direction += dir;
         }
+    }
+    let mut delta = direction * PAN_SPEED * rig.scale * time.delta_seconds();
 
-        if keyboard_input.pressed(KeyCode::X) {
-            ortho.scale -= 0.1;
+    let dragged: Vec2 = motion.read().map(|event| event.delta).sum();
+    if buttons.pressed(MouseButton::Right) && dragged != Vec2::ZERO {
+        if let (Ok(window), Ok(projection)) = (q_window.get_single(), q_camera.get_single()) {
+            // NOTE: screen y points down, world y up
+            let world_per_pixel = projection.area.width() / window.width();
+            delta += Vec2::new(-dragged.x, dragged.y) * world_per_pixel;
         }
+    }
+    if delta != Vec2::ZERO {
+        rig.pan(delta);
+    }
+}
 
-        if ortho.scale < 0.5 {
-            ortho.scale = 0.5;
+/// Eases the camera toward its target, on real time so pausing and hit-stops don't stall it.
+fn move_camera(
+    time: Res<Time<Real>>,
+    mut rig: ResMut<CameraRig>,
+    mut q_camera: Query<(&mut Transform, &mut OrthographicProjection), With<BoardCamera>>,
+) {
+    let by = 1. - SMOOTHING.powf(time.delta_seconds());
+    rig.position = rig.position.lerp(rig.target, by);
+    rig.scale = lerp(rig.scale, rig.target_scale, by);
+    for (mut transform, mut projection) in q_camera.iter_mut() {
+        let z = transform.translation.z;
+        transform.translation = rig.position.extend(z);
+        if projection.scale != rig.scale {
+            projection.scale = rig.scale;
         }
+    }
+}
 
-        let z = transform.translation.z;
-        transform.translation += time.delta_seconds() * direction * 500.;
-        // Important! We need to restore the Z values when moving the camera around.
-        // Bevy has a specific camera setup and this can mess with how our layers are shown.
-        transform.translation.z = z;
+/// What an unzoomed camera shows in a window of `window_size`: `FRAME`, widened or heightened to
+/// the window's aspect ratio.
+pub fn visible_area(window_size: Vec2) -> Rect {
+    if window_size.x <= 0. || window_size.y <= 0. {
+        return FRAME;
     }
+    let aspect = window_size.x / window_size.y;
+    let size = if aspect > FRAME.width() / FRAME.height() {
+        Vec2::new(FRAME.height() * aspect, FRAME.height())
+    } else {
+        Vec2::new(FRAME.width(), FRAME.width() / aspect)
+    };
+    Rect::from_center_size(FRAME.center(), size)
+}
+
+pub fn lerp(x: f32, y: f32, by: f32) -> f32 {
+    x * (1. - by) + y * by
 }
@@ -1,15 +1,23 @@
-use std::f32::consts::PI;
+use std::{f32::consts::PI, time::Duration};
 
-use bevy::{ecs::event::EventId, prelude::*, render::view::RenderLayers};
-use leafwing_input_manager::{prelude::InputManagerPlugin, Actionlike};
+use bevy::{prelude::*, render::view::RenderLayers, utils::HashMap};
+use bevy_tweening::{Animator, EaseFunction, Lens, Tween, TweenCompleted};
 
-use super::Actions;
+use super::{
+    definition::{CardDefinition, CardDefinitions, CardDefinitionsReloaded, CardId},
+    keyword::Keyword,
+    transfer::apply_zone_transfers,
+    CardSet,
+};
 use crate::{
-    game_shapes::{ColorMaterialAssets, Shape, ShapeAssets},
+    audio::{PlaySound, Sfx},
+    game_shapes::{ShapeAssets, ShapeMaterialAssets},
     goal::Goal,
-    loading::TextureAssets,
+    loading::{FontAssets, TextureAssets},
     operation::Operation,
-    AppState,
+    tween_ids::{FLIP_DONE, FLIP_MIDPOINT, RELEASE_TWEEN_DRIVER},
+    utils::ThrottledWarn,
+    AppState, GameplayEntity,
 };
 
 #[derive(Component)]
@@ -18,16 +26,39 @@ pub struct Card {
     pub back: Entity,
     pub face_up: bool,
     pub operation: Operation,
+    /// `None` for goal cards, which aren't defined by a `CardDefinition`.
+    pub definition: Option<CardId>,
+    /// `Energy` it takes to play.
+    pub cost: u32,
+    pub keywords: Vec<Keyword>,
+}
+
+impl Card {
+    pub fn has(&self, keyword: Keyword) -> bool {
+        self.keywords.contains(&keyword)
+    }
 }
 #[derive(Event)]
 pub struct FlipCard {
     pub card: Entity,
 }
+/// Sent halfway through a flip, as the card shows its other face.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct CardFlipped {
+    pub card: Entity,
+    pub face_up: bool,
+}
+/// What the front shows from the card's definition: the operation and the cost badge. Rebuilt when
+/// the definition is reloaded.
+#[derive(Component)]
+pub struct FaceDetail;
 #[derive(Event)]
 pub struct SpawnCard {
     pub zone_id: Entity,
-    pub operation: Operation,
+    pub card: CardId,
     pub face_up: bool,
+    /// Where among the zone's cards to put it; `None` puts it last.
+    pub index: Option<usize>,
 }
 #[derive(Event)]
 pub struct SpawnGoalCard {
@@ -39,39 +70,180 @@ pub struct SpawnGoalCard {
 #[derive(Bundle)]
 pub struct CardBundle {
     pub card: Card,
+    pub driver: TransformDriver,
     pub sprite: SpriteBundle,
 }
+
+/// The one system allowed to write a card's `Transform`. Whoever takes control of a card sets
+/// this, and hands it back to `Layout` when done.
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum TransformDriver {
+    #[default]
+    Layout,
+    Tween,
+    Flip,
+    Drag,
+    /// Flying between zones, see `CardTravel`.
+    Travel,
+}
+
+const COST_BADGE_COLOR: Color = Color::rgb(0.2, 0.3, 0.8);
+const COST_BADGE_SIZE: f32 = 30.;
+
+/// Cards in flight are drawn above every zone, the most recently sent on top.
+const TRAVEL_Z: f32 = 150.;
+
+/// How cards move when they aren't following their zone's layout.
+#[derive(Resource, Clone, Copy)]
+pub struct CardAnimationSettings {
+    /// A whole flip, both halves.
+    pub flip_duration: Duration,
+    /// Folding the card flat.
+    pub flip_ease_in: EaseFunction,
+    /// Unfolding it again, showing the other face.
+    pub flip_ease_out: EaseFunction,
+    /// A card's flight from one zone to another.
+    pub travel_duration: Duration,
+    /// How high a flight arcs, as a fraction of the distance covered.
+    pub travel_arc: f32,
+}
+
+impl Default for CardAnimationSettings {
+    fn default() -> Self {
+        Self {
+            flip_duration: Duration::from_millis(300),
+            flip_ease_in: EaseFunction::QuadraticIn,
+            flip_ease_out: EaseFunction::QuadraticOut,
+            travel_duration: Duration::from_millis(350),
+            travel_arc: 0.2,
+        }
+    }
+}
+
+/// Tweens only the card's height, leaving its width to the hover tweens.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct FlipScaleLens {
+    start: f32,
+    end: f32,
+}
+
+impl Lens<Transform> for FlipScaleLens {
+    fn lerp(&mut self, target: &mut Transform, ratio: f32) {
+        target.scale.y = self.start + (self.end - self.start) * ratio;
+    }
+}
+
+/// Which driver wrote each card's transform this frame, used to catch two systems fighting over
+/// the same card in debug builds.
+#[derive(Resource, Default)]
+pub struct TransformWrites(HashMap<Entity, TransformDriver>);
+
+impl TransformWrites {
+    pub fn claim(&mut self, entity: Entity, driver: TransformDriver) {
+        if cfg!(debug_assertions) {
+            if let Some(previous) = self.0.insert(entity, driver) {
+                debug_assert_eq!(
+                    previous, driver,
+                    "{entity:?} transform written by both {previous:?} and {driver:?} this frame"
+                );
+            }
+        }
+    }
+}
 #[derive(Component)]
 pub struct CardFace {
     pub is_front: bool,
 }
 
-#[derive(Component)]
+/// On a card until its flip is done. See `flip_card`.
+#[derive(Component, Default, Debug)]
 pub struct Flipping {
-    half: bool,
-    rotation_speed: f32,
-    current_rotation: f32,
+    /// Flips asked for while this one runs, started one after another as each is done.
+    pub queued: u32,
+}
+
+/// A card flying to `zone`, parentless and in world space until it lands. Only then does it join
+/// the zone's children, at `index`. Inserted by `apply_zone_transfers`.
+#[derive(Component, Debug)]
+pub struct CardTravel {
+    pub zone: Entity,
+    /// Where among the zone's cards it lands; `None` puts it last.
+    pub index: Option<usize>,
+    start: Transform,
+    /// The card's rotation within the zone it left, kept in the one it lands in.
+    local_rotation: Quat,
+    /// Turns made in flight, on top of the path from `start`. See `turn_flipped_cards`.
+    turn: Quat,
+    elapsed: Duration,
+}
+
+impl CardTravel {
+    pub fn new(zone: Entity, index: Option<usize>, start: Transform, local_rotation: Quat) -> Self {
+        Self {
+            zone,
+            index,
+            start,
+            local_rotation,
+            turn: Quat::IDENTITY,
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    /// Sends the card on to `zone` instead, flying from where it is now.
+    pub fn retarget(&mut self, zone: Entity, index: Option<usize>, from: Transform) {
+        self.zone = zone;
+        self.index = index;
+        // NOTE: `from` already has the flight's turns in it, which stay in `turn`
+        self.start = Transform {
+            rotation: self.turn.inverse() * from.rotation,
+            ..from
+        };
+        self.elapsed = Duration::ZERO;
+    }
 }
 
 pub struct CardPlugin;
 
 impl Plugin for CardPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            Update,
-            (flip_card, spawn_card, spawn_goal_card).run_if(in_state(AppState::Playing)),
-        )
-        .add_event::<SpawnGoalCard>()
-        .add_event::<FlipCard>()
-        .add_event::<SpawnCard>();
+        app.init_resource::<TransformWrites>()
+            .init_resource::<CardAnimationSettings>()
+            .add_systems(First, clear_transform_writes)
+            .add_systems(
+                Update,
+                (
+                    (spawn_card, spawn_goal_card).in_set(CardSet::Transfer),
+                    rebuild_reloaded_cards,
+                    flip_card.after(apply_zone_transfers),
+                    move_travelling_cards.after(flip_card),
+                    (release_tween_driver, turn_flipped_cards, play_flip_sound).chain(),
+                )
+                    .run_if(in_state(AppState::Playing)),
+            )
+            .add_event::<SpawnGoalCard>()
+            .add_event::<FlipCard>()
+            .add_event::<CardFlipped>()
+            .add_event::<SpawnCard>()
+            .add_systems(OnExit(AppState::Playing), reset_card_events);
     }
 }
+fn reset_card_events(
+    mut spawn_card: ResMut<Events<SpawnCard>>,
+    mut spawn_goal_card: ResMut<Events<SpawnGoalCard>>,
+    mut flip_card: ResMut<Events<FlipCard>>,
+    mut card_flipped: ResMut<Events<CardFlipped>>,
+) {
+    spawn_card.clear();
+    spawn_goal_card.clear();
+    flip_card.clear();
+    card_flipped.clear();
+}
 fn spawn_goal_card(
     mut cmd: Commands,
     mut reader: EventReader<SpawnGoalCard>,
     textures: Res<TextureAssets>,
     ma: Res<ShapeAssets>,
-    c_m: Res<ColorMaterialAssets>,
+    c_m: Res<ShapeMaterialAssets>,
 ) {
     for event in reader.read() {
         let operation_entity = event.goal.get_goal_entity(&mut cmd, &textures, &ma, &c_m);
@@ -119,10 +291,15 @@ fn spawn_goal_card(
                         front,
                         face_up: event.face_up,
                         operation: Operation::None,
+                        definition: None,
+                        cost: 0,
+                        keywords: Vec::new(),
                     },
+                    driver: TransformDriver::Layout,
                     sprite: SpriteBundle { ..default() },
                 },
                 RenderLayers::layer(1),
+                GameplayEntity,
             ))
             .id();
         cmd.entity(front).push_children(&operation_entity);
@@ -132,21 +309,28 @@ fn spawn_goal_card(
     }
 }
 
-fn spawn_card(
+pub fn spawn_card(
     mut cmd: Commands,
     mut reader: EventReader<SpawnCard>,
     textures: Res<TextureAssets>,
     ma: Res<ShapeAssets>,
-    c_m: Res<ColorMaterialAssets>,
+    c_m: Res<ShapeMaterialAssets>,
+    definitions: Res<CardDefinitions>,
+    fonts: Res<FontAssets>,
+    mut unknown_card: Local<ThrottledWarn>,
 ) {
     for event in reader.read() {
-        let operation_entity = event
-            .operation
-            .get_operation_entity(&mut cmd, &textures, &ma, &c_m);
+        let Some(definition) = definitions.get(&event.card) else {
+            unknown_card.warn(&format!("spawn_card: no card definition {:?}", event.card));
+            continue;
+        };
         let front = cmd
             .spawn((
                 SpriteBundle {
-                    texture: textures.card_blank.clone(),
+                    texture: definition
+                        .art
+                        .clone()
+                        .unwrap_or_else(|| textures.card_blank.clone()),
                     visibility: if event.face_up {
                         Visibility::Inherited
                     } else {
@@ -186,64 +370,350 @@ fn spawn_card(
                         back,
                         front,
                         face_up: event.face_up,
-                        operation: event.operation.clone(),
+                        operation: definition.operation.clone(),
+                        definition: Some(definition.id.clone()),
+                        cost: definition.cost,
+                        keywords: definition.keywords.clone(),
                     },
+                    driver: TransformDriver::Layout,
                     sprite: SpriteBundle { ..default() },
                 },
+                Name::new(definition.name.clone()),
+                definition.effect.clone(),
                 RenderLayers::layer(1),
+                GameplayEntity,
             ))
             .id();
-        cmd.entity(front).push_children(&operation_entity);
+        spawn_face_details(&mut cmd, front, definition, &textures, &ma, &c_m, &fonts);
 
         cmd.entity(card_id).push_children(&[front, back]);
-        cmd.entity(event.zone_id).push_children(&[card_id]);
+        match event.index {
+            Some(i) => cmd.entity(event.zone_id).insert_children(i, &[card_id]),
+            None => cmd.entity(event.zone_id).push_children(&[card_id]),
+        };
+    }
+}
+
+fn spawn_face_details(
+    cmd: &mut Commands,
+    front: Entity,
+    definition: &CardDefinition,
+    textures: &Res<TextureAssets>,
+    ma: &Res<ShapeAssets>,
+    c_m: &Res<ShapeMaterialAssets>,
+    fonts: &FontAssets,
+) {
+    let mut details = definition
+        .operation
+        .get_operation_entity(cmd, textures, ma, c_m);
+    details.push(spawn_cost_badge(cmd, definition.cost, fonts));
+    for &detail in details.iter() {
+        cmd.entity(detail).insert(FaceDetail);
+    }
+    cmd.entity(front).push_children(&details);
+}
+
+/// Brings the cards of reloaded definitions up to date where they are, face included.
+fn rebuild_reloaded_cards(
+    mut cmd: Commands,
+    mut reader: EventReader<CardDefinitionsReloaded>,
+    mut q_cards: Query<(Entity, &mut Card, &mut Name)>,
+    mut q_fronts: Query<(&mut Handle<Image>, Option<&Children>), With<CardFace>>,
+    q_details: Query<(), With<FaceDetail>>,
+    definitions: Res<CardDefinitions>,
+    textures: Res<TextureAssets>,
+    ma: Res<ShapeAssets>,
+    c_m: Res<ShapeMaterialAssets>,
+    fonts: Res<FontAssets>,
+) {
+    for CardDefinitionsReloaded(ids) in reader.read() {
+        for (entity, mut card, mut name) in q_cards.iter_mut() {
+            let Some(definition) = card
+                .definition
+                .as_ref()
+                .filter(|id| ids.contains(id))
+                .and_then(|id| definitions.get(id))
+            else {
+                continue;
+            };
+            card.operation = definition.operation.clone();
+            card.cost = definition.cost;
+            card.keywords = definition.keywords.clone();
+            *name = Name::new(definition.name.clone());
+            cmd.entity(entity).insert(definition.effect.clone());
+
+            let Ok((mut texture, children)) = q_fronts.get_mut(card.front) else {
+                continue;
+            };
+            *texture = definition
+                .art
+                .clone()
+                .unwrap_or_else(|| textures.card_blank.clone());
+            // NOTE: other children of the front, like the mulligan toggle, stay
+            for &child in children.into_iter().flatten() {
+                if q_details.contains(child) {
+                    cmd.entity(child).despawn_recursive();
+                }
+            }
+            spawn_face_details(
+                &mut cmd, card.front, definition, &textures, &ma, &c_m, &fonts,
+            );
+        }
     }
 }
 
-//TODO rotate in axis of rotation so the card flips not in y unless straight
+/// The card's cost in its top left corner.
+fn spawn_cost_badge(cmd: &mut Commands, cost: u32, fonts: &FontAssets) -> Entity {
+    cmd.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: COST_BADGE_COLOR,
+                custom_size: Some(Vec2::splat(COST_BADGE_SIZE)),
+                ..default()
+            },
+            transform: Transform::from_xyz(-48., 73., 1.),
+            ..default()
+        },
+        RenderLayers::layer(1),
+    ))
+    .with_children(|badge| {
+        badge.spawn((
+            Text2dBundle {
+                text: Text::from_section(
+                    cost.to_string(),
+                    TextStyle {
+                        font: fonts.fira.clone(),
+                        font_size: 24.,
+                        color: Color::WHITE,
+                    },
+                ),
+                transform: Transform::from_xyz(0., 0., 1.),
+                ..default()
+            },
+            RenderLayers::layer(1),
+        ));
+    })
+    .id()
+}
+
+fn clear_transform_writes(mut writes: ResMut<TransformWrites>) {
+    writes.0.clear();
+}
+
+fn release_tween_driver(
+    mut reader: EventReader<TweenCompleted>,
+    mut q_driver: Query<&mut TransformDriver>,
+) {
+    for event in reader.read() {
+        if event.user_data != RELEASE_TWEEN_DRIVER {
+            continue;
+        }
+        if let Ok(mut driver) = q_driver.get_mut(event.entity) {
+            if *driver == TransformDriver::Tween {
+                *driver = TransformDriver::Layout;
+            }
+        }
+    }
+}
+
+/// Folds the card flat along its height and unfolds it again, turning it over at the
+/// midpoint in `turn_flipped_cards`. A card that's already flipping queues the flip, so it still
+/// ends up facing the way it was last asked to.
 pub fn flip_card(
-    mut q_cards: Query<(Entity, &mut Card), Without<Flipping>>,
-    mut q_flipping: Query<(Entity, &mut Card, &mut Flipping, &mut Transform)>,
-    mut q_faces: Query<(&CardFace, &mut Visibility)>,
-    mut flip_event: EventReader<FlipCard>,
     mut cmd: Commands,
-    time: Res<Time>,
+    mut q_cards: Query<(
+        Entity,
+        &mut Card,
+        &mut TransformDriver,
+        &Transform,
+        Option<&mut Flipping>,
+    )>,
+    mut flip_event: EventReader<FlipCard>,
+    settings: Res<CardAnimationSettings>,
 ) {
+    #[cfg(feature = "trace")]
+    let _span = info_span!("flip_card", flips = flip_event.len()).entered();
+
+    // NOTE: `Flipping` is only inserted once the commands run, so flips started this frame are
+    // tracked here
+    let mut started: HashMap<Entity, Flipping> = HashMap::new();
     for e in flip_event.read() {
-        if let Ok((entity, mut card)) = q_cards.get_mut(e.card) {
-            card.face_up = !card.face_up;
-            cmd.entity(entity).insert(Flipping {
-                half: false,
-                rotation_speed: 400.0,
-                current_rotation: 0.0,
-            });
+        let Ok((entity, mut card, mut driver, transform, flipping)) = q_cards.get_mut(e.card)
+        else {
+            continue;
+        };
+        if let Some(mut flipping) = flipping {
+            flipping.queued += 1;
+            continue;
+        }
+        if let Some(flipping) = started.get_mut(&entity) {
+            flipping.queued += 1;
+            continue;
         }
+        card.face_up = !card.face_up;
+        // NOTE: a card in flight keeps flying, its flip turns it along the way
+        if *driver == TransformDriver::Layout {
+            *driver = TransformDriver::Flip;
+        }
+        cmd.entity(entity)
+            .insert(flip_animator(&settings, transform.scale.y));
+        started.insert(entity, Flipping::default());
+    }
+    for (entity, flipping) in started {
+        cmd.entity(entity).insert(flipping);
     }
-    for (entity, mut card, mut flipping, mut transform) in q_flipping.iter_mut() {
-        let rotation_angle = flipping.rotation_speed * time.delta_seconds();
-        flipping.current_rotation += rotation_angle;
-        if flipping.current_rotation > 90. && !flipping.half {
-            flipping.half = true;
+}
+
+fn flip_animator(settings: &CardAnimationSettings, height: f32) -> Animator<Transform> {
+    let half = settings.flip_duration / 2;
+    let fold = Tween::new(
+        settings.flip_ease_in,
+        half,
+        FlipScaleLens {
+            start: height,
+            end: 0.,
+        },
+    )
+    .with_completed_event(FLIP_MIDPOINT);
+    let unfold = Tween::new(
+        settings.flip_ease_out,
+        half,
+        FlipScaleLens {
+            start: 0.,
+            end: height,
+        },
+    )
+    .with_completed_event(FLIP_DONE);
+    Animator::new(fold.then(unfold))
+}
 
-            if let Ok((front, mut f_vis)) = q_faces.get_mut(card.front) {
-                match card.face_up {
-                    true => *f_vis = Visibility::Visible,
-                    false => *f_vis = Visibility::Hidden,
+/// Shows the card's other face once it's folded flat, and hands it back to `Layout` when the
+/// flip is done, or starts the next queued flip.
+fn turn_flipped_cards(
+    mut cmd: Commands,
+    mut reader: EventReader<TweenCompleted>,
+    settings: Res<CardAnimationSettings>,
+    mut q_flipping: Query<(
+        &mut Card,
+        &mut Flipping,
+        &mut Transform,
+        &mut TransformDriver,
+        Option<&mut CardTravel>,
+    )>,
+    mut q_faces: Query<&mut Visibility, With<CardFace>>,
+    mut writes: ResMut<TransformWrites>,
+    mut flipped: EventWriter<CardFlipped>,
+) {
+    for event in reader.read() {
+        let Ok((mut card, mut flipping, mut transform, mut driver, travel)) =
+            q_flipping.get_mut(event.entity)
+        else {
+            continue;
+        };
+        match event.user_data {
+            FLIP_MIDPOINT => {
+                // NOTE: zones lay cards out assuming a flipped card was turned over like this
+                let turn = Quat::from_rotation_y(PI);
+                match travel {
+                    Some(mut travel) => travel.turn = turn * travel.turn,
+                    None => {
+                        writes.claim(event.entity, TransformDriver::Flip);
+                        transform.rotate(turn);
+                    },
                 }
-            }
-            if let Ok((back, mut b_vis)) = q_faces.get_mut(card.back) {
-                match card.face_up {
-                    true => *b_vis = Visibility::Hidden,
-                    false => *b_vis = Visibility::Visible,
+                for (face, shown) in [(card.front, card.face_up), (card.back, !card.face_up)] {
+                    if let Ok(mut visibility) = q_faces.get_mut(face) {
+                        *visibility = if shown {
+                            Visibility::Visible
+                        } else {
+                            Visibility::Hidden
+                        };
+                    }
                 }
-            }
+                flipped.send(CardFlipped {
+                    card: event.entity,
+                    face_up: card.face_up,
+                });
+            },
+            FLIP_DONE => {
+                if flipping.queued > 0 {
+                    flipping.queued -= 1;
+                    card.face_up = !card.face_up;
+                    cmd.entity(event.entity)
+                        .insert(flip_animator(&settings, transform.scale.y));
+                    continue;
+                }
+                if *driver == TransformDriver::Flip {
+                    *driver = TransformDriver::Layout;
+                }
+                cmd.entity(event.entity).remove::<Flipping>();
+            },
+            _ => {},
         }
-        if flipping.current_rotation >= 180.0 {
-            flipping.current_rotation = 0.;
+    }
+}
+
+/// Flies each card in `CardTravel` along an arc to its zone, turning it to the zone's rotation on
+/// the way, and hands it to the zone once it lands.
+fn move_travelling_cards(
+    mut cmd: Commands,
+    time: Res<Time>,
+    settings: Res<CardAnimationSettings>,
+    mut q_cards: Query<
+        (
+            Entity,
+            &mut CardTravel,
+            &mut Transform,
+            &mut TransformDriver,
+            Has<Flipping>,
+        ),
+        With<Card>,
+    >,
+    q_zones: Query<&GlobalTransform, Without<Card>>,
+    mut writes: ResMut<TransformWrites>,
+) {
+    for (entity, mut travel, mut transform, mut driver, flipping) in q_cards.iter_mut() {
+        let Ok(zone_g) = q_zones.get(travel.zone) else {
+            continue;
+        };
+        travel.elapsed += time.delta();
+        let t = (travel.elapsed.as_secs_f32() / settings.travel_duration.as_secs_f32()).min(1.);
+        // NOTE: smoothstep, slow to leave and slow to land
+        let eased = t * t * (3. - 2. * t);
+        let (_, zone_rotation, zone_translation) = zone_g.to_scale_rotation_translation();
+        let start = travel.start.translation.truncate();
+        let end = zone_translation.truncate();
+        let arc = settings.travel_arc * start.distance(end) * 4. * eased * (1. - eased);
+        let rotation = travel
+            .start
+            .rotation
+            .slerp(zone_rotation * travel.local_rotation, eased);
 
-            cmd.entity(entity).remove::<Flipping>();
+        writes.claim(entity, TransformDriver::Travel);
+        transform.translation = (start.lerp(end, eased) + Vec2::Y * arc).extend(TRAVEL_Z + 1. - t);
+        transform.rotation = travel.turn * rotation;
+        if t < 1. {
+            continue;
         }
-        let rotation_quaternion = Quat::from_rotation_y(rotation_angle.to_radians());
-        transform.rotate(rotation_quaternion);
+
+        transform.translation.z = zone_translation.z;
+        *transform = GlobalTransform::from(*transform).reparented_to(zone_g);
+        *driver = if flipping {
+            TransformDriver::Flip
+        } else {
+            TransformDriver::Layout
+        };
+        match travel.index {
+            Some(i) => cmd.entity(travel.zone).insert_children(i, &[entity]),
+            None => cmd.entity(travel.zone).push_children(&[entity]),
+        };
+        cmd.entity(entity).remove::<CardTravel>();
+    }
+}
+
+fn play_flip_sound(mut reader: EventReader<CardFlipped>, mut sound: EventWriter<PlaySound>) {
+    // NOTE: a whole hand turning over at once is one sound
+    if reader.read().last().is_some() {
+        sound.send(PlaySound(Sfx::Flip));
     }
 }
@@ -0,0 +1,232 @@
+//! The cards the player owns, and the deck they build out of them between levels. The
+//! collection starts with every card from `assets/cards`, grows with each operation that joins
+//! the player's `Rule` and with the card picked after each cleared level, and is saved through
+//! `Storage` whenever it changes. A `Decklist` within the size limits replaces the random
+//! library at the start of a level.
+
+use bevy::prelude::*;
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    definition::{collect_definitions, CardDefinitions, CardId},
+    rules::RuleAdded,
+};
+use crate::{
+    operation::{generate_random_operations_with, Operation},
+    platform::Storage,
+    AppState,
+};
+
+const COLLECTION_KEY: &str = "collection.ron";
+
+pub const MIN_DECK_SIZE: usize = 20;
+pub const MAX_DECK_SIZE: usize = 40;
+/// Copies of one card a deck may hold.
+pub const MAX_COPIES: usize = 4;
+/// Cards offered to pick from after a cleared level.
+pub const REWARD_CHOICES: usize = 3;
+
+/// Every card the player can put in their deck, in the order they got them.
+#[derive(Resource, Default, Debug)]
+pub struct Collection(pub Vec<CardId>);
+
+impl Collection {
+    pub fn add(&mut self, id: CardId) {
+        if !self.0.contains(&id) {
+            self.0.push(id);
+        }
+    }
+}
+
+/// A collected card as saved. Generated definitions only exist while the game runs, so those
+/// are saved as their operation and registered again on load.
+#[derive(Serialize, Deserialize, Debug)]
+enum SavedCard {
+    Defined(CardId),
+    Generated(Operation),
+}
+
+/// `REWARD_CHOICES` cards the player doesn't have yet, drawn with `rng` and weighted by rarity.
+/// Made-up cards fill in when there aren't enough definitions left to offer.
+pub fn reward_offers(
+    definitions: &mut CardDefinitions,
+    collection: &Collection,
+    rng: &mut impl Rng,
+) -> Vec<CardId> {
+    let pool: Vec<_> = definitions
+        .iter()
+        .filter(|d| !collection.0.contains(&d.id))
+        .filter(|d| !(d.id.is_generated() && d.operation == Operation::None))
+        .map(|d| (d.id.clone(), d.rarity.reward_weight()))
+        .collect();
+    let mut offers: Vec<CardId> = pool
+        .choose_multiple_weighted(rng, REWARD_CHOICES, |(_, weight)| *weight)
+        .map(|chosen| chosen.map(|(id, _)| id.clone()).collect())
+        .unwrap_or_default();
+    // NOTE: a handful of tries is plenty, random operations rarely repeat
+    for operation in generate_random_operations_with(4 * REWARD_CHOICES, rng) {
+        if offers.len() >= REWARD_CHOICES {
+            break;
+        }
+        let id = definitions.id_for(&operation);
+        if !collection.0.contains(&id) && !offers.contains(&id) {
+            offers.push(id);
+        }
+    }
+    offers
+}
+
+/// The deck built on the `DeckBuilder` screen, one entry per copy. Empty until the player
+/// first builds one, and levels fall back to a random library.
+#[derive(Resource, Default, Debug)]
+pub struct Decklist(pub Vec<CardId>);
+
+impl Decklist {
+    pub fn count(&self, id: &CardId) -> usize {
+        self.0.iter().filter(|card| *card == id).count()
+    }
+
+    pub fn can_add(&self, id: &CardId) -> bool {
+        self.0.len() < MAX_DECK_SIZE && self.count(id) < MAX_COPIES
+    }
+
+    pub fn add(&mut self, id: &CardId) -> bool {
+        if !self.can_add(id) {
+            return false;
+        }
+        self.0.push(id.clone());
+        true
+    }
+
+    pub fn remove(&mut self, id: &CardId) -> bool {
+        let Some(i) = self.0.iter().rposition(|card| card == id) else {
+            return false;
+        };
+        self.0.remove(i);
+        true
+    }
+
+    /// Whether a level can start with this deck.
+    pub fn is_legal(&self) -> bool {
+        (MIN_DECK_SIZE..=MAX_DECK_SIZE).contains(&self.0.len())
+    }
+
+    /// Deals the collection out in turns, a copy of each card per round, until the deck is big
+    /// enough to play or out of cards to add.
+    pub fn fill_from(&mut self, collection: &Collection) {
+        while self.0.len() < MIN_DECK_SIZE {
+            let before = self.0.len();
+            for id in collection.0.iter() {
+                if self.0.len() < MIN_DECK_SIZE {
+                    self.add(id);
+                }
+            }
+            if self.0.len() == before {
+                break;
+            }
+        }
+    }
+}
+
+pub struct CollectionPlugin;
+impl Plugin for CollectionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Collection>()
+            .init_resource::<Decklist>()
+            .add_systems(
+                OnExit(AppState::Loading),
+                load_collection.after(collect_definitions),
+            )
+            .add_systems(
+                Update,
+                (
+                    collect_rules.run_if(in_state(AppState::Playing)),
+                    // NOTE: nothing was loaded after a failed load, and saving would wipe the save
+                    save_collection
+                        .run_if(not(in_state(AppState::Loading)))
+                        .run_if(not(in_state(AppState::LoadFailed))),
+                ),
+            );
+    }
+}
+
+fn load_collection(
+    mut collection: ResMut<Collection>,
+    mut definitions: ResMut<CardDefinitions>,
+    storage: Res<Storage>,
+) {
+    let mut ids: Vec<CardId> = definitions.iter().map(|d| d.id.clone()).collect();
+    ids.sort_by(|a, b| a.0.cmp(&b.0));
+    for id in ids {
+        collection.add(id);
+    }
+
+    let Some(text) = storage.load(COLLECTION_KEY) else {
+        return;
+    };
+    let saved: Vec<SavedCard> = match ron::from_str(&text) {
+        Ok(saved) => saved,
+        Err(err) => {
+            warn!(
+                "couldn't read {}, starting a new collection: {err}",
+                storage.location(COLLECTION_KEY)
+            );
+            return;
+        },
+    };
+    for card in saved {
+        match card {
+            // NOTE: a card whose file was removed since is dropped from the collection
+            SavedCard::Defined(id) if definitions.get(&id).is_some() => collection.add(id),
+            SavedCard::Defined(_) => {},
+            SavedCard::Generated(operation) => collection.add(definitions.id_for(&operation)),
+        }
+    }
+}
+
+fn save_collection(
+    collection: Res<Collection>,
+    definitions: Res<CardDefinitions>,
+    storage: Res<Storage>,
+    mut seen: Local<bool>,
+) {
+    // NOTE: the first run sees the collection that was just loaded as changed
+    if !std::mem::replace(&mut *seen, true) || !collection.is_changed() {
+        return;
+    }
+    let saved: Vec<SavedCard> = collection
+        .0
+        .iter()
+        .filter_map(|id| {
+            let definition = definitions.get(id)?;
+            Some(if id.is_generated() {
+                SavedCard::Generated(definition.operation.clone())
+            } else {
+                SavedCard::Defined(id.clone())
+            })
+        })
+        .collect();
+    let result = ron::ser::to_string_pretty(&saved, default())
+        .map_err(|e| e.to_string())
+        .and_then(|ron| {
+            storage
+                .save(COLLECTION_KEY, &ron)
+                .map_err(|e| e.to_string())
+        });
+    if let Err(err) = result {
+        error!("couldn't save the card collection: {err}");
+    }
+}
+
+fn collect_rules(
+    mut reader: EventReader<RuleAdded>,
+    mut collection: ResMut<Collection>,
+    mut definitions: ResMut<CardDefinitions>,
+) {
+    for event in reader.read() {
+        if event.rule != Operation::None {
+            collection.add(definitions.id_for(&event.rule));
+        }
+    }
+}
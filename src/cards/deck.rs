@@ -1,28 +1,40 @@
-use std::f32::consts::PI;
+use std::time::Duration;
 
 use bevy::{prelude::*, render::view::RenderLayers};
-use bevy_tweening::Lerp;
-use leafwing_input_manager::{
-    prelude::{ActionState, InputManagerPlugin, InputMap},
-    Actionlike, InputManagerBundle,
-};
+use bevy_tweening::{lens::SpriteColorLens, Animator, EaseFunction, Lerp, Tween};
+use rand::seq::SliceRandom;
 
 use super::{
-    card::{Card, CardBundle, CardFace, FlipCard, Flipping, SpawnCard},
-    hand::Hand,
-    Actions, GameState,
+    card::{spawn_card, Card, CardTravel, SpawnCard, TransformDriver, TransformWrites},
+    collection::Decklist,
+    definition::{CardDefinitions, CardId},
+    effect::{play_card, CardUsed, PurgeCards},
+    hand::{HandFull, HandLimit, Overdraw},
+    keyword::Keyword,
+    phase::{EndPhase, PhaseSet},
+    transfer::{TransferReason, ZoneTransfer},
+    zones::{ActivePlayer, GameMode, Owner, PlayerId, Seat, ZoneAnchors, ZoneKind, Zones},
+    CardSet,
 };
 use crate::{
-    board,
-    loading::{SoundAssets, TextureAssets},
-    operation::{generate_random_operations, Operation},
-    AppState,
+    audio::{PlaySound, Sfx},
+    content::{ContentSelection, CustomContent},
+    daily::DailyChallenge,
+    game_over::DeckedOut,
+    operation::generate_random_operations_with,
+    rng::GameRng,
+    save::PendingRun,
+    utils::ThrottledWarn,
+    AppState, GameplayEntity,
 };
 
 #[derive(Component)]
 pub struct Deck;
 #[derive(Component)]
 pub struct Discard;
+/// Cards removed for the rest of the level. See `ZoneKind::Exhaust`.
+#[derive(Component)]
+pub struct Exhaust;
 #[derive(Component)]
 pub struct Library;
 
@@ -33,176 +45,380 @@ pub struct DeckSetup {
     discard_timer: Timer,
     spawned: usize,
     hand_size: usize,
-    library_operations: Vec<Operation>,
+    /// One library per seat, indexed by `PlayerId`. Filled in by `fill_libraries`.
+    libraries: Vec<Vec<CardId>>,
 }
+
+pub const LIBRARY_SIZE: usize = 80;
+/// Tint of a burned card's back as it goes to the discard.
+const BURN_COLOR: Color = Color::rgb(1., 0.35, 0.1);
+
 #[derive(Event)]
-pub struct DrawCard;
+pub struct DrawCard {
+    pub owner: PlayerId,
+}
 
 pub struct DeckPlugin;
 
 impl Plugin for DeckPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(AppState::Playing), (spawn_deck, spawn_discard))
-            .add_event::<DrawCard>()
-            .add_event::<ShuffleDiscard>()
-            .add_systems(OnExit(AppState::Playing), reset_deck)
-            .add_systems(
-                Update,
+        app.add_systems(
+            OnEnter(AppState::Playing),
+            (spawn_deck, spawn_discard, spawn_exhaust, fill_libraries),
+        )
+        .add_event::<DrawCard>()
+        .add_event::<ShuffleDiscard>()
+        .add_systems(OnExit(AppState::Playing), reset_deck)
+        .add_systems(
+            Update,
+            (
                 (
                     (
-                        (draw_card, discard_into_library),
-                        setup_decks.run_if(in_state(GameState::Setup)),
-                        draw_to_hand_size.run_if(in_state(GameState::Draw)),
-                        discard_hand.run_if(in_state(GameState::Discard)),
+                        draw_to_hand_size.in_set(PhaseSet::Draw),
+                        draw_card,
+                        discard_into_library,
                     )
-                        .run_if(in_state(AppState::Playing)),
-                    position_cards,
-                ),
-            )
-            .insert_resource(DeckSetup {
-                deck_setup_timer: Timer::from_seconds(0.01, TimerMode::Repeating),
-                draw_timer: Timer::from_seconds(0.1, TimerMode::Repeating),
-                discard_timer: Timer::from_seconds(0.1, TimerMode::Repeating),
-                spawned: 0,
-                hand_size: 5,
-                library_operations: generate_random_operations(80),
-            });
+                        .chain(),
+                    (purge_cards, discard_used_cards).chain().after(play_card),
+                    setup_decks
+                        .in_set(PhaseSet::Setup)
+                        .before(spawn_card)
+                        .run_if(not(resource_exists::<PendingRun>())),
+                    discard_hand.in_set(PhaseSet::Discard),
+                )
+                    .in_set(CardSet::Transfer)
+                    .run_if(in_state(AppState::Playing)),
+                position_cards.in_set(CardSet::Layout),
+            ),
+        )
+        .insert_resource(DeckSetup {
+            deck_setup_timer: Timer::from_seconds(0.01, TimerMode::Repeating),
+            draw_timer: Timer::from_seconds(0.1, TimerMode::Repeating),
+            discard_timer: Timer::from_seconds(0.1, TimerMode::Repeating),
+            spawned: 0,
+            hand_size: 5,
+            libraries: Vec::new(),
+        });
     }
 }
 pub fn reset_deck(
-    mut cmd: Commands,
     mut deck_setup: ResMut<DeckSetup>,
-    q_decks: Query<Entity, With<Deck>>,
+    mut draw_events: ResMut<Events<DrawCard>>,
+    mut shuffle_events: ResMut<Events<ShuffleDiscard>>,
+    mut purge_events: ResMut<Events<PurgeCards>>,
 ) {
     deck_setup.spawned = 0;
-    deck_setup.library_operations = generate_random_operations(80);
-    for d in q_decks.iter() {
-        cmd.entity(d).despawn_recursive();
-    }
+    deck_setup.deck_setup_timer.reset();
+    deck_setup.draw_timer.reset();
+    deck_setup.discard_timer.reset();
+    deck_setup.libraries.clear();
+    draw_events.clear();
+    shuffle_events.clear();
+    purge_events.clear();
+}
+/// Gives every seat the daily challenge's library in a daily run, or else a shuffled copy of the
+/// `Decklist` if one was built, or else the custom deck picked in the menu, or else a random
+/// library.
+pub fn fill_libraries(
+    mut deck_setup: ResMut<DeckSetup>,
+    daily: Option<Res<DailyChallenge>>,
+    decklist: Res<Decklist>,
+    selection: Res<ContentSelection>,
+    content: Res<CustomContent>,
+    mode: Res<GameMode>,
+    mut definitions: ResMut<CardDefinitions>,
+    mut rng: ResMut<GameRng>,
+) {
+    deck_setup.libraries = mode
+        .seats()
+        .map(|_| {
+            if let Some(daily) = &daily {
+                return daily
+                    .library
+                    .iter()
+                    .map(|operation| definitions.id_for(operation))
+                    .collect();
+            }
+            if decklist.is_legal() {
+                let mut cards = decklist.0.clone();
+                cards.shuffle(&mut **rng);
+                return cards;
+            }
+            let operations = selection
+                .deck_cards(&content, &mut **rng)
+                .unwrap_or_else(|| generate_random_operations_with(LIBRARY_SIZE, &mut **rng));
+            operations
+                .iter()
+                .map(|operation| definitions.id_for(operation))
+                .collect()
+        })
+        .collect();
 }
 fn setup_decks(
-    mut cmd: Commands,
     time: Res<Time>,
     mut deck_setup: ResMut<DeckSetup>,
     mut writer: EventWriter<SpawnCard>,
-    mut q_library: Query<Entity, (With<Library>, Without<Discard>)>,
-    // mut q_discard: Query<(&Transform, &mut Deck, &mut Children), (With<Discard>, Without<Card>)>,
-    mut game_state: ResMut<State<GameState>>,
+    zones: Res<Zones>,
+    mode: Res<GameMode>,
+    mut end_phase: EventWriter<EndPhase>,
+    mut missing_zone: Local<ThrottledWarn>,
 ) {
-    let entity = q_library.single();
     deck_setup.deck_setup_timer.tick(time.delta());
     if deck_setup.deck_setup_timer.finished() {
         deck_setup.deck_setup_timer.reset();
-        writer.send(SpawnCard {
-            operation: deck_setup.library_operations[deck_setup.spawned].clone(),
-            zone_id: entity,
-            face_up: false,
-        });
+        for seat in mode.seats() {
+            let Some(entity) = zones.get(seat, ZoneKind::Library) else {
+                missing_zone.warn(&format!("setup_decks: player {seat:?} has no Library zone"));
+                return;
+            };
+            let Some(card) = deck_setup
+                .libraries
+                .get(seat.0 as usize)
+                .and_then(|library| library.get(deck_setup.spawned))
+            else {
+                continue;
+            };
+            writer.send(SpawnCard {
+                card: card.clone(),
+                zone_id: entity,
+                face_up: false,
+                index: None,
+            });
+        }
         deck_setup.spawned += 1;
     }
-    if deck_setup.spawned >= deck_setup.library_operations.len() {
+    let library_size = deck_setup
+        .libraries
+        .iter()
+        .map(Vec::len)
+        .max()
+        .unwrap_or_default();
+    if deck_setup.spawned >= library_size {
         deck_setup.deck_setup_timer.reset();
         deck_setup.spawned = 0;
-        cmd.insert_resource(NextState(Some(GameState::Start)));
+        end_phase.send(EndPhase);
     }
 }
 fn discard_hand(
-    mut cmd: Commands,
     time: Res<Time>,
     mut deck_setup: ResMut<DeckSetup>,
-    mut q_hand: Query<(&Children, &Transform), (With<Hand>, Without<Card>)>,
-    mut q_discard: Query<(Entity, &mut Transform), (With<Discard>, Without<Hand>)>,
-    mut q_cards: Query<(Entity, &mut Transform), (With<Card>, Without<Discard>)>,
-    mut flip_writer: EventWriter<FlipCard>,
+    zones: Res<Zones>,
+    active: Res<ActivePlayer>,
+    q_children: Query<&Children, Without<Card>>,
+    q_cards: Query<&Card>,
+    mut transfer: EventWriter<ZoneTransfer>,
+    mut end_phase: EventWriter<EndPhase>,
+    mut sound: EventWriter<PlaySound>,
+    mut missing_zone: Local<ThrottledWarn>,
 ) {
-    if q_hand.is_empty() {
+    let Some(hand_e) = zones.get(active.0, ZoneKind::Hand) else {
+        missing_zone.warn("discard_hand: active player has no Hand zone");
+        return;
+    };
+    // NOTE: `Retain` cards stay behind, so the hand is done once only those are left
+    let next = q_children.get(hand_e).ok().and_then(|children| {
+        children.iter().copied().find(|&child| {
+            q_cards
+                .get(child)
+                .is_ok_and(|card| !card.has(Keyword::Retain))
+        })
+    });
+    let Some(child) = next else {
         deck_setup.discard_timer.reset();
-        cmd.insert_resource(NextState(Some(GameState::Draw)));
+        end_phase.send(EndPhase);
         return;
-    }
+    };
 
     deck_setup.discard_timer.tick(time.delta());
 
     if deck_setup.discard_timer.finished() {
-        let (children, h_transform) = q_hand.single();
-        let (discard_e, discard_t) = q_discard.single();
-        let &child = children.first().unwrap();
-        if let Ok((card, mut card_transform)) = q_cards.get_mut(child) {
-            flip_writer.send(FlipCard { card: child });
+        if let Ok(card) = q_cards.get(child) {
+            let (kind, reason) = if card.has(Keyword::Ethereal) {
+                (ZoneKind::Exhaust, TransferReason::Exhaust)
+            } else {
+                (ZoneKind::Discard, TransferReason::Discard)
+            };
+            let Some(pile_e) = zones.get(active.0, kind) else {
+                missing_zone.warn(&format!("discard_hand: active player has no {kind:?} zone"));
+                return;
+            };
+            sound.send(PlaySound(Sfx::Discard));
+            transfer.send(ZoneTransfer {
+                card: child,
+                from: hand_e,
+                to: pile_e,
+                reason,
+            });
+        }
+    }
+}
+/// Puts cards that acted on the board in their owner's discard, face down, or on the exhaust
+/// pile if they `Exhaust`.
+fn discard_used_cards(
+    mut cmd: Commands,
+    mut reader: EventReader<CardUsed>,
+    zones: Res<Zones>,
+    active: Res<ActivePlayer>,
+    q_cards: Query<(&Card, &Parent)>,
+    mut transfer: EventWriter<ZoneTransfer>,
+    mut sound: EventWriter<PlaySound>,
+) {
+    for event in reader.read() {
+        let Ok((card, parent)) = q_cards.get(event.card) else {
+            continue;
+        };
+        let (kind, reason) = if card.has(Keyword::Exhaust) {
+            (ZoneKind::Exhaust, TransferReason::Exhaust)
+        } else {
+            (ZoneKind::Discard, TransferReason::Discard)
+        };
+        let Some(pile_e) = zones.get(active.0, kind) else {
+            cmd.entity(event.card).remove_parent();
+            cmd.entity(event.card).despawn_recursive();
+            continue;
+        };
+        sound.send(PlaySound(Sfx::Discard));
+        transfer.send(ZoneTransfer {
+            card: event.card,
+            from: parent.get(),
+            to: pile_e,
+            reason,
+        });
+    }
+}
 
+/// Takes cards off the top of the active player's discard for good: out of this level, and one
+/// copy each out of the `Decklist`.
+fn purge_cards(
+    mut cmd: Commands,
+    mut reader: EventReader<PurgeCards>,
+    zones: Res<Zones>,
+    active: Res<ActivePlayer>,
+    q_children: Query<&Children, Without<Card>>,
+    q_cards: Query<&Card>,
+    mut decklist: ResMut<Decklist>,
+) {
+    for event in reader.read() {
+        let Some(children) = zones
+            .get(active.0, ZoneKind::Discard)
+            .and_then(|discard| q_children.get(discard).ok())
+        else {
+            continue;
+        };
+        for &child in children.iter().take(event.count as usize) {
+            if let Some(id) = q_cards.get(child).ok().and_then(|c| c.definition.as_ref()) {
+                decklist.remove(id);
+            }
             cmd.entity(child).remove_parent();
-
-            card_transform.translation.x += h_transform.translation.x - discard_t.translation.x;
-            card_transform.translation.y += h_transform.translation.y - discard_t.translation.y;
-
-            cmd.entity(discard_e).insert_children(0, &[child]);
+            cmd.entity(child).despawn_recursive();
         }
     }
 }
-fn draw_to_hand_size(
-    mut cmd: Commands,
+pub fn draw_to_hand_size(
     time: Res<Time>,
     mut deck_setup: ResMut<DeckSetup>,
+    active: Res<ActivePlayer>,
     mut writer: EventWriter<DrawCard>,
-    mut game_state: ResMut<State<GameState>>,
+    mut end_phase: EventWriter<EndPhase>,
 ) {
     deck_setup.draw_timer.tick(time.delta());
 
     if deck_setup.draw_timer.finished() {
-        writer.send(DrawCard);
+        writer.send(DrawCard { owner: active.0 });
         deck_setup.spawned += 1;
         deck_setup.draw_timer.reset();
     }
     if deck_setup.spawned >= deck_setup.hand_size {
         deck_setup.spawned = 0;
 
-        cmd.insert_resource(NextState(Some(GameState::Playing)));
+        end_phase.send(EndPhase);
     }
 }
 
-fn spawn_discard(mut cmd: Commands) {
-    cmd.spawn((
-        Discard,
-        Deck,
-        SpatialBundle {
-            transform: board::get_discard_transform(board::config::SIZE.into(), 190. + 50.),
-            ..default()
-        },
-        RenderLayers::layer(1),
-    ));
+fn spawn_discard(mut cmd: Commands, mode: Res<GameMode>, anchors: Res<ZoneAnchors>) {
+    let transform = anchors.seat(ZoneKind::Discard);
+    for seat in mode.seats() {
+        cmd.spawn((
+            Discard,
+            Deck,
+            Owner(seat),
+            ZoneKind::Discard,
+            Seat(transform),
+            GameplayEntity,
+            SpatialBundle {
+                transform,
+                ..default()
+            },
+            RenderLayers::layer(1),
+        ));
+    }
+}
+
+fn spawn_exhaust(mut cmd: Commands, mode: Res<GameMode>, anchors: Res<ZoneAnchors>) {
+    let transform = anchors.seat(ZoneKind::Exhaust);
+    for seat in mode.seats() {
+        cmd.spawn((
+            Exhaust,
+            Deck,
+            Owner(seat),
+            ZoneKind::Exhaust,
+            Seat(transform),
+            GameplayEntity,
+            SpatialBundle {
+                transform,
+                ..default()
+            },
+            RenderLayers::layer(1),
+        ));
+    }
 }
 
 //spawn deck when deck plugin is made
-fn spawn_deck(mut cmd: Commands, textures: Res<TextureAssets>, sound: Res<SoundAssets>) {
-    cmd.spawn(AudioBundle {
-        source: sound.spawn_deck.clone(),
-        settings: PlaybackSettings {
-            mode: bevy::audio::PlaybackMode::Once,
-            ..default()
-        },
-    });
+fn spawn_deck(
+    mut cmd: Commands,
+    mut sound: EventWriter<PlaySound>,
+    mode: Res<GameMode>,
+    anchors: Res<ZoneAnchors>,
+) {
+    sound.send(PlaySound(Sfx::DeckSpawn));
 
-    let deck_id = cmd
-        .spawn((
+    let transform = anchors.seat(ZoneKind::Library);
+    for seat in mode.seats() {
+        cmd.spawn((
             Library,
             Deck,
+            Owner(seat),
+            ZoneKind::Library,
+            Seat(transform),
+            GameplayEntity,
             SpatialBundle {
-                transform: board::get_deck_transform(board::config::SIZE.into(), 190. + 50.), // Card
-                // Image size plus the offset of the stack cards...
+                transform,
                 ..default()
             },
             RenderLayers::layer(1),
-        ))
-        .id();
+        ));
+    }
 }
 fn position_cards(
     q_deck: Query<(&Transform, &Deck, &Children)>,
-    mut q_cards: Query<(&Card, &mut Transform), Without<Deck>>,
-    mut q_flipping: Query<&Flipping>,
+    mut q_cards: Query<(&Card, &TransformDriver, &mut Transform), Without<Deck>>,
+    mut writes: ResMut<TransformWrites>,
 ) {
-    for (deck_t, deck, children) in q_deck.iter() {
+    #[cfg(feature = "trace")]
+    let _span = info_span!(
+        "position_deck_cards",
+        cards = q_deck.iter().map(|(.., c)| c.len()).sum::<usize>()
+    )
+    .entered();
+
+    for (.., children) in q_deck.iter() {
         for (i, &child) in children.iter().enumerate() {
-            if let Ok((card, mut transform)) = q_cards.get_mut(child) {
+            if let Ok((card, driver, mut transform)) = q_cards.get_mut(child) {
+                if *driver != TransformDriver::Layout {
+                    continue;
+                }
+                writes.claim(child, TransformDriver::Layout);
+
                 transform.translation.x = transform.translation.x.lerp(&0., &0.2);
                 transform.translation.y = transform
                     .translation
@@ -210,90 +426,170 @@ fn position_cards(
                     .lerp(&((children.len() - 1 - i) as f32 * 0.5), &0.2);
 
                 transform.translation.z = (children.len() - 1 - i) as f32;
-                if !q_flipping.contains(child) {
-                    let before = transform.rotation.to_euler(EulerRot::XYZ);
-                    let mut rot: f32 = 0.;
-                    if card.face_up {
-                        rot += 180.;
-                    }
 
-                    transform.rotation = transform.rotation.lerp(
-                        Quat::from_euler(EulerRot::XYZ, before.0, before.1, rot.to_radians()),
-                        0.2,
-                    );
+                let before = transform.rotation.to_euler(EulerRot::XYZ);
+                let mut rot: f32 = 0.;
+                if card.face_up {
+                    rot += 180.;
                 }
+
+                transform.rotation = transform.rotation.lerp(
+                    Quat::from_euler(EulerRot::XYZ, before.0, before.1, rot.to_radians()),
+                    0.2,
+                );
             }
         }
     }
 }
 
+/// Moves the top card of the owner's library, its first child, into their hand. Past the
+/// `HandLimit` the card is burned or left in the library instead, depending on the game mode.
 pub fn draw_card(
     mut cmd: Commands,
-    mut query: Query<(&Transform, &mut Deck, &mut Children), (With<Library>, Without<Card>)>,
-    mut q_cards: Query<(&Card, &mut Transform), Without<Hand>>,
-    mut hand: Query<(Entity, &mut Hand, &mut Transform), Without<Library>>,
+    zones: Res<Zones>,
+    mode: Res<GameMode>,
+    limit: Res<HandLimit>,
+    q_children: Query<&Children, Without<Card>>,
+    q_cards: Query<&Card>,
+    q_travel: Query<&CardTravel>,
     mut reader: EventReader<DrawCard>,
-    mut flip_writer: EventWriter<FlipCard>,
+    mut transfer: EventWriter<ZoneTransfer>,
     mut shuffle_discard_writer: EventWriter<ShuffleDiscard>,
-    mut sound: Res<SoundAssets>,
+    mut hand_full: EventWriter<HandFull>,
+    mut sound: EventWriter<PlaySound>,
+    mut missing_zone: Local<ThrottledWarn>,
 ) {
     for event in reader.read() {
-        if let Ok((deck_transform, mut deck, children)) = query.get_single_mut() {
-            cmd.spawn(AudioBundle {
-                source: sound.draw_card.clone(),
-                settings: PlaybackSettings {
-                    mode: bevy::audio::PlaybackMode::Once,
-                    ..default()
-                },
+        let hand = zones.get(event.owner, ZoneKind::Hand);
+        // NOTE: cards still flying to the hand count, or a quick run of draws could overfill it
+        let in_hand = hand
+            .and_then(|hand| q_children.get(hand).ok())
+            .map_or(0, |children| children.len())
+            + q_travel
+                .iter()
+                .filter(|travel| Some(travel.zone) == hand)
+                .count();
+        if in_hand >= limit.max {
+            let burned = match limit.overdraw(*mode) {
+                Overdraw::Burn => burn_card(
+                    &mut cmd,
+                    event.owner,
+                    &zones,
+                    &q_children,
+                    &q_cards,
+                    &mut transfer,
+                ),
+                Overdraw::Block => None,
+            };
+            sound.send(PlaySound(Sfx::Reject));
+            hand_full.send(HandFull {
+                owner: event.owner,
+                burned,
             });
+            continue;
+        }
+
+        let (Some(library_e), Some(hand_e)) = (
+            zones.get(event.owner, ZoneKind::Library),
+            zones.get(event.owner, ZoneKind::Hand),
+        ) else {
+            missing_zone.warn(&format!(
+                "draw_card: player {:?} is missing a Library or Hand zone",
+                event.owner
+            ));
+            continue;
+        };
+        if let Ok(children) = q_children.get(library_e) {
+            sound.send(PlaySound(Sfx::Draw));
 
             if children.iter().len() < 5 {
-                shuffle_discard_writer.send(ShuffleDiscard);
+                shuffle_discard_writer.send(ShuffleDiscard { owner: event.owner });
             }
-            let (entity, mut hand, mut hand_transform) = hand.single_mut();
             let &child = children.first().unwrap();
 
-            if let Ok((card, mut card_transform)) = q_cards.get_mut(child) {
-                cmd.entity(child).remove_parent();
-
-                card_transform.translation.x +=
-                    deck_transform.translation.x - hand_transform.translation.x;
-                card_transform.translation.y +=
-                    deck_transform.translation.y - hand_transform.translation.y;
-
-                cmd.entity(entity).push_children(&[child]);
-                flip_writer.send(FlipCard { card: child });
-            }
+            transfer.send(ZoneTransfer {
+                card: child,
+                from: library_e,
+                to: hand_e,
+                reason: TransferReason::Draw,
+            });
         }
     }
 }
+
+/// Puts the top card of the owner's library on their discard, face down, its back glowing as it
+/// goes.
+fn burn_card(
+    cmd: &mut Commands,
+    owner: PlayerId,
+    zones: &Zones,
+    q_children: &Query<&Children, Without<Card>>,
+    q_cards: &Query<&Card>,
+    transfer: &mut EventWriter<ZoneTransfer>,
+) -> Option<Entity> {
+    let library_e = zones.get(owner, ZoneKind::Library)?;
+    let discard_e = zones.get(owner, ZoneKind::Discard)?;
+    let &child = q_children.get(library_e).ok()?.first()?;
+    let card = q_cards.get(child).ok()?;
+
+    let glow = Tween::new(
+        EaseFunction::QuadraticIn,
+        Duration::from_millis(600),
+        SpriteColorLens {
+            start: BURN_COLOR,
+            end: Color::WHITE,
+        },
+    );
+    cmd.entity(card.back).insert(Animator::new(glow));
+    transfer.send(ZoneTransfer {
+        card: child,
+        from: library_e,
+        to: discard_e,
+        reason: TransferReason::Burn,
+    });
+    Some(child)
+}
 #[derive(Event)]
-pub struct ShuffleDiscard;
+pub struct ShuffleDiscard {
+    pub owner: PlayerId,
+}
 
+/// Shuffles the owner's discard pile and puts it under what's left of their library. An owner
+/// with nothing left to shuffle has decked out.
 pub fn discard_into_library(
-    mut cmd: Commands,
-    mut q_library: Query<(Entity, &Transform, &mut Deck), (With<Library>, Without<Discard>)>,
-    mut q_discard: Query<(&Transform, &mut Deck, &mut Children), (With<Discard>, Without<Card>)>,
-    mut q_cards: Query<(&Card, &mut Transform), Without<Library>>,
+    zones: Res<Zones>,
+    q_children: Query<&Children, Without<Card>>,
     mut event: EventReader<ShuffleDiscard>,
-    mut flip_writer: EventWriter<FlipCard>,
+    mut transfer: EventWriter<ZoneTransfer>,
+    mut decked_out: EventWriter<DeckedOut>,
+    mut rng: ResMut<GameRng>,
+    mut missing_zone: Local<ThrottledWarn>,
 ) {
     for e in event.read() {
-        let (library_e, library_t, mut library_d) = q_library.single_mut();
-        if q_discard.is_empty() {
-            cmd.insert_resource(NextState(Some(GameState::Scoring)));
-            return;
-        }
-
-        let (discard_t, mut discard_d, children) = q_discard.single_mut();
+        let (Some(library_e), Some(discard_e)) = (
+            zones.get(e.owner, ZoneKind::Library),
+            zones.get(e.owner, ZoneKind::Discard),
+        ) else {
+            missing_zone.warn(&format!(
+                "discard_into_library: player {:?} is missing a Library or Discard zone",
+                e.owner
+            ));
+            continue;
+        };
+        let Ok(children) = q_children.get(discard_e) else {
+            decked_out.send(DeckedOut { owner: e.owner });
+            continue;
+        };
 
-        for &child in children.iter() {
-            if let Ok((card, mut card_t)) = q_cards.get_mut(child) {
-                cmd.entity(child).remove_parent();
-                card_t.translation.x += discard_t.translation.x - library_t.translation.x;
-                card_t.translation.y += discard_t.translation.y - library_t.translation.y;
-                cmd.entity(library_e).push_children(&[child]);
-            }
+        let mut shuffled = children.to_vec();
+        shuffled.shuffle(&mut **rng);
+        for card in shuffled {
+            transfer.send(ZoneTransfer {
+                card,
+                from: discard_e,
+                to: library_e,
+                reason: TransferReason::Shuffle,
+            });
         }
     }
 }
@@ -0,0 +1,290 @@
+//! Card definitions: named cards with art and a rarity, loaded from `assets/cards/*.card.ron`.
+//! Cards are spawned by definition id. Operations generated at runtime get a plain definition of
+//! their own the first time they're spawned, so every card on the table has one. Editing a card
+//! file while the game runs updates its definition, and `CardDefinitionsReloaded` has the cards
+//! using it rebuilt.
+
+use std::io;
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+    utils::{BoxedFuture, HashMap},
+};
+use serde::{Deserialize, Serialize};
+
+use super::{effect::CardEffect, keyword::Keyword};
+use crate::{loading::CardAssets, operation::Operation, AppState};
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Hash, Debug)]
+#[serde(transparent)]
+pub struct CardId(pub String);
+
+/// Starts the ids `CardDefinitions::id_for` makes up for generated operations.
+const GENERATED_PREFIX: &str = "generated/";
+
+impl CardId {
+    /// Whether the definition was made up at runtime rather than loaded from `assets/cards`.
+    pub fn is_generated(&self) -> bool {
+        self.0.starts_with(GENERATED_PREFIX)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum Rarity {
+    #[default]
+    Common,
+    Uncommon,
+    Rare,
+}
+
+impl Rarity {
+    /// What a card of this rarity costs to play when its definition doesn't say.
+    pub fn default_cost(self) -> u32 {
+        match self {
+            Rarity::Common => 1,
+            Rarity::Uncommon => 2,
+            Rarity::Rare => 3,
+        }
+    }
+
+    /// How likely a card of this rarity is to be offered as a reward, relative to the others.
+    pub fn reward_weight(self) -> f32 {
+        match self {
+            Rarity::Common => 6.,
+            Rarity::Uncommon => 3.,
+            Rarity::Rare => 1.,
+        }
+    }
+}
+
+#[derive(Asset, TypePath, Clone, Debug)]
+pub struct CardDefinition {
+    pub id: CardId,
+    pub name: String,
+    /// Drawn on the card's front instead of the blank face.
+    pub art: Option<Handle<Image>>,
+    /// What the card shows.
+    pub operation: Operation,
+    /// What playing the card does. Defaults to what its operation does.
+    pub effect: CardEffect,
+    pub rarity: Rarity,
+    /// `Energy` it takes to play.
+    pub cost: u32,
+    /// Decides between this and other rules matching the same contact, under
+    /// `RuleResolution::HighestPriority`.
+    pub priority: i32,
+    pub keywords: Vec<Keyword>,
+    /// Rules text for the tooltip, in place of the one made up from the effect.
+    pub text: Option<String>,
+}
+
+impl CardDefinition {
+    /// The keywords on a line of their own, then what the card does.
+    pub fn rules_text(&self) -> String {
+        self.with_keywords(self.text.clone().unwrap_or_else(|| self.effect.describe()))
+    }
+
+    /// `text` under the card's keywords.
+    pub fn with_keywords(&self, text: String) -> String {
+        if self.keywords.is_empty() {
+            return text;
+        }
+        let keywords: Vec<String> = self.keywords.iter().map(|k| format!("{k:?}")).collect();
+        format!("{}.\n{text}", keywords.join(". "))
+    }
+}
+
+/// A `*.card.ron` file. `art` is a path relative to `assets/`.
+#[derive(Deserialize)]
+struct CardDefinitionFile {
+    id: CardId,
+    name: String,
+    #[serde(default)]
+    art: Option<String>,
+    operation: Operation,
+    #[serde(default)]
+    effect: Option<CardEffect>,
+    #[serde(default)]
+    rarity: Rarity,
+    #[serde(default)]
+    cost: Option<u32>,
+    #[serde(default)]
+    priority: i32,
+    #[serde(default)]
+    keywords: Vec<Keyword>,
+    #[serde(default)]
+    text: Option<String>,
+}
+
+#[derive(Default)]
+pub struct CardDefinitionLoader;
+
+impl AssetLoader for CardDefinitionLoader {
+    type Asset = CardDefinition;
+    type Settings = ();
+    type Error = io::Error;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<CardDefinition, io::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            let file: CardDefinitionFile = ron::de::from_bytes(&bytes)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            Ok(CardDefinition {
+                id: file.id,
+                name: file.name,
+                art: file.art.map(|path| load_context.load(path)),
+                effect: file
+                    .effect
+                    .unwrap_or_else(|| CardEffect::from(&file.operation)),
+                operation: file.operation,
+                rarity: file.rarity,
+                cost: file.cost.unwrap_or_else(|| file.rarity.default_cost()),
+                priority: file.priority,
+                keywords: file.keywords,
+                text: file.text,
+            })
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["card.ron"]
+    }
+}
+
+/// Definitions whose file changed while the game was running, already updated in
+/// `CardDefinitions`.
+#[derive(Event, Clone, Debug)]
+pub struct CardDefinitionsReloaded(pub Vec<CardId>);
+
+/// Every card definition by id: the ones from `assets/cards` plus those registered for
+/// generated operations.
+#[derive(Resource, Default)]
+pub struct CardDefinitions(HashMap<CardId, CardDefinition>);
+
+impl CardDefinitions {
+    pub fn get(&self, id: &CardId) -> Option<&CardDefinition> {
+        self.0.get(id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &CardDefinition> {
+        self.0.values()
+    }
+
+    /// The priority of the card that adds `operation` to the `Rule`, 0 without one from
+    /// `assets/cards`.
+    pub fn priority_of(&self, operation: &Operation) -> i32 {
+        let effect = CardEffect::from(operation);
+        self.0
+            .values()
+            .filter(|d| d.operation == *operation && d.effect == effect)
+            .map(|d| d.priority)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// The id of a definition that shows and plays `operation`, preferring one from
+    /// `assets/cards`. Registers a plain definition for it if there is none yet.
+    pub fn id_for(&mut self, operation: &Operation) -> CardId {
+        let effect = CardEffect::from(operation);
+        if let Some(definition) = self
+            .0
+            .values()
+            .find(|d| d.operation == *operation && d.effect == effect)
+        {
+            return definition.id.clone();
+        }
+        let id = CardId(format!("{GENERATED_PREFIX}{operation:?}"));
+        self.0.insert(
+            id.clone(),
+            CardDefinition {
+                id: id.clone(),
+                name: format!("{operation:?}"),
+                art: None,
+                operation: operation.clone(),
+                effect,
+                rarity: Rarity::default(),
+                cost: Rarity::default().default_cost(),
+                priority: 0,
+                keywords: Vec::new(),
+                text: None,
+            },
+        );
+        id
+    }
+}
+
+pub struct CardDefinitionPlugin;
+impl Plugin for CardDefinitionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<CardDefinition>()
+            .init_asset_loader::<CardDefinitionLoader>()
+            .init_resource::<CardDefinitions>()
+            .add_event::<CardDefinitionsReloaded>()
+            .add_systems(
+                Update,
+                reload_definitions
+                    .run_if(not(in_state(AppState::Loading)))
+                    .run_if(resource_exists::<CardAssets>()),
+            )
+            .add_systems(
+                OnExit(AppState::Loading),
+                collect_definitions.run_if(resource_exists::<CardAssets>()),
+            );
+    }
+}
+
+pub fn collect_definitions(
+    mut definitions: ResMut<CardDefinitions>,
+    cards: Res<CardAssets>,
+    assets: Res<Assets<CardDefinition>>,
+) {
+    for definition in cards.definitions.iter().filter_map(|h| assets.get(h)) {
+        if definitions.0.contains_key(&definition.id) {
+            warn!(
+                "duplicate card definition {:?}, keeping the first",
+                definition.id
+            );
+            continue;
+        }
+        definitions
+            .0
+            .insert(definition.id.clone(), definition.clone());
+    }
+    info!("loaded {} card definitions", definitions.0.len());
+}
+
+fn reload_definitions(
+    mut reader: EventReader<AssetEvent<CardDefinition>>,
+    cards: Res<CardAssets>,
+    assets: Res<Assets<CardDefinition>>,
+    mut definitions: ResMut<CardDefinitions>,
+    mut writer: EventWriter<CardDefinitionsReloaded>,
+) {
+    let mut reloaded = Vec::new();
+    for event in reader.read() {
+        let AssetEvent::Modified { id } = event else {
+            continue;
+        };
+        if !cards.definitions.iter().any(|handle| handle.id() == *id) {
+            continue;
+        }
+        let Some(definition) = assets.get(*id) else {
+            continue;
+        };
+        info!("card definition {:?} updated", definition.id);
+        definitions
+            .0
+            .insert(definition.id.clone(), definition.clone());
+        reloaded.push(definition.id.clone());
+    }
+    if !reloaded.is_empty() {
+        writer.send(CardDefinitionsReloaded(reloaded));
+    }
+}
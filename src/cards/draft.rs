@@ -0,0 +1,98 @@
+//! Drafting rules. Every `DRAFT_EVERY` turns the player who just finished their turn is offered
+//! `DRAFT_SIZE` rule cards, and the one they pick joins the `Rule` the way a played rule card
+//! does, pushing the oldest rule out if it's full.
+
+use bevy::prelude::*;
+
+use super::{
+    card::{spawn_card, SpawnCard},
+    definition::CardDefinitions,
+    phase::{EndPhase, PhaseSet},
+    rules::{cycle_rule, AddRule, Rule},
+    CardSet, GameState,
+};
+use crate::{
+    operation::{generate_random_operations_with, Operation},
+    rng::GameRng,
+    utils::ThrottledWarn,
+    AppState,
+};
+
+/// How many rules a draft offers.
+pub const DRAFT_SIZE: usize = 3;
+
+/// The rules on offer. Present only during `GameState::Draft`.
+#[derive(Resource, Deref, Debug)]
+pub struct DraftOffer(pub Vec<Operation>);
+
+/// Picks a rule and ends the draft. Sent by the draft screen.
+#[derive(Event, Clone, Debug)]
+pub struct DraftRule {
+    pub rule: Operation,
+}
+
+pub struct DraftPlugin;
+impl Plugin for DraftPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<DraftRule>()
+            .add_systems(OnEnter(GameState::Draft), offer_rules)
+            .add_systems(OnExit(GameState::Draft), clear_offer)
+            .add_systems(
+                Update,
+                take_draft
+                    .in_set(PhaseSet::Draft)
+                    .in_set(CardSet::Transfer)
+                    .before(spawn_card)
+                    .before(cycle_rule)
+                    .run_if(in_state(AppState::Playing)),
+            )
+            .add_systems(OnExit(AppState::Playing), reset_draft_events);
+    }
+}
+
+/// Draws the offer from `GameRng`, so a seeded run is offered the same rules.
+pub fn offer_rules(mut cmd: Commands, mut rng: ResMut<GameRng>) {
+    cmd.insert_resource(DraftOffer(generate_random_operations_with(
+        DRAFT_SIZE, &mut rng.0,
+    )));
+}
+
+fn clear_offer(mut cmd: Commands) {
+    cmd.remove_resource::<DraftOffer>();
+}
+
+/// Deals the picked rule's card to the front of the `Rule`, where `cycle_rule` expects it.
+pub fn take_draft(
+    mut reader: EventReader<DraftRule>,
+    q_rule: Query<Entity, With<Rule>>,
+    mut definitions: ResMut<CardDefinitions>,
+    mut spawn_card: EventWriter<SpawnCard>,
+    mut add_rule: EventWriter<AddRule>,
+    mut end_phase: EventWriter<EndPhase>,
+    mut missing_rule: Local<ThrottledWarn>,
+) {
+    // NOTE: only one pick counts, so two clicks landing on the same frame add one rule
+    let Some(event) = reader.read().last() else {
+        return;
+    };
+    let Ok(rule_e) = q_rule.get_single() else {
+        missing_rule.warn("take_draft: expected exactly one Rule entity");
+        return;
+    };
+
+    spawn_card.send(SpawnCard {
+        zone_id: rule_e,
+        card: definitions.id_for(&event.rule),
+        face_up: true,
+        index: Some(0),
+    });
+    add_rule.send(AddRule {
+        rule: event.rule.clone(),
+        drafted: true,
+    });
+    end_phase.send(EndPhase);
+}
+
+fn reset_draft_events(mut draft: ResMut<Events<DraftRule>>) {
+    draft.clear();
+}
@@ -0,0 +1,338 @@
+//! What a card does when it's dropped on the board. Most cards add their operation to the
+//! `Rule`; the rest act on the board straight away, around where they were dropped, and are
+//! then `CardUsed`.
+
+use std::f32::consts::PI;
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_xpbd_2d::prelude::LinearVelocity;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    card::Card,
+    energy::{CardRejected, Energy},
+    hand::select_card,
+    phase::EndPhase,
+    rules::{cycle_rule, AddRule, Rule},
+    search::{CardFilter, ScryCards, TutorCard},
+    transfer::{TransferReason, ZoneTransfer},
+    zones::{ActivePlayer, PlayerId},
+    CardSet,
+};
+use crate::{
+    board::{
+        gravity::ShiftGravity,
+        population::DespawnShape,
+        status::{ApplyStatus, StatusKind},
+        transmute::TransmuteShapes,
+        IsOnBoard, SpawnBody, SpawnSource,
+    },
+    game_shapes::{GamePolygon, Shape},
+    operation::Operation,
+    score::Score,
+    utils::ThrottledWarn,
+    AppState,
+};
+
+/// How far from the drop point `SpawnShapes` scatters its shapes.
+const SPAWN_SPREAD: f32 = 60.;
+
+#[derive(Component, Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub enum CardEffect {
+    /// Adds the operation to the front of the `Rule`, pushing the oldest one out.
+    Rule(Operation),
+    /// Turns every shape of the first polygon on the board into the second.
+    Transmute(GamePolygon, GamePolygon),
+    /// Spawns this many shapes around the drop point.
+    SpawnShapes(Shape, u32),
+    /// Removes every shape within this radius of the drop point.
+    ClearRegion(f32),
+    /// Pushes every shape within `radius` of the drop point away from it, adding `strength`
+    /// to its speed.
+    Impulse { radius: f32, strength: f32 },
+    /// Removes this many cards from the top of the player's discard for the rest of the run,
+    /// taking them out of the `Decklist` too.
+    Purge(u32),
+    /// Looks at this many cards from the top of the player's library, to put back in any order
+    /// or under it.
+    Scry(u32),
+    /// Searches the player's library for a card to take into their hand.
+    Tutor(CardFilter),
+    /// Puts a status on the whole board for `duration` seconds.
+    Status { kind: StatusKind, duration: f32 },
+    /// Turns gravity by `angle` radians for `duration` seconds.
+    Gravity { angle: f32, duration: f32 },
+}
+
+impl From<&Operation> for CardEffect {
+    fn from(operation: &Operation) -> Self {
+        match *operation {
+            Operation::Transmute(from, to) => CardEffect::Transmute(from, to),
+            ref operation => CardEffect::Rule(operation.clone()),
+        }
+    }
+}
+
+impl CardEffect {
+    /// What the card does, for its tooltip.
+    pub fn describe(&self) -> String {
+        let name = |shape: &Shape| format!("{:?} {:?}", shape.color, shape.polygon);
+        match self {
+            CardEffect::Rule(Operation::Add(s1, s2)) => format!(
+                "Rule: a {} touching a {} combine into one shape.",
+                name(s1),
+                name(s2)
+            ),
+            CardEffect::Rule(Operation::Sub(s1, s2)) => format!(
+                "Rule: a {} touching a {} leave their difference.",
+                name(s1),
+                name(s2)
+            ),
+            CardEffect::Rule(Operation::Mul(shape, n)) => format!(
+                "Rule: two {}s touching each break into {n} smaller copies.",
+                name(shape)
+            ),
+            CardEffect::Rule(Operation::Div(shape)) => {
+                format!("Rule: two {}s touching are each cut in two.", name(shape))
+            },
+            CardEffect::Rule(operation) => format!("Joins the rule as {operation:?}."),
+            CardEffect::Transmute(from, to) => {
+                format!("Turns every {from:?} on the board into a {to:?}.")
+            },
+            CardEffect::SpawnShapes(shape, count) => {
+                format!("Drops {count} {}s where it's played.", name(shape))
+            },
+            CardEffect::ClearRegion(radius) => {
+                format!("Removes every shape within {radius:.0} of where it's played.")
+            },
+            CardEffect::Impulse { radius, .. } => {
+                format!("Pushes shapes within {radius:.0} of where it's played away.")
+            },
+            CardEffect::Purge(count) => {
+                format!("Removes the top {count} cards of your discard from the run.")
+            },
+            CardEffect::Scry(count) => format!(
+                "Look at the top {count} cards of your library. Put any of them back in any \
+                 order, the rest under it."
+            ),
+            CardEffect::Tutor(filter) => format!(
+                "Search your library for {} and put it in your hand.",
+                filter.describe()
+            ),
+            CardEffect::Status { kind, duration } => {
+                format!("{} for {duration:.0}s: {}.", kind.name(), kind.describe())
+            },
+            CardEffect::Gravity { angle, duration } if (angle.abs() - PI).abs() < 0.01 => {
+                format!("Inverts gravity for {duration:.0}s, pushing shapes away from the middle.")
+            },
+            CardEffect::Gravity { angle, duration } => format!(
+                "Turns gravity {:.0} degrees for {duration:.0}s.",
+                angle.to_degrees()
+            ),
+        }
+    }
+}
+
+/// A card from the hand dropped at `board_pos` (in world space).
+#[derive(Event, Clone, Copy, Debug)]
+pub struct PlayCard {
+    pub card: Entity,
+    pub board_pos: Vec2,
+}
+
+/// Sent by `play_card` for a `Purge` card, for the deck to remove `count` cards from the
+/// active player's discard.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct PurgeCards {
+    pub count: u32,
+}
+
+/// A card that acted on the board is done with, for the deck to discard or exhaust.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct CardUsed {
+    pub card: Entity,
+}
+
+/// Where `play_card` sends the effects that act on the board.
+#[derive(SystemParam)]
+pub struct BoardEffects<'w> {
+    transmute: EventWriter<'w, TransmuteShapes>,
+    spawn_body: EventWriter<'w, SpawnBody>,
+    despawn: EventWriter<'w, DespawnShape>,
+    status: EventWriter<'w, ApplyStatus>,
+    gravity: EventWriter<'w, ShiftGravity>,
+}
+
+/// Where `play_card` sends the effects that act on the player's cards instead of the board.
+#[derive(SystemParam)]
+pub struct DeckEffects<'w> {
+    purge: EventWriter<'w, PurgeCards>,
+    scry: EventWriter<'w, ScryCards>,
+    tutor: EventWriter<'w, TutorCard>,
+}
+
+pub struct CardEffectPlugin;
+impl Plugin for CardEffectPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<PlayCard>()
+            .add_event::<CardUsed>()
+            .add_event::<PurgeCards>()
+            .add_systems(
+                Update,
+                play_card
+                    .in_set(CardSet::Transfer)
+                    .after(select_card)
+                    .before(cycle_rule)
+                    .run_if(in_state(AppState::Playing)),
+            )
+            .add_systems(OnExit(AppState::Playing), reset_play_events);
+    }
+}
+
+pub fn play_card(
+    mut reader: EventReader<PlayCard>,
+    q_cards: Query<(&Card, &CardEffect, &Parent)>,
+    q_rules: Query<Entity, (With<Rule>, Without<CardEffect>)>,
+    mut q_shapes: Query<
+        (Entity, &Transform, &mut LinearVelocity),
+        (With<IsOnBoard>, Without<CardEffect>),
+    >,
+    mut add_rule: EventWriter<AddRule>,
+    mut board_effects: BoardEffects,
+    mut score: ResMut<Score>,
+    mut end_phase: EventWriter<EndPhase>,
+    mut energy: ResMut<Energy>,
+    mut rejected: EventWriter<CardRejected>,
+    mut used: EventWriter<CardUsed>,
+    mut deck_effects: DeckEffects,
+    mut transfer: EventWriter<ZoneTransfer>,
+    active: Res<ActivePlayer>,
+    mut missing_rule: Local<ThrottledWarn>,
+) {
+    for event in reader.read() {
+        let Ok((card, effect, parent)) = q_cards.get(event.card) else {
+            continue;
+        };
+        // NOTE: `select_card` already handed the card back to the hand's layout
+        if !energy.spend(card.cost) {
+            rejected.send(CardRejected {
+                card: event.card,
+                cost: card.cost,
+            });
+            continue;
+        }
+
+        match effect {
+            CardEffect::Rule(operation) => {
+                let Ok(rules_e) = q_rules.get_single() else {
+                    missing_rule.warn("play_card: expected exactly one Rule entity");
+                    continue;
+                };
+                // NOTE: `cycle_rule` counts the card as played once it joins the rule
+                transfer.send(ZoneTransfer {
+                    card: event.card,
+                    from: parent.get(),
+                    to: rules_e,
+                    reason: TransferReason::Play,
+                });
+                add_rule.send(AddRule {
+                    rule: operation.clone(),
+                    drafted: false,
+                });
+            },
+            CardEffect::Purge(count) => {
+                deck_effects.purge.send(PurgeCards { count: *count });
+                score.cards_played += 1;
+                used.send(CardUsed { card: event.card });
+            },
+            CardEffect::Scry(count) => {
+                deck_effects.scry.send(ScryCards { count: *count });
+                score.cards_played += 1;
+                used.send(CardUsed { card: event.card });
+            },
+            CardEffect::Tutor(filter) => {
+                deck_effects.tutor.send(TutorCard {
+                    filter: filter.clone(),
+                });
+                score.cards_played += 1;
+                used.send(CardUsed { card: event.card });
+            },
+            effect => {
+                apply_to_board(
+                    effect,
+                    event.board_pos,
+                    active.0,
+                    &mut q_shapes,
+                    &mut board_effects,
+                );
+                score.cards_played += 1;
+                used.send(CardUsed { card: event.card });
+            },
+        }
+        end_phase.send(EndPhase);
+    }
+}
+
+fn apply_to_board(
+    effect: &CardEffect,
+    at: Vec2,
+    owner: PlayerId,
+    q_shapes: &mut Query<
+        (Entity, &Transform, &mut LinearVelocity),
+        (With<IsOnBoard>, Without<CardEffect>),
+    >,
+    effects: &mut BoardEffects,
+) {
+    match *effect {
+        // NOTE: these act on the cards instead, see `play_card`
+        CardEffect::Rule(_) | CardEffect::Purge(_) | CardEffect::Scry(_) | CardEffect::Tutor(_) => {
+        },
+        CardEffect::Transmute(from, to) => effects.transmute.send(TransmuteShapes { from, to }),
+        CardEffect::SpawnShapes(shape, count) => {
+            effects.spawn_body.send_batch((0..count).map(|i| {
+                // NOTE: spread evenly on a circle so the new shapes don't start on top of each
+                // other
+                let angle = i as f32 / count as f32 * std::f32::consts::TAU;
+                let pos = at + Vec2::from_angle(angle) * SPAWN_SPREAD;
+                SpawnBody {
+                    shape,
+                    transform: Transform::from_translation(pos.extend(10.)),
+                    velocity: None,
+                    combo: 0,
+                    element: None,
+                    source: SpawnSource::Card(owner),
+                }
+            }));
+        },
+        CardEffect::ClearRegion(radius) => {
+            for (entity, transform, _) in q_shapes.iter() {
+                if transform.translation.truncate().distance(at) <= radius {
+                    effects.despawn.send(DespawnShape { entity });
+                }
+            }
+        },
+        CardEffect::Impulse { radius, strength } => {
+            for (_, transform, mut velocity) in q_shapes.iter_mut() {
+                let offset = transform.translation.truncate() - at;
+                if offset.length() <= radius {
+                    // NOTE: `clamp_vel` caps the result at `config::MAX_SPEED`
+                    velocity.0 += offset.normalize_or_zero() * strength;
+                }
+            }
+        },
+        CardEffect::Status { kind, duration } => {
+            effects.status.send(ApplyStatus { kind, duration })
+        },
+        CardEffect::Gravity { angle, duration } => {
+            effects.gravity.send(ShiftGravity { angle, duration })
+        },
+    }
+}
+
+fn reset_play_events(
+    mut play_card: ResMut<Events<PlayCard>>,
+    mut card_used: ResMut<Events<CardUsed>>,
+) {
+    play_card.clear();
+    card_used.clear();
+}
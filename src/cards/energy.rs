@@ -0,0 +1,107 @@
+//! What playing a card costs. Every card has a `cost`, paid out of the `Energy` refilled at the
+//! start of each Draw phase. `play_card` turns a card down with `CardRejected` when there isn't
+//! enough left, and the card goes back into the hand. A combine credited to a player (see
+//! `SpawnSource`) banks `COMBINE_ENERGY` for them, paid out on top of their next refill.
+
+use bevy::{prelude::*, utils::HashMap};
+
+use super::{
+    zones::{ActivePlayer, PlayerId},
+    GameState,
+};
+use crate::{
+    audio::{PlaySound, Sfx},
+    board::ShapesCombined,
+    AppState,
+};
+
+/// Energy to spend each turn.
+pub const TURN_ENERGY: u32 = 3;
+/// Energy a credited combine banks for its player.
+pub const COMBINE_ENERGY: u32 = 1;
+/// The most a player can bank between two of their turns.
+pub const MAX_BANKED_ENERGY: u32 = TURN_ENERGY;
+
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Energy {
+    pub current: u32,
+    /// What the Draw phase refills `current` to.
+    pub max: u32,
+}
+
+impl Default for Energy {
+    fn default() -> Self {
+        Self {
+            current: TURN_ENERGY,
+            max: TURN_ENERGY,
+        }
+    }
+}
+
+impl Energy {
+    /// Takes `cost` out of the energy left, if there's enough.
+    pub fn spend(&mut self, cost: u32) -> bool {
+        let Some(left) = self.current.checked_sub(cost) else {
+            return false;
+        };
+        self.current = left;
+        true
+    }
+}
+
+/// Energy each player earned from combines since their last refill.
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct EnergyBank(HashMap<PlayerId, u32>);
+
+/// A played card cost more than the `Energy` left.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct CardRejected {
+    pub card: Entity,
+    pub cost: u32,
+}
+
+pub struct EnergyPlugin;
+impl Plugin for EnergyPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Energy>()
+            .init_resource::<EnergyBank>()
+            .add_event::<CardRejected>()
+            .add_systems(OnEnter(GameState::Draw), refill_energy)
+            .add_systems(OnExit(AppState::Playing), reset_energy)
+            .add_systems(
+                Update,
+                (play_reject_sound, bank_combine_energy).run_if(in_state(AppState::Playing)),
+            );
+    }
+}
+
+fn refill_energy(
+    mut energy: ResMut<Energy>,
+    mut bank: ResMut<EnergyBank>,
+    active: Res<ActivePlayer>,
+) {
+    energy.current = energy.max + bank.remove(&active.0).unwrap_or(0);
+}
+
+pub fn bank_combine_energy(mut reader: EventReader<ShapesCombined>, mut bank: ResMut<EnergyBank>) {
+    for owner in reader.read().filter_map(|combined| combined.credit) {
+        let banked = bank.entry(owner).or_default();
+        *banked = (*banked + COMBINE_ENERGY).min(MAX_BANKED_ENERGY);
+    }
+}
+
+fn play_reject_sound(mut reader: EventReader<CardRejected>, mut sound: EventWriter<PlaySound>) {
+    if reader.read().last().is_some() {
+        sound.send(PlaySound(Sfx::Reject));
+    }
+}
+
+fn reset_energy(
+    mut energy: ResMut<Energy>,
+    mut bank: ResMut<EnergyBank>,
+    mut rejected: ResMut<Events<CardRejected>>,
+) {
+    *energy = Energy::default();
+    bank.clear();
+    rejected.clear();
+}
@@ -0,0 +1,174 @@
+//! Playing without a mouse. The focus actions (the d-pad or left stick on a gamepad) move a
+//! `Focused` marker between the cards of the active hand, which is shown like a hovered card, and
+//! the right stick moves a `BoardCursor` over the board. `PlayFocused` plays the focused card at
+//! the cursor, through the same `PlayCard` a mouse drop sends.
+
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::ActionState;
+
+use super::{
+    effect::{play_card, PlayCard},
+    hand::{select_card, Hand},
+    phase::PhaseSet,
+    zones::{ActivePlayer, ZoneKind, Zones},
+    CardSet,
+};
+use crate::{
+    board::config, input::GameAction, pause::PauseState, utils::point_in_board, AppState,
+    GameplayEntity,
+};
+
+/// Pixels per second the cursor moves with the stick all the way over.
+const CURSOR_SPEED: f32 = 600.;
+const CURSOR_SIZE: f32 = 24.;
+
+/// The card the focus actions landed on. At most one card has it.
+#[derive(Component, Debug)]
+pub struct Focused;
+
+/// Where a gamepad player aims the focused card. Hidden until the stick first moves it.
+#[derive(Component, Debug)]
+pub struct BoardCursor;
+
+pub struct FocusPlugin;
+impl Plugin for FocusPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::Playing), spawn_board_cursor)
+            .add_systems(
+                Update,
+                (
+                    move_board_cursor,
+                    (cycle_focus, play_focused)
+                        .chain()
+                        .in_set(CardSet::Transfer)
+                        .before(select_card)
+                        .before(play_card)
+                        .in_set(PhaseSet::Play),
+                )
+                    .run_if(in_state(PauseState::Running))
+                    .run_if(in_state(AppState::Playing)),
+            );
+    }
+}
+
+fn spawn_board_cursor(mut cmd: Commands) {
+    cmd.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::rgba(1., 1., 1., 0.6),
+                custom_size: Some(Vec2::splat(CURSOR_SIZE)),
+                ..default()
+            },
+            transform: Transform::from_translation(config::CENTER.extend(30.))
+                .with_rotation(Quat::from_rotation_z(std::f32::consts::FRAC_PI_4)),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        BoardCursor,
+        GameplayEntity,
+    ));
+}
+
+fn move_board_cursor(
+    time: Res<Time<Real>>,
+    actions: Res<ActionState<GameAction>>,
+    mut q_cursor: Query<(&mut Transform, &mut Visibility), With<BoardCursor>>,
+) {
+    let Some(axis) = actions.clamped_axis_pair(GameAction::MoveCursor) else {
+        return;
+    };
+    if axis.xy() == Vec2::ZERO {
+        return;
+    }
+    let half = config::SIZE / 2.;
+    for (mut transform, mut visibility) in q_cursor.iter_mut() {
+        let moved =
+            transform.translation.truncate() + axis.xy() * CURSOR_SPEED * time.delta_seconds();
+        let clamped = moved.clamp(config::CENTER - half, config::CENTER + half);
+        transform.translation = clamped.extend(transform.translation.z);
+        *visibility = Visibility::Inherited;
+    }
+}
+
+/// Moves the focus along the active hand, wrapping at the ends. Cancel drops it.
+fn cycle_focus(
+    mut cmd: Commands,
+    actions: Res<ActionState<GameAction>>,
+    zones: Res<Zones>,
+    active: Res<ActivePlayer>,
+    q_hand: Query<(&Hand, &Children)>,
+    q_focused: Query<Entity, With<Focused>>,
+) {
+    if actions.just_pressed(GameAction::Cancel) {
+        for entity in q_focused.iter() {
+            cmd.entity(entity).remove::<Focused>();
+        }
+        return;
+    }
+    let step: isize = match (
+        actions.just_pressed(GameAction::FocusNext),
+        actions.just_pressed(GameAction::FocusPrevious),
+    ) {
+        (true, false) => 1,
+        (false, true) => -1,
+        _ => return,
+    };
+    let Some(Ok((hand, children))) = zones
+        .get(active.0, ZoneKind::Hand)
+        .map(|entity| q_hand.get(entity))
+    else {
+        return;
+    };
+    // NOTE: a card being dragged with the mouse keeps the hand to itself
+    if hand.selected.is_some() || children.is_empty() {
+        return;
+    }
+    let count = children.len() as isize;
+    let next = match children.iter().position(|&child| q_focused.contains(child)) {
+        Some(index) => (index as isize + step).rem_euclid(count),
+        None if step > 0 => 0,
+        None => count - 1,
+    };
+    for entity in q_focused.iter() {
+        cmd.entity(entity).remove::<Focused>();
+    }
+    cmd.entity(children[next as usize]).insert(Focused);
+}
+
+fn play_focused(
+    mut cmd: Commands,
+    actions: Res<ActionState<GameAction>>,
+    zones: Res<Zones>,
+    active: Res<ActivePlayer>,
+    q_hand: Query<(&Hand, &Children)>,
+    q_focused: Query<Entity, With<Focused>>,
+    q_cursor: Query<(&Transform, &Visibility), With<BoardCursor>>,
+    mut writer: EventWriter<PlayCard>,
+) {
+    if !actions.just_pressed(GameAction::PlayFocused) {
+        return;
+    }
+    let Some(Ok((hand, children))) = zones
+        .get(active.0, ZoneKind::Hand)
+        .map(|entity| q_hand.get(entity))
+    else {
+        return;
+    };
+    let Some(&card) = children.iter().find(|&&child| q_focused.contains(child)) else {
+        return;
+    };
+    if hand.selected.is_some() {
+        return;
+    }
+    // NOTE: without a cursor on the board, the card goes to the middle of it
+    let board_pos = q_cursor
+        .get_single()
+        .ok()
+        .filter(|(_, visibility)| **visibility != Visibility::Hidden)
+        .map(|(transform, _)| transform.translation.truncate())
+        .filter(|pos| point_in_board(pos.x, pos.y, config::SIZE, config::CENTER))
+        .unwrap_or(config::CENTER);
+
+    cmd.entity(card).remove::<Focused>();
+    writer.send(PlayCard { card, board_pos });
+}
@@ -2,13 +2,16 @@ use bevy::{prelude::*, render::view::RenderLayers};
 use bevy_tweening::Lerp;
 
 use super::{
-    card::{Card, SpawnCard, SpawnGoalCard},
-    GameState,
+    card::{Card, SpawnGoalCard, TransformDriver, TransformWrites},
+    deck::fill_libraries,
+    zones::{ActivePlayer, GameMode, Owner},
+    CardSet, GameState,
 };
 use crate::{
-    goal::{self, generate_random_goals, Goal},
-    operation::{generate_random_operations, Operation},
-    AppState,
+    goal::{generate_random_goals_with, Goal},
+    rng::GameRng,
+    save::PendingRun,
+    AppState, GameplayEntity,
 };
 
 #[derive(Component, Deref, DerefMut)]
@@ -19,54 +22,100 @@ impl Plugin for GoalsPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
             OnEnter(AppState::Playing),
-            (spawn_goals).run_if(in_state(GameState::Setup)),
+            // NOTE: both draw from `GameRng`, a fixed order keeps seeded runs the same
+            spawn_goals
+                .after(fill_libraries)
+                .run_if(in_state(GameState::Setup)),
         )
-        .add_systems(OnExit(AppState::Playing), reset_goals)
-        .add_systems(Update, (position_goals));
+        .add_systems(
+            Update,
+            (show_active_goals, position_goals).in_set(CardSet::Layout),
+        );
     }
 }
 
-pub fn spawn_goals(mut cmd: Commands, mut writer: EventWriter<SpawnGoalCard>) {
-    let goals = generate_random_goals(3);
-    dbg!(goals.clone());
-    let rules_e = cmd
-        .spawn((
-            Goals(goals.clone()),
-            SpatialBundle {
-                transform: Transform {
-                    translation: Vec3::new(-1100., -600., 0.),
+pub fn spawn_goals(
+    mut cmd: Commands,
+    mut writer: EventWriter<SpawnGoalCard>,
+    mode: Res<GameMode>,
+    pending: Option<Res<PendingRun>>,
+    mut rng: ResMut<GameRng>,
+) {
+    for seat in mode.seats() {
+        let goals = pending
+            .as_ref()
+            .and_then(|run| run.seats.get(seat.0 as usize))
+            .map(|saved| saved.goals.clone())
+            .unwrap_or_else(|| generate_random_goals_with(3, &mut rng.0));
+        dbg!(goals.clone());
+        let rules_e = cmd
+            .spawn((
+                Goals(goals.clone()),
+                Owner(seat),
+                SpatialBundle {
+                    transform: Transform {
+                        translation: Vec3::new(-1100., -600., 0.),
+                        ..default()
+                    },
                     ..default()
                 },
-                ..default()
-            },
-            RenderLayers::layer(1),
-        ))
-        .id();
-    for g in goals {
-        writer.send(SpawnGoalCard {
-            zone_id: rules_e,
-            goal: g,
-            face_up: true,
-        });
+                RenderLayers::layer(1),
+                GameplayEntity,
+            ))
+            .id();
+        for g in goals {
+            writer.send(SpawnGoalCard {
+                zone_id: rules_e,
+                goal: g,
+                face_up: true,
+            });
+        }
+    }
+}
+/// Only the goals of the player at the device are shown, the other player's stay hidden until
+/// their turn. Against the `Opponent`, that's always the player's own.
+fn show_active_goals(
+    mode: Res<GameMode>,
+    active: Res<ActivePlayer>,
+    mut q_goals: Query<(&Owner, &mut Visibility), With<Goals>>,
+) {
+    let viewer = mode.viewer(active.0);
+    for (owner, mut visibility) in q_goals.iter_mut() {
+        let target = if owner.0 == viewer {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        if *visibility != target {
+            *visibility = target;
+        }
     }
 }
 pub fn position_goals(
     q_criteria: Query<&Children, With<Goals>>,
-    mut q_cards: Query<&mut Transform, With<Card>>,
+    mut q_cards: Query<(&TransformDriver, &mut Transform), With<Card>>,
+    mut writes: ResMut<TransformWrites>,
 ) {
-    if q_criteria.is_empty() {
-        return;
-    }
-    let children = q_criteria.single();
-    for (i, &entity) in children.iter().enumerate() {
-        if let Ok(mut transform) = q_cards.get_mut(entity) {
-            transform.translation.x = transform.translation.x.lerp(&(i as f32 * 150.), &0.2);
-            transform.translation.y = transform.translation.y.lerp(&0., &0.2);
+    #[cfg(feature = "trace")]
+    let _span = info_span!(
+        "position_goals",
+        cards = q_criteria.iter().map(|c| c.len()).sum::<usize>()
+    )
+    .entered();
+
+    for children in q_criteria.iter() {
+        for (i, &entity) in children.iter().enumerate() {
+            if let Ok((driver, mut transform)) = q_cards.get_mut(entity) {
+                if *driver != TransformDriver::Layout {
+                    continue;
+                }
+                writes.claim(entity, TransformDriver::Layout);
 
-            transform.translation.z = 20.;
+                transform.translation.x = transform.translation.x.lerp(&(i as f32 * 150.), &0.2);
+                transform.translation.y = transform.translation.y.lerp(&0., &0.2);
+
+                transform.translation.z = 20.;
+            }
         }
     }
 }
-pub fn reset_goals(mut cmd: Commands, q_goals: Query<Entity, With<Goals>>) {
-    cmd.entity(q_goals.single()).despawn_recursive();
-}
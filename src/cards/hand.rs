@@ -1,33 +1,30 @@
-use std::{
-    f32::{consts::PI, INFINITY},
-    time::Duration,
-};
+use std::{f32::consts::PI, time::Duration};
 
-use bevy::{
-    input::mouse::MouseButtonInput, math::Vec2Swizzles, prelude::*, render::view::RenderLayers,
-    window::PrimaryWindow,
-};
+use bevy::{prelude::*, render::view::RenderLayers};
 use bevy_tweening::{
-    lens::{TransformRotationLens, TransformScaleLens},
+    lens::{SpriteColorLens, TransformScaleLens},
     *,
 };
-use leafwing_input_manager::{
-    action_state,
-    prelude::{ActionState, InputManagerPlugin, InputMap},
-    Actionlike, InputManagerBundle,
-};
+use leafwing_input_manager::prelude::ActionState;
 
 use super::{
-    card::{Card, FlipCard, Flipping},
-    deck::{draw_card, Deck, Discard},
-    rules::{AddRule, Rule},
-    Actions, GameState,
+    card::{Card, TransformDriver, TransformWrites},
+    effect::PlayCard,
+    focus::Focused,
+    opponent::opponent_turn,
+    phase::PhaseSet,
+    rules::cycle_rule,
+    zones::{ActivePlayer, GameMode, Owner, PlayerId, Seat, ZoneAnchors, ZoneKind, Zones},
+    CardSet,
 };
 use crate::{
-    board::{self, config},
-    camera::{lerp, CardCamera},
-    utils::{calculate_rotated_bounds, point_in_board, point_in_polygon},
-    AppState,
+    input::GameAction,
+    pause::PauseState,
+    pointer::Pointer,
+    tween_ids::RELEASE_TWEEN_DRIVER,
+    ui::PileBrowserOpen,
+    utils::{calculate_rotated_bounds, point_in_polygon, ThrottledWarn},
+    AppState, GameplayEntity,
 };
 
 #[derive(Component)]
@@ -57,18 +54,111 @@ impl Lens<Transform> for TransformLens {
     }
 }
 
+/// How the hand fans out. Cards sit on an arc of a circle centered below the hand, `spacing`
+/// apart, and tilt with it.
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct HandLayout {
+    /// Distance between neighbouring cards along the arc.
+    pub spacing: f32,
+    /// Widest the fan gets; bigger hands are squeezed closer together.
+    pub max_width: f32,
+    pub arc_radius: f32,
+    /// Scale of the hovered card.
+    pub hover_scale: f32,
+    /// How far the hovered card rises out of the fan. Kept below the height the card gains
+    /// from `hover_scale` (95 * 0.3), so its bottom edge never rises off the cursor.
+    pub hover_rise: f32,
+    pub hover_duration: Duration,
+}
+
+impl Default for HandLayout {
+    fn default() -> Self {
+        Self {
+            spacing: 90.,
+            max_width: 600.,
+            arc_radius: 900.,
+            hover_scale: 1.3,
+            hover_rise: 25.,
+            hover_duration: Duration::from_millis(100),
+        }
+    }
+}
+
+/// What happens to a card drawn into a full hand.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Overdraw {
+    /// It goes straight from the library to the discard.
+    Burn,
+    /// It stays in the library.
+    Block,
+}
+
+/// The most cards a hand can hold, and what each game mode does past that. Games against the
+/// `Opponent` follow `hotseat`.
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct HandLimit {
+    pub max: usize,
+    pub solo: Overdraw,
+    pub hotseat: Overdraw,
+}
+
+impl Default for HandLimit {
+    fn default() -> Self {
+        Self {
+            max: 10,
+            solo: Overdraw::Burn,
+            // NOTE: burning would let one player thin out their library faster than the other
+            hotseat: Overdraw::Block,
+        }
+    }
+}
+
+impl HandLimit {
+    pub fn overdraw(&self, mode: GameMode) -> Overdraw {
+        match mode {
+            GameMode::Solo | GameMode::Endless => self.solo,
+            GameMode::Hotseat | GameMode::Versus => self.hotseat,
+        }
+    }
+}
+
+/// A card was drawn into a full hand. `burned` is the card sent to the discard instead, if the
+/// game mode burns them.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct HandFull {
+    pub owner: PlayerId,
+    pub burned: Option<Entity>,
+}
+
+/// Flashes over the owner's hand when it's full. Not one of the hand's children, which are all
+/// cards.
+#[derive(Component)]
+pub struct HandWarning;
+
+const HAND_WARNING_COLOR: Color = Color::rgba(1., 0.2, 0.2, 0.45);
+
 pub struct HandPlugin;
 
 impl Plugin for HandPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(AppState::Playing), spawn_hand)
-            .add_systems(Update, component_animator_system::<Transform>)
-            .add_systems(OnExit(AppState::Playing), reset_hand)
+        app.init_resource::<HandLayout>()
+            .init_resource::<HandLimit>()
+            .add_event::<HandFull>()
+            .add_systems(OnEnter(AppState::Playing), spawn_hand)
+            .add_systems(OnExit(AppState::Playing), reset_hand_events)
             .add_systems(
                 Update,
                 (
-                    position_cards.before(draw_card),
-                    (pickable_lerp, select_card).run_if(in_state(GameState::Playing)),
+                    position_cards.in_set(CardSet::Layout),
+                    flash_full_hand.after(CardSet::Transfer),
+                    (
+                        pickable_lerp.after(CardSet::Transfer),
+                        select_card.in_set(CardSet::Transfer).before(cycle_rule),
+                    )
+                        .in_set(PhaseSet::Play)
+                        .run_if(in_state(PauseState::Running))
+                        .run_if(not(resource_exists::<PileBrowserOpen>()))
+                        .run_if(not(opponent_turn)),
                 )
                     .run_if(in_state(AppState::Playing)),
             );
@@ -76,49 +166,127 @@ impl Plugin for HandPlugin {
 }
 
 //spawn deck when deck plugin is made
-fn spawn_hand(mut commands: Commands) {
-    commands
-        .spawn((
-            SpatialBundle {
-                transform: Transform::from_xyz(0., -(board::config::SIZE.y + 190. + 50.) / 2., 0.),
-                ..Default::default()
+fn spawn_hand(mut commands: Commands, mode: Res<GameMode>, anchors: Res<ZoneAnchors>) {
+    let transform = anchors.seat(ZoneKind::Hand);
+    for seat in mode.seats() {
+        commands
+            .spawn((
+                SpatialBundle {
+                    transform,
+                    ..Default::default()
+                },
+                RenderLayers::layer(1),
+                Owner(seat),
+                ZoneKind::Hand,
+                Seat(transform),
+                GameplayEntity,
+            ))
+            .insert(Hand {
+                selected: None,
+                hovered: None,
+            });
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: HAND_WARNING_COLOR.with_a(0.),
+                    custom_size: Some(Vec2::new(760., 260.)),
+                    ..default()
+                },
+                ..default()
             },
             RenderLayers::layer(1),
-        ))
-        .insert(Hand {
-            selected: None,
-            hovered: None,
-        });
+            HandWarning,
+            Owner(seat),
+            GameplayEntity,
+        ));
+    }
+}
+
+/// Moves the warning under the full hand, wherever its zone is seated now, and flashes it.
+fn flash_full_hand(
+    mut cmd: Commands,
+    mut reader: EventReader<HandFull>,
+    zones: Res<Zones>,
+    q_hands: Query<&Transform, (With<Hand>, Without<HandWarning>)>,
+    mut q_warnings: Query<(Entity, &Owner, &mut Transform), With<HandWarning>>,
+) {
+    for event in reader.read() {
+        let Some(hand_t) = zones
+            .get(event.owner, ZoneKind::Hand)
+            .and_then(|hand| q_hands.get(hand).ok())
+        else {
+            continue;
+        };
+        for (entity, _, mut transform) in
+            q_warnings.iter_mut().filter(|(_, o, _)| o.0 == event.owner)
+        {
+            *transform = hand_t.with_translation(hand_t.translation - Vec3::Z);
+            let flash = Tween::new(
+                EaseFunction::QuadraticOut,
+                Duration::from_millis(500),
+                SpriteColorLens {
+                    start: HAND_WARNING_COLOR,
+                    end: HAND_WARNING_COLOR.with_a(0.),
+                },
+            );
+            cmd.entity(entity).insert(Animator::new(flash));
+        }
+    }
+}
+
+fn reset_hand_events(mut hand_full: ResMut<Events<HandFull>>) {
+    hand_full.clear();
 }
 //whenever hand is updated position cards in hand that are not selected by ord using a tween
 fn position_cards(
-    mut cmd: Commands,
     q_hand: Query<(&Hand, &Children)>,
-    mut q_cards: Query<(Entity, &Card, &mut Transform)>,
-    mut q_flipping: Query<&Flipping>,
+    mut q_cards: Query<(Entity, &Card, &TransformDriver, &mut Transform)>,
+    layout: Res<HandLayout>,
+    mut writes: ResMut<TransformWrites>,
 ) {
-    if q_hand.is_empty() {
-        return;
-    }
+    #[cfg(feature = "trace")]
+    let _span = info_span!(
+        "position_hand_cards",
+        cards = q_hand.iter().map(|(_, c)| c.len()).sum::<usize>()
+    )
+    .entered();
 
-    let (hand, children) = q_hand.single();
+    for (hand, children) in q_hand.iter() {
+        position_hand(children, hand.hovered, &layout, &mut q_cards, &mut writes);
+    }
+}
+fn position_hand(
+    children: &Children,
+    hovered: Option<Entity>,
+    layout: &HandLayout,
+    q_cards: &mut Query<(Entity, &Card, &TransformDriver, &mut Transform)>,
+    writes: &mut TransformWrites,
+) {
     let hand_size = children.len();
-    let arc_length = 180.0;
-    let rotation_factor = 30.; // Adjust the rotation factor as desired
-
-    let width = (hand_size * 80).clamp(0, 600);
+    let gaps = hand_size.saturating_sub(1).max(1) as f32;
+    let spacing = layout.spacing.min(layout.max_width / gaps);
+    let step = spacing / layout.arc_radius;
+    let middle = (hand_size as f32 - 1.) / 2.;
 
     for (i, &child) in children.iter().enumerate() {
-        if let Ok((entity, card, mut transform)) = q_cards.get_mut(child) {
-            if hand.selected == Some(entity) {
+        if let Ok((entity, card, driver, mut transform)) = q_cards.get_mut(child) {
+            if *driver != TransformDriver::Layout {
                 continue;
             }
+            writes.claim(entity, TransformDriver::Layout);
 
-            let angle = (i as f32 / (hand_size as f32)) * arc_length;
-            let x = i as f32 / hand_size as f32 * width as f32 - 300.;
-            let y = angle.to_radians().sin() * 40.0; // Calculate y position along the arc
+            let angle = (i as f32 - middle) * step;
+            let x = layout.arc_radius * angle.sin();
+            let mut y = layout.arc_radius * (angle.cos() - 1.);
+            let mut z = i as f32 * 10.;
+            let mut rot = angle.to_degrees();
+            // NOTE: the hovered card straightens up and comes out in front so it can be read
+            if hovered == Some(entity) {
+                y += layout.hover_rise;
+                z = hand_size as f32 * 10.;
+                rot = 0.;
+            }
 
-            let mut rot = i as f32 / hand_size as f32 * rotation_factor - rotation_factor / 2.;
             if !card.face_up {
                 rot *= -1.;
             } else {
@@ -126,117 +294,168 @@ fn position_cards(
             }
             transform.translation.x = transform.translation.x.lerp(&x, &0.2);
             transform.translation.y = transform.translation.y.lerp(&y, &0.2);
-            transform.translation.z = i as f32 * 10.;
-            if !q_flipping.contains(entity) {
-                transform.rotation = transform.rotation.lerp(
-                    Quat::from_euler(EulerRot::XYZ, PI, 0., rot.to_radians()),
-                    0.2,
-                );
-            }
+            transform.translation.z = z;
+            transform.rotation = transform.rotation.lerp(
+                Quat::from_euler(EulerRot::XYZ, PI, 0., rot.to_radians()),
+                0.2,
+            );
         }
     }
 }
-//whenever a card is selected move it toward the target
+//whenever a card is selected move it toward the target and straighten it
 fn pickable_lerp(
-    mut q_hand: Query<(&Hand, &Transform), (Without<Card>)>,
-    mut q_cards: Query<(Entity, &Card, &mut Transform)>,
-    mut q_camera: Query<(&Camera, &GlobalTransform), With<CardCamera>>,
-    mut q_window: Query<&Window, With<PrimaryWindow>>,
+    q_hand: Query<(&Hand, &Transform), Without<Card>>,
+    zones: Res<Zones>,
+    active: Res<ActivePlayer>,
+    mut q_cards: Query<(Entity, &Card, &TransformDriver, &mut Transform)>,
+    pointer: Res<Pointer>,
+    mut writes: ResMut<TransformWrites>,
 ) {
-    if let Ok((hand_comp, hand_transform)) = q_hand.get_single() {
+    #[cfg(feature = "trace")]
+    let _span = info_span!("pickable_lerp").entered();
+
+    let Some(hand_e) = zones.get(active.0, ZoneKind::Hand) else {
+        return;
+    };
+    if let Ok((hand_comp, hand_transform)) = q_hand.get(hand_e) {
         let Some(selected) = hand_comp.selected else {
             return;
         };
 
-        if let Some(pos) = q_window.single().cursor_position() {
-            let (camera, camera_transform) = q_camera.single();
-            if let Some(world_pos) = camera.viewport_to_world_2d(camera_transform, pos) {
-                let world_pos = world_pos
-                    - Vec2::new(hand_transform.translation.x, hand_transform.translation.y);
+        if let Some(world_pos) = pointer.cards {
+            let world_pos =
+                world_pos - Vec2::new(hand_transform.translation.x, hand_transform.translation.y);
+
+            if let Ok((entity, card, driver, mut transform)) = q_cards.get_mut(selected) {
+                if *driver != TransformDriver::Drag {
+                    return;
+                }
+                writes.claim(entity, TransformDriver::Drag);
 
-                if let Ok((entity, card, mut transform)) = q_cards.get_mut(selected) {
-                    transform.translation.x = transform.translation.x.lerp(&world_pos.x, &0.2);
-                    transform.translation.y = transform.translation.y.lerp(&world_pos.y, &0.2);
-                    transform.translation.z = 100.
+                transform.translation.x = transform.translation.x.lerp(&world_pos.x, &0.2);
+                transform.translation.y = transform.translation.y.lerp(&world_pos.y, &0.2);
+                transform.translation.z = 100.;
+
+                let before = transform.rotation.to_euler(EulerRot::XYZ);
+                let mut rot: f32 = 0.;
+                if card.face_up {
+                    rot = 180.;
                 }
+                transform.rotation = transform.rotation.lerp(
+                    Quat::from_euler(EulerRot::XYZ, before.0, before.1, rot.to_radians()),
+                    0.2,
+                );
             }
         }
     }
 }
 
-fn select_card(
+/// The topmost of a hand's `cards` under `world_pos`. Cards are given with their transform
+/// relative to the hand, bottom first.
+pub fn card_at<'a>(
+    world_pos: Vec2,
+    hand_transform: &Transform,
+    cards: impl IntoIterator<Item = (Entity, &'a Transform)>,
+) -> Option<Entity> {
+    //card is 140,190
+    let half_width = 70.;
+    let half_height = 95.;
+    let mut topmost = None;
+    for (entity, transform) in cards {
+        let rotated_bounds = calculate_rotated_bounds(transform, half_width, half_height)
+            .map(|corner| hand_transform.translation.xy() + corner);
+        if point_in_polygon(world_pos, &rotated_bounds) {
+            topmost = Some(entity);
+        }
+    }
+    topmost
+}
+
+pub fn select_card(
     mut cmd: Commands,
-    mut actions: Query<&ActionState<Actions>>,
+    actions: Res<ActionState<GameAction>>,
     mut q_hand: Query<(&mut Hand, &mut Children, &Transform)>,
-    mut q_window: Query<&Window, (With<PrimaryWindow>, Without<Discard>)>,
-    mut q_cards: Query<(Entity, &Card, &mut Transform), Without<Hand>>,
-    mut q_camera: Query<(&Camera, &GlobalTransform), With<CardCamera>>,
-    mut q_rules: Query<(Entity, &Transform), (With<Rule>, Without<Card>)>,
-    mut add_rule: EventWriter<AddRule>,
+    mut q_cards: Query<(Entity, &Card, &mut Transform, &mut TransformDriver), Without<Hand>>,
+    q_focused: Query<(), With<Focused>>,
+    pointer: Res<Pointer>,
+    layout: Res<HandLayout>,
+    mut play_card: EventWriter<PlayCard>,
+    zones: Res<Zones>,
+    active: Res<ActivePlayer>,
+    mut missing_hand: Local<ThrottledWarn>,
 ) {
-    if q_hand.is_empty() {
+    let Some(Ok((mut hand, children, hand_transform))) = zones
+        .get(active.0, ZoneKind::Hand)
+        .map(|e| q_hand.get_mut(e))
+    else {
+        missing_hand.warn("select_card: active player has no Hand, cards can't be selected");
         return;
-    }
-
-    let (mut hand, children, hand_transform) = q_hand.single_mut();
-    let action_state = actions.single();
+    };
     let mut hovered_entity = None;
 
     if hand.selected.is_none() {
-        if let Some(pos) = q_window.single().cursor_position() {
-            let (camera, camera_transform) = q_camera.single();
-            if let Some(world_pos) = camera.viewport_to_world_2d(camera_transform, pos) {
-                for &child in children.iter() {
-                    //get the topmost hovered card
-                    if let Ok((entity, card, transform)) = q_cards.get_mut(child) {
-                        //card is 140,190
-                        let half_width = 70.;
-                        let half_height = 95.;
-                        let rotated_bounds = calculate_rotated_bounds(
-                            &transform,
-                            half_width,
-                            half_height,
-                        )
-                        .map(|corner| {
-                            Vec2::new(hand_transform.translation.x, hand_transform.translation.y)
-                                + corner
-                        });
-
-                        if point_in_polygon(world_pos, &rotated_bounds) {
-                            hovered_entity = Some(entity);
-                        }
-                    }
-                }
-            }
+        if let Some(world_pos) = pointer.cards {
+            hovered_entity = card_at(
+                world_pos,
+                hand_transform,
+                children
+                    .iter()
+                    .filter_map(|&child| q_cards.get(child).ok().map(|(e, _, t, _)| (e, t))),
+            );
+        }
+        // NOTE: the gamepad's focused card looks hovered while the mouse isn't over another
+        if hovered_entity.is_none() {
+            hovered_entity = children
+                .iter()
+                .copied()
+                .find(|&child| q_focused.contains(child));
         }
         if hovered_entity != hand.hovered {
+            // NOTE: a card being flipped keeps its flip's tween, and is scaled once it's hovered
+            // again
             if let Some(h) = hand.hovered {
-                if let Ok((entity, card, transform)) = q_cards.get(h) {
+                if let Some((entity, _card, transform, mut driver)) = q_cards
+                    .get_mut(h)
+                    .ok()
+                    .filter(|(.., driver)| **driver != TransformDriver::Flip)
+                {
                     let tween = Tween::new(
                         EaseFunction::QuadraticInOut,
-                        Duration::from_millis(100),
+                        layout.hover_duration,
                         TransformScaleLens {
                             start: transform.scale,
-                            end: Vec3::new(1., 1., 1.),
+                            end: Vec3::ONE,
                         },
-                    );
+                    )
+                    .with_completed_event(RELEASE_TWEEN_DRIVER);
 
-                    cmd.entity(hand.hovered.unwrap())
-                        .insert(Animator::new(tween));
+                    if *driver == TransformDriver::Layout {
+                        *driver = TransformDriver::Tween;
+                    }
+                    cmd.entity(entity).insert(Animator::new(tween));
                 }
             }
 
             hand.hovered = hovered_entity;
             if let Some(h) = hand.hovered {
-                if let Ok((entity, card, transform)) = q_cards.get(h) {
+                if let Some((entity, _card, transform, mut driver)) = q_cards
+                    .get_mut(h)
+                    .ok()
+                    .filter(|(.., driver)| **driver != TransformDriver::Flip)
+                {
                     let tween = Tween::new(
                         EaseFunction::QuadraticInOut,
-                        Duration::from_millis(100),
+                        layout.hover_duration,
                         TransformScaleLens {
                             start: transform.scale,
-                            end: Vec3::new(1.1, 1.1, 1.),
+                            end: Vec3::new(layout.hover_scale, layout.hover_scale, 1.),
                         },
-                    );
+                    )
+                    .with_completed_event(RELEASE_TWEEN_DRIVER);
+
+                    if *driver == TransformDriver::Layout {
+                        *driver = TransformDriver::Tween;
+                    }
                     cmd.entity(entity).insert(Animator::new(tween));
                 }
             }
@@ -246,48 +465,33 @@ fn select_card(
         //         card: hand.hovered.unwrap(),
         //     });
         // }
-        if action_state.just_pressed(Actions::Select) && hand.hovered.is_some() {
+        if actions.just_pressed(GameAction::Select) && hand.hovered.is_some() {
             hand.selected = hand.hovered;
 
-            if let Ok((entity, card, transform)) = q_cards.get(hand.selected.unwrap()) {
-                //straigten the card
-                let before = transform.rotation.to_euler(EulerRot::XYZ);
-                let mut rot: f32 = 0.;
-                if card.face_up {
-                    rot = 180.;
-                }
-                let tween = Tween::new(
-                    EaseFunction::QuadraticInOut,
-                    Duration::from_millis(250),
-                    TransformRotationLens {
-                        start: transform.rotation,
-                        end: Quat::from_euler(EulerRot::XYZ, before.0, before.1, rot.to_radians()),
-                    },
-                );
-                cmd.entity(entity).insert(Animator::new(tween));
+            // dragging straightens the card, see `pickable_lerp`
+            if let Ok((_, _, _, mut driver)) = q_cards.get_mut(hand.selected.unwrap()) {
+                *driver = TransformDriver::Drag;
             }
         }
     }
 
-    let select_released = action_state.just_released(Actions::Select);
+    if actions.just_pressed(GameAction::Cancel) && hand.selected.is_some() {
+        if let Ok((_, _, _, mut driver)) = q_cards.get_mut(hand.selected.unwrap()) {
+            *driver = TransformDriver::Layout;
+        }
+        hand.selected = None;
+    }
+
+    let select_released = actions.just_released(GameAction::Select);
     if select_released && hand.selected.is_some() {
-        if let Ok((entity, card, mut card_transform)) = q_cards.get_mut(hand.selected.unwrap()) {
-            let g_x = card_transform.translation.x + hand_transform.translation.x;
-            let g_y = card_transform.translation.y + hand_transform.translation.y;
-            if point_in_board(g_x, g_y, config::SIZE, config::CENTER) {
-                let (rules_e, rules_t) = q_rules.single();
-
-                cmd.entity(entity).remove_parent();
-                card_transform.translation.x +=
-                    -rules_t.translation.x + hand_transform.translation.x;
-                card_transform.translation.y +=
-                    -rules_t.translation.y + hand_transform.translation.y;
-
-                cmd.entity(rules_e).insert_children(0, &[entity]);
-
-                cmd.insert_resource(NextState(Some(GameState::Discard)));
-                add_rule.send(AddRule {
-                    rule: card.operation.clone(),
+        if let Ok((entity, _, _, mut driver)) = q_cards.get_mut(hand.selected.unwrap()) {
+            // NOTE: a card dropped anywhere but the board is laid out back into the hand
+            *driver = TransformDriver::Layout;
+
+            if let Some(board_pos) = pointer.over_board() {
+                play_card.send(PlayCard {
+                    card: entity,
+                    board_pos,
                 });
             }
         }
@@ -295,6 +499,3 @@ fn select_card(
         hand.selected = None;
     }
 }
-pub fn reset_hand(mut cmd: Commands, q_hand: Query<Entity, With<Hand>>) {
-    cmd.entity(q_hand.single()).despawn_recursive();
-}
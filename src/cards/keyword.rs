@@ -0,0 +1,100 @@
+//! Keywords a card definition can list, each changing what happens to the card around the end of
+//! a turn or once it's played. The `KeywordRegistry` holds what the tooltips say about them, and
+//! picks them out of rules text so they can be highlighted.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Keyword {
+    /// Goes to the exhaust pile if it's still in the hand when the turn ends.
+    Ethereal,
+    /// Stays in the hand when the rest of it is discarded.
+    Retain,
+    /// Goes to the exhaust pile once played, instead of the discard.
+    Exhaust,
+}
+
+#[derive(Clone, Debug)]
+pub struct KeywordInfo {
+    pub keyword: Keyword,
+    /// How the keyword is written in rules text.
+    pub name: String,
+    pub description: String,
+    pub color: Color,
+}
+
+/// Every keyword and how it's shown.
+#[derive(Resource, Debug)]
+pub struct KeywordRegistry(pub Vec<KeywordInfo>);
+
+impl Default for KeywordRegistry {
+    fn default() -> Self {
+        let info = |keyword, description: &str, color| KeywordInfo {
+            keyword,
+            name: format!("{keyword:?}"),
+            description: description.to_string(),
+            color,
+        };
+        Self(vec![
+            info(
+                Keyword::Ethereal,
+                "If this is still in your hand at the end of your turn, it's removed for the rest \
+                 of the level.",
+                Color::rgb(0.6, 0.8, 1.),
+            ),
+            info(
+                Keyword::Retain,
+                "Stays in your hand at the end of your turn.",
+                Color::rgb(0.5, 1., 0.5),
+            ),
+            info(
+                Keyword::Exhaust,
+                "Removed for the rest of the level once played.",
+                Color::rgb(1., 0.5, 0.4),
+            ),
+        ])
+    }
+}
+
+impl KeywordRegistry {
+    pub fn get(&self, keyword: Keyword) -> Option<&KeywordInfo> {
+        self.0.iter().find(|info| info.keyword == keyword)
+    }
+
+    /// `text` split into sections in `style`, with every keyword name in its own color.
+    pub fn highlight(&self, text: &str, style: &TextStyle) -> Vec<TextSection> {
+        let mut sections = Vec::new();
+        let mut rest = text;
+        while !rest.is_empty() {
+            let next = self
+                .0
+                .iter()
+                .filter_map(|info| rest.find(&info.name).map(|at| (at, info)))
+                .min_by_key(|(at, _)| *at);
+            let Some((at, info)) = next else {
+                sections.push(TextSection::new(rest, style.clone()));
+                break;
+            };
+            if at > 0 {
+                sections.push(TextSection::new(&rest[..at], style.clone()));
+            }
+            sections.push(TextSection::new(
+                info.name.clone(),
+                TextStyle {
+                    color: info.color,
+                    ..style.clone()
+                },
+            ));
+            rest = &rest[at + info.name.len()..];
+        }
+        sections
+    }
+}
+
+pub struct KeywordPlugin;
+impl Plugin for KeywordPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<KeywordRegistry>();
+    }
+}
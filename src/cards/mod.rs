@@ -1,120 +1,164 @@
 use std::time::Duration;
 
-use bevy::{ecs::system::Command, prelude::*};
-use leafwing_input_manager::{
-    prelude::{ActionState, InputManagerPlugin, InputMap},
-    Actionlike, InputManagerBundle,
-};
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::ActionState;
+use serde::{Deserialize, Serialize};
 
 use self::{
     card::CardPlugin,
+    collection::CollectionPlugin,
     deck::DeckPlugin,
+    definition::CardDefinitionPlugin,
+    draft::DraftPlugin,
+    effect::CardEffectPlugin,
+    energy::EnergyPlugin,
+    focus::FocusPlugin,
     goals::{Goals, GoalsPlugin},
-    hand::HandPlugin,
+    hand::{Hand, HandPlugin},
+    keyword::KeywordPlugin,
+    mulligan::MulliganPlugin,
+    opponent::{opponent_turn, OpponentPlugin},
+    phase::{advance_phase, EndPhase, PhasePlugin, PhaseSet},
     rules::RulePlugin,
+    search::SearchPlugin,
+    transfer::TransferPlugin,
+    zones::{ActivePlayer, GameMode, Owner, Winner, ZonesPlugin},
 };
 use super::ui::StartText;
-use crate::{board::IsOnBoard, game_shapes::Shape, AppState};
+use crate::{
+    board::IsOnBoard, game_shapes::Shape, input::GameAction, pause::PauseState, score::Score,
+    AppState,
+};
 
 pub mod card;
+pub mod collection;
 pub mod deck;
+pub mod definition;
+pub mod draft;
+pub mod effect;
+pub mod energy;
+pub mod focus;
 pub mod goals;
 pub mod hand;
+pub mod keyword;
+pub mod mulligan;
+pub mod opponent;
+pub mod phase;
 pub mod rules;
+pub mod search;
+pub mod transfer;
+pub mod zones;
 
-#[derive(States, Default, Clone, Eq, PartialEq, Debug, Hash, Reflect)]
+#[derive(States, Default, Clone, Eq, PartialEq, Debug, Hash, Reflect, Serialize, Deserialize)]
 pub enum GameState {
     #[default]
     Setup,
     Start,
     Draw,
-    Playing,
+    /// Before their first turn, a player may send cards from their opening hand back to be
+    /// redrawn. See `mulligan`.
+    Mulligan,
+    Play,
+    /// A played card's effects settle before the hand is discarded.
+    Resolve,
     Discard,
+    /// Every few turns, the active player picks a rule to add. See `draft`.
+    Draft,
+    /// Between two-player turns. In hotseat, waiting for the next player to take the device;
+    /// against the `Opponent`, over straight away.
+    PassDevice,
     Scoring,
 }
 
-#[derive(Actionlike, PartialEq, Eq, Clone, Copy, Hash, Debug, Reflect)]
-pub enum Actions {
-    Select,
-    Play,
+/// Card systems move cards between zones in `Transfer` and position them in `Layout`, so a card
+/// that changed zone is laid out in its new zone on the same frame. Events sent within
+/// `Transfer` (`SpawnCard`, `DrawCard`, `ShuffleDiscard`, `AddRule`) are ordered to be read the
+/// frame they're sent, and the `ZoneTransfer`s they lead to are applied between the two sets.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CardSet {
+    Transfer,
+    Layout,
 }
+
 #[derive(Resource)]
 pub struct GameTimer {
     pub timer: Timer,
 }
-#[derive(Resource)]
-pub struct Score {
-    pub score: u32,
-    pub base_score: u32,
-    pub goal_status: Vec<bool>,
-    pub cards_played: u32,
-}
-impl Score {
-    pub fn reset(&mut self) {
-        self.score = 0;
-        self.base_score = 0;
-        self.goal_status = vec![false, false, false];
-        self.cards_played = 0;
-    }
-}
 
 pub struct CardsPlugin;
 impl Plugin for CardsPlugin {
     fn build(&self, app: &mut App) {
         app.add_state::<GameState>()
-            .insert_resource(Score {
-                score: 0,
-                base_score: 0,
-                goal_status: vec![false, false, false],
-                cards_played: 0,
-            })
+            .configure_sets(Update, (CardSet::Transfer, CardSet::Layout).chain())
             .insert_resource(GameTimer {
                 timer: Timer::new(Duration::from_secs(120), TimerMode::Once),
             })
-            .add_plugins((DeckPlugin, HandPlugin, CardPlugin, RulePlugin, GoalsPlugin))
-            .add_systems(OnEnter(AppState::Playing), setup_input)
-            .add_systems(Update, start_game.run_if(in_state(GameState::Start)))
+            .add_plugins((
+                DeckPlugin,
+                HandPlugin,
+                CardPlugin,
+                CardDefinitionPlugin,
+                CardEffectPlugin,
+                RulePlugin,
+                GoalsPlugin,
+                ZonesPlugin,
+                PhasePlugin,
+                DraftPlugin,
+                FocusPlugin,
+                EnergyPlugin,
+                CollectionPlugin,
+                KeywordPlugin,
+                MulliganPlugin,
+            ))
+            .add_plugins((TransferPlugin, SearchPlugin, OpponentPlugin))
+            .add_systems(
+                Update,
+                (
+                    start_game.run_if(in_state(GameState::Start)),
+                    pass_device
+                        .run_if(in_state(GameState::PassDevice))
+                        .run_if(resource_equals(GameMode::Hotseat)),
+                    skip_play.in_set(PhaseSet::Play).run_if(not(opponent_turn)),
+                )
+                    .before(advance_phase)
+                    .run_if(in_state(PauseState::Running)),
+            )
+            .add_systems(OnEnter(GameState::PassDevice), end_turn)
             .add_systems(OnExit(AppState::Playing), reset_resources)
             .add_systems(
                 Update,
                 time_game
                     .run_if(not(in_state(GameState::Setup)))
-                    .run_if(not(in_state(GameState::Start))),
-            )
-            .add_plugins(InputManagerPlugin::<Actions>::default());
+                    .run_if(not(in_state(GameState::Start)))
+                    .run_if(not(in_state(GameState::Draft)))
+                    .run_if(not(in_state(GameState::Mulligan)))
+                    .run_if(not(in_state(GameState::PassDevice))),
+            );
     }
 }
 pub fn reset_resources(
     mut cmd: Commands,
     mut game_timer: ResMut<GameTimer>,
     mut score: ResMut<Score>,
-    mut q_actions: Query<Entity, With<ActionState<Actions>>>,
+    mut active: ResMut<ActivePlayer>,
+    mut winner: ResMut<Winner>,
 ) {
     cmd.insert_resource(NextState(Some(GameState::Setup)));
-    cmd.entity(q_actions.single()).despawn_recursive();
 
     game_timer.timer.reset();
     score.reset();
-}
-pub fn setup_input(mut cmd: Commands) {
-    let mut input_map = InputMap::new([(MouseButton::Left, Actions::Select)]);
-    input_map.insert(KeyCode::Space, Actions::Play);
-
-    cmd.spawn((InputManagerBundle::<Actions> {
-        action_state: ActionState::default(),
-        input_map,
-    },));
+    *active = ActivePlayer::default();
+    **winner = None;
 }
 pub fn start_game(
-    mut cmd: Commands,
-    mut actions: Query<&ActionState<Actions>>,
+    actions: Res<ActionState<GameAction>>,
     mut q_start_text: Query<&mut Visibility, With<StartText>>,
+    mut end_phase: EventWriter<EndPhase>,
 ) {
-    let action_state = actions.single();
-    if action_state.just_pressed(Actions::Play) {
+    if actions.just_pressed(GameAction::Play) {
         let mut v = q_start_text.single_mut();
         *v = Visibility::Hidden;
-        cmd.insert_resource(NextState(Some(GameState::Draw)));
+        end_phase.send(EndPhase);
     }
 }
 pub fn time_game(mut cmd: Commands, time: Res<Time>, mut game_timer: ResMut<GameTimer>) {
@@ -123,3 +167,44 @@ pub fn time_game(mut cmd: Commands, time: Res<Time>, mut game_timer: ResMut<Game
         cmd.insert_resource(NextState(Some(GameState::Scoring)));
     }
 }
+/// Ends a two-player turn: the first player, starting with the one who just played, to have met
+/// all of their goals wins. Otherwise the turn goes to the next player.
+pub fn end_turn(
+    mut cmd: Commands,
+    mode: Res<GameMode>,
+    mut active: ResMut<ActivePlayer>,
+    mut winner: ResMut<Winner>,
+    q_goals: Query<(&Goals, &Owner)>,
+    q_shapes: Query<&Shape, With<IsOnBoard>>,
+) {
+    let next = mode.next(active.0);
+    for player in [active.0, next] {
+        let met = q_goals
+            .iter()
+            .filter(|(_, owner)| owner.0 == player)
+            .all(|(goals, _)| goals.iter().all(|goal| goal.is_met(q_shapes.iter())));
+        if met {
+            **winner = Some(player);
+            cmd.insert_resource(NextState(Some(GameState::Scoring)));
+            return;
+        }
+    }
+    active.0 = next;
+}
+pub fn pass_device(actions: Res<ActionState<GameAction>>, mut end_phase: EventWriter<EndPhase>) {
+    if actions.just_pressed(GameAction::Play) {
+        end_phase.send(EndPhase);
+    }
+}
+/// Ends the play phase without playing a card, so the hand is discarded and a new one drawn.
+/// Not while a card is held.
+pub fn skip_play(
+    actions: Res<ActionState<GameAction>>,
+    q_hands: Query<&Hand>,
+    mut end_phase: EventWriter<EndPhase>,
+) {
+    if actions.just_pressed(GameAction::Draw) && q_hands.iter().all(|hand| hand.selected.is_none())
+    {
+        end_phase.send(EndPhase);
+    }
+}
@@ -0,0 +1,279 @@
+//! The mulligan before each player's first turn. Clicking a card in the opening hand marks it to
+//! go back, and once the player confirms on the mulligan screen the marked cards go under their
+//! library and as many are drawn in their place. It only happens once per player: the redrawn
+//! hand is kept.
+
+use bevy::{prelude::*, render::view::RenderLayers};
+use leafwing_input_manager::prelude::ActionState;
+
+use super::{
+    card::Card,
+    deck::{draw_to_hand_size, DrawCard},
+    hand::{card_at, Hand},
+    opponent::opponent_turn,
+    phase::{EndPhase, PhaseSet},
+    transfer::{TransferReason, ZoneTransfer},
+    zones::{ActivePlayer, ZoneKind, Zones},
+    CardSet, GameState,
+};
+use crate::{
+    audio::{PlaySound, Sfx},
+    input::GameAction,
+    loading::FontAssets,
+    pause::PauseState,
+    pointer::Pointer,
+    ui::PileBrowserOpen,
+    utils::ThrottledWarn,
+    AppState,
+};
+
+const KEEP_COLOR: Color = Color::rgb(0.2, 0.5, 0.2);
+const REDRAW_COLOR: Color = Color::rgb(0.7, 0.2, 0.2);
+/// Seconds between redrawn cards, the same pace as the opening hand.
+const REDRAW_INTERVAL: f32 = 0.1;
+
+/// The active player's choice. Present only during `GameState::Mulligan`.
+#[derive(Resource, Debug)]
+pub struct MulliganSelection {
+    /// Cards in the active hand marked to go back.
+    pub cards: Vec<Entity>,
+    /// Cards still to draw, once the player has confirmed.
+    pub redraws: Option<usize>,
+    timer: Timer,
+}
+
+/// Puts these cards from the active hand under the library and draws as many, ending the
+/// mulligan. Sent by the mulligan screen; an empty list keeps the hand.
+#[derive(Event, Clone, Debug)]
+pub struct Mulligan {
+    pub cards: Vec<Entity>,
+}
+
+/// Shows whether `card` is kept or redrawn. On the card's front, while the mulligan lasts.
+#[derive(Component)]
+pub struct MulliganToggle {
+    card: Entity,
+}
+
+pub struct MulliganPlugin;
+impl Plugin for MulliganPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<Mulligan>()
+            .add_systems(OnEnter(GameState::Mulligan), start_mulligan)
+            .add_systems(OnExit(GameState::Mulligan), end_mulligan)
+            .add_systems(
+                Update,
+                (
+                    (
+                        toggle_card
+                            .run_if(not(resource_exists::<PileBrowserOpen>()))
+                            .run_if(not(opponent_turn)),
+                        take_mulligan,
+                        redraw_cards,
+                    )
+                        .chain()
+                        .in_set(CardSet::Transfer)
+                        .before(draw_to_hand_size)
+                        .run_if(in_state(PauseState::Running)),
+                    show_toggles.after(toggle_card),
+                )
+                    .in_set(PhaseSet::Mulligan)
+                    .run_if(in_state(AppState::Playing)),
+            )
+            .add_systems(OnExit(AppState::Playing), reset_mulligan_events);
+    }
+}
+
+fn start_mulligan(
+    mut cmd: Commands,
+    zones: Res<Zones>,
+    active: Res<ActivePlayer>,
+    q_children: Query<&Children, With<Hand>>,
+    q_cards: Query<&Card>,
+    fonts: Res<FontAssets>,
+) {
+    cmd.insert_resource(MulliganSelection {
+        cards: Vec::new(),
+        redraws: None,
+        timer: Timer::from_seconds(REDRAW_INTERVAL, TimerMode::Repeating),
+    });
+    let Some(children) = zones
+        .get(active.0, ZoneKind::Hand)
+        .and_then(|hand| q_children.get(hand).ok())
+    else {
+        return;
+    };
+    for &child in children.iter() {
+        let Ok(card) = q_cards.get(child) else {
+            continue;
+        };
+        let toggle = cmd
+            .spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: KEEP_COLOR,
+                        custom_size: Some(Vec2::new(110., 30.)),
+                        ..default()
+                    },
+                    transform: Transform::from_xyz(0., -70., 2.),
+                    ..default()
+                },
+                MulliganToggle { card: child },
+                RenderLayers::layer(1),
+            ))
+            .with_children(|toggle| {
+                toggle.spawn((
+                    Text2dBundle {
+                        text: Text::from_section(
+                            "Keep",
+                            TextStyle {
+                                font: fonts.fira.clone(),
+                                font_size: 22.,
+                                color: Color::WHITE,
+                            },
+                        ),
+                        transform: Transform::from_xyz(0., 0., 1.),
+                        ..default()
+                    },
+                    RenderLayers::layer(1),
+                ));
+            })
+            .id();
+        cmd.entity(card.front).add_child(toggle);
+    }
+}
+
+fn end_mulligan(mut cmd: Commands, q_toggles: Query<Entity, With<MulliganToggle>>) {
+    cmd.remove_resource::<MulliganSelection>();
+    for entity in q_toggles.iter() {
+        cmd.entity(entity).remove_parent();
+        cmd.entity(entity).despawn_recursive();
+    }
+}
+
+/// Marks the clicked card in the active hand to be redrawn, or keeps it again.
+fn toggle_card(
+    actions: Res<ActionState<GameAction>>,
+    pointer: Res<Pointer>,
+    zones: Res<Zones>,
+    active: Res<ActivePlayer>,
+    q_hand: Query<(&Children, &Transform), With<Hand>>,
+    q_cards: Query<&Transform, With<Card>>,
+    mut selection: ResMut<MulliganSelection>,
+    mut sound: EventWriter<PlaySound>,
+) {
+    if selection.redraws.is_some() || !actions.just_pressed(GameAction::Select) {
+        return;
+    }
+    let (Some(world_pos), Some(Ok((children, hand_transform)))) = (
+        pointer.cards,
+        zones.get(active.0, ZoneKind::Hand).map(|e| q_hand.get(e)),
+    ) else {
+        return;
+    };
+    let clicked = card_at(
+        world_pos,
+        hand_transform,
+        children
+            .iter()
+            .filter_map(|&child| Some((child, q_cards.get(child).ok()?))),
+    );
+    let Some(clicked) = clicked else {
+        return;
+    };
+    match selection.cards.iter().position(|&card| card == clicked) {
+        Some(i) => {
+            selection.cards.remove(i);
+        },
+        None => selection.cards.push(clicked),
+    }
+    sound.send(PlaySound(Sfx::Click));
+}
+
+fn show_toggles(
+    selection: Option<Res<MulliganSelection>>,
+    mut q_toggles: Query<(&MulliganToggle, &mut Sprite, &Children)>,
+    mut q_text: Query<&mut Text>,
+) {
+    let Some(selection) = selection.filter(|s| s.is_changed()) else {
+        return;
+    };
+    for (toggle, mut sprite, children) in q_toggles.iter_mut() {
+        let redraw = selection.cards.contains(&toggle.card);
+        sprite.color = if redraw { REDRAW_COLOR } else { KEEP_COLOR };
+        let mut texts = q_text.iter_many_mut(children);
+        while let Some(mut text) = texts.fetch_next() {
+            text.sections[0].value = if redraw { "Redraw" } else { "Keep" }.to_string();
+        }
+    }
+}
+
+/// Sends the chosen cards face down under the library. They're drawn again one at a time by
+/// `redraw_cards`, since each draw takes the top card as it was at the start of the frame.
+pub fn take_mulligan(
+    mut reader: EventReader<Mulligan>,
+    zones: Res<Zones>,
+    active: Res<ActivePlayer>,
+    q_children: Query<&Children, Without<Card>>,
+    mut selection: ResMut<MulliganSelection>,
+    mut transfer: EventWriter<ZoneTransfer>,
+    mut sound: EventWriter<PlaySound>,
+    mut missing_zone: Local<ThrottledWarn>,
+) {
+    // NOTE: only the first choice counts, the hand is only redrawn once
+    let Some(event) = reader.read().last() else {
+        return;
+    };
+    if selection.redraws.is_some() {
+        return;
+    }
+    let (Some(hand_e), Some(library_e)) = (
+        zones.get(active.0, ZoneKind::Hand),
+        zones.get(active.0, ZoneKind::Library),
+    ) else {
+        missing_zone.warn("take_mulligan: active player is missing a Hand or Library zone");
+        return;
+    };
+    let Ok(hand) = q_children.get(hand_e) else {
+        return;
+    };
+
+    let mut returned = 0;
+    for &card in event.cards.iter().filter(|card| hand.contains(card)) {
+        transfer.send(ZoneTransfer {
+            card,
+            from: hand_e,
+            to: library_e,
+            reason: TransferReason::Mulligan,
+        });
+        returned += 1;
+    }
+    if returned > 0 {
+        sound.send(PlaySound(Sfx::Discard));
+    }
+    selection.redraws = Some(returned);
+}
+
+fn redraw_cards(
+    time: Res<Time>,
+    active: Res<ActivePlayer>,
+    mut selection: ResMut<MulliganSelection>,
+    mut writer: EventWriter<DrawCard>,
+    mut end_phase: EventWriter<EndPhase>,
+) {
+    let Some(redraws) = selection.redraws else {
+        return;
+    };
+    if redraws == 0 {
+        end_phase.send(EndPhase);
+        return;
+    }
+    if selection.timer.tick(time.delta()).just_finished() {
+        writer.send(DrawCard { owner: active.0 });
+        selection.redraws = Some(redraws - 1);
+    }
+}
+
+fn reset_mulligan_events(mut mulligan: ResMut<Events<Mulligan>>) {
+    mulligan.clear();
+}
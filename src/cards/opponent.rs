@@ -0,0 +1,266 @@
+//! The computer player of a versus game. It takes the second seat, with its own library, hand,
+//! discard and goals, and plays its turns through the same events the player's clicks send: it
+//! sends back the cards it has no use for in its opening hand, takes the first rule it's offered in
+//! a draft, and in `Play` alternates between playing its cheapest rule card and dropping shapes
+//! that work against the player's goals.
+
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+
+use super::{
+    card::Card,
+    draft::{take_draft, DraftOffer, DraftRule},
+    effect::{play_card, CardEffect, PlayCard},
+    energy::Energy,
+    goals::Goals,
+    mulligan::{take_mulligan, Mulligan, MulliganSelection},
+    phase::{advance_phase, EndPhase, PhaseSet},
+    zones::{ActivePlayer, GameMode, Owner, PlayerId, Winner, ZoneKind, Zones},
+    CardSet, GameState,
+};
+use crate::{
+    board::{IsOnBoard, SpawnBody, SpawnSource},
+    game_shapes::Shape,
+    pause::PauseState,
+    replay::Playback,
+    AppState,
+};
+
+/// Seconds the opponent waits before each choice, so the player can follow what it does.
+const THINK_TIME: f32 = 1.2;
+/// Shapes dropped on a turn the opponent doesn't play a card.
+const HOSTILE_SHAPES: usize = 3;
+/// How far from the middle of the board the hostile shapes are dropped.
+const HOSTILE_SPREAD: f32 = 150.;
+
+/// The computer player. Present only in `GameMode::Versus`.
+#[derive(Resource, Debug)]
+pub struct Opponent {
+    pub seat: PlayerId,
+    /// Turns the opponent has played. It plays a card on even turns and drops shapes on odd ones.
+    pub turns: u32,
+    think: Timer,
+}
+
+impl Opponent {
+    fn new(seat: PlayerId) -> Self {
+        Self {
+            seat,
+            turns: 0,
+            think: Timer::from_seconds(THINK_TIME, TimerMode::Once),
+        }
+    }
+
+    /// Whether the opponent has thought long enough this phase, on the one frame it has.
+    fn ready(&self) -> bool {
+        self.think.just_finished()
+    }
+}
+
+/// Ends the opponent's `Play` after it dropped its shapes, the way pressing Draw ends the
+/// player's.
+#[derive(Event, Clone, Copy, Debug, Default)]
+pub struct OpponentPass;
+
+pub struct OpponentPlugin;
+impl Plugin for OpponentPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<OpponentPass>()
+            .add_systems(OnEnter(AppState::Playing), join_opponent)
+            .add_systems(
+                OnEnter(GameState::Mulligan),
+                start_thinking.run_if(resource_exists::<Opponent>()),
+            )
+            .add_systems(
+                OnEnter(GameState::Play),
+                start_thinking.run_if(resource_exists::<Opponent>()),
+            )
+            .add_systems(
+                OnEnter(GameState::Draft),
+                start_thinking.run_if(resource_exists::<Opponent>()),
+            )
+            .add_systems(
+                Update,
+                (
+                    (
+                        think,
+                        (
+                            keep_opening_hand
+                                .in_set(PhaseSet::Mulligan)
+                                .before(take_mulligan),
+                            take_turn
+                                .in_set(PhaseSet::Play)
+                                .in_set(CardSet::Transfer)
+                                .before(play_card),
+                            pick_draft.in_set(PhaseSet::Draft).before(take_draft),
+                        ),
+                    )
+                        .chain()
+                        .run_if(opponent_turn)
+                        // NOTE: a replay plays back what the opponent did instead
+                        .run_if(not(resource_exists::<Playback>()))
+                        .run_if(in_state(PauseState::Running)),
+                    (
+                        end_opponent_play.in_set(PhaseSet::Play).after(take_turn),
+                        hand_over_turn
+                            .run_if(in_state(GameState::PassDevice))
+                            .run_if(|winner: Res<Winner>| winner.is_none()),
+                    )
+                        .before(advance_phase)
+                        .run_if(resource_exists::<Opponent>()),
+                )
+                    .run_if(in_state(AppState::Playing)),
+            )
+            .add_systems(OnExit(AppState::Playing), leave_opponent);
+    }
+}
+
+/// Whether it's the opponent's turn, for keeping the player's input out of it.
+pub fn opponent_turn(opponent: Option<Res<Opponent>>, active: Res<ActivePlayer>) -> bool {
+    opponent.is_some_and(|opponent| opponent.seat == active.0)
+}
+
+fn join_opponent(mut cmd: Commands, mode: Res<GameMode>) {
+    if *mode == GameMode::Versus {
+        cmd.insert_resource(Opponent::new(PlayerId(1)));
+    }
+}
+
+fn leave_opponent(mut cmd: Commands, mut pass: ResMut<Events<OpponentPass>>) {
+    cmd.remove_resource::<Opponent>();
+    pass.clear();
+}
+
+fn start_thinking(mut opponent: ResMut<Opponent>) {
+    opponent.think.reset();
+}
+
+fn think(time: Res<Time>, mut opponent: ResMut<Opponent>) {
+    opponent.think.tick(time.delta());
+}
+
+/// Sends back every card in the opening hand that doesn't join the `Rule`, the only cards the
+/// opponent plays.
+pub fn keep_opening_hand(
+    opponent: Res<Opponent>,
+    zones: Res<Zones>,
+    selection: Option<Res<MulliganSelection>>,
+    q_children: Query<&Children>,
+    q_effects: Query<&CardEffect, With<Card>>,
+    mut mulligan: EventWriter<Mulligan>,
+) {
+    let undecided = selection.is_some_and(|selection| selection.redraws.is_none());
+    if !opponent.ready() || !undecided {
+        return;
+    }
+    let hand = zones
+        .get(opponent.seat, ZoneKind::Hand)
+        .and_then(|hand| q_children.get(hand).ok());
+    let cards = hand
+        .into_iter()
+        .flat_map(|hand| hand.iter())
+        .filter(|&&card| !matches!(q_effects.get(card), Ok(CardEffect::Rule(_))))
+        .copied()
+        .collect();
+    mulligan.send(Mulligan { cards });
+}
+
+/// Plays the cheapest rule card it can afford on even turns, and on odd turns, or when it has no
+/// such card, drops shapes the player's goals want fewer of.
+pub fn take_turn(
+    mut opponent: ResMut<Opponent>,
+    zones: Res<Zones>,
+    energy: Res<Energy>,
+    q_children: Query<&Children>,
+    q_cards: Query<(&Card, &CardEffect)>,
+    q_goals: Query<(&Goals, &Owner)>,
+    q_shapes: Query<&Shape, With<IsOnBoard>>,
+    mut play: EventWriter<PlayCard>,
+    mut spawn_body: EventWriter<SpawnBody>,
+    mut pass: EventWriter<OpponentPass>,
+) {
+    if !opponent.ready() {
+        return;
+    }
+    let spawn_turn = opponent.turns % 2 == 1;
+    opponent.turns += 1;
+
+    let hand = zones
+        .get(opponent.seat, ZoneKind::Hand)
+        .and_then(|hand| q_children.get(hand).ok());
+    let rule_card = hand
+        .into_iter()
+        .flat_map(|hand| hand.iter())
+        .filter_map(|&entity| Some((entity, q_cards.get(entity).ok()?)))
+        .filter(|(_, (card, effect))| {
+            matches!(effect, CardEffect::Rule(_)) && card.cost <= energy.current
+        })
+        .min_by_key(|(_, (card, _))| card.cost)
+        .map(|(entity, _)| entity);
+    if let Some(card) = rule_card.filter(|_| !spawn_turn) {
+        play.send(PlayCard {
+            card,
+            board_pos: Vec2::ZERO,
+        });
+        return;
+    }
+
+    let theirs: Vec<_> = q_goals
+        .iter()
+        .filter(|(_, owner)| owner.0 != opponent.seat)
+        .flat_map(|(goals, _)| goals.iter())
+        .collect();
+    // NOTE: the goals the player has yet to meet, or all of them once they're all met
+    let mut hostile: Vec<Shape> = theirs
+        .iter()
+        .filter(|goal| !goal.is_met(q_shapes.iter()))
+        .map(|goal| goal.s2)
+        .collect();
+    if hostile.is_empty() {
+        hostile = theirs.iter().map(|goal| goal.s2).collect();
+    }
+    if !hostile.is_empty() {
+        spawn_body.send_batch((0..HOSTILE_SHAPES).map(|i| {
+            let angle = i as f32 / HOSTILE_SHAPES as f32 * TAU;
+            SpawnBody {
+                shape: hostile[i % hostile.len()],
+                transform: Transform::from_translation(
+                    (Vec2::from_angle(angle) * HOSTILE_SPREAD).extend(10.),
+                ),
+                velocity: None,
+                combo: 0,
+                element: None,
+                source: SpawnSource::Board,
+            }
+        }));
+    }
+    pass.send(OpponentPass);
+}
+
+pub fn pick_draft(
+    opponent: Res<Opponent>,
+    offer: Option<Res<DraftOffer>>,
+    mut draft: EventWriter<DraftRule>,
+) {
+    if !opponent.ready() {
+        return;
+    }
+    if let Some(rule) = offer.and_then(|offer| offer.first().cloned()) {
+        draft.send(DraftRule { rule });
+    }
+}
+
+pub fn end_opponent_play(
+    mut reader: EventReader<OpponentPass>,
+    mut end_phase: EventWriter<EndPhase>,
+) {
+    if reader.read().count() > 0 {
+        end_phase.send(EndPhase);
+    }
+}
+
+/// There's no device to pass against the opponent, so the next turn starts straight away.
+fn hand_over_turn(mut end_phase: EventWriter<EndPhase>) {
+    end_phase.send(EndPhase);
+}
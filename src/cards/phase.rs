@@ -0,0 +1,150 @@
+//! The turn loop. Each phase of `GameState` ends with an `EndPhase` event from whatever system
+//! finishes it, and `advance_phase` alone decides what comes next:
+//!
+//! `Setup → Start → Draw → Play → Resolve → Discard → Draw …`, with `PassDevice` between
+//! `Discard` and `Draw` in two-player games. Every `DRAFT_EVERY` turns, `Draft` comes straight
+//! after `Discard`, and each player's first `Draw` is followed by a `Mulligan` before they play.
+//!
+//! Ending the run (`Scoring`) isn't a phase change; it can happen from any phase and is set
+//! directly by whatever ends it.
+
+use bevy::prelude::*;
+
+use super::{search::LibrarySearch, zones::GameMode, CardSet, GameState};
+use crate::AppState;
+
+/// How long `Resolve` waits for a played card's effects to settle before discarding.
+const RESOLVE_DURATION: f32 = 0.5;
+
+/// Turns between rule drafts.
+pub const DRAFT_EVERY: u32 = 3;
+
+/// Systems that only run during one phase. Add a system to the matching set instead of giving it
+/// its own `in_state` condition.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PhaseSet {
+    Setup,
+    Draw,
+    Mulligan,
+    Play,
+    Resolve,
+    Discard,
+    Draft,
+}
+
+/// Turns finished this run, counted as each `Discard` ends.
+#[derive(Resource, Default, Deref, DerefMut, Debug)]
+pub struct TurnCount(pub u32);
+
+/// Ends the current phase. Sent by the system that finishes it.
+#[derive(Event, Clone, Copy, Debug, Default)]
+pub struct EndPhase;
+
+/// Ends a timed phase when it finishes. Inserted on entering the phase.
+#[derive(Resource, Deref, DerefMut)]
+pub struct PhaseTimer(pub Timer);
+
+/// The phase after `state`, or `None` for states outside the turn loop. `turns` counts the turn
+/// that's ending once `Discard` is over.
+pub fn next_phase(state: &GameState, mode: GameMode, turns: u32) -> Option<GameState> {
+    let end_turn = match mode {
+        GameMode::Solo | GameMode::Endless => GameState::Draw,
+        GameMode::Hotseat | GameMode::Versus => GameState::PassDevice,
+    };
+    Some(match state {
+        GameState::Setup => GameState::Start,
+        GameState::Start => GameState::Draw,
+        GameState::Draw if turns < mode.seats().count() as u32 => GameState::Mulligan,
+        GameState::Draw | GameState::Mulligan => GameState::Play,
+        GameState::Play => GameState::Resolve,
+        GameState::Resolve => GameState::Discard,
+        GameState::Discard if turns.is_multiple_of(DRAFT_EVERY) => GameState::Draft,
+        GameState::Discard | GameState::Draft => end_turn,
+        GameState::PassDevice => GameState::Draw,
+        GameState::Scoring => return None,
+    })
+}
+
+pub struct PhasePlugin;
+impl Plugin for PhasePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<EndPhase>()
+            .init_resource::<TurnCount>()
+            .configure_sets(
+                Update,
+                (
+                    PhaseSet::Setup.run_if(in_state(GameState::Setup)),
+                    PhaseSet::Draw.run_if(in_state(GameState::Draw)),
+                    PhaseSet::Mulligan.run_if(in_state(GameState::Mulligan)),
+                    PhaseSet::Play.run_if(in_state(GameState::Play)),
+                    PhaseSet::Resolve.run_if(in_state(GameState::Resolve)),
+                    PhaseSet::Discard.run_if(in_state(GameState::Discard)),
+                    PhaseSet::Draft.run_if(in_state(GameState::Draft)),
+                ),
+            )
+            .add_systems(OnEnter(GameState::Resolve), start_resolve_timer)
+            .add_systems(OnExit(GameState::Resolve), remove_phase_timer)
+            .add_systems(
+                Update,
+                (
+                    // NOTE: a search holds `Resolve` open until the player has chosen
+                    tick_phase_timer
+                        .run_if(resource_exists::<PhaseTimer>())
+                        .run_if(not(resource_exists::<LibrarySearch>())),
+                    // NOTE: after everything that can end a phase, so the next phase starts on
+                    // the following frame and nothing in the old one runs twice
+                    advance_phase.after(CardSet::Layout),
+                )
+                    .chain()
+                    .run_if(in_state(AppState::Playing)),
+            )
+            .add_systems(OnExit(AppState::Playing), reset_phase_events);
+    }
+}
+
+fn start_resolve_timer(mut cmd: Commands) {
+    cmd.insert_resource(PhaseTimer(Timer::from_seconds(
+        RESOLVE_DURATION,
+        TimerMode::Once,
+    )));
+}
+
+fn remove_phase_timer(mut cmd: Commands) {
+    cmd.remove_resource::<PhaseTimer>();
+}
+
+fn tick_phase_timer(
+    time: Res<Time>,
+    mut timer: ResMut<PhaseTimer>,
+    mut end_phase: EventWriter<EndPhase>,
+) {
+    if timer.tick(time.delta()).just_finished() {
+        end_phase.send(EndPhase);
+    }
+}
+
+/// Sets `NextState` directly rather than through `Commands`, so a run ending on the same frame
+/// (set through `Commands`, applied afterwards) takes priority over the phase change.
+pub fn advance_phase(
+    mut reader: EventReader<EndPhase>,
+    state: Res<State<GameState>>,
+    mut next: ResMut<NextState<GameState>>,
+    mode: Res<GameMode>,
+    mut turns: ResMut<TurnCount>,
+) {
+    // NOTE: several systems may finish the same phase on one frame; it only ends once
+    if reader.read().count() == 0 {
+        return;
+    }
+    if *state.get() == GameState::Discard {
+        **turns += 1;
+    }
+    if let Some(phase) = next_phase(state.get(), *mode, **turns) {
+        next.set(phase);
+    }
+}
+
+fn reset_phase_events(mut end_phase: ResMut<Events<EndPhase>>, mut turns: ResMut<TurnCount>) {
+    end_phase.clear();
+    **turns = 0;
+}
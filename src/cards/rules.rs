@@ -1,23 +1,46 @@
-use std::{f32::consts::PI, iter::repeat};
+use std::{f32::consts::PI, iter::repeat_n};
 
 use bevy::{prelude::*, render::view::RenderLayers};
 use bevy_tweening::Lerp;
 
 use super::{
-    card::{Card, SpawnCard},
-    GameState, Score,
+    card::{Card, SpawnCard, TransformDriver, TransformWrites},
+    definition::CardDefinitions,
+    CardSet, GameState,
 };
 use crate::{
-    operation::{generate_random_operations, Operation},
-    AppState,
+    content::{ContentSelection, CustomContent},
+    daily::DailyChallenge,
+    operation::Operation,
+    save::PendingRun,
+    score::Score,
+    AppState, GameplayEntity,
 };
 
+/// How many operations the `Rule` holds. Adding one to a full rule pushes the oldest out.
+pub const MAX_RULES: usize = 3;
+
+/// Newest first.
 #[derive(Component, Deref, DerefMut)]
-pub struct Rule(Vec<Operation>);
+pub struct Rule(pub Vec<Operation>);
 
 #[derive(Event)]
 pub struct AddRule {
     pub rule: Operation,
+    /// Drafted rules don't count as a played card.
+    pub drafted: bool,
+}
+
+/// Sent by `cycle_rule` once an operation has joined the `Rule`.
+#[derive(Event, Clone, Debug)]
+pub struct RuleAdded {
+    pub rule: Operation,
+}
+
+/// Sent by `cycle_rule` when an operation is pushed out of the `Rule`.
+#[derive(Event, Clone, Debug)]
+pub struct RuleRemoved {
+    pub rule: Operation,
 }
 
 pub struct RulePlugin;
@@ -29,14 +52,36 @@ impl Plugin for RulePlugin {
         )
         .add_systems(OnExit(AppState::Playing), reset_rules)
         .add_event::<AddRule>()
-        .add_systems(Update, (position_rules, cycle_rule));
+        .add_event::<RuleAdded>()
+        .add_event::<RuleRemoved>()
+        .add_systems(
+            Update,
+            (
+                position_rules.in_set(CardSet::Layout),
+                cycle_rule.in_set(CardSet::Transfer),
+            ),
+        );
     }
 }
 
-pub fn spawn_rules(mut cmd: Commands, mut writer: EventWriter<SpawnCard>) {
+pub fn spawn_rules(
+    mut cmd: Commands,
+    mut writer: EventWriter<SpawnCard>,
+    selection: Res<ContentSelection>,
+    content: Res<CustomContent>,
+    mut definitions: ResMut<CardDefinitions>,
+    pending: Option<Res<PendingRun>>,
+    daily: Option<Res<DailyChallenge>>,
+) {
+    let rules: Vec<Operation> = match (pending, daily, selection.rules(&content)) {
+        (Some(run), ..) => run.rules.clone(),
+        (None, Some(daily), _) => daily.rules.clone(),
+        (None, None, Some(rules)) => rules.to_vec(),
+        (None, None, None) => repeat_n(Operation::None, MAX_RULES).collect(),
+    };
     let rules_e = cmd
         .spawn((
-            Rule(repeat(Operation::None).take(3).collect()),
+            Rule(rules.clone()),
             SpatialBundle {
                 transform: Transform {
                     translation: Vec3::new(800., -600., 0.),
@@ -45,26 +90,38 @@ pub fn spawn_rules(mut cmd: Commands, mut writer: EventWriter<SpawnCard>) {
                 ..default()
             },
             RenderLayers::layer(1),
+            GameplayEntity,
         ))
         .id();
-    for _ in 0..3 {
+    for operation in rules {
         writer.send(SpawnCard {
             zone_id: rules_e,
-            operation: Operation::None,
+            card: definitions.id_for(&operation),
             face_up: true,
+            index: None,
         });
     }
 }
 pub fn position_rules(
     q_criteria: Query<&Children, With<Rule>>,
-    mut q_cards: Query<&mut Transform, With<Card>>,
+    mut q_cards: Query<(&TransformDriver, &mut Transform), With<Card>>,
+    mut writes: ResMut<TransformWrites>,
 ) {
     if q_criteria.is_empty() {
         return;
     }
     let children = q_criteria.single();
+
+    #[cfg(feature = "trace")]
+    let _span = info_span!("position_rules", cards = children.len()).entered();
+
     for (i, &entity) in children.iter().enumerate() {
-        if let Ok(mut transform) = q_cards.get_mut(entity) {
+        if let Ok((driver, mut transform)) = q_cards.get_mut(entity) {
+            if *driver != TransformDriver::Layout {
+                continue;
+            }
+            writes.claim(entity, TransformDriver::Layout);
+
             transform.translation.x = transform.translation.x.lerp(&(i as f32 * 150.), &0.2);
             transform.translation.y = transform.translation.y.lerp(&0., &0.2);
 
@@ -75,27 +132,37 @@ pub fn position_rules(
         }
     }
 }
+/// Puts the new operation first, pushing the oldest out if the rule is full. The card for it may
+/// still be flying to the `Rule`, so the oldest card is always its last child.
 pub fn cycle_rule(
     mut cmd: Commands,
-    mut q_rules: Query<(&mut Rule, &mut Children)>,
+    mut q_rules: Query<(&mut Rule, Option<&Children>)>,
     mut reader: EventReader<AddRule>,
     mut score: ResMut<Score>,
+    mut added: EventWriter<RuleAdded>,
+    mut removed: EventWriter<RuleRemoved>,
 ) {
     for event in reader.read() {
-        let (mut rule, mut children) = q_rules.single_mut();
-        dbg!(rule.len());
+        let (mut rule, children) = q_rules.single_mut();
 
-        if rule.len() >= 3 {
+        if !event.drafted {
             score.cards_played += 1;
-            rule.remove(2);
-            cmd.entity(*children.iter().last().unwrap()).remove_parent();
-
-            cmd.entity(*children.iter().last().unwrap())
-                .despawn_recursive();
-            rule.insert(0, event.rule.clone());
         }
+        if rule.len() >= MAX_RULES {
+            if let Some(oldest) = rule.pop() {
+                removed.send(RuleRemoved { rule: oldest });
+            }
+            if let Some(&last) = children.and_then(|children| children.last()) {
+                cmd.entity(last).remove_parent();
+                cmd.entity(last).despawn_recursive();
+            }
+        }
+        rule.insert(0, event.rule.clone());
+        added.send(RuleAdded {
+            rule: event.rule.clone(),
+        });
     }
 }
-pub fn reset_rules(mut cmd: Commands, q_rule: Query<Entity, With<Rule>>) {
-    cmd.entity(q_rule.single()).despawn_recursive();
+pub fn reset_rules(mut add_rule: ResMut<Events<AddRule>>) {
+    add_rule.clear();
 }
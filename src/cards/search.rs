@@ -0,0 +1,341 @@
+//! Cards that look into the active player's library. A scry shows the top few cards to put back
+//! in any order, or under the library; a tutor shows every card matching a `CardFilter` to take
+//! one into the hand. Either way the `Resolve` phase waits on the player's choice, made on the
+//! library search screen, and the cards then move as `ZoneTransfer`s.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    card::Card,
+    definition::{CardDefinition, CardDefinitions, Rarity},
+    effect::{play_card, CardEffect},
+    keyword::Keyword,
+    transfer::{TransferReason, ZoneTransfer},
+    zones::{ActivePlayer, ZoneKind, Zones},
+    CardSet,
+};
+use crate::{
+    audio::{PlaySound, Sfx},
+    pause::PauseState,
+    utils::ThrottledWarn,
+    AppState,
+};
+
+/// Seconds between the cards a scry puts back, so they land in the order they were sent.
+const SCRY_INTERVAL: f32 = 0.1;
+
+/// Which cards a tutor can find.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub enum CardFilter {
+    Keyword(Keyword),
+    Rarity(Rarity),
+    /// Cards that join the `Rule`.
+    Rule,
+    /// Cards that act on the board when played.
+    Board,
+}
+
+impl CardFilter {
+    pub fn matches(&self, definition: &CardDefinition) -> bool {
+        match self {
+            CardFilter::Keyword(keyword) => definition.keywords.contains(keyword),
+            CardFilter::Rarity(rarity) => definition.rarity == *rarity,
+            CardFilter::Rule => matches!(definition.effect, CardEffect::Rule(_)),
+            CardFilter::Board => !matches!(
+                definition.effect,
+                CardEffect::Rule(_)
+                    | CardEffect::Purge(_)
+                    | CardEffect::Scry(_)
+                    | CardEffect::Tutor(_)
+            ),
+        }
+    }
+
+    /// "a ... card", for rules text.
+    pub fn describe(&self) -> String {
+        match self {
+            CardFilter::Keyword(keyword) => format!("a card with {keyword:?}"),
+            CardFilter::Rarity(rarity) => format!("a {rarity:?} card"),
+            CardFilter::Rule => "a rule card".to_string(),
+            CardFilter::Board => "a card that acts on the board".to_string(),
+        }
+    }
+}
+
+/// Sent by `play_card` for a `Scry` card: the active player looks at the top `count` cards of
+/// their library.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct ScryCards {
+    pub count: u32,
+}
+
+/// Sent by `play_card` for a `Tutor` card: the active player searches their library.
+#[derive(Event, Clone, Debug)]
+pub struct TutorCard {
+    pub filter: CardFilter,
+}
+
+/// Ends a scry. `top` goes back on the library in this order, top first, and `bottom` goes under
+/// it.
+#[derive(Event, Clone, Debug)]
+pub struct ScryChoice {
+    pub top: Vec<Entity>,
+    pub bottom: Vec<Entity>,
+}
+
+/// Ends a tutor, taking `card` into the hand. `None` takes nothing.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct TutorChoice {
+    pub card: Option<Entity>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum SearchKind {
+    Scry,
+    Tutor(CardFilter),
+}
+
+/// The search in progress. Present from the card being played until the last card it moves has
+/// been sent on its way.
+#[derive(Resource, Debug)]
+pub struct LibrarySearch {
+    pub kind: SearchKind,
+    /// The cards shown. For a scry, in the order they'll go back, top first.
+    pub cards: Vec<Entity>,
+    /// Cards a scry puts under the library.
+    pub bottom: Vec<Entity>,
+    /// Transfers still to send once the choice is made.
+    queued: Vec<ZoneTransfer>,
+    chosen: bool,
+    timer: Timer,
+}
+
+impl LibrarySearch {
+    fn new(kind: SearchKind, cards: Vec<Entity>) -> Self {
+        Self {
+            kind,
+            cards,
+            bottom: Vec::new(),
+            queued: Vec::new(),
+            chosen: false,
+            timer: Timer::from_seconds(SCRY_INTERVAL, TimerMode::Repeating),
+        }
+    }
+
+    /// Whether the player has made their choice, and the search is only moving cards.
+    pub fn chosen(&self) -> bool {
+        self.chosen
+    }
+
+    /// Moves `card` `by` places towards the bottom of a scry's order, negative towards the top.
+    pub fn move_card(&mut self, card: Entity, by: isize) {
+        let Some(from) = self.cards.iter().position(|&c| c == card) else {
+            return;
+        };
+        let to = from.saturating_add_signed(by).min(self.cards.len() - 1);
+        let card = self.cards.remove(from);
+        self.cards.insert(to, card);
+    }
+
+    /// Sends `card` under the library, or brings it back to the top.
+    pub fn toggle_bottom(&mut self, card: Entity) {
+        match self.bottom.iter().position(|&c| c == card) {
+            Some(i) => {
+                self.bottom.remove(i);
+            },
+            None => self.bottom.push(card),
+        }
+    }
+
+    /// The scry as it stands.
+    pub fn scry_choice(&self) -> ScryChoice {
+        let (bottom, top) = self
+            .cards
+            .iter()
+            .partition(|card| self.bottom.contains(card));
+        ScryChoice { top, bottom }
+    }
+}
+
+pub struct SearchPlugin;
+impl Plugin for SearchPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ScryCards>()
+            .add_event::<TutorCard>()
+            .add_event::<ScryChoice>()
+            .add_event::<TutorChoice>()
+            .add_systems(
+                Update,
+                (open_search, take_scry, take_tutor, send_queued_transfers)
+                    .chain()
+                    .after(play_card)
+                    .in_set(CardSet::Transfer)
+                    .run_if(in_state(PauseState::Running))
+                    .run_if(in_state(AppState::Playing)),
+            )
+            .add_systems(OnExit(AppState::Playing), reset_search);
+    }
+}
+
+fn open_search(
+    mut cmd: Commands,
+    search: Option<Res<LibrarySearch>>,
+    mut scry: EventReader<ScryCards>,
+    mut tutor: EventReader<TutorCard>,
+    zones: Res<Zones>,
+    active: Res<ActivePlayer>,
+    q_children: Query<&Children, Without<Card>>,
+    q_cards: Query<&Card>,
+    definitions: Res<CardDefinitions>,
+    mut missing_zone: Local<ThrottledWarn>,
+) {
+    // NOTE: only one card is played at a time, so there's never more than one search to open
+    let scry = scry.read().last().map(|event| event.count);
+    let tutor = tutor.read().last().map(|event| event.filter.clone());
+    if search.is_some() || (scry.is_none() && tutor.is_none()) {
+        return;
+    }
+    let Some(library_e) = zones.get(active.0, ZoneKind::Library) else {
+        missing_zone.warn("open_search: active player has no Library zone");
+        return;
+    };
+    let library: Vec<Entity> = q_children
+        .get(library_e)
+        .map(|children| children.to_vec())
+        .unwrap_or_default();
+    let definition = |card: Entity| definitions.get(q_cards.get(card).ok()?.definition.as_ref()?);
+
+    if let Some(count) = scry {
+        let cards: Vec<Entity> = library.into_iter().take(count as usize).collect();
+        if !cards.is_empty() {
+            cmd.insert_resource(LibrarySearch::new(SearchKind::Scry, cards));
+        }
+    } else if let Some(filter) = tutor {
+        let mut cards: Vec<(Entity, &CardDefinition)> = library
+            .into_iter()
+            .filter_map(|card| Some((card, definition(card)?)))
+            .filter(|(_, definition)| filter.matches(definition))
+            .collect();
+        // NOTE: sorted by name, so the search doesn't give the library's order away
+        cards.sort_by(|(_, a), (_, b)| a.name.cmp(&b.name));
+        let cards = cards.into_iter().map(|(card, _)| card).collect();
+        cmd.insert_resource(LibrarySearch::new(SearchKind::Tutor(filter), cards));
+    }
+}
+
+/// Queues the scried cards to go back: the ones kept on top last, so the first of them lands on
+/// top.
+pub fn take_scry(
+    mut reader: EventReader<ScryChoice>,
+    search: Option<ResMut<LibrarySearch>>,
+    zones: Res<Zones>,
+    active: Res<ActivePlayer>,
+) {
+    let Some(choice) = reader.read().last() else {
+        return;
+    };
+    let (Some(mut search), Some(library_e)) = (search, zones.get(active.0, ZoneKind::Library))
+    else {
+        return;
+    };
+    if search.chosen || search.kind != SearchKind::Scry {
+        return;
+    }
+    let transfer = |reason| {
+        move |&card| ZoneTransfer {
+            card,
+            from: library_e,
+            to: library_e,
+            reason,
+        }
+    };
+    let shown = |card: &&Entity| search.cards.contains(card);
+    let queued = choice
+        .top
+        .iter()
+        .rev()
+        .filter(shown)
+        .map(transfer(TransferReason::Scry))
+        .chain(
+            choice
+                .bottom
+                .iter()
+                .filter(shown)
+                .map(transfer(TransferReason::Bottom)),
+        )
+        .collect();
+    search.queued = queued;
+    search.chosen = true;
+}
+
+pub fn take_tutor(
+    mut reader: EventReader<TutorChoice>,
+    search: Option<ResMut<LibrarySearch>>,
+    zones: Res<Zones>,
+    active: Res<ActivePlayer>,
+) {
+    let Some(choice) = reader.read().last() else {
+        return;
+    };
+    let Some(mut search) = search else {
+        return;
+    };
+    if search.chosen || !matches!(search.kind, SearchKind::Tutor(_)) {
+        return;
+    }
+    let (Some(library_e), Some(hand_e)) = (
+        zones.get(active.0, ZoneKind::Library),
+        zones.get(active.0, ZoneKind::Hand),
+    ) else {
+        return;
+    };
+    if let Some(card) = choice.card.filter(|card| search.cards.contains(card)) {
+        search.queued.push(ZoneTransfer {
+            card,
+            from: library_e,
+            to: hand_e,
+            reason: TransferReason::Tutor,
+        });
+    }
+    search.chosen = true;
+}
+
+/// Sends the chosen moves one at a time, and ends the search once they're all sent.
+fn send_queued_transfers(
+    mut cmd: Commands,
+    time: Res<Time>,
+    search: Option<ResMut<LibrarySearch>>,
+    mut transfer: EventWriter<ZoneTransfer>,
+    mut sound: EventWriter<PlaySound>,
+) {
+    let Some(mut search) = search.filter(|search| search.chosen) else {
+        return;
+    };
+    if search.queued.is_empty() {
+        cmd.remove_resource::<LibrarySearch>();
+        return;
+    }
+    if search.timer.tick(time.delta()).just_finished() {
+        let next = search.queued.remove(0);
+        sound.send(PlaySound(match next.reason {
+            TransferReason::Tutor => Sfx::Draw,
+            _ => Sfx::Flip,
+        }));
+        transfer.send(next);
+    }
+}
+
+fn reset_search(
+    mut cmd: Commands,
+    mut scry: ResMut<Events<ScryCards>>,
+    mut tutor: ResMut<Events<TutorCard>>,
+    mut scry_choice: ResMut<Events<ScryChoice>>,
+    mut tutor_choice: ResMut<Events<TutorChoice>>,
+) {
+    cmd.remove_resource::<LibrarySearch>();
+    scry.clear();
+    tutor.clear();
+    scry_choice.clear();
+    tutor_choice.clear();
+}
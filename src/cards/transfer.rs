@@ -0,0 +1,143 @@
+//! Every move of a card from one zone to another is a `ZoneTransfer`. Systems that move cards only
+//! send these; `apply_zone_transfers` is the one place cards change parent, taking care of where
+//! in the new zone they go, which way up they land, and their flight there.
+
+use bevy::{prelude::*, utils::HashSet};
+
+use super::{
+    card::{Card, CardTravel, FlipCard, TransformDriver},
+    zones::ZoneKind,
+    CardSet,
+};
+use crate::AppState;
+
+/// Why a card is moving, which decides where among the destination's cards it goes.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TransferReason {
+    Draw,
+    Discard,
+    Exhaust,
+    /// Drawn past the hand limit, straight onto the discard.
+    Burn,
+    /// Played onto the `Rule`.
+    Play,
+    /// The discard shuffled under the library.
+    Shuffle,
+    Mulligan,
+    /// Put back on top of the library by a scry.
+    Scry,
+    /// Put under the library by a scry.
+    Bottom,
+    /// Found in the library by a tutor.
+    Tutor,
+}
+
+impl TransferReason {
+    /// Where among the destination's cards the card goes; `None` puts it last.
+    pub fn index(self) -> Option<usize> {
+        match self {
+            TransferReason::Draw
+            | TransferReason::Shuffle
+            | TransferReason::Mulligan
+            | TransferReason::Bottom
+            | TransferReason::Tutor => None,
+            TransferReason::Discard
+            | TransferReason::Exhaust
+            | TransferReason::Burn
+            | TransferReason::Play
+            | TransferReason::Scry => Some(0),
+        }
+    }
+}
+
+/// Moves `card` from the zone `from` to the zone `to`. Ignored if the card isn't in, or flying to,
+/// `from` by the time it's applied, or has already moved this frame.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct ZoneTransfer {
+    pub card: Entity,
+    pub from: Entity,
+    pub to: Entity,
+    pub reason: TransferReason,
+}
+
+pub struct TransferPlugin;
+impl Plugin for TransferPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ZoneTransfer>()
+            .add_systems(
+                Update,
+                apply_zone_transfers
+                    .after(CardSet::Transfer)
+                    .before(CardSet::Layout)
+                    .run_if(in_state(AppState::Playing)),
+            )
+            .add_systems(OnExit(AppState::Playing), reset_transfer_events);
+    }
+}
+
+/// Whether a card in a zone of `kind` is shown face up. `None` is the `Rule`, whose cards stay
+/// the way they were played.
+fn faces_up(kind: Option<&ZoneKind>) -> Option<bool> {
+    match kind? {
+        ZoneKind::Hand => Some(true),
+        ZoneKind::Library | ZoneKind::Discard | ZoneKind::Exhaust => Some(false),
+    }
+}
+
+/// Sends each transferred card flying from where it sits in the world into its new zone, which it
+/// only joins once it lands. It keeps its scale, and its rotation within the zone it left. A card
+/// already in flight is turned towards the new zone mid-air. See `CardTravel`.
+pub fn apply_zone_transfers(
+    mut cmd: Commands,
+    mut reader: EventReader<ZoneTransfer>,
+    mut q_cards: Query<(
+        &Card,
+        &mut Transform,
+        &GlobalTransform,
+        Option<&Parent>,
+        Option<&mut CardTravel>,
+    )>,
+    q_zones: Query<Option<&ZoneKind>, Without<Card>>,
+    mut flip_writer: EventWriter<FlipCard>,
+) {
+    let mut moved = HashSet::new();
+    for transfer in reader.read() {
+        let Ok((card, mut transform, global, parent, travel)) = q_cards.get_mut(transfer.card)
+        else {
+            continue;
+        };
+        // NOTE: a card in flight counts as in the zone it's headed for
+        let zone = match &travel {
+            Some(travel) => Some(travel.zone),
+            None => parent.map(Parent::get),
+        };
+        if zone != Some(transfer.from) || !moved.insert(transfer.card) {
+            continue;
+        }
+        let Ok(kind) = q_zones.get(transfer.to) else {
+            continue;
+        };
+
+        match travel {
+            Some(mut travel) => travel.retarget(transfer.to, transfer.reason.index(), *transform),
+            None => {
+                let start = global.compute_transform();
+                let local_rotation = transform.rotation;
+                *transform = start;
+                cmd.entity(transfer.card).remove_parent().insert((
+                    CardTravel::new(transfer.to, transfer.reason.index(), start, local_rotation),
+                    TransformDriver::Travel,
+                ));
+            },
+        }
+        if faces_up(kind).is_some_and(|up| up != card.face_up) {
+            flip_writer.send(FlipCard {
+                card: transfer.card,
+            });
+        }
+    }
+}
+
+fn reset_transfer_events(mut transfers: ResMut<Events<ZoneTransfer>>) {
+    transfers.clear();
+}
@@ -0,0 +1,213 @@
+use std::f32::consts::PI;
+
+use bevy::{
+    prelude::*,
+    utils::HashMap,
+    window::{PrimaryWindow, WindowResized},
+};
+use serde::{Deserialize, Serialize};
+
+use super::CardSet;
+use crate::camera::{visible_area, FRAME};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, Reflect, Serialize, Deserialize)]
+pub struct PlayerId(pub u8);
+
+#[derive(Component, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Owner(pub PlayerId);
+
+#[derive(Component, Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum ZoneKind {
+    Library,
+    Hand,
+    Discard,
+    /// Cards removed for the rest of the level. Never shuffled back into the library.
+    Exhaust,
+}
+
+/// How far in from the bottom corners of the view the library and discard sit, and from the bottom
+/// edge the hand sits.
+const ZONE_INSET: Vec2 = Vec2::new(250., 130.);
+/// How far above the discard the exhaust pile sits.
+const EXHAUST_ABOVE_DISCARD: f32 = 230.;
+/// How far past the top of the view the waiting player's zones sit, so only the edge of their
+/// cards peeks in.
+const WAITING_SEAT_OFFSET: f32 = 10.;
+
+/// One player alone, two players taking turns on the same device, one player against the
+/// `Opponent`, or one player alone against endless waves of shapes (see `endless`).
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum GameMode {
+    #[default]
+    Solo,
+    Hotseat,
+    Versus,
+    Endless,
+}
+
+impl GameMode {
+    pub fn seats(self) -> impl Iterator<Item = PlayerId> {
+        let players = match self {
+            GameMode::Solo | GameMode::Endless => 1,
+            GameMode::Hotseat | GameMode::Versus => 2,
+        };
+        (0..players).map(PlayerId)
+    }
+
+    /// The player sitting at the device while `active` takes their turn, whose zones sit at the
+    /// bottom of the screen.
+    pub fn viewer(self, active: PlayerId) -> PlayerId {
+        match self {
+            GameMode::Solo | GameMode::Hotseat | GameMode::Endless => active,
+            GameMode::Versus => PlayerId(0),
+        }
+    }
+
+    pub fn next(self, player: PlayerId) -> PlayerId {
+        PlayerId((player.0 + 1) % self.seats().count() as u8)
+    }
+}
+
+/// The first player to meet all of their goals in a two-player game.
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct Winner(pub Option<PlayerId>);
+
+/// Where a zone sits while its owner is the one at the device. The other player's zones are turned
+/// around and moved to the top of the screen.
+#[derive(Component, Clone, Copy)]
+pub struct Seat(pub Transform);
+
+/// The area the card camera shows, which the zones are placed against. Refit whenever the window
+/// is resized, and the zones' seats with it.
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct ZoneAnchors {
+    pub area: Rect,
+}
+
+impl Default for ZoneAnchors {
+    fn default() -> Self {
+        Self { area: FRAME }
+    }
+}
+
+impl ZoneAnchors {
+    /// Where a zone of `kind` sits while its owner is the one at the device.
+    pub fn seat(&self, kind: ZoneKind) -> Transform {
+        let bottom = self.area.min.y + ZONE_INSET.y;
+        match kind {
+            ZoneKind::Library => Transform::from_xyz(self.area.min.x + ZONE_INSET.x, bottom, 20.),
+            ZoneKind::Discard => Transform::from_xyz(self.area.max.x - ZONE_INSET.x, bottom, 20.),
+            ZoneKind::Exhaust => Transform::from_xyz(
+                self.area.max.x - ZONE_INSET.x,
+                bottom + EXHAUST_ABOVE_DISCARD,
+                20.,
+            ),
+            ZoneKind::Hand => Transform::from_xyz(FRAME.center().x, bottom, 0.),
+        }
+    }
+
+    pub fn waiting_y(&self) -> f32 {
+        self.area.max.y + WAITING_SEAT_OFFSET
+    }
+}
+
+/// The player whose zones the deck systems currently act on.
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct ActivePlayer(pub PlayerId);
+
+/// Zone entities by owner and kind, kept in sync as zones spawn and despawn so systems can look
+/// them up without assuming there is only one of each.
+#[derive(Resource, Default)]
+pub struct Zones(HashMap<(PlayerId, ZoneKind), Entity>);
+
+impl Zones {
+    pub fn get(&self, owner: PlayerId, kind: ZoneKind) -> Option<Entity> {
+        self.0.get(&(owner, kind)).copied()
+    }
+}
+
+pub struct ZonesPlugin;
+impl Plugin for ZonesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ActivePlayer>()
+            .init_resource::<GameMode>()
+            .init_resource::<Winner>()
+            .init_resource::<Zones>()
+            .init_resource::<ZoneAnchors>()
+            .add_systems(Startup, fit_zone_anchors)
+            // NOTE: zones spawned on entering a state are tracked in the same frame
+            .add_systems(Update, track_zones.before(CardSet::Transfer))
+            .add_systems(
+                Update,
+                (
+                    fit_zone_anchors.run_if(on_event::<WindowResized>()),
+                    reseat_zones,
+                    seat_zones,
+                )
+                    .chain()
+                    .before(CardSet::Layout),
+            );
+    }
+}
+
+fn track_zones(
+    mut zones: ResMut<Zones>,
+    q_added: Query<(Entity, &Owner, &ZoneKind), Added<ZoneKind>>,
+    mut removed: RemovedComponents<ZoneKind>,
+) {
+    for entity in removed.read() {
+        zones.0.retain(|_, &mut zone| zone != entity);
+    }
+    for (entity, owner, kind) in q_added.iter() {
+        zones.0.insert((owner.0, *kind), entity);
+    }
+}
+
+fn fit_zone_anchors(
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    mut anchors: ResMut<ZoneAnchors>,
+) {
+    let Ok(window) = q_window.get_single() else {
+        return;
+    };
+    let area = visible_area(Vec2::new(window.width(), window.height()));
+    if anchors.area != area {
+        anchors.area = area;
+    }
+}
+
+fn reseat_zones(anchors: Res<ZoneAnchors>, mut q_zones: Query<(&ZoneKind, &mut Seat)>) {
+    if !anchors.is_changed() {
+        return;
+    }
+    for (kind, mut seat) in q_zones.iter_mut() {
+        seat.0 = anchors.seat(*kind);
+    }
+}
+
+fn seat_zones(
+    mode: Res<GameMode>,
+    active: Res<ActivePlayer>,
+    anchors: Res<ZoneAnchors>,
+    mut q_zones: Query<(&Owner, &Seat, &mut Transform)>,
+) {
+    let viewer = mode.viewer(active.0);
+    for (owner, seat, mut transform) in q_zones.iter_mut() {
+        let target = if owner.0 == viewer {
+            seat.0
+        } else {
+            Transform {
+                translation: Vec3::new(
+                    -seat.0.translation.x,
+                    anchors.waiting_y(),
+                    seat.0.translation.z,
+                ),
+                rotation: Quat::from_rotation_z(PI) * seat.0.rotation,
+                ..seat.0
+            }
+        };
+        if *transform != target {
+            *transform = target;
+        }
+    }
+}
@@ -0,0 +1,472 @@
+//! Player-made content. RON files in a `mods/` directory are read at startup, validated, and
+//! offered in the main menu next to the built-in ones. Files that don't parse or validate are
+//! listed as content problems instead of stopping the game.
+//!
+//! A file's kind comes from its name: `*.deck.ron` holds a `DeckList`, `*.rules.ron` a `RuleSet`,
+//! `*.shapes.ron` a `ShapeDistribution`, `*.fight.ron` a `FightTable` and `*.difficulty.ron` a
+//! `DifficultyPreset`. See the examples in the repo's `mods/` directory.
+
+use bevy::{prelude::*, utils::HashMap};
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+
+use crate::{
+    board::spawner::WeightedShape,
+    daily::DailyChallenge,
+    game_shapes::{
+        interaction::{ColorInteraction, ColorOutcome},
+        GameColor,
+    },
+    level::{apply_level, CurrentLevel, Level},
+    loading::BalanceAssets,
+    operation::Operation,
+    AppState,
+};
+
+pub const MODS_DIR: &str = "mods";
+const MIN_DECK_SIZE: usize = 10;
+const MAX_DECK_SIZE: usize = 200;
+/// `cycle_rule` always keeps this many rules in play.
+const RULE_COUNT: usize = 3;
+/// How far a difficulty preset can scale a level, either way.
+const MIN_DIFFICULTY_SCALE: f32 = 0.25;
+const MAX_DIFFICULTY_SCALE: f32 = 4.;
+
+/// A library to play with instead of a randomly generated one. Shuffled for every seat.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DeckList {
+    pub name: String,
+    pub cards: Vec<Operation>,
+}
+
+/// The rules in play at the start of a run, instead of three blank ones.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RuleSet {
+    pub name: String,
+    pub rules: Vec<Operation>,
+}
+
+/// What the spawners and endless waves drop, in place of each spawner's own table and the
+/// uniformly random shapes.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ShapeDistribution {
+    pub name: String,
+    pub shapes: Vec<WeightedShape>,
+}
+
+/// How colors settle a fight, in place of `assets/balance/default.colors.ron`. Pairs left out
+/// keep the built-in outcome.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct FightTable {
+    pub name: String,
+    pub outcomes: HashMap<(GameColor, GameColor), ColorOutcome>,
+}
+
+impl FightTable {
+    pub fn interaction(&self) -> ColorInteraction {
+        let mut interaction = ColorInteraction::default();
+        interaction.outcomes.extend(self.outcomes.clone());
+        interaction
+    }
+}
+
+/// Scales every level of a run. Above 1 makes that part of the level bigger, so a harder preset
+/// has a lower `spawn_interval` and a higher `target_score`.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DifficultyPreset {
+    pub name: String,
+    pub spawn_interval: f32,
+    pub target_score: f32,
+    pub time_limit: f32,
+}
+
+impl DifficultyPreset {
+    /// Level `number` with this preset on top.
+    pub fn level(&self, number: u32) -> Level {
+        let level = Level::new(number);
+        Level {
+            spawn_interval: level.spawn_interval * self.spawn_interval,
+            target_score: (level.target_score as f32 * self.target_score).round() as u32,
+            time_limit: level.time_limit * self.time_limit,
+            ..level
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum Content {
+    Deck(DeckList),
+    Rules(RuleSet),
+    Shapes(ShapeDistribution),
+    Fight(FightTable),
+    Difficulty(DifficultyPreset),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, EnumIter)]
+pub enum ContentKind {
+    Deck,
+    Rules,
+    Shapes,
+    Fight,
+    Difficulty,
+}
+
+/// A content file that was skipped, and why.
+#[derive(Clone, Debug)]
+pub struct ContentProblem {
+    pub path: String,
+    pub message: String,
+}
+
+#[derive(Resource, Default)]
+pub struct CustomContent {
+    pub decks: Vec<DeckList>,
+    pub rule_sets: Vec<RuleSet>,
+    pub shape_distributions: Vec<ShapeDistribution>,
+    pub fight_tables: Vec<FightTable>,
+    pub difficulties: Vec<DifficultyPreset>,
+    pub problems: Vec<ContentProblem>,
+}
+
+impl CustomContent {
+    pub fn add(&mut self, path: &str, result: Result<Content, String>) -> Option<ContentKind> {
+        match result {
+            Ok(Content::Deck(deck)) => {
+                self.decks.push(deck);
+                Some(ContentKind::Deck)
+            },
+            Ok(Content::Rules(rules)) => {
+                self.rule_sets.push(rules);
+                Some(ContentKind::Rules)
+            },
+            Ok(Content::Shapes(shapes)) => {
+                self.shape_distributions.push(shapes);
+                Some(ContentKind::Shapes)
+            },
+            Ok(Content::Fight(table)) => {
+                self.fight_tables.push(table);
+                Some(ContentKind::Fight)
+            },
+            Ok(Content::Difficulty(preset)) => {
+                self.difficulties.push(preset);
+                Some(ContentKind::Difficulty)
+            },
+            Err(message) => {
+                warn!("content problem in {path}: {message}");
+                self.problems.push(ContentProblem {
+                    path: path.to_string(),
+                    message,
+                });
+                None
+            },
+        }
+    }
+
+    pub fn len(&self, kind: ContentKind) -> usize {
+        match kind {
+            ContentKind::Deck => self.decks.len(),
+            ContentKind::Rules => self.rule_sets.len(),
+            ContentKind::Shapes => self.shape_distributions.len(),
+            ContentKind::Fight => self.fight_tables.len(),
+            ContentKind::Difficulty => self.difficulties.len(),
+        }
+    }
+
+    pub fn name(&self, kind: ContentKind, index: usize) -> Option<&str> {
+        match kind {
+            ContentKind::Deck => self.decks.get(index).map(|d| d.name.as_str()),
+            ContentKind::Rules => self.rule_sets.get(index).map(|r| r.name.as_str()),
+            ContentKind::Shapes => self.shape_distributions.get(index).map(|s| s.name.as_str()),
+            ContentKind::Fight => self.fight_tables.get(index).map(|f| f.name.as_str()),
+            ContentKind::Difficulty => self.difficulties.get(index).map(|d| d.name.as_str()),
+        }
+    }
+}
+
+/// Which custom content the next run uses; `None` keeps the built-in one of that kind.
+#[derive(Resource, Default)]
+pub struct ContentSelection {
+    pub deck: Option<usize>,
+    pub rules: Option<usize>,
+    pub shapes: Option<usize>,
+    pub fight: Option<usize>,
+    pub difficulty: Option<usize>,
+}
+
+impl ContentSelection {
+    pub fn get(&self, kind: ContentKind) -> Option<usize> {
+        match kind {
+            ContentKind::Deck => self.deck,
+            ContentKind::Rules => self.rules,
+            ContentKind::Shapes => self.shapes,
+            ContentKind::Fight => self.fight,
+            ContentKind::Difficulty => self.difficulty,
+        }
+    }
+
+    /// Steps through the built-in option and then each custom one.
+    pub fn cycle(&mut self, kind: ContentKind, available: usize) {
+        let current = match kind {
+            ContentKind::Deck => &mut self.deck,
+            ContentKind::Rules => &mut self.rules,
+            ContentKind::Shapes => &mut self.shapes,
+            ContentKind::Fight => &mut self.fight,
+            ContentKind::Difficulty => &mut self.difficulty,
+        };
+        *current = match *current {
+            None if available > 0 => Some(0),
+            Some(i) if i + 1 < available => Some(i + 1),
+            _ => None,
+        };
+    }
+
+    /// The selected deck, shuffled with `rng`.
+    pub fn deck_cards(
+        &self,
+        content: &CustomContent,
+        rng: &mut impl Rng,
+    ) -> Option<Vec<Operation>> {
+        let mut cards = content.decks.get(self.deck?)?.cards.clone();
+        cards.shuffle(rng);
+        Some(cards)
+    }
+
+    pub fn rules<'a>(&self, content: &'a CustomContent) -> Option<&'a [Operation]> {
+        Some(&content.rule_sets.get(self.rules?)?.rules)
+    }
+
+    pub fn shapes<'a>(&self, content: &'a CustomContent) -> Option<&'a [WeightedShape]> {
+        Some(&content.shape_distributions.get(self.shapes?)?.shapes)
+    }
+
+    pub fn fight_table<'a>(&self, content: &'a CustomContent) -> Option<&'a FightTable> {
+        content.fight_tables.get(self.fight?)
+    }
+
+    pub fn difficulty<'a>(&self, content: &'a CustomContent) -> Option<&'a DifficultyPreset> {
+        content.difficulties.get(self.difficulty?)
+    }
+}
+
+pub struct ContentPlugin;
+impl Plugin for ContentPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CustomContent>()
+            .init_resource::<ContentSelection>()
+            .add_systems(Startup, load_custom_content)
+            .add_systems(
+                OnEnter(AppState::Playing),
+                apply_custom_content.before(apply_level),
+            )
+            .add_systems(OnExit(AppState::Playing), restore_fight_table);
+    }
+}
+
+/// Parses and validates one content file. `file_name` decides what kind of content it holds.
+pub fn parse_content(file_name: &str, text: &str) -> Result<Content, String> {
+    let content = if file_name.ends_with(".deck.ron") {
+        Content::Deck(ron::from_str(text).map_err(|e| e.to_string())?)
+    } else if file_name.ends_with(".rules.ron") {
+        Content::Rules(ron::from_str(text).map_err(|e| e.to_string())?)
+    } else if file_name.ends_with(".shapes.ron") {
+        Content::Shapes(ron::from_str(text).map_err(|e| e.to_string())?)
+    } else if file_name.ends_with(".fight.ron") {
+        Content::Fight(ron::from_str(text).map_err(|e| e.to_string())?)
+    } else if file_name.ends_with(".difficulty.ron") {
+        Content::Difficulty(ron::from_str(text).map_err(|e| e.to_string())?)
+    } else {
+        return Err(
+            "unknown content type, expected a .deck.ron, .rules.ron, .shapes.ron, \
+             .fight.ron or .difficulty.ron file"
+                .to_string(),
+        );
+    };
+    validate(&content)?;
+    Ok(content)
+}
+
+fn validate(content: &Content) -> Result<(), String> {
+    let (name, operations) = match content {
+        Content::Deck(deck) => {
+            if !(MIN_DECK_SIZE..=MAX_DECK_SIZE).contains(&deck.cards.len()) {
+                return Err(format!(
+                    "a deck needs {MIN_DECK_SIZE} to {MAX_DECK_SIZE} cards, found {}",
+                    deck.cards.len()
+                ));
+            }
+            (&deck.name, &deck.cards)
+        },
+        Content::Rules(rules) => {
+            if rules.rules.len() != RULE_COUNT {
+                return Err(format!(
+                    "a rule set needs exactly {RULE_COUNT} rules, found {}",
+                    rules.rules.len()
+                ));
+            }
+            (&rules.name, &rules.rules)
+        },
+        Content::Shapes(shapes) => {
+            if shapes.shapes.iter().all(|entry| entry.weight == 0) {
+                return Err("a shape distribution needs a shape with a weight above 0".to_string());
+            }
+            return validate_name(&shapes.name);
+        },
+        Content::Fight(table) => {
+            if table.outcomes.is_empty() {
+                return Err("a fight table needs at least one outcome".to_string());
+            }
+            return validate_name(&table.name);
+        },
+        Content::Difficulty(preset) => {
+            for (field, scale) in [
+                ("spawn_interval", preset.spawn_interval),
+                ("target_score", preset.target_score),
+                ("time_limit", preset.time_limit),
+            ] {
+                if !(MIN_DIFFICULTY_SCALE..=MAX_DIFFICULTY_SCALE).contains(&scale) {
+                    return Err(format!(
+                        "{field} must be between {MIN_DIFFICULTY_SCALE} and \
+                         {MAX_DIFFICULTY_SCALE}, found {scale}"
+                    ));
+                }
+            }
+            return validate_name(&preset.name);
+        },
+    };
+    validate_name(name)?;
+    // NOTE: the card art only has a "2" for the multiplier
+    if let Some(i) = operations
+        .iter()
+        .position(|op| matches!(op, Operation::Mul(_, n) if *n != 2))
+    {
+        return Err(format!("operation {i}: only Mul(_, 2) is supported"));
+    }
+    if let Some(i) = operations
+        .iter()
+        .position(|op| matches!(op, Operation::Div(s) if s.polygon.divide(2).is_none()))
+    {
+        return Err(format!(
+            "operation {i}: Div can't cut a {:?}",
+            operations[i]
+        ));
+    }
+    if let Some(i) = operations
+        .iter()
+        .position(|op| matches!(op, Operation::Transmute(from, to) if from == to))
+    {
+        return Err(format!(
+            "operation {i}: Transmute needs two different polygons"
+        ));
+    }
+    Ok(())
+}
+
+fn validate_name(name: &str) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("name is empty".to_string());
+    }
+    Ok(())
+}
+
+/// Puts the selected fight table and difficulty in play. The daily challenge keeps the built-in
+/// ones, like it keeps its own deck and rules.
+pub fn apply_custom_content(
+    selection: Res<ContentSelection>,
+    content: Res<CustomContent>,
+    daily: Option<Res<DailyChallenge>>,
+    mut level: ResMut<CurrentLevel>,
+    mut interaction: ResMut<ColorInteraction>,
+) {
+    if daily.is_some() {
+        return;
+    }
+    if let Some(table) = selection.fight_table(&content) {
+        *interaction = table.interaction();
+        info!("fight table: {}", table.name);
+    }
+    // NOTE: rebuilt from the level number, so entering the same level twice doesn't scale it twice
+    if let Some(preset) = selection.difficulty(&content) {
+        level.0 = preset.level(level.number);
+        info!("difficulty: {}", preset.name);
+    }
+}
+
+/// Puts the balance file's matrix back, since a custom fight table only lasts for the run.
+fn restore_fight_table(
+    assets: Res<Assets<ColorInteraction>>,
+    balance: Option<Res<BalanceAssets>>,
+    mut interaction: ResMut<ColorInteraction>,
+) {
+    *interaction = balance
+        .and_then(|balance| assets.get(&balance.colors).cloned())
+        .unwrap_or_default();
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn mod_dirs() -> Vec<std::path::PathBuf> {
+    let mut dirs = Vec::new();
+    if let Some(exe_dir) = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|p| p.to_path_buf()))
+    {
+        dirs.push(exe_dir.join(MODS_DIR));
+    }
+    // NOTE: `cargo run` puts the executable under target/, so also look in the working directory
+    if let Ok(cwd) = std::env::current_dir() {
+        let dir = cwd.join(MODS_DIR);
+        if !dirs.contains(&dir) {
+            dirs.push(dir);
+        }
+    }
+    dirs
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn load_custom_content(
+    mut content: ResMut<CustomContent>,
+    mut selection: ResMut<ContentSelection>,
+    args: Option<Res<crate::args::LaunchArgs>>,
+) {
+    for dir in mod_dirs() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        let mut paths: Vec<_> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "ron"))
+            .collect();
+        paths.sort();
+        for path in paths {
+            content.add(&path.display().to_string(), load_file(&path));
+        }
+    }
+
+    if let Some(path) = args.and_then(|args| args.deck.clone()) {
+        if content.add(&path, load_file(std::path::Path::new(&path))) == Some(ContentKind::Deck) {
+            selection.deck = Some(content.decks.len() - 1);
+        }
+    }
+
+    info!(
+        "custom content: {} decks, {} rule sets, {} shape distributions, {} fight tables, {} \
+         difficulty presets, {} problems",
+        content.decks.len(),
+        content.rule_sets.len(),
+        content.shape_distributions.len(),
+        content.fight_tables.len(),
+        content.difficulties.len(),
+        content.problems.len()
+    );
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn load_file(path: &std::path::Path) -> Result<Content, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    parse_content(&file_name, &text)
+}
+
+// NOTE: the web build has no directory to scan
+#[cfg(target_arch = "wasm32")]
+fn load_custom_content() {}
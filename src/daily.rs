@@ -0,0 +1,158 @@
+//! The daily challenge: one run a day that's the same for everyone. The day, counted in UTC,
+//! picks the seed, the board layout, the rules in play and the library, so players only differ in
+//! how they play it. The best score of each day is kept in `daily.ron` and shown on the menu.
+
+use std::{collections::BTreeMap, iter::repeat_with};
+
+use bevy::prelude::*;
+use rand::{rngs::StdRng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    cards::{deck::LIBRARY_SIZE, rules::MAX_RULES, GameState},
+    operation::Operation,
+    platform::{self, Storage},
+    score::Score,
+    AppState,
+};
+
+const RECORD_KEY: &str = "daily.ron";
+const MILLIS_PER_DAY: u64 = 24 * 60 * 60 * 1000;
+
+/// Days since the Unix epoch, in UTC.
+pub fn today() -> u64 {
+    platform::unix_millis() / MILLIS_PER_DAY
+}
+
+/// `day` as a date, e.g. "2024-01-31".
+pub fn date_label(day: u64) -> String {
+    // NOTE: Howard Hinnant's days_from_civil, run backwards
+    let z = day as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + i64::from(m <= 2);
+    format!("{y:04}-{m:02}-{d:02}")
+}
+
+/// The run of one day. Present from starting the daily run until it's scored, and kept in a saved
+/// run so a continued one still counts.
+#[derive(Resource, Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct DailyChallenge {
+    pub day: u64,
+    pub seed: u64,
+    /// The name of the `BoardLayout`.
+    pub layout: String,
+    pub rules: Vec<Operation>,
+    /// Dealt to every seat as is, top first.
+    pub library: Vec<Operation>,
+}
+
+impl DailyChallenge {
+    /// The challenge of `day`, on one of `layouts`.
+    pub fn new(day: u64, layouts: &[String]) -> Self {
+        // NOTE: splitmix64 finalizer, so neighbouring days get unrelated seeds
+        let mut seed = day.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        seed = (seed ^ (seed >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        seed = (seed ^ (seed >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        seed ^= seed >> 31;
+
+        let mut rng = StdRng::seed_from_u64(seed);
+        let layout = if layouts.is_empty() {
+            String::new()
+        } else {
+            layouts[(seed % layouts.len() as u64) as usize].clone()
+        };
+        let rules = repeat_with(|| Operation::random_operation_with(&mut rng))
+            .filter(|operation| *operation != Operation::None)
+            .take(MAX_RULES)
+            .collect();
+        let library = repeat_with(|| Operation::random_operation_with(&mut rng))
+            .take(LIBRARY_SIZE)
+            .collect();
+        Self {
+            day,
+            seed,
+            layout,
+            rules,
+            library,
+        }
+    }
+}
+
+/// The best daily score of each day played, saved between sessions.
+#[derive(Resource, Serialize, Deserialize, Default, Clone, Debug)]
+#[serde(default)]
+pub struct DailyRecord {
+    pub best: BTreeMap<u64, u32>,
+}
+
+impl DailyRecord {
+    pub fn best(&self, day: u64) -> Option<u32> {
+        self.best.get(&day).copied()
+    }
+}
+
+pub struct DailyPlugin;
+impl Plugin for DailyPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DailyRecord>()
+            .add_systems(OnEnter(AppState::Loading), load_record)
+            .add_systems(
+                OnEnter(GameState::Scoring),
+                record_daily_score.run_if(resource_exists::<DailyChallenge>()),
+            )
+            .add_systems(OnEnter(AppState::Menu), end_daily);
+    }
+}
+
+fn load_record(mut record: ResMut<DailyRecord>, storage: Res<Storage>) {
+    let Some(text) = storage.load(RECORD_KEY) else {
+        return;
+    };
+    match ron::from_str(&text) {
+        Ok(loaded) => *record = loaded,
+        Err(err) => warn!(
+            "couldn't read {}, starting the daily scores over: {err}",
+            storage.location(RECORD_KEY)
+        ),
+    }
+}
+
+/// Keeps the score if it's the day's best. Only the first level of the run counts, later ones
+/// play on as a normal run. The scoreboard totals the score, so this runs after it.
+pub fn record_daily_score(
+    mut cmd: Commands,
+    daily: Res<DailyChallenge>,
+    score: Res<Score>,
+    mut record: ResMut<DailyRecord>,
+    storage: Res<Storage>,
+) {
+    cmd.remove_resource::<DailyChallenge>();
+    if record
+        .best(daily.day)
+        .is_some_and(|best| best >= score.score)
+    {
+        return;
+    }
+    info!(
+        "new best daily score for {}: {}",
+        date_label(daily.day),
+        score.score
+    );
+    record.best.insert(daily.day, score.score);
+    let result = ron::ser::to_string_pretty(&*record, default())
+        .map_err(|e| e.to_string())
+        .and_then(|ron| storage.save(RECORD_KEY, &ron).map_err(|e| e.to_string()));
+    if let Err(err) = result {
+        error!("couldn't save the daily scores: {err}");
+    }
+}
+
+fn end_daily(mut cmd: Commands) {
+    cmd.remove_resource::<DailyChallenge>();
+}
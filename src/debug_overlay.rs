@@ -0,0 +1,189 @@
+//! A corner of numbers for chasing performance problems: FPS, a graph of recent frame times,
+//! what's on the board and the current states. Only built with the `debug-ui` feature, and shown
+//! with `GameAction::DebugToggle` alongside xpbd's collider outlines.
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use bevy_xpbd_2d::prelude::Collisions;
+use leafwing_input_manager::common_conditions::action_just_pressed;
+
+use crate::{
+    board::IsOnBoard,
+    cards::{card::Card, GameState},
+    input::GameAction,
+    AppState,
+};
+
+/// Frames kept for the graph, one bar each.
+const HISTORY: usize = 90;
+const BAR_WIDTH: f32 = 3.;
+/// Graph pixels per millisecond of frame time.
+const PX_PER_MS: f32 = 2.;
+const GRAPH_HEIGHT: f32 = 100.;
+
+#[derive(Component)]
+pub struct DebugOverlay;
+
+#[derive(Component)]
+struct DebugText;
+
+/// The `n`th bar from the left, oldest first.
+#[derive(Component)]
+struct FrameBar(usize);
+
+/// Frame times in seconds, of real time so pausing and hit-stops don't show up as fast frames.
+#[derive(Resource, Default)]
+struct FrameTimes(VecDeque<f32>);
+
+pub struct DebugOverlayPlugin;
+impl Plugin for DebugOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FrameTimes>()
+            .add_systems(Startup, spawn_overlay)
+            .add_systems(
+                Update,
+                (
+                    toggle_overlay.run_if(action_just_pressed(GameAction::DebugToggle)),
+                    record_frame_time,
+                    (update_text, update_graph).run_if(overlay_visible),
+                )
+                    .chain(),
+            );
+    }
+}
+
+fn spawn_overlay(mut cmd: Commands) {
+    cmd.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                left: Val::Px(8.),
+                top: Val::Px(8.),
+                padding: UiRect::all(Val::Px(6.)),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.),
+                ..default()
+            },
+            background_color: BackgroundColor(Color::rgba(0., 0., 0., 0.6)),
+            visibility: Visibility::Hidden,
+            z_index: ZIndex::Global(100),
+            ..default()
+        },
+        DebugOverlay,
+    ))
+    .with_children(|parent| {
+        parent.spawn((
+            TextBundle::from_section(
+                "",
+                TextStyle {
+                    font_size: 16.,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ),
+            DebugText,
+        ));
+        parent
+            .spawn(NodeBundle {
+                style: Style {
+                    width: Val::Px(BAR_WIDTH * HISTORY as f32),
+                    height: Val::Px(GRAPH_HEIGHT),
+                    align_items: AlignItems::FlexEnd,
+                    ..default()
+                },
+                ..default()
+            })
+            .with_children(|graph| {
+                for n in 0..HISTORY {
+                    graph.spawn((
+                        NodeBundle {
+                            style: Style {
+                                width: Val::Px(BAR_WIDTH),
+                                height: Val::Px(0.),
+                                ..default()
+                            },
+                            ..default()
+                        },
+                        FrameBar(n),
+                    ));
+                }
+            });
+    });
+}
+
+fn toggle_overlay(mut q_overlay: Query<&mut Visibility, With<DebugOverlay>>) {
+    for mut visibility in q_overlay.iter_mut() {
+        *visibility = match *visibility {
+            Visibility::Hidden => Visibility::Inherited,
+            _ => Visibility::Hidden,
+        };
+    }
+}
+
+fn overlay_visible(q_overlay: Query<&Visibility, With<DebugOverlay>>) -> bool {
+    q_overlay
+        .iter()
+        .any(|visibility| *visibility != Visibility::Hidden)
+}
+
+fn record_frame_time(time: Res<Time<Real>>, mut frames: ResMut<FrameTimes>) {
+    if frames.0.len() == HISTORY {
+        frames.0.pop_front();
+    }
+    frames.0.push_back(time.delta_seconds());
+}
+
+fn update_text(
+    frames: Res<FrameTimes>,
+    collisions: Option<Res<Collisions>>,
+    app_state: Res<State<AppState>>,
+    game_state: Option<Res<State<GameState>>>,
+    q_shapes: Query<(), With<IsOnBoard>>,
+    q_cards: Query<(), With<Card>>,
+    mut q_text: Query<&mut Text, With<DebugText>>,
+) {
+    let average = frames.0.iter().sum::<f32>() / frames.0.len().max(1) as f32;
+    let fps = if average > 0. { 1. / average } else { 0. };
+    let collisions = collisions.map_or(0, |collisions| {
+        collisions
+            .iter()
+            .filter(|contacts| contacts.during_current_frame)
+            .count()
+    });
+    let game_state =
+        game_state.map_or_else(|| "-".to_string(), |state| format!("{:?}", state.get()));
+
+    for mut text in q_text.iter_mut() {
+        text.sections[0].value = format!(
+            "{fps:.0} fps ({:.1} ms)\n{} shapes, {} cards\n{collisions} collisions\n{:?} / {game_state}",
+            average * 1000.,
+            q_shapes.iter().len(),
+            q_cards.iter().len(),
+            app_state.get(),
+        );
+    }
+}
+
+fn update_graph(
+    frames: Res<FrameTimes>,
+    mut q_bars: Query<(&FrameBar, &mut Style, &mut BackgroundColor)>,
+) {
+    // NOTE: bars fill in from the right while the history is still short
+    let offset = HISTORY - frames.0.len();
+    for (bar, mut style, mut color) in q_bars.iter_mut() {
+        let ms = bar
+            .0
+            .checked_sub(offset)
+            .and_then(|n| frames.0.get(n))
+            .map_or(0., |seconds| seconds * 1000.);
+        style.height = Val::Px((ms * PX_PER_MS).min(GRAPH_HEIGHT));
+        color.0 = if ms <= 1000. / 60. {
+            Color::GREEN
+        } else if ms <= 1000. / 30. {
+            Color::YELLOW
+        } else {
+            Color::RED
+        };
+    }
+}
@@ -0,0 +1,161 @@
+//! Endless mode (`GameMode::Endless`): no time limit and no target, just waves of shapes that keep
+//! getting bigger. Each wave opens with a burst of shapes and trickles more in for as long as it
+//! lasts, faster every wave. The run ends when the board overflows, so the cards are there to keep
+//! the population down, and the score is how long the player held out.
+
+use bevy::prelude::*;
+use rand::seq::SliceRandom;
+
+use crate::{
+    board::{
+        config, launcher::fill_launch_queue, layout::ActiveLayout, SpawnBody, SpawnSet, SpawnSource,
+    },
+    cards::{zones::GameMode, GameState, GameTimer},
+    content::{ContentSelection, CustomContent},
+    game_over::GameOverRules,
+    game_shapes::{element::ElementReactions, Shape},
+    level::apply_level,
+    pause::PauseState,
+    platform::Duration,
+    popup::SpawnPopup,
+    rng::GameRng,
+    AppState,
+};
+
+/// Seconds each wave lasts.
+pub const WAVE_LENGTH: f32 = 30.;
+/// Shapes per second during the first wave.
+const BASE_RATE: f32 = 0.4;
+/// How much faster shapes come in each wave than in the one before.
+const WAVE_GROWTH: f32 = 1.3;
+/// Shapes in the burst that opens the first wave; later ones add `BURST_GROWTH` each.
+const BASE_BURST: u32 = 4;
+const BURST_GROWTH: u32 = 2;
+/// More shapes than this on the board ends the run.
+const ENDLESS_MAX_SHAPES: usize = 150;
+/// Long enough that the run's timer never runs out.
+const ENDLESS_TIME_LIMIT: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// The wave the run is in. Worked out from the run's elapsed time, so a restored run picks up
+/// in the wave it was saved in.
+#[derive(Resource, Default, Debug)]
+pub struct Waves {
+    /// Starts at 1, 0 before the first wave.
+    pub number: u32,
+    /// Shapes owed to the trickle, spawned once whole.
+    pending: f32,
+}
+
+impl Waves {
+    pub fn wave_at(elapsed: f32) -> u32 {
+        (elapsed / WAVE_LENGTH) as u32 + 1
+    }
+
+    /// Shapes per second during `wave`.
+    pub fn rate(wave: u32) -> f32 {
+        BASE_RATE * WAVE_GROWTH.powi(wave.saturating_sub(1) as i32)
+    }
+
+    pub fn burst(wave: u32) -> u32 {
+        BASE_BURST + BURST_GROWTH * wave.saturating_sub(1)
+    }
+}
+
+#[derive(Event, Clone, Copy, Debug)]
+pub struct WaveStarted {
+    pub number: u32,
+}
+
+pub struct EndlessPlugin;
+impl Plugin for EndlessPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Waves>()
+            .add_event::<WaveStarted>()
+            .add_systems(
+                OnEnter(AppState::Playing),
+                setup_endless
+                    .after(apply_level)
+                    .run_if(resource_equals(GameMode::Endless)),
+            )
+            .add_systems(
+                Update,
+                run_waves
+                    .before(SpawnSet)
+                    .after(fill_launch_queue)
+                    .run_if(in_state(AppState::Playing))
+                    .run_if(resource_equals(GameMode::Endless))
+                    .run_if(in_state(PauseState::Running))
+                    .run_if(not(in_state(GameState::Setup)))
+                    .run_if(not(in_state(GameState::Start)))
+                    .run_if(not(in_state(GameState::Scoring))),
+            )
+            .add_systems(OnExit(AppState::Playing), reset_endless);
+    }
+}
+
+/// Runs after the level has set the timer, and takes the time limit away again.
+fn setup_endless(mut game_timer: ResMut<GameTimer>, mut rules: ResMut<GameOverRules>) {
+    game_timer.timer.set_duration(ENDLESS_TIME_LIMIT);
+    rules.max_shapes = ENDLESS_MAX_SHAPES;
+}
+
+fn run_waves(
+    time: Res<Time>,
+    game_timer: Res<GameTimer>,
+    layout: Res<ActiveLayout>,
+    reactions: Res<ElementReactions>,
+    selection: Res<ContentSelection>,
+    content: Res<CustomContent>,
+    mut rng: ResMut<GameRng>,
+    mut waves: ResMut<Waves>,
+    mut started: EventWriter<WaveStarted>,
+    mut spawns: EventWriter<SpawnBody>,
+    mut popups: EventWriter<SpawnPopup>,
+) {
+    let rng = &mut rng.0;
+    let mut spawn = |count: u32| {
+        for _ in 0..count {
+            // NOTE: a custom shape distribution picked in the menu replaces the uniform draw
+            let entry = selection
+                .shapes(&content)
+                .and_then(|shapes| shapes.choose_weighted(rng, |entry| entry.weight).ok());
+            let shape = match entry {
+                Some(entry) => entry.shape_with(rng),
+                None => Shape::random_shape_with(rng),
+            };
+            spawns.send(SpawnBody {
+                shape,
+                transform: Transform::from_translation(layout.spawn_point(rng).extend(10.)),
+                velocity: None,
+                combo: 0,
+                element: entry
+                    .and_then(|entry| entry.element)
+                    .or_else(|| reactions.roll(rng)),
+                source: SpawnSource::Board,
+            });
+        }
+    };
+
+    let wave = Waves::wave_at(game_timer.timer.elapsed_secs());
+    if wave != waves.number {
+        waves.number = wave;
+        info!("wave {wave}");
+        started.send(WaveStarted { number: wave });
+        popups.send(SpawnPopup {
+            text: format!("Wave {wave}"),
+            position: config::CENTER,
+            color: Color::WHITE,
+        });
+        spawn(Waves::burst(wave));
+    }
+
+    waves.pending += Waves::rate(wave) * time.delta_seconds();
+    let whole = waves.pending.floor();
+    waves.pending -= whole;
+    spawn(whole as u32);
+}
+
+fn reset_endless(mut waves: ResMut<Waves>, mut rules: ResMut<GameOverRules>) {
+    *waves = Waves::default();
+    *rules = GameOverRules::default();
+}
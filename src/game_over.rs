@@ -0,0 +1,200 @@
+//! Ends runs early, and decides which end screen a run that ended goes to. A decided run leaves
+//! `AppState::Playing` for `AppState::Won` or `AppState::Lost` once `GameState::Scoring` has
+//! tallied it; one that simply ran out of time keeps its scoreboard in `GameState::Scoring`.
+
+use bevy::prelude::*;
+
+use crate::{
+    board::{IsOnBoard, ShapesCombined},
+    cards::{
+        zones::{ActivePlayer, GameMode, PlayerId, Winner},
+        GameState,
+    },
+    game_shapes::{GamePolygon, Shape},
+    AppState,
+};
+
+/// What ends a run early, besides the timer.
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct GameOverRules {
+    /// The run is lost once more shapes than this are on the board at once.
+    pub max_shapes: usize,
+    /// The run is won as soon as a combine produces this polygon.
+    pub target: GamePolygon,
+}
+
+impl Default for GameOverRules {
+    fn default() -> Self {
+        Self {
+            max_shapes: 500,
+            target: GamePolygon::Octagon,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Outcome {
+    Won,
+    Lost,
+    /// The level's target score was reached.
+    Cleared,
+}
+
+/// How the last run ended, if it ended before time ran out.
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct GameOutcome(pub Option<Outcome>);
+
+#[derive(Event, Clone, Copy, Debug)]
+pub struct GameWon {
+    pub shape: Shape,
+}
+
+#[derive(Event, Clone, Copy, Debug)]
+pub struct GameLost {
+    pub shapes: usize,
+}
+
+/// `owner` had to shuffle their discard pile back into their library, and it was empty.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct DeckedOut {
+    pub owner: PlayerId,
+}
+
+/// Ends the run in `GameState::Scoring` when the board overflows, the target shape is made or a
+/// player decks out, and moves decided runs on to `AppState::Won` / `AppState::Lost`.
+pub struct GameOverPlugin;
+impl Plugin for GameOverPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameOverRules>()
+            .init_resource::<GameOutcome>()
+            .add_event::<GameWon>()
+            .add_event::<GameLost>()
+            .add_event::<DeckedOut>()
+            .add_systems(
+                Update,
+                (
+                    check_target_shape.run_if(not(resource_equals(GameMode::Endless))),
+                    check_overflow,
+                    check_decked_out,
+                )
+                    .chain()
+                    .run_if(in_state(AppState::Playing))
+                    .run_if(not(in_state(GameState::Setup)))
+                    .run_if(not(in_state(GameState::Scoring))),
+            )
+            .add_systems(
+                OnEnter(GameState::Scoring),
+                leave_decided_run.run_if(in_state(AppState::Playing)),
+            )
+            .add_systems(OnExit(AppState::Playing), reset_outcome);
+    }
+}
+
+/// The end screen a run that ended goes to, `None` when nobody won or lost it before the timer ran
+/// out.
+pub fn decided_state(
+    mode: GameMode,
+    winner: Option<PlayerId>,
+    outcome: Option<Outcome>,
+) -> Option<AppState> {
+    match (mode, winner, outcome) {
+        (_, _, Some(Outcome::Lost)) => Some(AppState::Lost),
+        (_, _, Some(Outcome::Won | Outcome::Cleared)) => Some(AppState::Won),
+        (GameMode::Hotseat, Some(_), _) | (GameMode::Versus, Some(PlayerId(0)), _) => {
+            Some(AppState::Won)
+        },
+        (GameMode::Versus, Some(_), _) => Some(AppState::Lost),
+        _ => None,
+    }
+}
+
+/// Whether the run that just ended goes on to `AppState::Won` or `AppState::Lost`.
+pub fn run_decided(mode: Res<GameMode>, winner: Res<Winner>, outcome: Res<GameOutcome>) -> bool {
+    decided_state(*mode, **winner, **outcome).is_some()
+}
+
+fn leave_decided_run(
+    mut cmd: Commands,
+    mode: Res<GameMode>,
+    winner: Res<Winner>,
+    outcome: Res<GameOutcome>,
+) {
+    if let Some(state) = decided_state(*mode, **winner, **outcome) {
+        cmd.insert_resource(NextState(Some(state)));
+    }
+}
+
+fn check_target_shape(
+    mut cmd: Commands,
+    rules: Res<GameOverRules>,
+    mut outcome: ResMut<GameOutcome>,
+    mut winner: ResMut<Winner>,
+    active: Res<ActivePlayer>,
+    mut reader: EventReader<ShapesCombined>,
+    mut won: EventWriter<GameWon>,
+) {
+    if outcome.is_some() {
+        return;
+    }
+    let Some(combined) = reader
+        .read()
+        .find(|combined| combined.result.polygon == rules.target)
+    else {
+        return;
+    };
+
+    **outcome = Some(Outcome::Won);
+    // NOTE: the player whose card the combine came from, or whoever's turn it is
+    **winner = Some(combined.credit.unwrap_or(active.0));
+    won.send(GameWon {
+        shape: combined.result,
+    });
+    cmd.insert_resource(NextState(Some(GameState::Scoring)));
+}
+
+fn check_overflow(
+    mut cmd: Commands,
+    rules: Res<GameOverRules>,
+    mut outcome: ResMut<GameOutcome>,
+    q_shapes: Query<(), (With<Shape>, With<IsOnBoard>)>,
+    mut lost: EventWriter<GameLost>,
+) {
+    if outcome.is_some() {
+        return;
+    }
+    let shapes = q_shapes.iter().len();
+    if shapes <= rules.max_shapes {
+        return;
+    }
+
+    **outcome = Some(Outcome::Lost);
+    lost.send(GameLost { shapes });
+    cmd.insert_resource(NextState(Some(GameState::Scoring)));
+}
+
+/// In a two-player game the other player wins, unless both decked out together. Alone, the run
+/// just ends.
+fn check_decked_out(
+    mut cmd: Commands,
+    mode: Res<GameMode>,
+    outcome: Res<GameOutcome>,
+    mut winner: ResMut<Winner>,
+    mut reader: EventReader<DeckedOut>,
+) {
+    let owners: Vec<PlayerId> = reader.read().map(|decked| decked.owner).collect();
+    let Some(&first) = owners.first() else {
+        return;
+    };
+    if outcome.is_some() || winner.is_some() {
+        return;
+    }
+    // NOTE: a player's draws can each ask for a shuffle, so the same owner may come up repeatedly
+    if mode.seats().count() > 1 && owners.iter().all(|&owner| owner == first) {
+        **winner = Some(mode.next(first));
+    }
+    cmd.insert_resource(NextState(Some(GameState::Scoring)));
+}
+
+fn reset_outcome(mut outcome: ResMut<GameOutcome>) {
+    **outcome = None;
+}
@@ -0,0 +1,175 @@
+//! Elements, a layer on top of colors: some shapes are Fire, Water or Nature, and react when they
+//! touch a shape of another element. What each pair does is read from
+//! `assets/balance/default.elements.ron`, along with how often shapes spawn with an element, so it
+//! can be rebalanced without touching code. Pairs the file leaves out don't react.
+
+use std::io;
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+    utils::{BoxedFuture, HashMap},
+};
+use rand::{seq::IteratorRandom, Rng};
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+use crate::loading::BalanceAssets;
+
+#[derive(
+    Component, EnumIter, Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize, Reflect,
+)]
+pub enum Element {
+    Fire,
+    Water,
+    Nature,
+}
+
+impl Element {
+    pub fn name(self) -> &'static str {
+        match self {
+            Element::Fire => "Fire",
+            Element::Water => "Water",
+            Element::Nature => "Nature",
+        }
+    }
+
+    /// The color of the mark elemental shapes carry.
+    pub fn color(self) -> Color {
+        match self {
+            Element::Fire => Color::rgb(1., 0.55, 0.1),
+            Element::Water => Color::rgb(0.3, 0.75, 1.),
+            Element::Nature => Color::rgb(0.45, 0.9, 0.35),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum Reaction {
+    /// Both shapes lose their element, and a cloud of steam slows the shapes within `radius` of
+    /// where they met for `duration` seconds.
+    Steam { radius: f32, duration: f32 },
+    /// The second shape catches fire, and its color drains away over `duration` seconds until it
+    /// burns out.
+    Burn { duration: f32 },
+}
+
+impl Reaction {
+    pub fn name(self) -> &'static str {
+        match self {
+            Reaction::Steam { .. } => "Steam",
+            Reaction::Burn { .. } => "Burn",
+        }
+    }
+}
+
+/// The reaction for ordered pairs of elements, read as (first, second), and the chance a shape
+/// spawns with an element.
+#[derive(Asset, Resource, TypePath, Serialize, Deserialize, Clone, Debug)]
+pub struct ElementReactions {
+    pub element_chance: f32,
+    pub reactions: HashMap<(Element, Element), Reaction>,
+}
+
+impl Default for ElementReactions {
+    /// Water puts out fire, and fire burns nature.
+    fn default() -> Self {
+        use Element::*;
+
+        Self {
+            element_chance: 0.15,
+            reactions: [
+                (
+                    (Fire, Water),
+                    Reaction::Steam {
+                        radius: 120.,
+                        duration: 4.,
+                    },
+                ),
+                ((Fire, Nature), Reaction::Burn { duration: 5. }),
+            ]
+            .into_iter()
+            .collect(),
+        }
+    }
+}
+
+impl ElementReactions {
+    /// What happens when `first` touches `second`. The flag is set when the table has the pair
+    /// the other way around, so the shapes swap roles.
+    pub fn react(&self, first: Element, second: Element) -> Option<(Reaction, bool)> {
+        self.reactions
+            .get(&(first, second))
+            .map(|&reaction| (reaction, false))
+            .or_else(|| {
+                self.reactions
+                    .get(&(second, first))
+                    .map(|&reaction| (reaction, true))
+            })
+    }
+
+    /// The element a newly spawned shape gets, if any.
+    pub fn roll(&self, rng: &mut impl Rng) -> Option<Element> {
+        if rng.gen::<f32>() < self.element_chance {
+            Element::iter().choose(rng)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct ElementReactionsLoader;
+
+impl AssetLoader for ElementReactionsLoader {
+    type Asset = ElementReactions;
+    type Settings = ();
+    type Error = io::Error;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<ElementReactions, io::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            ron::de::from_bytes(&bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["elements.ron"]
+    }
+}
+
+/// Copies the loaded table into the resource, again whenever the file changes.
+pub fn apply_element_reactions(
+    mut reader: EventReader<AssetEvent<ElementReactions>>,
+    assets: Res<Assets<ElementReactions>>,
+    balance: Option<Res<BalanceAssets>>,
+    mut reactions: ResMut<ElementReactions>,
+) {
+    // NOTE: the collection is only inserted once loading finishes, so the first load is read then
+    let Some(balance) = balance else {
+        return;
+    };
+    if balance.is_added() {
+        if let Some(loaded) = assets.get(&balance.elements) {
+            *reactions = loaded.clone();
+        }
+    }
+    for event in reader.read() {
+        if let AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } = event {
+            if *id != balance.elements.id() {
+                continue;
+            }
+            if let Some(loaded) = assets.get(*id) {
+                *reactions = loaded.clone();
+                info!("element reactions updated");
+            }
+        }
+    }
+}
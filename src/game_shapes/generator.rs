@@ -0,0 +1,85 @@
+//! Builds shape meshes and colliders from an outline, so every `GamePolygon` gets both from its
+//! `sides()` alone and a new polygon only needs its line in the enum. Meshes are filled with
+//! triangles from the center, with UVs laid over the square around the outline so a texture covers
+//! the shape the way it would a sprite.
+
+use bevy::{
+    prelude::*,
+    render::{
+        mesh::{Indices, MeshVertexAttribute},
+        render_resource::{PrimitiveTopology, VertexFormat},
+    },
+};
+use bevy_xpbd_2d::prelude::Collider;
+
+/// How far out toward its outline each vertex is: 0 at the center, 1 on the outline. The
+/// `ShapeMaterial` draws its outline from it.
+pub const ATTRIBUTE_RIM: MeshVertexAttribute =
+    MeshVertexAttribute::new("Rim", 770_254_121, VertexFormat::Float32);
+
+/// Fills `outline` with a fan of triangles from its center. The outline has to be star-shaped
+/// around the center, which every regular polygon and star is.
+pub fn outline_mesh(outline: &[Vec2]) -> Mesh {
+    let extent = outline
+        .iter()
+        .map(|p| p.abs().max_element())
+        .fold(f32::EPSILON, f32::max);
+    let positions: Vec<[f32; 3]> = std::iter::once(Vec2::ZERO)
+        .chain(outline.iter().copied())
+        .map(|p| [p.x, p.y, 0.])
+        .collect();
+    // NOTE: UV space points y down
+    let uvs: Vec<[f32; 2]> = positions
+        .iter()
+        .map(|p| [0.5 + p[0] / (2. * extent), 0.5 - p[1] / (2. * extent)])
+        .collect();
+    let count = outline.len() as u32;
+    let indices = (0..count)
+        .flat_map(|i| [0, 1 + (i + 1) % count, 1 + i])
+        .collect();
+
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, vec![[0., 0., 1.]; positions.len()]);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+    mesh.insert_attribute(
+        ATTRIBUTE_RIM,
+        std::iter::once(0.)
+            .chain(std::iter::repeat_n(1., outline.len()))
+            .collect::<Vec<f32>>(),
+    );
+    mesh.set_indices(Some(Indices::U32(indices)));
+    mesh
+}
+
+/// A convex hull when the outline is convex, otherwise the outline cut into convex parts.
+pub fn outline_collider(outline: &[Vec2]) -> Collider {
+    if is_convex(outline) {
+        if let Some(hull) = Collider::convex_hull(outline.to_vec()) {
+            return hull;
+        }
+    }
+    let count = outline.len();
+    Collider::convex_decomposition(
+        outline.to_vec(),
+        (0..count)
+            .map(|i| [i as u32, ((i + 1) % count) as u32])
+            .collect(),
+    )
+}
+
+/// Whether every corner of the outline turns the same way.
+fn is_convex(outline: &[Vec2]) -> bool {
+    let count = outline.len();
+    let turns: Vec<f32> = (0..count)
+        .map(|i| {
+            let (a, b, c) = (
+                outline[i],
+                outline[(i + 1) % count],
+                outline[(i + 2) % count],
+            );
+            (b - a).perp_dot(c - b)
+        })
+        .collect();
+    turns.iter().all(|&turn| turn <= 0.) || turns.iter().all(|&turn| turn >= 0.)
+}
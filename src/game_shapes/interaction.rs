@@ -0,0 +1,139 @@
+//! How colors settle a fight when two shapes combine. The matrix is read from
+//! `assets/balance/default.colors.ron`, so it can be rebalanced without touching code. Pairs the
+//! file leaves out keep their built-in outcome.
+
+use std::io;
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+    utils::{BoxedFuture, HashMap},
+};
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+
+use super::GameColor;
+use crate::loading::BalanceAssets;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ColorOutcome {
+    /// The first color wins.
+    Win,
+    /// The second color wins.
+    Lose,
+    /// Neither wins; the result takes this color.
+    Merge(GameColor),
+}
+
+/// The outcome for every ordered pair of colors.
+#[derive(Asset, Resource, TypePath, Serialize, Deserialize, Clone, Debug)]
+pub struct ColorInteraction {
+    pub outcomes: HashMap<(GameColor, GameColor), ColorOutcome>,
+}
+
+impl Default for ColorInteraction {
+    /// Red beats blue, blue beats green and green beats red.
+    fn default() -> Self {
+        use ColorOutcome::*;
+        use GameColor::*;
+
+        Self {
+            outcomes: [
+                ((Red, Green), Lose),
+                ((Red, Blue), Win),
+                ((Green, Red), Win),
+                ((Green, Blue), Lose),
+                ((Blue, Red), Lose),
+                ((Blue, Green), Win),
+            ]
+            .into_iter()
+            .chain(GameColor::iter().map(|color| ((color, color), Win)))
+            .collect(),
+        }
+    }
+}
+
+impl ColorInteraction {
+    pub fn outcome(&self, first: GameColor, second: GameColor) -> ColorOutcome {
+        self.outcomes
+            .get(&(first, second))
+            .copied()
+            .unwrap_or(ColorOutcome::Win)
+    }
+
+    /// The color a combine of `first` and `second` ends up.
+    pub fn fight(&self, first: GameColor, second: GameColor) -> GameColor {
+        match self.outcome(first, second) {
+            ColorOutcome::Win => first,
+            ColorOutcome::Lose => second,
+            ColorOutcome::Merge(color) => color,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct ColorInteractionLoader;
+
+impl AssetLoader for ColorInteractionLoader {
+    type Asset = ColorInteraction;
+    type Settings = ();
+    type Error = io::Error;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<ColorInteraction, io::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            let mut interaction: ColorInteraction = ron::de::from_bytes(&bytes)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+            for (pair, outcome) in ColorInteraction::default().outcomes {
+                interaction.outcomes.entry(pair).or_insert_with(|| {
+                    warn!(
+                        "{} has no outcome for {pair:?}, using {outcome:?}",
+                        load_context.path().display()
+                    );
+                    outcome
+                });
+            }
+            Ok(interaction)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["colors.ron"]
+    }
+}
+
+/// Copies the loaded matrix into the resource, again whenever the file changes.
+pub fn apply_color_interaction(
+    mut reader: EventReader<AssetEvent<ColorInteraction>>,
+    assets: Res<Assets<ColorInteraction>>,
+    balance: Option<Res<BalanceAssets>>,
+    mut interaction: ResMut<ColorInteraction>,
+) {
+    // NOTE: the collection is only inserted once loading finishes, so the first load is read then
+    let Some(balance) = balance else {
+        return;
+    };
+    if balance.is_added() {
+        if let Some(loaded) = assets.get(&balance.colors) {
+            *interaction = loaded.clone();
+        }
+    }
+    for event in reader.read() {
+        if let AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } = event {
+            if *id != balance.colors.id() {
+                continue;
+            }
+            if let Some(loaded) = assets.get(*id) {
+                *interaction = loaded.clone();
+                info!("color interactions updated");
+            }
+        }
+    }
+}
@@ -0,0 +1,82 @@
+//! The material shapes are drawn with: a fill color from their `GameColor` and an emissive outline
+//! around it, which pulses while `pulse` is above zero, with the color's `Pattern` over the fill
+//! when patterns are on. See `assets/shaders/shape.wgsl`. It needs the rim attribute the
+//! `generator` gives every shape mesh.
+
+use bevy::{
+    prelude::*,
+    render::{
+        mesh::MeshVertexBufferLayout,
+        render_resource::{
+            AsBindGroup, RenderPipelineDescriptor, ShaderRef, SpecializedMeshPipelineError,
+        },
+    },
+    sprite::{Material2d, Material2dKey},
+};
+
+use super::{generator::ATTRIBUTE_RIM, palette::ColorOptions, GameColor};
+
+/// How far in from the outline the glow reaches, relative to the distance to the center.
+const OUTLINE_WIDTH: f32 = 0.18;
+/// How much lighter than the fill the outline is.
+const OUTLINE_LIGHTEN: f32 = 0.45;
+
+#[derive(Asset, AsBindGroup, TypePath, Clone, Debug)]
+pub struct ShapeMaterial {
+    #[uniform(0)]
+    pub color: Color,
+    #[uniform(0)]
+    pub outline: Color,
+    #[uniform(0)]
+    pub outline_width: f32,
+    /// How strongly the outline pulses, 0 for not at all.
+    #[uniform(0)]
+    pub pulse: f32,
+    /// A `Pattern`, as a number the shader reads.
+    #[uniform(0)]
+    pub pattern: u32,
+}
+
+impl ShapeMaterial {
+    pub fn new(color: GameColor, colors: ColorOptions, pulse: f32) -> Self {
+        let fill = colors.color(color);
+        let [r, g, b, _] = fill.as_rgba_f32();
+        let lighten = |c: f32| c + (1. - c) * OUTLINE_LIGHTEN;
+        Self {
+            color: fill,
+            outline: Color::rgb(lighten(r), lighten(g), lighten(b)),
+            outline_width: OUTLINE_WIDTH,
+            pulse,
+            pattern: colors.pattern(color) as u32,
+        }
+    }
+}
+
+impl Default for ShapeMaterial {
+    fn default() -> Self {
+        Self::new(GameColor::default(), ColorOptions::default(), 0.)
+    }
+}
+
+impl Material2d for ShapeMaterial {
+    fn vertex_shader() -> ShaderRef {
+        "shaders/shape.wgsl".into()
+    }
+
+    fn fragment_shader() -> ShaderRef {
+        "shaders/shape.wgsl".into()
+    }
+
+    fn specialize(
+        descriptor: &mut RenderPipelineDescriptor,
+        layout: &MeshVertexBufferLayout,
+        _key: Material2dKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        let vertex_layout = layout.get_layout(&[
+            Mesh::ATTRIBUTE_POSITION.at_shader_location(0),
+            ATTRIBUTE_RIM.at_shader_location(1),
+        ])?;
+        descriptor.vertex.buffers = vec![vertex_layout];
+        Ok(())
+    }
+}
@@ -1,72 +1,96 @@
 use core::ops::{Add, Sub};
 
 use bevy::{
-    ecs::system::Command,
     prelude::{shape::RegularPolygon, *},
-    sprite::MaterialMesh2dBundle,
+    sprite::{Material2dPlugin, MaterialMesh2dBundle},
     utils::HashMap,
 };
 use bevy_xpbd_2d::{self, prelude::Collider};
-use rand::seq::IteratorRandom;
+use rand::{seq::IteratorRandom, Rng};
+use serde::{Deserialize, Serialize};
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 
-use crate::{loading::TextureAssets, utils, AppState};
+use self::{
+    element::{apply_element_reactions, ElementReactions, ElementReactionsLoader},
+    interaction::{apply_color_interaction, ColorInteraction, ColorInteractionLoader},
+    material::ShapeMaterial,
+    palette::{recolor_materials, ColorOptions},
+    stats::ShapeStats,
+};
+use crate::utils;
+
+pub mod element;
+pub mod generator;
+pub mod interaction;
+pub mod material;
+pub mod palette;
+pub mod stats;
 
 pub mod config {
     pub const POLYGON_RADIUS: f32 = 80.;
+    /// Enough sides for a `Circle` to look round.
+    pub const CIRCLE_SIDES: usize = 32;
+    pub const PRIME_POINTS: usize = 5;
+    /// How far in a `Prime`'s star points dip, relative to `POLYGON_RADIUS`.
+    pub const PRIME_INNER_RADIUS: f32 = 0.5;
+    /// Shared shape colors plus pooled copies; anything past this means materials are leaking.
+    pub const MAX_SHAPE_MATERIALS: usize = 256;
+    /// How strongly the outline of a pulsing shape glows.
+    pub const PULSE_STRENGTH: f32 = 1.;
 }
 
-#[derive(EnumIter, Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(
+    EnumIter, Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize, Reflect, Default,
+)]
 pub enum GameColor {
+    #[default]
     Red,
     Green,
     Blue,
 }
 impl GameColor {
     pub fn random_color() -> GameColor {
-        let mut rng = rand::thread_rng();
-        GameColor::iter().choose(&mut rng).unwrap()
+        Self::random_color_with(&mut rand::thread_rng())
     }
-    pub fn fight(self, other: GameColor) -> GameColor {
-        match (self, other) {
-            (GameColor::Red, GameColor::Red) => GameColor::Red,
-            (GameColor::Red, GameColor::Green) => GameColor::Green,
-            (GameColor::Red, GameColor::Blue) => GameColor::Red,
-            (GameColor::Green, GameColor::Red) => GameColor::Green,
-            (GameColor::Green, GameColor::Green) => GameColor::Green,
-            (GameColor::Green, GameColor::Blue) => GameColor::Blue,
-            (GameColor::Blue, GameColor::Red) => GameColor::Red,
-            (GameColor::Blue, GameColor::Green) => GameColor::Blue,
-            (GameColor::Blue, GameColor::Blue) => GameColor::Blue,
-        }
+    pub fn random_color_with(rng: &mut impl Rng) -> GameColor {
+        GameColor::iter().choose(rng).unwrap()
     }
 }
-impl Into<Color> for GameColor {
-    fn into(self) -> Color {
-        match self {
+impl From<GameColor> for Color {
+    fn from(val: GameColor) -> Self {
+        match val {
             GameColor::Red => Color::RED,
             GameColor::Blue => Color::BLUE,
             GameColor::Green => Color::GREEN,
         }
     }
 }
-impl Into<ColorMaterial> for GameColor {
-    fn into(self) -> ColorMaterial {
+impl From<GameColor> for ColorMaterial {
+    fn from(val: GameColor) -> Self {
         ColorMaterial {
-            color: self.into(),
+            color: val.into(),
             texture: None,
         }
     }
 }
-#[derive(EnumIter, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+/// Each polygon counts as its number of sides, which is what the arithmetic adds up. Its mesh and
+/// collider are generated from `sides()`, so a new polygon only needs its line here.
+#[derive(
+    EnumIter, Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize, Reflect, Default,
+)]
 pub enum GamePolygon {
-    Triangle,
-    Square,
-    Pentagon,
-    Hexagon,
-    Heptagon,
-    Octagon,
+    #[default]
+    Triangle = 3,
+    Square = 4,
+    Pentagon = 5,
+    Hexagon = 6,
+    Heptagon = 7,
+    Octagon = 8,
+    /// The top of the chain, as if it had nine sides.
+    Circle = 9,
+    /// What a combine past the `Circle` makes, as if it had ten sides. See `board::prime`.
+    Prime = 10,
 }
 
 const STARTING_SHAPE: [GamePolygon; 3] = [
@@ -76,55 +100,72 @@ const STARTING_SHAPE: [GamePolygon; 3] = [
 ];
 
 impl GamePolygon {
-    fn get_vertices(self) -> u8 {
+    pub fn vertices(self) -> u8 {
+        self as u8
+    }
+    fn from_vertices(n: usize) -> Option<GamePolygon> {
+        GamePolygon::iter().find(|polygon| polygon.vertices() as usize == n)
+    }
+    /// How many sides the shape is drawn with. The `Circle` is a polygon with enough sides to
+    /// look round, and the `Prime` a star, with two sides per point.
+    pub fn sides(self) -> usize {
         match self {
-            GamePolygon::Triangle => 3,
-            GamePolygon::Square => 4,
-            GamePolygon::Pentagon => 5,
-            GamePolygon::Hexagon => 6,
-            GamePolygon::Heptagon => 7,
-            GamePolygon::Octagon => 8,
+            GamePolygon::Circle => config::CIRCLE_SIDES,
+            GamePolygon::Prime => 2 * config::PRIME_POINTS,
+            _ => self.vertices() as usize,
         }
     }
-    fn from_vertices(n: usize) -> Option<GamePolygon> {
-        match n {
-            3 => Some(GamePolygon::Triangle),
-            4 => Some(GamePolygon::Square),
-            5 => Some(GamePolygon::Pentagon),
-            6 => Some(GamePolygon::Hexagon),
-            7 => Some(GamePolygon::Heptagon),
-            8 => Some(GamePolygon::Octagon),
-            _ => None,
+    fn outline(self) -> Vec<Vec2> {
+        match self {
+            GamePolygon::Prime => utils::star_vertices(
+                self.sides() / 2,
+                config::POLYGON_RADIUS,
+                config::POLYGON_RADIUS * config::PRIME_INNER_RADIUS,
+            ),
+            _ => utils::regular_polygon_vertices(self.sides(), config::POLYGON_RADIUS),
         }
     }
     pub fn create_collider(self) -> Collider {
-        // Collider::ball(config::POLYGON_RADIUS)
-        Collider::convex_decomposition(
-            utils::regular_polygon_vertices(self.get_vertices() as usize, config::POLYGON_RADIUS),
-            (0..(self.get_vertices() as usize))
-                .map(|i| [i as u32, ((i + 1) % (self.get_vertices() as usize)) as u32])
-                .collect(),
-        )
+        generator::outline_collider(&self.outline())
+    }
+    pub fn create_mesh(self) -> Mesh {
+        generator::outline_mesh(&self.outline())
+    }
+    /// Cuts the polygon into `parts` pieces along `parts - 1` diagonals. Each cut adds its two
+    /// ends to both sides, so the pieces' vertices add up to `vertices + 2 * (parts - 1)`, spread
+    /// as evenly as possible. `None` if a piece would have fewer than three vertices.
+    pub fn divide(self, parts: u8) -> Option<Vec<GamePolygon>> {
+        if parts == 0 {
+            return None;
+        }
+        let total = self.vertices() as usize + 2 * (parts as usize - 1);
+        let (base, extra) = (total / parts as usize, total % parts as usize);
+        (0..parts as usize)
+            .map(|i| GamePolygon::from_vertices(base + usize::from(i < extra)))
+            .collect()
     }
     pub fn random_polygon() -> GamePolygon {
-        let mut rng = rand::thread_rng();
-        STARTING_SHAPE.iter().choose(&mut rng).unwrap().clone()
+        Self::random_polygon_with(&mut rand::thread_rng())
+    }
+    pub fn random_polygon_with(rng: &mut impl Rng) -> GamePolygon {
+        *STARTING_SHAPE.iter().choose(rng).unwrap()
     }
 }
-impl Into<RegularPolygon> for GamePolygon {
-    fn into(self) -> RegularPolygon {
+impl From<GamePolygon> for RegularPolygon {
+    fn from(val: GamePolygon) -> Self {
         RegularPolygon {
             radius: config::POLYGON_RADIUS,
-            sides: self.get_vertices().into(),
+            sides: val.sides(),
         }
     }
 }
 impl Add<Self> for GamePolygon {
     type Output = GamePolygon;
 
+    /// Past the `Circle`, the sum overflows into a `Prime`.
     fn add(self, rhs: Self) -> Self::Output {
-        GamePolygon::from_vertices(self.get_vertices() as usize + rhs.get_vertices() as usize)
-            .unwrap_or(GamePolygon::Octagon)
+        GamePolygon::from_vertices(self.vertices() as usize + rhs.vertices() as usize)
+            .unwrap_or(GamePolygon::Prime)
     }
 }
 
@@ -133,13 +174,16 @@ impl Sub<Self> for GamePolygon {
 
     fn sub(self, rhs: Self) -> Self::Output {
         GamePolygon::from_vertices(
-            ((self.get_vertices() as i8) - (rhs.get_vertices() as i8)).max(3) as usize,
+            ((self.vertices() as i8) - (rhs.vertices() as i8)).max(3) as usize
         )
         .unwrap_or(GamePolygon::Triangle)
     }
 }
 
-#[derive(Clone, Copy, Component, PartialEq, Eq, Debug)]
+#[derive(
+    Clone, Copy, Component, PartialEq, Eq, Hash, Debug, Serialize, Deserialize, Reflect, Default,
+)]
+#[reflect(Component)]
 pub struct Shape {
     pub polygon: GamePolygon,
     pub color: GameColor,
@@ -148,9 +192,9 @@ impl Shape {
     pub fn get_bundle(
         self,
         ma: &Res<ShapeAssets>,
-        c_m: &Res<ColorMaterialAssets>,
-    ) -> ColorMesh2dBundle {
-        ColorMesh2dBundle {
+        c_m: &Res<ShapeMaterialAssets>,
+    ) -> MaterialMesh2dBundle<ShapeMaterial> {
+        MaterialMesh2dBundle {
             mesh: get_polygon_mesh(&self.polygon, ma).into(),
             material: get_color_material(&self.color, c_m),
             ..Default::default()
@@ -158,9 +202,12 @@ impl Shape {
     }
 
     pub fn random_shape() -> Shape {
+        Self::random_shape_with(&mut rand::thread_rng())
+    }
+    pub fn random_shape_with(rng: &mut impl Rng) -> Shape {
         Shape {
-            polygon: GamePolygon::random_polygon(),
-            color: GameColor::random_color(),
+            polygon: GamePolygon::random_polygon_with(rng),
+            color: GameColor::random_color_with(rng),
         }
     }
 }
@@ -168,89 +215,148 @@ impl Shape {
 #[derive(Resource, Deref, DerefMut)]
 pub struct PolygonColliders(HashMap<GamePolygon, Collider>);
 
+/// Every polygon's mesh, generated at startup.
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct ShapeAssets(HashMap<GamePolygon, Handle<Mesh>>);
+
+/// The material every shape of a color shares, still and pulsing.
 #[derive(Resource, Default)]
-pub struct ShapeAssets {
-    pub triangle: Handle<Mesh>,
-    pub square: Handle<Mesh>,
-    pub pentagon: Handle<Mesh>,
-    pub hexagon: Handle<Mesh>,
-    pub heptagon: Handle<Mesh>,
-    pub octagon: Handle<Mesh>,
+pub struct ShapeMaterialAssets {
+    still: HashMap<GameColor, Handle<ShapeMaterial>>,
+    pulsing: HashMap<GameColor, Handle<ShapeMaterial>>,
 }
 
+/// Marks an entity that owns a pooled copy of a shape material (for tints, fades, ...). The copy
+/// goes back to the `MaterialPool` when this is removed or the entity is despawned.
+#[derive(Component)]
+pub struct AnimatedMaterial;
+
+/// Reuses per-entity material copies instead of adding a new `ShapeMaterial` for every effect.
 #[derive(Resource, Default)]
-pub struct ColorMaterialAssets {
-    pub red: Handle<ColorMaterial>,
-    pub green: Handle<ColorMaterial>,
-    pub blue: Handle<ColorMaterial>,
+pub struct MaterialPool {
+    free: Vec<Handle<ShapeMaterial>>,
+    in_use: HashMap<Entity, Handle<ShapeMaterial>>,
 }
 
-pub fn get_polygon_mesh(p: &GamePolygon, ma: &Res<ShapeAssets>) -> Handle<Mesh> {
-    match *p {
-        GamePolygon::Triangle => ma.triangle.clone_weak(),
-        GamePolygon::Square => ma.square.clone_weak(),
-        GamePolygon::Pentagon => ma.pentagon.clone_weak(),
-        GamePolygon::Hexagon => ma.hexagon.clone_weak(),
-        GamePolygon::Heptagon => ma.heptagon.clone_weak(),
-        GamePolygon::Octagon => ma.octagon.clone_weak(),
+impl MaterialPool {
+    /// Hands `entity` its own copy of `base` to animate. The entity needs `AnimatedMaterial` for
+    /// the copy to be returned.
+    pub fn take(
+        &mut self,
+        entity: Entity,
+        base: &Handle<ShapeMaterial>,
+        materials: &mut Assets<ShapeMaterial>,
+    ) -> Handle<ShapeMaterial> {
+        let material = materials.get(base).cloned().unwrap_or_default();
+        let handle = match self.free.pop() {
+            Some(handle) => {
+                materials.insert(&handle, material);
+                handle
+            },
+            None => materials.add(material),
+        };
+        self.in_use.insert(entity, handle.clone());
+        handle
     }
 }
 
-pub fn get_color_material(p: &GameColor, c_m: &Res<ColorMaterialAssets>) -> Handle<ColorMaterial> {
-    match *p {
-        GameColor::Red => c_m.red.clone_weak(),
-        GameColor::Green => c_m.green.clone_weak(),
-        GameColor::Blue => c_m.blue.clone_weak(),
+fn release_animated_materials(
+    mut pool: ResMut<MaterialPool>,
+    mut removed: RemovedComponents<AnimatedMaterial>,
+) {
+    for entity in removed.read() {
+        if let Some(handle) = pool.in_use.remove(&entity) {
+            pool.free.push(handle);
+        }
     }
 }
 
+fn check_material_count(materials: Res<Assets<ShapeMaterial>>) {
+    debug_assert!(
+        materials.len() <= config::MAX_SHAPE_MATERIALS,
+        "{} shape materials alive, expected at most {}",
+        materials.len(),
+        config::MAX_SHAPE_MATERIALS
+    );
+}
+
+pub fn get_polygon_mesh(p: &GamePolygon, ma: &Res<ShapeAssets>) -> Handle<Mesh> {
+    ma.get(p).map(Handle::clone_weak).unwrap_or_default()
+}
+
+pub fn get_color_material(p: &GameColor, c_m: &Res<ShapeMaterialAssets>) -> Handle<ShapeMaterial> {
+    c_m.still.get(p).map(Handle::clone_weak).unwrap_or_default()
+}
+
+/// The shared material for shapes of the color that are pulsing, see `board::pulse`.
+pub fn get_pulsing_material(
+    p: &GameColor,
+    c_m: &Res<ShapeMaterialAssets>,
+) -> Handle<ShapeMaterial> {
+    c_m.pulsing
+        .get(p)
+        .map(Handle::clone_weak)
+        .unwrap_or_default()
+}
+
 pub struct GameShapePlugin;
 
 impl Plugin for GameShapePlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(ShapeAssets::default())
-            .insert_resource(ColorMaterialAssets::default())
+            .insert_resource(ShapeMaterialAssets::default())
             .insert_resource(PolygonColliders(HashMap::new()))
+            .init_resource::<MaterialPool>()
+            .init_resource::<ColorOptions>()
+            .init_resource::<ShapeStats>()
+            .add_plugins(Material2dPlugin::<ShapeMaterial>::default())
+            .init_asset::<ColorInteraction>()
+            .init_asset_loader::<ColorInteractionLoader>()
+            .init_resource::<ColorInteraction>()
+            .init_asset::<ElementReactions>()
+            .init_asset_loader::<ElementReactionsLoader>()
+            .init_resource::<ElementReactions>()
+            .add_systems(
+                Update,
+                (
+                    apply_color_interaction,
+                    apply_element_reactions,
+                    recolor_materials,
+                ),
+            )
+            .add_systems(
+                Last,
+                (
+                    release_animated_materials,
+                    check_material_count.run_if(|| cfg!(debug_assertions)),
+                ),
+            )
             .add_systems(
                 Startup,
                 |mut a: ResMut<Assets<Mesh>>,
-                 mut m: ResMut<Assets<ColorMaterial>>,
+                 mut m: ResMut<Assets<ShapeMaterial>>,
                  mut s_a: ResMut<ShapeAssets>,
                  mut p_c: ResMut<PolygonColliders>,
-                 mut c_m_a: ResMut<ColorMaterialAssets>| {
-                    s_a.triangle =
-                        a.add(shape::RegularPolygon::new(config::POLYGON_RADIUS, 3).into());
-                    s_a.square =
-                        a.add(shape::RegularPolygon::new(config::POLYGON_RADIUS, 4).into());
-                    s_a.pentagon =
-                        a.add(shape::RegularPolygon::new(config::POLYGON_RADIUS, 5).into());
-                    s_a.hexagon =
-                        a.add(shape::RegularPolygon::new(config::POLYGON_RADIUS, 6).into());
-                    s_a.heptagon =
-                        a.add(shape::RegularPolygon::new(config::POLYGON_RADIUS, 7).into());
-                    s_a.octagon =
-                        a.add(shape::RegularPolygon::new(config::POLYGON_RADIUS, 8).into());
-
-                    c_m_a.red = m.add(ColorMaterial::from(Color::RED));
-                    c_m_a.green = m.add(ColorMaterial::from(Color::GREEN));
-                    c_m_a.blue = m.add(ColorMaterial::from(Color::BLUE));
-
-                    p_c.insert(
-                        GamePolygon::Triangle,
-                        GamePolygon::Triangle.create_collider(),
-                    );
+                 mut c_m_a: ResMut<ShapeMaterialAssets>| {
+                    for polygon in GamePolygon::iter() {
+                        s_a.insert(polygon, a.add(polygon.create_mesh()));
+                        p_c.insert(polygon, polygon.create_collider());
+                    }
 
-                    p_c.insert(GamePolygon::Square, GamePolygon::Square.create_collider());
-                    p_c.insert(
-                        GamePolygon::Pentagon,
-                        GamePolygon::Pentagon.create_collider(),
-                    );
-                    p_c.insert(GamePolygon::Hexagon, GamePolygon::Hexagon.create_collider());
-                    p_c.insert(
-                        GamePolygon::Heptagon,
-                        GamePolygon::Heptagon.create_collider(),
-                    );
-                    p_c.insert(GamePolygon::Octagon, GamePolygon::Octagon.create_collider());
+                    for color in GameColor::iter() {
+                        c_m_a.still.insert(
+                            color,
+                            m.add(ShapeMaterial::new(color, ColorOptions::default(), 0.)),
+                        );
+                        c_m_a.pulsing.insert(
+                            color,
+                            m.add(ShapeMaterial::new(
+                                color,
+                                ColorOptions::default(),
+                                config::PULSE_STRENGTH,
+                            )),
+                        );
+                    }
                 },
             );
     }
@@ -0,0 +1,109 @@
+//! How a `GameColor` looks. Telling the colors apart matters to play, so the settings offer
+//! palettes for the common kinds of color blindness, and a pattern drawn over each color's shapes
+//! (stripes, dots or a grid) so they can be told apart without color at all. Shapes on the board
+//! and on cards share their materials, and `recolor_materials` rewrites those whenever the options
+//! change. Anything else tinted by a shape's color (particles, trails, popups, zones) goes through
+//! `ColorOptions::color` too.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+
+use super::{config, material::ShapeMaterial, GameColor, ShapeMaterialAssets};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Palette {
+    #[default]
+    Normal,
+    /// Red-green, without green cones.
+    Deuteranopia,
+    /// Red-green, without red cones.
+    Protanopia,
+    /// Blue-yellow.
+    Tritanopia,
+}
+
+impl Palette {
+    pub const ALL: [Palette; 4] = [
+        Palette::Normal,
+        Palette::Deuteranopia,
+        Palette::Protanopia,
+        Palette::Tritanopia,
+    ];
+}
+
+/// The pattern drawn over a color's shapes, as the shader numbers them.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Pattern {
+    None = 0,
+    Stripes = 1,
+    Dots = 2,
+    Grid = 3,
+}
+
+impl From<GameColor> for Pattern {
+    fn from(color: GameColor) -> Self {
+        match color {
+            GameColor::Red => Pattern::Stripes,
+            GameColor::Green => Pattern::Dots,
+            GameColor::Blue => Pattern::Grid,
+        }
+    }
+}
+
+/// Saved with the rest of the `Settings`, picked on the pause menu.
+#[derive(Resource, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(default)]
+pub struct ColorOptions {
+    pub palette: Palette,
+    /// Draws each color's `Pattern` over its shapes.
+    pub patterns: bool,
+}
+
+impl ColorOptions {
+    pub fn color(self, color: GameColor) -> Color {
+        // NOTE: the alternative palettes are picked from Okabe and Ito's, which stay apart under
+        // each kind of color blindness
+        match (self.palette, color) {
+            (Palette::Normal, _) => color.into(),
+            (Palette::Deuteranopia, GameColor::Red) => Color::rgb(0.84, 0.37, 0.),
+            (Palette::Deuteranopia, GameColor::Green) => Color::rgb(0.94, 0.89, 0.26),
+            (Palette::Deuteranopia, GameColor::Blue) => Color::rgb(0., 0.45, 0.7),
+            (Palette::Protanopia, GameColor::Red) => Color::rgb(0.9, 0.62, 0.),
+            (Palette::Protanopia, GameColor::Green) => Color::rgb(0.34, 0.71, 0.91),
+            (Palette::Protanopia, GameColor::Blue) => Color::rgb(0.1, 0.1, 0.45),
+            (Palette::Tritanopia, GameColor::Red) => Color::rgb(0.84, 0.1, 0.2),
+            (Palette::Tritanopia, GameColor::Green) => Color::rgb(0., 0.62, 0.45),
+            (Palette::Tritanopia, GameColor::Blue) => Color::rgb(0.9, 0.9, 0.9),
+        }
+    }
+
+    pub fn pattern(self, color: GameColor) -> Pattern {
+        if self.patterns {
+            color.into()
+        } else {
+            Pattern::None
+        }
+    }
+}
+
+/// Rewrites the shared shape materials in place, so every shape using them follows.
+pub fn recolor_materials(
+    colors: Res<ColorOptions>,
+    shape_materials: Res<ShapeMaterialAssets>,
+    mut materials: ResMut<Assets<ShapeMaterial>>,
+) {
+    if !colors.is_changed() {
+        return;
+    }
+    for color in GameColor::iter() {
+        for (handles, pulse) in [
+            (&shape_materials.still, 0.),
+            (&shape_materials.pulsing, config::PULSE_STRENGTH),
+        ] {
+            if let Some(material) = handles.get(&color).and_then(|h| materials.get_mut(h)) {
+                *material = ShapeMaterial::new(color, *colors, pulse);
+            }
+        }
+    }
+}
@@ -0,0 +1,74 @@
+//! How each polygon behaves on the board. Bigger shapes are heavier, less bouncy and spin down
+//! faster, and the smallest ones and the unstable `Prime` only stick around for a while.
+
+use bevy::{prelude::*, utils::HashMap};
+use bevy_xpbd_2d::prelude::{AngularDamping, ColliderDensity, Restitution};
+use strum::IntoEnumIterator;
+
+use super::GamePolygon;
+
+/// Seconds a `Prime` holds together before it fades away.
+const PRIME_LIFETIME: f32 = 20.;
+
+#[derive(Clone, Copy, Debug)]
+pub struct PolygonStats {
+    pub density: f32,
+    pub restitution: f32,
+    pub angular_damping: f32,
+    /// Seconds on the board before the shape fades away. `None` keeps it for good.
+    pub lifetime: Option<f32>,
+}
+
+impl Default for PolygonStats {
+    fn default() -> Self {
+        Self {
+            density: 1.,
+            restitution: 1.,
+            angular_damping: 0.,
+            lifetime: None,
+        }
+    }
+}
+
+impl PolygonStats {
+    pub fn physics(&self) -> (ColliderDensity, Restitution, AngularDamping) {
+        (
+            ColliderDensity(self.density),
+            Restitution::new(self.restitution),
+            AngularDamping(self.angular_damping),
+        )
+    }
+}
+
+/// The stats of every polygon, read whenever a shape is spawned or transmuted.
+#[derive(Resource, Clone, Debug)]
+pub struct ShapeStats(pub HashMap<GamePolygon, PolygonStats>);
+
+impl Default for ShapeStats {
+    fn default() -> Self {
+        Self(
+            GamePolygon::iter()
+                .map(|polygon| {
+                    let extra = (polygon.vertices() - 3) as f32;
+                    let stats = PolygonStats {
+                        density: 1. + 0.25 * extra,
+                        restitution: 1. - 0.04 * extra,
+                        angular_damping: 0.1 * extra,
+                        lifetime: match polygon {
+                            GamePolygon::Triangle => Some(60.),
+                            GamePolygon::Prime => Some(PRIME_LIFETIME),
+                            _ => None,
+                        },
+                    };
+                    (polygon, stats)
+                })
+                .collect(),
+        )
+    }
+}
+
+impl ShapeStats {
+    pub fn get(&self, polygon: GamePolygon) -> PolygonStats {
+        self.0.get(&polygon).copied().unwrap_or_default()
+    }
+}
@@ -1,31 +1,45 @@
 use std::iter::repeat_with;
 
 use bevy::{prelude::*, render::view::RenderLayers};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 
 use crate::{
-    game_shapes::{ColorMaterialAssets, Shape, ShapeAssets},
+    game_shapes::{Shape, ShapeAssets, ShapeMaterialAssets},
     loading::TextureAssets,
-    operation::Operation,
 };
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Goal {
     pub s1: Shape,
     pub s2: Shape,
 }
 impl Goal {
-    pub fn random_goal() -> Self {
+    pub fn random_goal_with(rng: &mut impl Rng) -> Self {
         Goal {
-            s1: Shape::random_shape(),
-            s2: Shape::random_shape(),
+            s1: Shape::random_shape_with(rng),
+            s2: Shape::random_shape_with(rng),
         }
     }
+    /// A goal is met when the board holds more of `s1` than of `s2`.
+    pub fn is_met<'a>(&self, shapes: impl IntoIterator<Item = &'a Shape>) -> bool {
+        let (mut lhs, mut rhs) = (0, 0);
+        for shape in shapes {
+            if self.s1 == *shape {
+                lhs += 1;
+            }
+            if self.s2 == *shape {
+                rhs += 1;
+            }
+        }
+        lhs > rhs
+    }
     pub(crate) fn get_goal_entity(
         &self,
         cmd: &mut bevy::prelude::Commands<'_, '_>,
         textures: &Res<TextureAssets>,
 
         ma: &Res<ShapeAssets>,
-        c_m: &Res<ColorMaterialAssets>,
+        c_m: &Res<ShapeMaterialAssets>,
     ) -> Vec<Entity> {
         vec![
             cmd.spawn(self.s1.get_bundle(ma, c_m))
@@ -60,8 +74,8 @@ impl Goal {
     }
 }
 
-pub fn generate_random_goals(amount: usize) -> Vec<Goal> {
-    let mut rng = rand::thread_rng();
-
-    repeat_with(|| Goal::random_goal()).take(amount).collect()
+pub fn generate_random_goals_with(amount: usize, rng: &mut impl Rng) -> Vec<Goal> {
+    repeat_with(|| Goal::random_goal_with(rng))
+        .take(amount)
+        .collect()
 }
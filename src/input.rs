@@ -0,0 +1,236 @@
+//! Everything the player can do from the keyboard and mouse, as `GameAction`s. The bindings live
+//! in `Settings`, so they're saved with them and can be changed on the controls screen; the
+//! `InputMap` resource leafwing reads is kept in sync with them. Read the global
+//! `ActionState<GameAction>` resource, or `action_just_pressed` in a `run_if`.
+//!
+//! A single finger on a touchscreen holds `Select` down like the left mouse button, and `Pointer`
+//! follows it, so tapping a card picks it up and dragging it onto the board plays it. Two fingers
+//! pinch the camera instead. `Touches` is just empty without a touchscreen, so none of this is
+//! specific to the web or mobile builds.
+
+use bevy::prelude::*;
+use bevy_xpbd_2d::prelude::PhysicsDebugConfig;
+use leafwing_input_manager::{
+    buttonlike::ButtonState,
+    common_conditions::action_just_pressed,
+    plugin::InputManagerSystem,
+    prelude::{ActionState, DualAxis, InputManagerPlugin, InputMap, SingleAxis, VirtualDPad},
+    user_input::{InputKind, UserInput},
+    Actionlike,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::settings::Settings;
+
+/// How far a stick has to be pushed to count as pressing a direction.
+const STICK_THRESHOLD: f32 = 0.5;
+
+#[derive(Actionlike, Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Hash, Debug, Reflect)]
+pub enum GameAction {
+    /// Picking up and dropping cards. Stays on the mouse.
+    Select,
+    /// Moves the focus to the next card in the hand, for playing without a mouse.
+    FocusNext,
+    FocusPrevious,
+    /// Plays the focused card where the board cursor is.
+    PlayFocused,
+    /// Moves the board cursor. Stays on the right stick and the arrow keys.
+    MoveCursor,
+    /// Moves the keyboard focus to the next button on screen, for menus without a mouse.
+    MenuNext,
+    MenuPrevious,
+    /// Presses the focused button.
+    MenuSelect,
+    /// Fires the next shape from the board's launcher, if it has one.
+    Launch,
+    /// Starts the run and passes the device in a hotseat game.
+    Play,
+    /// Passes on playing a card this turn, so the hand is discarded and a new one drawn.
+    Draw,
+    /// Puts the held card back in the hand, and drops the focus.
+    Cancel,
+    Pause,
+    /// Quits to the main menu while paused.
+    QuitToMenu,
+    ZoomIn,
+    ZoomOut,
+    /// Zooms and pans the camera back out to the whole board and hand.
+    FrameCamera,
+    Snapshot,
+    /// Shows the physics colliders, when the app has xpbd's `PhysicsDebugPlugin`, and the
+    /// `debug-ui` feature's overlay.
+    DebugToggle,
+}
+
+impl GameAction {
+    pub fn label(self) -> &'static str {
+        match self {
+            GameAction::Select => "Select card",
+            GameAction::FocusNext => "Next card",
+            GameAction::FocusPrevious => "Previous card",
+            GameAction::PlayFocused => "Play card at cursor",
+            GameAction::MoveCursor => "Move board cursor",
+            GameAction::MenuNext => "Next button",
+            GameAction::MenuPrevious => "Previous button",
+            GameAction::MenuSelect => "Press button",
+            GameAction::Launch => "Launch shape",
+            GameAction::Play => "Start / pass device",
+            GameAction::Draw => "Skip turn and draw",
+            GameAction::Cancel => "Put card back",
+            GameAction::Pause => "Pause",
+            GameAction::QuitToMenu => "Save and quit",
+            GameAction::ZoomIn => "Zoom in",
+            GameAction::ZoomOut => "Zoom out",
+            GameAction::FrameCamera => "Reset camera",
+            GameAction::Snapshot => "Board snapshot",
+            GameAction::DebugToggle => "Debug view",
+        }
+    }
+
+    /// Moving between and pressing buttons, which the mouse could do anywhere too.
+    pub fn is_menu(self) -> bool {
+        matches!(
+            self,
+            GameAction::MenuNext | GameAction::MenuPrevious | GameAction::MenuSelect
+        )
+    }
+
+    /// Whether the controls screen can bind it to a key.
+    pub fn rebindable(self) -> bool {
+        !matches!(self, GameAction::Select | GameAction::MoveCursor)
+    }
+
+    pub fn default_input_map() -> InputMap<GameAction> {
+        let mut input_map = InputMap::new([
+            (KeyCode::Space, GameAction::Play),
+            (KeyCode::D, GameAction::Draw),
+            (KeyCode::Back, GameAction::Cancel),
+            (KeyCode::Escape, GameAction::Pause),
+            (KeyCode::M, GameAction::QuitToMenu),
+            (KeyCode::Equals, GameAction::ZoomIn),
+            (KeyCode::Minus, GameAction::ZoomOut),
+            (KeyCode::Home, GameAction::FrameCamera),
+            (KeyCode::F9, GameAction::Snapshot),
+            (KeyCode::F3, GameAction::DebugToggle),
+            (KeyCode::E, GameAction::FocusNext),
+            (KeyCode::Q, GameAction::FocusPrevious),
+            (KeyCode::Return, GameAction::PlayFocused),
+            (KeyCode::F, GameAction::Launch),
+            (KeyCode::Tab, GameAction::MenuNext),
+            (KeyCode::Return, GameAction::MenuSelect),
+        ]);
+        input_map
+            .insert(MouseButton::Left, GameAction::Select)
+            .insert(DualAxis::right_stick(), GameAction::MoveCursor)
+            .insert(VirtualDPad::arrow_keys(), GameAction::MoveCursor)
+            // NOTE: plain Tab is shorter, so leafwing's clash strategy leaves Shift+Tab to this
+            .insert_chord([KeyCode::ShiftLeft, KeyCode::Tab], GameAction::MenuPrevious);
+        for (button, action) in [
+            (GamepadButtonType::DPadRight, GameAction::FocusNext),
+            (GamepadButtonType::DPadLeft, GameAction::FocusPrevious),
+            (GamepadButtonType::DPadDown, GameAction::MenuNext),
+            (GamepadButtonType::DPadUp, GameAction::MenuPrevious),
+            (GamepadButtonType::South, GameAction::PlayFocused),
+            (GamepadButtonType::South, GameAction::Play),
+            (GamepadButtonType::South, GameAction::MenuSelect),
+            (GamepadButtonType::East, GameAction::Cancel),
+            (GamepadButtonType::West, GameAction::Launch),
+            (GamepadButtonType::Start, GameAction::Pause),
+            (GamepadButtonType::Select, GameAction::FrameCamera),
+            (GamepadButtonType::RightTrigger, GameAction::ZoomIn),
+            (GamepadButtonType::LeftTrigger, GameAction::ZoomOut),
+        ] {
+            input_map.insert(button, action);
+        }
+        input_map
+            .insert(
+                SingleAxis::positive_only(GamepadAxisType::LeftStickX, STICK_THRESHOLD),
+                GameAction::FocusNext,
+            )
+            .insert(
+                SingleAxis::negative_only(GamepadAxisType::LeftStickX, -STICK_THRESHOLD),
+                GameAction::FocusPrevious,
+            );
+        input_map
+    }
+}
+
+/// Binds `key` to `action` in place of its other keys. Mouse and gamepad bindings are kept, after
+/// the key, so the key is what `binding_label` shows.
+pub fn rebind_key(input_map: &mut InputMap<GameAction>, action: GameAction, key: KeyCode) {
+    let Some(inputs) = input_map.get_mut(action) else {
+        input_map.insert(key, action);
+        return;
+    };
+    inputs.retain(|input| !matches!(input, UserInput::Single(InputKind::Keyboard(_))));
+    inputs.insert(0, key.into());
+}
+
+/// Gives actions that have no bindings at all their default ones, e.g. actions added since the
+/// settings were saved.
+pub fn add_missing_bindings(input_map: &mut InputMap<GameAction>) {
+    for (action, inputs) in GameAction::default_input_map().iter() {
+        if input_map.get(*action).is_none_or(|bound| bound.is_empty()) {
+            for input in inputs {
+                input_map.insert(input.clone(), *action);
+            }
+        }
+    }
+}
+
+/// What `action` is bound to, for showing the player, e.g. "Press Space to Start".
+pub fn binding_label(input_map: &InputMap<GameAction>, action: GameAction) -> String {
+    input_map
+        .get(action)
+        .and_then(|inputs| inputs.first())
+        .map_or_else(|| "(unbound)".to_string(), |input| input.to_string())
+}
+
+pub struct InputPlugin;
+impl Plugin for InputPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(InputManagerPlugin::<GameAction>::default())
+            .init_resource::<ActionState<GameAction>>()
+            .insert_resource(GameAction::default_input_map())
+            .add_systems(
+                PreUpdate,
+                press_select_on_touch.after(InputManagerSystem::Update),
+            )
+            .add_systems(
+                Update,
+                (
+                    sync_input_map,
+                    toggle_physics_debug.run_if(action_just_pressed(GameAction::DebugToggle)),
+                ),
+            );
+    }
+}
+
+fn sync_input_map(settings: Res<Settings>, mut input_map: ResMut<InputMap<GameAction>>) {
+    if settings.is_changed() && *input_map != settings.input_map {
+        *input_map = settings.input_map.clone();
+    }
+}
+
+/// Runs after leafwing has read the mouse, which releases `Select` every frame no button is down.
+fn press_select_on_touch(
+    touches: Res<Touches>,
+    mut actions: ResMut<ActionState<GameAction>>,
+    mut was_touching: Local<bool>,
+) {
+    let touching = touches.iter().count() == 1;
+    if touching && !actions.action_data(GameAction::Select).state.just_pressed() {
+        actions.action_data_mut(GameAction::Select).state = if *was_touching {
+            ButtonState::Pressed
+        } else {
+            ButtonState::JustPressed
+        };
+    }
+    *was_touching = touching;
+}
+
+fn toggle_physics_debug(config: Option<ResMut<PhysicsDebugConfig>>) {
+    if let Some(mut config) = config {
+        config.enabled = !config.enabled;
+    }
+}
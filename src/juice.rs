@@ -0,0 +1,157 @@
+//! Screen shake and hit-stop. `Shake` jolts the board camera around where it's headed, and
+//! `HitStop` slows the virtual and physics clocks to a crawl for a moment of real time. Both are
+//! sent for big combines and long chains, and any other system can send them too.
+
+use bevy::prelude::*;
+use bevy_xpbd_2d::prelude::{Physics, PhysicsTime};
+use rand::Rng;
+
+use crate::{
+    board::ShapesCombined,
+    camera::{BoardCamera, CameraSet},
+    pause::PauseState,
+    AppState,
+};
+
+/// How fast the clocks run during a hit-stop.
+const HIT_STOP_SPEED: f32 = 0.05;
+/// Combines into a polygon with at least this many vertices shake the screen.
+pub const BIG_COMBINE_VERTICES: u8 = 6;
+/// Chains at least this deep shake the screen and stop it for a moment.
+pub const BIG_CHAIN_DEPTH: u32 = 3;
+
+/// Shakes the board camera by up to `intensity` pixels, easing off over `duration` seconds.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct Shake {
+    pub intensity: f32,
+    pub duration: f32,
+}
+
+/// Slows time down for `duration` seconds of real time.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct HitStop {
+    pub duration: f32,
+}
+
+/// The shake in progress. A new `Shake` replaces it only if it's stronger right now.
+#[derive(Resource, Default, Debug)]
+struct ShakeState {
+    intensity: f32,
+    timer: Timer,
+}
+
+impl ShakeState {
+    fn current(&self) -> f32 {
+        self.intensity * self.timer.percent_left()
+    }
+}
+
+/// Ticked on real time, since virtual time is what it slows.
+#[derive(Resource, Default, Debug)]
+struct HitStopTimer(Option<Timer>);
+
+pub struct JuicePlugin;
+impl Plugin for JuicePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<Shake>()
+            .add_event::<HitStop>()
+            .init_resource::<ShakeState>()
+            .init_resource::<HitStopTimer>()
+            .add_systems(
+                Update,
+                (
+                    juice_big_combines,
+                    (shake_camera, hit_stop).run_if(in_state(PauseState::Running)),
+                )
+                    .chain()
+                    .after(CameraSet)
+                    .run_if(in_state(AppState::Playing)),
+            )
+            .add_systems(OnExit(AppState::Playing), reset_juice);
+    }
+}
+
+fn juice_big_combines(
+    mut reader: EventReader<ShapesCombined>,
+    mut shakes: EventWriter<Shake>,
+    mut stops: EventWriter<HitStop>,
+) {
+    for combined in reader.read() {
+        let big = combined.result.polygon.vertices() >= BIG_COMBINE_VERTICES;
+        let chain = combined.depth >= BIG_CHAIN_DEPTH;
+        if big || chain {
+            shakes.send(Shake {
+                intensity: 4. + 2. * combined.depth.min(5) as f32,
+                duration: 0.3,
+            });
+        }
+        if chain {
+            stops.send(HitStop { duration: 0.08 });
+        }
+    }
+}
+
+/// The camera rig puts the camera back in place every frame, so the offset is only added.
+fn shake_camera(
+    time: Res<Time>,
+    mut reader: EventReader<Shake>,
+    mut shake: ResMut<ShakeState>,
+    mut q_camera: Query<&mut Transform, With<BoardCamera>>,
+) {
+    for event in reader.read() {
+        if event.intensity > shake.current() {
+            shake.intensity = event.intensity;
+            shake.timer = Timer::from_seconds(event.duration, TimerMode::Once);
+        }
+    }
+    if shake.timer.tick(time.delta()).finished() {
+        return;
+    }
+    let mut rng = rand::thread_rng();
+    let current = shake.current();
+    let offset = Vec2::new(
+        rng.gen_range(-current..=current),
+        rng.gen_range(-current..=current),
+    );
+    for mut transform in q_camera.iter_mut() {
+        transform.translation += offset.extend(0.);
+    }
+}
+
+fn hit_stop(
+    real_time: Res<Time<Real>>,
+    mut reader: EventReader<HitStop>,
+    mut stop: ResMut<HitStopTimer>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+    mut physics_time: ResMut<Time<Physics>>,
+) {
+    for event in reader.read() {
+        let remaining = stop.0.as_ref().map_or(0., |timer| timer.remaining_secs());
+        if event.duration > remaining {
+            stop.0 = Some(Timer::from_seconds(event.duration, TimerMode::Once));
+        }
+    }
+    let Some(timer) = stop.0.as_mut() else {
+        return;
+    };
+    if timer.tick(real_time.delta()).finished() {
+        stop.0 = None;
+        virtual_time.set_relative_speed(1.);
+        physics_time.set_relative_speed(1.);
+    } else {
+        virtual_time.set_relative_speed(HIT_STOP_SPEED);
+        physics_time.set_relative_speed(HIT_STOP_SPEED);
+    }
+}
+
+fn reset_juice(
+    mut shake: ResMut<ShakeState>,
+    mut stop: ResMut<HitStopTimer>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+    mut physics_time: ResMut<Time<Physics>>,
+) {
+    *shake = ShakeState::default();
+    stop.0 = None;
+    virtual_time.set_relative_speed(1.);
+    physics_time.set_relative_speed(1.);
+}
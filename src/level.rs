@@ -0,0 +1,117 @@
+//! Levels. Each one sets how fast shapes spawn, which polygons spawn, the score to reach and the
+//! time limit. Reaching the score clears the level, and the next one is harder on every count.
+//! Going back to the menu starts over from the first level.
+
+use bevy::prelude::*;
+use strum::IntoEnumIterator;
+
+use crate::{
+    board::BoardTick,
+    cards::{zones::GameMode, GameState, GameTimer},
+    game_over::{GameOutcome, Outcome},
+    game_shapes::GamePolygon,
+    platform::Duration,
+    score::Score,
+    AppState,
+};
+
+#[derive(Clone, Debug)]
+pub struct Level {
+    /// Starts at 1.
+    pub number: u32,
+    /// Seconds between timed spawns.
+    pub spawn_interval: f32,
+    /// Timed spawns of other polygons are skipped.
+    pub polygons: Vec<GamePolygon>,
+    /// Combine points that clear the level.
+    pub target_score: u32,
+    /// Seconds before the run ends.
+    pub time_limit: f32,
+}
+
+impl Level {
+    pub fn new(number: u32) -> Self {
+        let number = number.max(1);
+        let harder = (number - 1) as f32;
+        Self {
+            number,
+            spawn_interval: (1.25 * 0.85_f32.powf(harder)).max(0.4),
+            polygons: GamePolygon::iter().take(number as usize + 2).collect(),
+            target_score: 200 * number,
+            time_limit: (120. - 10. * harder).max(60.),
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        Self::new(self.number + 1)
+    }
+
+    pub fn allows(&self, polygon: GamePolygon) -> bool {
+        self.polygons.contains(&polygon)
+    }
+}
+
+#[derive(Resource, Deref, Clone, Debug)]
+pub struct CurrentLevel(pub Level);
+
+impl Default for CurrentLevel {
+    fn default() -> Self {
+        Self(Level::new(1))
+    }
+}
+
+#[derive(Event, Clone, Copy, Debug)]
+pub struct LevelCleared {
+    pub level: u32,
+}
+
+pub struct LevelPlugin;
+impl Plugin for LevelPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CurrentLevel>()
+            .add_event::<LevelCleared>()
+            .add_systems(OnEnter(AppState::Playing), apply_level)
+            .add_systems(
+                Update,
+                check_target_score
+                    .run_if(in_state(AppState::Playing))
+                    .run_if(not(resource_equals(GameMode::Endless)))
+                    .run_if(not(in_state(GameState::Setup)))
+                    .run_if(not(in_state(GameState::Scoring))),
+            )
+            .add_systems(OnEnter(AppState::Menu), reset_level);
+    }
+}
+
+pub fn apply_level(
+    level: Res<CurrentLevel>,
+    mut game_timer: ResMut<GameTimer>,
+    mut board_tick: ResMut<BoardTick>,
+) {
+    game_timer
+        .timer
+        .set_duration(Duration::from_secs_f32(level.time_limit));
+    board_tick.set_duration(Duration::from_secs_f32(level.spawn_interval));
+    info!("starting level {}", level.number);
+}
+
+fn check_target_score(
+    mut cmd: Commands,
+    level: Res<CurrentLevel>,
+    score: Res<Score>,
+    mut outcome: ResMut<GameOutcome>,
+    mut cleared: EventWriter<LevelCleared>,
+) {
+    if outcome.is_some() || score.combine_points < level.target_score {
+        return;
+    }
+    **outcome = Some(Outcome::Cleared);
+    cleared.send(LevelCleared {
+        level: level.number,
+    });
+    cmd.insert_resource(NextState(Some(GameState::Scoring)));
+}
+
+fn reset_level(mut level: ResMut<CurrentLevel>) {
+    *level = CurrentLevel::default();
+}
@@ -1,26 +1,159 @@
-mod board;
+#![allow(clippy::too_many_arguments, clippy::type_complexity)]
+
+pub mod achievements;
+pub mod args;
+pub mod audio;
+pub mod background;
+pub mod board;
 mod camera;
-mod cards;
-mod game_shapes;
+pub mod cards;
+pub mod content;
+pub mod daily;
+#[cfg(feature = "debug-ui")]
+pub mod debug_overlay;
+pub mod endless;
+pub mod game_over;
+pub mod game_shapes;
 mod goal;
+pub mod input;
+pub mod juice;
+pub mod level;
 mod loading;
-mod operation;
+pub mod locale;
+pub mod operation;
+pub mod particles;
+pub mod pause;
+pub mod platform;
+pub mod pointer;
+pub mod popup;
+pub mod prelude;
+pub mod replay;
+pub mod rng;
+pub mod save;
+pub mod score;
+pub mod settings;
+mod stats;
+pub mod trail;
+pub mod tutorial;
+pub mod tween_ids;
 mod ui;
 mod utils;
 
-use std::default;
-
-use bevy::prelude::*;
+use achievements::AchievementsPlugin;
+use audio::AudioPlugin;
+use background::BackgroundPlugin;
+use bevy::{app::PluginGroupBuilder, prelude::*};
+use bevy_tweening::TweeningPlugin;
 use bevy_xpbd_2d::resources::Gravity;
-use board::BoardPlugin;
+use board::{Board, BoardPlugin};
 use camera::CameraPlugin;
-use cards::CardsPlugin;
+use cards::{
+    rules::Rule,
+    zones::{ActivePlayer, ZoneKind, Zones},
+    CardsPlugin,
+};
+use content::ContentPlugin;
+use daily::DailyPlugin;
+use endless::EndlessPlugin;
+use game_over::GameOverPlugin;
 use game_shapes::GameShapePlugin;
+use input::InputPlugin;
+use juice::JuicePlugin;
+use level::LevelPlugin;
 use loading::LoadingPlugin;
+use locale::LocalePlugin;
+use particles::ParticlePlugin;
+use pause::PausePlugin;
+use platform::PlatformPlugin;
+use pointer::PointerPlugin;
+use popup::PopupPlugin;
+use replay::ReplayPlugin;
+use rng::RngPlugin;
+use save::SavePlugin;
+use score::ScorePlugin;
+use settings::SettingsPlugin;
+use stats::StatsPlugin;
+use trail::TrailPlugin;
+use tutorial::TutorialPlugin;
 use ui::UIPlugin;
 
+/// The app state and the resources every other game plugin shares. Added by `GamePlugins`.
 pub struct GamePlugin;
 
+/// All of the game's plugins, in the order they need to be built. Add bevy's `DefaultPlugins`
+/// (or an equivalent set with assets, audio and input) and xpbd's `PhysicsPlugins` before it.
+#[derive(Default)]
+pub struct GamePlugins {
+    headless: bool,
+}
+
+impl GamePlugins {
+    /// Leaves out the cameras and UI, for tools and tests that run without a window.
+    pub fn headless(mut self) -> Self {
+        self.headless = true;
+        self
+    }
+}
+
+impl PluginGroup for GamePlugins {
+    fn build(self) -> PluginGroupBuilder {
+        let group = PluginGroupBuilder::start::<Self>()
+            .add(PlatformPlugin)
+            .add(SettingsPlugin)
+            .add(LocalePlugin)
+            .add(InputPlugin)
+            .add(RngPlugin)
+            .add(ContentPlugin)
+            .add(GamePlugin)
+            .add(PausePlugin)
+            .add(AudioPlugin)
+            .add(TweeningPlugin)
+            .add(LoadingPlugin)
+            .add(GameShapePlugin)
+            .add(BoardPlugin)
+            .add(BackgroundPlugin)
+            .add(PointerPlugin)
+            .add(CardsPlugin)
+            .add(ScorePlugin)
+            .add(PopupPlugin)
+            .add(ParticlePlugin)
+            .add(TrailPlugin)
+            .add(JuicePlugin)
+            .add(SavePlugin)
+            .add(ReplayPlugin)
+            .add(GameOverPlugin)
+            .add(LevelPlugin)
+            .add(DailyPlugin)
+            .add(EndlessPlugin)
+            .add(TutorialPlugin)
+            .add(StatsPlugin)
+            .add(AchievementsPlugin)
+            .add(CameraPlugin)
+            .add(UIPlugin);
+        #[cfg(feature = "debug-ui")]
+        let group = group.add(debug_overlay::DebugOverlayPlugin);
+
+        if self.headless {
+            let group = group.disable::<CameraPlugin>().disable::<UIPlugin>();
+            #[cfg(feature = "debug-ui")]
+            let group = group.disable::<debug_overlay::DebugOverlayPlugin>();
+            group
+        } else {
+            group
+        }
+    }
+}
+
+/// Marks every root entity spawned for a run, so leaving `AppState::Playing` can tear the whole
+/// run down in one place.
+#[derive(Component)]
+pub struct GameplayEntity;
+
+/// Counts down after entering `AppState::Playing`, then checks that every gameplay singleton was
+/// spawned.
+#[derive(Resource, Deref, DerefMut)]
+struct SetupCheck(Timer);
+
 #[derive(States, Default, Clone, Eq, PartialEq, Debug, Hash)]
 pub enum AppState {
     // During the loading State the LoadingPlugin will load our assets
@@ -30,19 +163,85 @@ pub enum AppState {
     // During this State the actual game logic is executed
     Playing,
     Menu,
+    /// Picking the `BoardLayout` for a new run.
+    LevelSelect,
+    /// Picking a card for the `Collection` after a cleared level.
+    CardReward,
+    /// Building the `Decklist` between two levels.
+    DeckBuilder,
+    /// Rebinding the `GameAction`s.
+    Controls,
+    /// Plays back `--replay`, then carries on in `Playing`.
+    Replay,
+    /// Some assets couldn't be loaded. Shows which, and goes nowhere.
+    LoadFailed,
+    /// The scoreboard of a run that was won, once its board is gone. See `game_over`.
+    Won,
+    /// The scoreboard of a run that was lost.
+    Lost,
 }
 
 impl Plugin for GamePlugin {
     fn build(&self, app: &mut App) {
         app.add_state::<AppState>()
             .insert_resource(Gravity(Vec2::ZERO))
-            .add_plugins((
-                CameraPlugin,
-                CardsPlugin,
-                LoadingPlugin,
-                GameShapePlugin,
-                UIPlugin,
-                BoardPlugin,
-            ));
+            .add_systems(OnEnter(AppState::Playing), start_setup_check)
+            .add_systems(
+                Update,
+                check_setup
+                    .run_if(in_state(AppState::Playing))
+                    .run_if(resource_exists::<SetupCheck>()),
+            )
+            .add_systems(OnExit(AppState::Playing), teardown_gameplay);
+    }
+}
+
+fn teardown_gameplay(
+    mut cmd: Commands,
+    q_gameplay: Query<Entity, (With<GameplayEntity>, Without<Parent>)>,
+) {
+    for e in q_gameplay.iter() {
+        cmd.entity(e).despawn_recursive();
+    }
+}
+
+fn start_setup_check(mut cmd: Commands) {
+    cmd.insert_resource(SetupCheck(Timer::from_seconds(1., TimerMode::Once)));
+}
+
+fn check_setup(
+    mut cmd: Commands,
+    time: Res<Time>,
+    mut check: ResMut<SetupCheck>,
+    zones: Res<Zones>,
+    active: Res<ActivePlayer>,
+    q_rule: Query<(), With<Rule>>,
+    q_board: Query<(), With<Board>>,
+) {
+    if !check.tick(time.delta()).finished() {
+        return;
+    }
+    cmd.remove_resource::<SetupCheck>();
+
+    let mut missing = Vec::new();
+    if q_rule.iter().len() != 1 {
+        missing.push("Rule".to_string());
+    }
+    if q_board.is_empty() {
+        missing.push("Board".to_string());
+    }
+    for kind in [
+        ZoneKind::Library,
+        ZoneKind::Hand,
+        ZoneKind::Discard,
+        ZoneKind::Exhaust,
+    ] {
+        if zones.get(active.0, kind).is_none() {
+            missing.push(format!("{kind:?}"));
+        }
+    }
+
+    if !missing.is_empty() {
+        error!("gameplay setup incomplete, missing: {}", missing.join(", "));
     }
 }
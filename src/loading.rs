@@ -1,21 +1,164 @@
-use bevy::{prelude::*, render::texture::ImageSampler};
+//! Loads every asset collection during `AppState::Loading`, then moves on to the menu (or wherever
+//! the launch arguments say). `LoadingProgress` counts the loaded assets of each group for the
+//! loading screen, and remembers the ones that failed: a failed asset stops the load and goes to
+//! `AppState::LoadFailed`, where the error screen lists them, rather than waiting forever.
+
+use bevy::{
+    asset::{LoadState, RecursiveDependencyLoadState},
+    prelude::*,
+};
 use bevy_asset_loader::prelude::*;
 
-use crate::AppState;
+use crate::{
+    achievements::Achievements,
+    args::LaunchArgs,
+    board::layout::BoardLayout,
+    cards::definition::CardDefinition,
+    game_shapes::{element::ElementReactions, interaction::ColorInteraction, ShapeAssets},
+    locale::Locale,
+    tutorial::TutorialScript,
+    AppState,
+};
+
+/// The assets of one kind being loaded, and how many of them are done.
+#[derive(Debug)]
+pub struct AssetGroup {
+    pub label: &'static str,
+    handles: Vec<UntypedHandle>,
+    pub loaded: usize,
+}
+
+impl AssetGroup {
+    pub fn total(&self) -> usize {
+        self.handles.len()
+    }
+}
+
+#[derive(Resource, Default, Debug)]
+pub struct LoadingProgress {
+    pub groups: Vec<AssetGroup>,
+    /// Generated at startup rather than loaded: (ready, total).
+    pub meshes: (usize, usize),
+    /// Paths of the assets that failed to load.
+    pub failed: Vec<String>,
+}
+
+impl LoadingProgress {
+    /// From 0 to 1, over every asset of every group.
+    pub fn fraction(&self) -> f32 {
+        let (done, total) = self
+            .groups
+            .iter()
+            .fold(self.meshes, |(done, total), group| {
+                (done + group.loaded, total + group.total())
+            });
+        if total == 0 {
+            return 0.;
+        }
+        done as f32 / total as f32
+    }
+}
 
 pub struct LoadingPlugin;
 
 impl Plugin for LoadingPlugin {
     fn build(&self, app: &mut App) {
-        app.add_loading_state(
-            LoadingState::new(AppState::Loading).continue_to_state(AppState::Menu),
-        )
-        // .add_systems(Update, set_texture_tiled)
-        // .add_collection_to_loading_state::<_, FontAssets>(GameState::Loading)
-        // .add_collection_to_loading_state::<_, AudioAssets>(GameState::Loading)
-        .add_collection_to_loading_state::<_, TextureAssets>(AppState::Loading)
-        .add_collection_to_loading_state::<_, SoundAssets>(AppState::Loading)
-        .add_collection_to_loading_state::<_, FontAssets>(AppState::Loading);
+        let args = app.world.get_resource::<LaunchArgs>();
+        let next_state = if args.is_some_and(|args| args.replay.is_some()) {
+            AppState::Replay
+        } else {
+            args.and_then(|args| args.skip_to.clone())
+                .unwrap_or(AppState::Menu)
+        };
+
+        app.init_resource::<LoadingProgress>()
+            .add_loading_state(
+                LoadingState::new(AppState::Loading)
+                    .continue_to_state(next_state)
+                    .on_failure_continue_to_state(AppState::LoadFailed),
+            )
+            // .add_systems(Update, set_texture_tiled)
+            // .add_collection_to_loading_state::<_, FontAssets>(GameState::Loading)
+            // .add_collection_to_loading_state::<_, AudioAssets>(GameState::Loading)
+            .add_collection_to_loading_state::<_, TextureAssets>(AppState::Loading)
+            .add_collection_to_loading_state::<_, SoundAssets>(AppState::Loading)
+            .add_collection_to_loading_state::<_, FontAssets>(AppState::Loading)
+            .add_collection_to_loading_state::<_, CardAssets>(AppState::Loading)
+            .add_collection_to_loading_state::<_, BalanceAssets>(AppState::Loading)
+            .add_collection_to_loading_state::<_, LayoutAssets>(AppState::Loading)
+            .add_collection_to_loading_state::<_, AchievementAssets>(AppState::Loading)
+            .add_collection_to_loading_state::<_, TutorialAssets>(AppState::Loading)
+            .add_collection_to_loading_state::<_, LocaleAssets>(AppState::Loading)
+            .add_systems(OnEnter(AppState::Loading), track_collections)
+            .add_systems(
+                Update,
+                update_progress
+                    .run_if(in_state(AppState::Loading).or_else(in_state(AppState::LoadFailed))),
+            );
+    }
+}
+
+/// Asks for every collection's handles. The asset server hands back the ones the loading state
+/// already asked for, so nothing is loaded twice.
+fn track_collections(world: &mut World) {
+    let groups = vec![
+        ("textures", TextureAssets::load(world)),
+        ("sounds", SoundAssets::load(world)),
+        ("fonts", FontAssets::load(world)),
+        ("cards", CardAssets::load(world)),
+        ("balance", BalanceAssets::load(world)),
+        ("boards", LayoutAssets::load(world)),
+        ("achievements", AchievementAssets::load(world)),
+        ("tutorial", TutorialAssets::load(world)),
+        ("languages", LocaleAssets::load(world)),
+    ];
+    world.resource_mut::<LoadingProgress>().groups = groups
+        .into_iter()
+        .map(|(label, handles)| AssetGroup {
+            label,
+            handles,
+            loaded: 0,
+        })
+        .collect();
+}
+
+fn update_progress(
+    asset_server: Res<AssetServer>,
+    shape_assets: Option<Res<ShapeAssets>>,
+    meshes: Res<Assets<Mesh>>,
+    mut progress: ResMut<LoadingProgress>,
+) {
+    let progress = &mut *progress;
+    for group in progress.groups.iter_mut() {
+        group.loaded = 0;
+        for handle in group.handles.iter() {
+            let failed = asset_server.get_load_state(handle.id()) == Some(LoadState::Failed)
+                || asset_server.get_recursive_dependency_load_state(handle.id())
+                    == Some(RecursiveDependencyLoadState::Failed);
+            if failed {
+                let path = handle.path().map_or_else(
+                    || format!("{:?}", handle.id()),
+                    |path| path.path().display().to_string(),
+                );
+                if !progress.failed.contains(&path) {
+                    error!("couldn't load {path}");
+                    progress.failed.push(path);
+                }
+            } else if asset_server.get_recursive_dependency_load_state(handle.id())
+                == Some(RecursiveDependencyLoadState::Loaded)
+            {
+                group.loaded += 1;
+            }
+        }
+    }
+    if let Some(shape_assets) = shape_assets {
+        progress.meshes = (
+            shape_assets
+                .values()
+                .filter(|mesh| meshes.contains(*mesh))
+                .count(),
+            shape_assets.len(),
+        );
     }
 }
 
@@ -90,3 +233,67 @@ pub struct SoundAssets {
     #[asset(path = "sounds/pop.ogg")]
     pub spawn: Handle<AudioSource>,
 }
+/// Tuning data designers edit without touching code. Kept loaded so edits are picked up.
+#[derive(AssetCollection, Resource)]
+pub struct BalanceAssets {
+    #[asset(path = "balance/default.colors.ron")]
+    pub colors: Handle<ColorInteraction>,
+    #[asset(path = "balance/default.elements.ron")]
+    pub elements: Handle<ElementReactions>,
+}
+#[derive(AssetCollection, Resource)]
+pub struct AchievementAssets {
+    #[asset(path = "achievements/default.achievements.ron")]
+    pub achievements: Handle<Achievements>,
+}
+#[derive(AssetCollection, Resource)]
+pub struct TutorialAssets {
+    #[asset(path = "tutorial/intro.tutorial.ron")]
+    pub script: Handle<TutorialScript>,
+}
+// NOTE: offered on the language picker in this order
+#[derive(AssetCollection, Resource)]
+pub struct LocaleAssets {
+    #[asset(
+        paths("locale/en.locale.ron", "locale/fr.locale.ron"),
+        collection(typed)
+    )]
+    pub locales: Vec<Handle<Locale>>,
+}
+// NOTE: listed one by one, like the cards, and offered on the level select screen in this order
+#[derive(AssetCollection, Resource)]
+pub struct LayoutAssets {
+    #[asset(
+        paths(
+            "boards/default.board.ron",
+            "boards/walled.board.ron",
+            "boards/open.board.ron"
+        ),
+        collection(typed)
+    )]
+    pub layouts: Vec<Handle<BoardLayout>>,
+}
+// NOTE: listed one by one rather than as a folder, which can't be loaded on the web
+#[derive(AssetCollection, Resource)]
+pub struct CardAssets {
+    #[asset(
+        paths(
+            "cards/red_doubler.card.ron",
+            "cards/square_up.card.ron",
+            "cards/octagon_rush.card.ron",
+            "cards/clean_sweep.card.ron",
+            "cards/shockwave.card.ron",
+            "cards/triangle_rain.card.ron",
+            "cards/purge.card.ron",
+            "cards/foresight.card.ron",
+            "cards/seek_the_rule.card.ron",
+            "cards/featherfall.card.ron",
+            "cards/feeding_frenzy.card.ron",
+            "cards/deep_freeze.card.ron",
+            "cards/upheaval.card.ron",
+            "cards/crosswind.card.ron"
+        ),
+        collection(typed)
+    )]
+    pub definitions: Vec<Handle<CardDefinition>>,
+}
@@ -0,0 +1,201 @@
+//! Translations. Each language is a `*.locale.ron` file in `assets/locale`: its code, its name
+//! written in that language, and a map from keys to strings. UI text looks its strings up through
+//! `Localization`, falling back to English and then to the key itself, so a missing translation
+//! shows up rather than an empty label. Text spawned with a `Localized` key is rewritten whenever
+//! the `Language` changes or a locale file is edited, so switching needs no restart.
+//!
+//! Cards and keywords already carry English text in their own files; a locale can override them
+//! with `card.<id>.name`, `card.<id>.text` and `keyword.<Keyword>.description`.
+
+use std::{collections::BTreeMap, io};
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    ecs::system::SystemParam,
+    prelude::*,
+    utils::BoxedFuture,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    cards::{definition::CardDefinition, keyword::KeywordInfo},
+    loading::LocaleAssets,
+};
+
+/// Used for any key the current language is missing.
+pub const FALLBACK_LANGUAGE: &str = "en";
+
+#[derive(Asset, TypePath, Serialize, Deserialize, Clone, Debug)]
+pub struct Locale {
+    /// What `Language` holds, like `"en"`.
+    pub code: String,
+    /// Shown on the language picker, in the language itself.
+    pub name: String,
+    pub strings: BTreeMap<String, String>,
+}
+
+/// The code of the language the UI is shown in. Saved with the rest of the `Settings`.
+#[derive(Resource, Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(transparent)]
+pub struct Language(pub String);
+
+impl Default for Language {
+    fn default() -> Self {
+        Self(FALLBACK_LANGUAGE.to_string())
+    }
+}
+
+/// A text whose first section is the string for `key`, with each `{name}` in it replaced by the
+/// matching argument.
+#[derive(Component, Clone, Debug)]
+pub struct Localized {
+    pub key: String,
+    pub args: Vec<(String, String)>,
+}
+
+impl Localized {
+    pub fn new(key: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            args: Vec::new(),
+        }
+    }
+
+    pub fn with(mut self, name: impl Into<String>, value: impl ToString) -> Self {
+        self.args.push((name.into(), value.to_string()));
+        self
+    }
+}
+
+/// Looks strings up in the current language.
+#[derive(SystemParam)]
+pub struct Localization<'w> {
+    language: Res<'w, Language>,
+    handles: Option<Res<'w, LocaleAssets>>,
+    locales: Res<'w, Assets<Locale>>,
+}
+
+impl Localization<'_> {
+    /// Whether the language changed since the system last ran.
+    pub fn is_changed(&self) -> bool {
+        self.language.is_changed()
+    }
+
+    /// The loaded languages, in the order they're listed.
+    pub fn languages(&self) -> Vec<&Locale> {
+        self.handles.as_ref().map_or_else(Vec::new, |handles| {
+            handles
+                .locales
+                .iter()
+                .filter_map(|handle| self.locales.get(handle))
+                .collect()
+        })
+    }
+
+    /// The name of the current language.
+    pub fn language_name(&self) -> String {
+        self.locale(&self.language.0)
+            .map_or_else(|| self.language.0.clone(), |locale| locale.name.clone())
+    }
+
+    fn locale(&self, code: &str) -> Option<&Locale> {
+        self.languages()
+            .into_iter()
+            .find(|locale| locale.code == code)
+    }
+
+    /// The string for `key` in the current language, or in English.
+    pub fn lookup(&self, key: &str) -> Option<String> {
+        [self.language.0.as_str(), FALLBACK_LANGUAGE]
+            .into_iter()
+            .find_map(|code| self.locale(code)?.strings.get(key).cloned())
+    }
+
+    pub fn get(&self, key: &str) -> String {
+        self.lookup(key).unwrap_or_else(|| key.to_string())
+    }
+
+    /// The string for `key`, with each `{name}` replaced by its value.
+    pub fn format(&self, key: &str, args: &[(&str, String)]) -> String {
+        args.iter().fold(self.get(key), |text, (name, value)| {
+            text.replace(&format!("{{{name}}}"), value)
+        })
+    }
+
+    pub fn card_name(&self, definition: &CardDefinition) -> String {
+        self.lookup(&format!("card.{}.name", definition.id.0))
+            .unwrap_or_else(|| definition.name.clone())
+    }
+
+    /// `CardDefinition::rules_text`, with the card's own text translated when there's a string
+    /// for it.
+    pub fn card_text(&self, definition: &CardDefinition) -> String {
+        match self.lookup(&format!("card.{}.text", definition.id.0)) {
+            Some(text) => definition.with_keywords(text),
+            None => definition.rules_text(),
+        }
+    }
+
+    pub fn keyword_description(&self, info: &KeywordInfo) -> String {
+        self.lookup(&format!("keyword.{:?}.description", info.keyword))
+            .unwrap_or_else(|| info.description.clone())
+    }
+}
+
+#[derive(Default)]
+pub struct LocaleLoader;
+
+impl AssetLoader for LocaleLoader {
+    type Asset = Locale;
+    type Settings = ();
+    type Error = io::Error;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Locale, io::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            ron::de::from_bytes(&bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["locale.ron"]
+    }
+}
+
+pub struct LocalePlugin;
+impl Plugin for LocalePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<Locale>()
+            .init_asset_loader::<LocaleLoader>()
+            .init_resource::<Language>()
+            .add_systems(PostUpdate, localize_text);
+    }
+}
+
+/// Runs in `PostUpdate`, so text spawned this frame is filled in before it's laid out.
+fn localize_text(
+    loc: Localization,
+    mut edits: EventReader<AssetEvent<Locale>>,
+    mut q_text: Query<(Ref<Localized>, &mut Text)>,
+) {
+    let edited = edits.read().count() > 0;
+    for (localized, mut text) in q_text.iter_mut() {
+        if !(edited || loc.is_changed() || localized.is_changed()) {
+            continue;
+        }
+        let args: Vec<(&str, String)> = localized
+            .args
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.clone()))
+            .collect();
+        if let Some(section) = text.sections.first_mut() {
+            section.value = loc.format(&localized.key, &args);
+        }
+    }
+}
@@ -1,28 +1,47 @@
 #![allow(clippy::too_many_arguments, clippy::type_complexity)]
 
-use bevy::{asset::AssetMetaCheck, prelude::*};
-use bevy_tweening::*;
-use bevy_xpbd_2d::prelude::{PhysicsDebugPlugin, PhysicsPlugins};
+use bevy::{asset::AssetMetaCheck, audio::AudioPlugin, prelude::*};
+use bevy_xpbd_2d::prelude::{PhysicsDebugConfig, PhysicsDebugPlugin, PhysicsPlugins};
 // use bevy_xpbd
-use shapecraft::GamePlugin;
+use shapecraft::prelude::*;
 
 fn main() {
+    let args = LaunchArgs::from_env();
+    let resolution = args.windowed.unwrap_or(Vec2::new(1920., 1080.));
+    let volume = if args.mute { 0. } else { 1. };
+    // NOTE: only picks up edits when built with the `dev` feature, bevy warns when it's missing
+    let watch_assets = args.dev.then_some(true);
+
     App::new()
         .insert_resource(AssetMetaCheck::Never)
+        .insert_resource(args)
+        // NOTE: hidden until `GameAction::DebugToggle`
+        .insert_resource(PhysicsDebugConfig {
+            enabled: false,
+            ..default()
+        })
         .add_plugins((
-            PhysicsPlugins::default(),
-            // PhysicsDebugPlugin::default(),
-            TweeningPlugin,
-            GamePlugin,
-            DefaultPlugins.set(WindowPlugin {
-                primary_window: Some(Window {
-                    title: "Shapecraft".to_string(),
-                    resolution: (1920., 1080.).into(),
-                    canvas: Some("#bevy".to_owned()),
+            DefaultPlugins
+                .set(WindowPlugin {
+                    primary_window: Some(Window {
+                        title: "Shapecraft".to_string(),
+                        resolution: resolution.into(),
+                        canvas: Some("#bevy".to_owned()),
+                        ..default()
+                    }),
+                    ..default()
+                })
+                .set(AudioPlugin {
+                    global_volume: GlobalVolume::new(volume),
+                    ..default()
+                })
+                .set(AssetPlugin {
+                    watch_for_changes_override: watch_assets,
                     ..default()
                 }),
-                ..default()
-            }),
+            PhysicsPlugins::default(),
+            PhysicsDebugPlugin::default(),
+            GamePlugins::default(),
         ))
         .run();
 }
@@ -1,22 +1,27 @@
 use std::iter::repeat_with;
 
 use bevy::{prelude::*, render::view::RenderLayers, sprite::SpriteBundle, utils::default};
-use rand::{seq::IteratorRandom, Rng};
-use strum::IntoEnumIterator;
-use strum_macros::EnumIter;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 
 use crate::{
-    game_shapes::{ColorMaterialAssets, Shape, ShapeAssets},
+    game_shapes::{get_polygon_mesh, GamePolygon, Shape, ShapeAssets, ShapeMaterialAssets},
     loading::TextureAssets,
 };
-#[derive(Clone, Debug)]
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub enum Operation {
+    /// Two of the shape touching each break into this many smaller copies.
     Mul(Shape, u32),
+    /// Two of the shape touching are each cut into two lower-order polygons.
+    Div(Shape),
     Sub(Shape, Shape),
     Add(Shape, Shape),
     // Sqr(Shape),
     Inc(Shape),
     Dec(Shape),
+    /// Played once rather than kept as a rule: every shape of the first polygon on the board
+    /// becomes the second.
+    Transmute(GamePolygon, GamePolygon),
     None,
 }
 impl Operation {
@@ -26,15 +31,29 @@ impl Operation {
     //     }
     // }
     pub fn random_operation() -> Operation {
-        let mut rng = rand::thread_rng();
-
+        Self::random_operation_with(&mut rand::thread_rng())
+    }
+    /// Same odds as `random_operation`, drawn from `rng` so a seed picks the same operations.
+    pub fn random_operation_with(rng: &mut impl Rng) -> Operation {
         let o = rng.gen_range(0..100);
         match o {
-            0..=9 => Operation::Mul(Shape::random_shape(), 2),
-            10..=19 => Operation::Sub(Shape::random_shape(), Shape::random_shape()),
-            20..=39 => Operation::Add(Shape::random_shape(), Shape::random_shape()),
-            40..=69 => Operation::Inc(Shape::random_shape()),
-            70..=89 => Operation::Dec(Shape::random_shape()),
+            0..=9 => Operation::Mul(Shape::random_shape_with(rng), 2),
+            10..=19 => Operation::Sub(Shape::random_shape_with(rng), Shape::random_shape_with(rng)),
+            20..=39 => Operation::Add(Shape::random_shape_with(rng), Shape::random_shape_with(rng)),
+            40..=69 => Operation::Inc(Shape::random_shape_with(rng)),
+            70..=89 => Operation::Dec(Shape::random_shape_with(rng)),
+            90..=94 => {
+                let from = GamePolygon::random_polygon_with(rng);
+                let to = repeat_with(|| GamePolygon::random_polygon_with(rng))
+                    .find(|&p| p != from)
+                    .unwrap();
+                Operation::Transmute(from, to)
+            },
+            95..=97 => Operation::Div(
+                repeat_with(|| Shape::random_shape_with(rng))
+                    .find(|s| s.polygon.divide(2).is_some())
+                    .unwrap(),
+            ),
             _ => Operation::None,
         }
     }
@@ -45,10 +64,10 @@ impl Operation {
         textures: &Res<TextureAssets>,
 
         ma: &Res<ShapeAssets>,
-        c_m: &Res<ColorMaterialAssets>,
+        c_m: &Res<ShapeMaterialAssets>,
     ) -> Vec<Entity> {
         match self {
-            Operation::Mul(s, i) => {
+            Operation::Mul(s, _i) => {
                 vec![
                     cmd.spawn(s.get_bundle(ma, c_m))
                         .insert(Transform {
@@ -171,6 +190,88 @@ impl Operation {
             //     ]
             // },
             Operation::None => vec![],
+            Operation::Transmute(from, to) => {
+                // NOTE: drawn with the default (white) material, since any color transmutes
+                vec![
+                    cmd.spawn(ColorMesh2dBundle {
+                        mesh: get_polygon_mesh(from, ma).into(),
+                        transform: Transform {
+                            translation: Vec3::new(-40., 0., 1.),
+                            scale: Vec3::new(0.3, 0.3, 1.),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .insert(RenderLayers::layer(1))
+                    .id(),
+                    cmd.spawn(SpriteBundle {
+                        texture: textures.gt.clone(),
+                        transform: Transform {
+                            translation: Vec3::new(0., 0., 1.),
+                            scale: Vec3::new(0.4, 0.4, 1.),
+                            ..default()
+                        },
+
+                        ..default()
+                    })
+                    .insert(RenderLayers::layer(1))
+                    .id(),
+                    cmd.spawn(ColorMesh2dBundle {
+                        mesh: get_polygon_mesh(to, ma).into(),
+                        transform: Transform {
+                            translation: Vec3::new(40., 0., 1.),
+                            scale: Vec3::new(0.3, 0.3, 1.),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .insert(RenderLayers::layer(1))
+                    .id(),
+                ]
+            },
+            Operation::Div(s) => {
+                let mut entities = vec![
+                    cmd.spawn(s.get_bundle(ma, c_m))
+                        .insert(Transform {
+                            translation: Vec3::new(-40., 0., 1.),
+                            scale: Vec3::new(0.3, 0.3, 1.),
+                            ..default()
+                        })
+                        .insert(RenderLayers::layer(1))
+                        .id(),
+                    cmd.spawn(SpriteBundle {
+                        texture: textures.gt.clone(),
+                        transform: Transform {
+                            translation: Vec3::new(0., 0., 1.),
+                            scale: Vec3::new(0.4, 0.4, 1.),
+                            ..default()
+                        },
+
+                        ..default()
+                    })
+                    .insert(RenderLayers::layer(1))
+                    .id(),
+                ];
+                // NOTE: there's no divide symbol, so the card shows the pieces it cuts into
+                let pieces = s.polygon.divide(2).unwrap_or_default();
+                for (i, polygon) in pieces.into_iter().enumerate() {
+                    let piece = Shape {
+                        polygon,
+                        color: s.color,
+                    };
+                    entities.push(
+                        cmd.spawn(piece.get_bundle(ma, c_m))
+                            .insert(Transform {
+                                translation: Vec3::new(40., 25. - i as f32 * 50., 1.),
+                                scale: Vec3::new(0.18, 0.18, 1.),
+                                ..default()
+                            })
+                            .insert(RenderLayers::layer(1))
+                            .id(),
+                    );
+                }
+                entities
+            },
             Operation::Inc(s) => {
                 vec![
                     cmd.spawn(s.get_bundle(ma, c_m))
@@ -223,9 +324,10 @@ impl Operation {
     }
 }
 pub fn generate_random_operations(num: usize) -> Vec<Operation> {
-    let mut rng = rand::thread_rng();
-
-    repeat_with(|| Operation::random_operation())
+    generate_random_operations_with(num, &mut rand::thread_rng())
+}
+pub fn generate_random_operations_with(num: usize, rng: &mut impl Rng) -> Vec<Operation> {
+    repeat_with(|| Operation::random_operation_with(rng))
         .take(num)
         .collect()
 }
@@ -0,0 +1,180 @@
+//! Bursts of small sprites that fly apart, slow down and fade. Shapes combining, splitting and
+//! leaving the board each have a preset, colored like the shape. Any system can make a burst by
+//! sending `SpawnParticles`.
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::{
+    board::{population::DespawnShape, ShapesCombined, ShapesSplit},
+    game_shapes::{palette::ColorOptions, Shape},
+    AppState, GameplayEntity,
+};
+
+/// Fraction of its speed a particle keeps after a second.
+const PARTICLE_DRAG: f32 = 0.05;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ParticlePreset {
+    Merge,
+    Split,
+    Despawn,
+}
+
+/// How a preset's burst looks.
+#[derive(Clone, Copy, Debug)]
+pub struct BurstSettings {
+    pub count: usize,
+    /// The fastest a particle starts, in pixels per second. Each picks between half and all of it.
+    pub speed: f32,
+    pub size: f32,
+    /// Seconds until the particle has faded out.
+    pub lifetime: f32,
+}
+
+impl ParticlePreset {
+    pub fn burst(self) -> BurstSettings {
+        match self {
+            ParticlePreset::Merge => BurstSettings {
+                count: 16,
+                speed: 220.,
+                size: 6.,
+                lifetime: 0.6,
+            },
+            ParticlePreset::Split => BurstSettings {
+                count: 10,
+                speed: 160.,
+                size: 5.,
+                lifetime: 0.45,
+            },
+            ParticlePreset::Despawn => BurstSettings {
+                count: 6,
+                speed: 60.,
+                size: 4.,
+                lifetime: 0.8,
+            },
+        }
+    }
+}
+
+#[derive(Event, Clone, Copy, Debug)]
+pub struct SpawnParticles {
+    pub preset: ParticlePreset,
+    /// In world space.
+    pub position: Vec2,
+    pub color: Color,
+}
+
+#[derive(Component, Debug)]
+pub struct Particle {
+    velocity: Vec2,
+    lifetime: Timer,
+    color: Color,
+}
+
+pub struct ParticlePlugin;
+impl Plugin for ParticlePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SpawnParticles>().add_systems(
+            Update,
+            (
+                (
+                    particles_on_combine,
+                    particles_on_split,
+                    particles_on_despawn,
+                ),
+                spawn_particles,
+                update_particles,
+            )
+                .chain()
+                .run_if(in_state(AppState::Playing)),
+        );
+    }
+}
+
+fn particles_on_combine(
+    mut reader: EventReader<ShapesCombined>,
+    mut writer: EventWriter<SpawnParticles>,
+    colors: Res<ColorOptions>,
+) {
+    writer.send_batch(reader.read().map(|combined| SpawnParticles {
+        preset: ParticlePreset::Merge,
+        position: combined.position,
+        color: colors.color(combined.result.color),
+    }));
+}
+
+fn particles_on_split(
+    mut reader: EventReader<ShapesSplit>,
+    mut writer: EventWriter<SpawnParticles>,
+    colors: Res<ColorOptions>,
+) {
+    writer.send_batch(reader.read().map(|split| SpawnParticles {
+        preset: ParticlePreset::Split,
+        position: split.position,
+        color: colors.color(split.shape.color),
+    }));
+}
+
+/// The shape is still around, shrinking, when its `DespawnShape` is read.
+fn particles_on_despawn(
+    mut reader: EventReader<DespawnShape>,
+    q_shapes: Query<(&Shape, &GlobalTransform)>,
+    mut writer: EventWriter<SpawnParticles>,
+    colors: Res<ColorOptions>,
+) {
+    writer.send_batch(reader.read().filter_map(|DespawnShape { entity }| {
+        let (shape, transform) = q_shapes.get(*entity).ok()?;
+        Some(SpawnParticles {
+            preset: ParticlePreset::Despawn,
+            position: transform.translation().truncate(),
+            color: colors.color(shape.color),
+        })
+    }));
+}
+
+fn spawn_particles(mut cmd: Commands, mut reader: EventReader<SpawnParticles>) {
+    let mut rng = rand::thread_rng();
+    for burst in reader.read() {
+        let settings = burst.preset.burst();
+        for _ in 0..settings.count {
+            let dir = Vec2::from_angle(rng.gen_range(0. ..std::f32::consts::TAU));
+            let speed = settings.speed * rng.gen_range(0.5..=1.);
+            cmd.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: burst.color,
+                        custom_size: Some(Vec2::splat(settings.size)),
+                        ..default()
+                    },
+                    transform: Transform::from_translation(burst.position.extend(40.)),
+                    ..default()
+                },
+                Particle {
+                    velocity: dir * speed,
+                    lifetime: Timer::from_seconds(settings.lifetime, TimerMode::Once),
+                    color: burst.color,
+                },
+                GameplayEntity,
+            ));
+        }
+    }
+}
+
+fn update_particles(
+    mut cmd: Commands,
+    time: Res<Time>,
+    mut q_particles: Query<(Entity, &mut Particle, &mut Transform, &mut Sprite)>,
+) {
+    let drag = PARTICLE_DRAG.powf(time.delta_seconds());
+    for (entity, mut particle, mut transform, mut sprite) in q_particles.iter_mut() {
+        if particle.lifetime.tick(time.delta()).finished() {
+            cmd.entity(entity).despawn_recursive();
+            continue;
+        }
+        transform.translation += (particle.velocity * time.delta_seconds()).extend(0.);
+        particle.velocity *= drag;
+        let alpha = particle.color.a() * particle.lifetime.percent_left();
+        sprite.color = particle.color.with_a(alpha);
+    }
+}
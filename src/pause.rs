@@ -0,0 +1,74 @@
+use bevy::prelude::*;
+use bevy_xpbd_2d::prelude::{Physics, PhysicsTime};
+use leafwing_input_manager::common_conditions::action_just_pressed;
+
+use crate::{
+    cards::GameState,
+    input::GameAction,
+    save::{save_run, SaveRun},
+    ui::PileBrowserOpen,
+    AppState,
+};
+
+/// The pause action (Escape by default) pauses a run in progress. While paused the virtual clock is stopped, so every timer
+/// and tween driven by `Time` holds still, and the physics clock is stopped with it.
+#[derive(States, Default, Clone, Copy, Eq, PartialEq, Debug, Hash)]
+pub enum PauseState {
+    #[default]
+    Running,
+    Paused,
+}
+
+pub struct PausePlugin;
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_state::<PauseState>()
+            .add_systems(
+                Update,
+                (
+                    toggle_pause
+                        .run_if(action_just_pressed(GameAction::Pause))
+                        .run_if(not(in_state(GameState::Scoring)))
+                        // NOTE: Escape closes the pile browser first
+                        .run_if(not(resource_exists::<PileBrowserOpen>())),
+                    quit_to_menu
+                        .before(save_run)
+                        .run_if(action_just_pressed(GameAction::QuitToMenu))
+                        .run_if(in_state(PauseState::Paused)),
+                )
+                    .run_if(in_state(AppState::Playing)),
+            )
+            .add_systems(OnEnter(PauseState::Paused), freeze_time)
+            .add_systems(OnExit(PauseState::Paused), resume_time)
+            .add_systems(OnExit(AppState::Playing), unpause);
+    }
+}
+
+fn toggle_pause(mut cmd: Commands, state: Res<State<PauseState>>) {
+    let next = match state.get() {
+        PauseState::Running => PauseState::Paused,
+        PauseState::Paused => PauseState::Running,
+    };
+    cmd.insert_resource(NextState(Some(next)));
+}
+
+/// Saves the run on the way out, so Continue can pick it back up.
+fn quit_to_menu(mut cmd: Commands, mut save: EventWriter<SaveRun>) {
+    save.send(SaveRun);
+    cmd.insert_resource(NextState(Some(AppState::Menu)));
+}
+
+fn freeze_time(mut virtual_time: ResMut<Time<Virtual>>, mut physics_time: ResMut<Time<Physics>>) {
+    virtual_time.pause();
+    // NOTE: xpbd's fixed timestep accumulates real time, so its clock has to be paused as well
+    physics_time.pause();
+}
+
+fn resume_time(mut virtual_time: ResMut<Time<Virtual>>, mut physics_time: ResMut<Time<Physics>>) {
+    virtual_time.unpause();
+    physics_time.unpause();
+}
+
+fn unpause(mut cmd: Commands) {
+    cmd.insert_resource(NextState(Some(PauseState::Running)));
+}
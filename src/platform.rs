@@ -0,0 +1,149 @@
+//! The few things that work differently in the web build: persistent storage, wall-clock time
+//! and seeding randomness. Everything else should go through these instead of `std::fs`,
+//! `std::time` or OS entropy directly.
+
+use std::{
+    io,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use bevy::prelude::*;
+pub use bevy::utils::{Duration, Instant};
+
+/// Small string values that survive restarts, such as settings, saved decks and run history.
+/// Files in the game's data directory on native, `localStorage` on the web.
+#[derive(Resource)]
+pub struct Storage {
+    #[cfg(not(target_arch = "wasm32"))]
+    dir: std::path::PathBuf,
+}
+
+impl Default for Storage {
+    #[cfg(not(target_arch = "wasm32"))]
+    fn default() -> Self {
+        let base = std::env::var_os("APPDATA")
+            .or_else(|| std::env::var_os("XDG_DATA_HOME"))
+            .map(std::path::PathBuf::from)
+            .or_else(|| {
+                std::env::var_os("HOME")
+                    .map(|home| std::path::PathBuf::from(home).join(".local/share"))
+            })
+            .unwrap_or_default();
+
+        Self {
+            dir: base.join("shapecraft"),
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn default() -> Self {
+        Self {}
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Storage {
+    pub fn load(&self, key: &str) -> Option<String> {
+        std::fs::read_to_string(self.dir.join(key)).ok()
+    }
+
+    pub fn save(&self, key: &str, value: &str) -> io::Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        std::fs::write(self.dir.join(key), value)
+    }
+
+    /// Removing a key that was never saved isn't an error.
+    pub fn remove(&self, key: &str) -> io::Result<()> {
+        match std::fs::remove_file(self.dir.join(key)) {
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        }
+    }
+
+    /// Where `key` is stored, for log messages.
+    pub fn location(&self, key: &str) -> String {
+        self.dir.join(key).display().to_string()
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Storage {
+    fn local_storage() -> io::Result<web_sys::Storage> {
+        web_sys::window()
+            .and_then(|window| window.local_storage().ok().flatten())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::Unsupported, "localStorage unavailable"))
+    }
+
+    pub fn load(&self, key: &str) -> Option<String> {
+        Self::local_storage().ok()?.get_item(key).ok().flatten()
+    }
+
+    pub fn save(&self, key: &str, value: &str) -> io::Result<()> {
+        Self::local_storage()?
+            .set_item(key, value)
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "localStorage is full"))
+    }
+
+    /// Removing a key that was never saved isn't an error.
+    pub fn remove(&self, key: &str) -> io::Result<()> {
+        Self::local_storage()?
+            .remove_item(key)
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "localStorage unavailable"))
+    }
+
+    /// Where `key` is stored, for log messages.
+    pub fn location(&self, key: &str) -> String {
+        format!("localStorage[{key}]")
+    }
+}
+
+/// Reads a file named on the command line, such as `--load-scene` or `--replay`.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn read_file(path: &str) -> Result<String, String> {
+    std::fs::read_to_string(path).map_err(|e| e.to_string())
+}
+
+/// Reads a file named in the query string, such as `?load-scene=`. Files saved on the web live in
+/// `localStorage` under their key.
+#[cfg(target_arch = "wasm32")]
+pub fn read_file(key: &str) -> Result<String, String> {
+    Storage::default()
+        .load(key)
+        .ok_or_else(|| format!("nothing saved as {key}"))
+}
+
+/// Milliseconds since the Unix epoch. `SystemTime::now` panics on the web, so use this for
+/// timestamps (run history, daily seeds) instead.
+pub fn unix_millis() -> u64 {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis() as u64)
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        js_sys::Date::now() as u64
+    }
+}
+
+/// A seed for runs started without `--seed`. Built from the clock rather than OS entropy, which
+/// isn't available in every wasm context.
+pub fn entropy_seed() -> u64 {
+    static CALLS: AtomicU64 = AtomicU64::new(0);
+
+    // NOTE: splitmix64 finalizer, so seeds from close timestamps still differ in every bit
+    let mut z = unix_millis()
+        .wrapping_add(CALLS.fetch_add(1, Ordering::Relaxed))
+        .wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+pub struct PlatformPlugin;
+impl Plugin for PlatformPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Storage>();
+    }
+}
@@ -0,0 +1,64 @@
+//! Where the cursor points, in the world of each camera. The board and the cards are drawn by
+//! different cameras, so a cursor position means something different to each of them. Without a
+//! cursor, a finger on a touchscreen points instead.
+
+use bevy::{prelude::*, window::PrimaryWindow};
+
+use crate::{
+    board::config,
+    camera::{BoardCamera, CardCamera},
+    utils::point_in_board,
+};
+
+/// Updated in `PreUpdate`. Everything is `None` while the cursor is outside the window and no
+/// finger is down, or when there are no cameras (e.g. headless).
+#[derive(Resource, Default, Debug)]
+pub struct Pointer {
+    /// In the board camera's world, where shapes live.
+    pub board: Option<Vec2>,
+    /// In the card camera's world, where the hand, deck and rules live.
+    pub cards: Option<Vec2>,
+}
+
+impl Pointer {
+    /// The point on the board under the cursor, if the cursor is over the board.
+    pub fn over_board(&self) -> Option<Vec2> {
+        self.board
+            .filter(|pos| point_in_board(pos.x, pos.y, config::SIZE, config::CENTER))
+    }
+}
+
+pub struct PointerPlugin;
+impl Plugin for PointerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Pointer>()
+            .add_systems(PreUpdate, update_pointer);
+    }
+}
+
+fn update_pointer(
+    mut pointer: ResMut<Pointer>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    touches: Res<Touches>,
+    q_board_camera: Query<(&Camera, &GlobalTransform), With<BoardCamera>>,
+    q_card_camera: Query<(&Camera, &GlobalTransform), (With<CardCamera>, Without<BoardCamera>)>,
+) {
+    let cursor = q_window
+        .get_single()
+        .ok()
+        .and_then(|window| window.cursor_position())
+        .or_else(|| touches.first_pressed_position())
+        // NOTE: a lifted finger still points for the frame `Select` is released, so drops land
+        .or_else(|| {
+            touches
+                .iter_just_released()
+                .next()
+                .map(|touch| touch.position())
+        });
+    let to_world = |(camera, transform): (&Camera, &GlobalTransform)| {
+        camera.viewport_to_world_2d(transform, cursor?)
+    };
+
+    pointer.board = q_board_camera.get_single().ok().and_then(to_world);
+    pointer.cards = q_card_camera.get_single().ok().and_then(to_world);
+}
@@ -0,0 +1,127 @@
+//! Text that pops up on the board, floats upward and fades, e.g. the points a combine scored.
+//! Any system can show one by sending `SpawnPopup`.
+
+use bevy::prelude::*;
+use bevy_tweening::{
+    lens::{TextColorLens, TransformPositionLens},
+    Animator, EaseFunction, Tween, TweenCompleted,
+};
+
+use crate::{
+    board::ShapesCombined, game_shapes::palette::ColorOptions, loading::FontAssets,
+    platform::Duration, score::combine_points, tween_ids::DESPAWN_POPUP, AppState, GameplayEntity,
+};
+
+const POPUP_DURATION: f32 = 1.;
+/// How far a popup floats before it's gone.
+const POPUP_RISE: f32 = 60.;
+const POPUP_FONT_SIZE: f32 = 28.;
+
+/// Shows `text` at `position`, in world space.
+#[derive(Event, Clone, Debug)]
+pub struct SpawnPopup {
+    pub text: String,
+    pub position: Vec2,
+    pub color: Color,
+}
+
+#[derive(Component)]
+pub struct Popup;
+
+pub struct PopupPlugin;
+impl Plugin for PopupPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SpawnPopup>().add_systems(
+            Update,
+            (
+                (popup_combines, spawn_popups)
+                    .chain()
+                    .run_if(in_state(AppState::Playing)),
+                despawn_popups,
+            ),
+        );
+    }
+}
+
+fn popup_combines(
+    mut reader: EventReader<ShapesCombined>,
+    mut writer: EventWriter<SpawnPopup>,
+    colors: Res<ColorOptions>,
+) {
+    for combined in reader.read() {
+        let points = combine_points(combined.inputs, combined.result) * combined.depth;
+        writer.send(SpawnPopup {
+            text: format!("+{points}"),
+            position: combined.position,
+            color: colors.color(combined.result.color),
+        });
+    }
+}
+
+fn spawn_popups(
+    mut cmd: Commands,
+    mut reader: EventReader<SpawnPopup>,
+    fonts: Option<Res<FontAssets>>,
+) {
+    // NOTE: headless apps never load fonts
+    let Some(fonts) = fonts else {
+        reader.clear();
+        return;
+    };
+    for popup in reader.read() {
+        let start = popup.position.extend(50.);
+        let rise = Tween::new(
+            EaseFunction::QuadraticOut,
+            Duration::from_secs_f32(POPUP_DURATION),
+            TransformPositionLens {
+                start,
+                end: start + Vec3::Y * POPUP_RISE,
+            },
+        );
+        let fade = Tween::new(
+            EaseFunction::QuadraticIn,
+            Duration::from_secs_f32(POPUP_DURATION),
+            TextColorLens {
+                start: popup.color,
+                end: popup.color.with_a(0.),
+                section: 0,
+            },
+        )
+        .with_completed_event(DESPAWN_POPUP);
+
+        cmd.spawn((
+            Text2dBundle {
+                text: Text::from_section(
+                    popup.text.clone(),
+                    TextStyle {
+                        font: fonts.fira.clone_weak(),
+                        font_size: POPUP_FONT_SIZE,
+                        color: popup.color,
+                    },
+                ),
+                transform: Transform::from_translation(start),
+                ..default()
+            },
+            Animator::new(rise),
+            Animator::new(fade),
+            Popup,
+            GameplayEntity,
+        ));
+    }
+}
+
+fn despawn_popups(
+    mut cmd: Commands,
+    mut reader: EventReader<TweenCompleted>,
+    q_popups: Query<(), With<Popup>>,
+) {
+    for event in reader.read() {
+        if event.user_data != DESPAWN_POPUP || !q_popups.contains(event.entity) {
+            continue;
+        }
+        // NOTE: the run may have been torn down while the popup was fading
+        if let Some(entity) = cmd.get_entity(event.entity) {
+            entity.despawn_recursive();
+        }
+    }
+}
@@ -0,0 +1,83 @@
+//! The types most code outside the crate needs: `use shapecraft::prelude::*;`.
+
+pub use crate::{
+    achievements::{
+        AchievementDef, AchievementGoal, AchievementProgress, AchievementSignal,
+        AchievementUnlocked, Achievements,
+    },
+    args::LaunchArgs,
+    audio::{
+        music::{MusicEvent, TrackId},
+        PlaySound, Sfx, SoundChannel, Volume, VolumeSlider,
+    },
+    background::{BackgroundLayer, BackgroundTheme, LayerDef},
+    board::{
+        combo::{ComboDepth, ComboEvent, ComboTracker},
+        gravity::{BoardGravity, GravityChanged, ShiftGravity},
+        launcher::{LaunchQueue, Launcher, LauncherDef},
+        layout::{ActiveLayout, BoardLayout, SelectedLayout, SpawnZone, WallDef},
+        obstacle::{BoardObstacle, Bumper, ObstacleDef, ObstacleKind},
+        population::{BoardPopulation, DespawnPolicy, DespawnShape, Lifetime},
+        pulse::PulseTargets,
+        reaction::{Burning, ElementMark, ElementsReacted, SteamCloud},
+        spawner::{RateCurve, Spawner, SpawnerDef, WeightedShape},
+        status::{ActiveStatus, ApplyStatus, BoardStatus, Frozen, StatusDef, StatusKind},
+        transmute::{BoardTransformed, TransmuteShapes},
+        zone::{BoardZone, BoardZoneDef, BoardZoneKind, ZoneEntered, ZoneExited},
+        CombinedShapes, Dragged, IsOnBoard, ProcessedContacts, RuleApplied, RuleLookup, RuleMatch,
+        RuleResolution, ShapesCombined, ShapesSplit, SpawnBody, SpawnSource,
+    },
+    cards::{
+        card::{Card, CardAnimationSettings, CardFlipped, FlipCard, SpawnCard, SpawnGoalCard},
+        collection::{Collection, Decklist},
+        deck::{DrawCard, ShuffleDiscard},
+        definition::{CardDefinition, CardDefinitions, CardDefinitionsReloaded, CardId, Rarity},
+        draft::{DraftOffer, DraftRule},
+        effect::{CardEffect, CardUsed, PlayCard, PurgeCards},
+        energy::{CardRejected, Energy, EnergyBank},
+        focus::{BoardCursor, Focused},
+        hand::{HandFull, HandLimit, Overdraw},
+        keyword::{Keyword, KeywordRegistry},
+        mulligan::{Mulligan, MulliganSelection},
+        opponent::{Opponent, OpponentPass},
+        phase::{EndPhase, PhaseSet, PhaseTimer, TurnCount},
+        rules::{AddRule, Rule, RuleAdded, RuleRemoved},
+        search::{CardFilter, LibrarySearch, ScryCards, ScryChoice, TutorCard, TutorChoice},
+        transfer::{TransferReason, ZoneTransfer},
+        zones::{ActivePlayer, GameMode, Owner, PlayerId, Winner, ZoneKind, Zones},
+        CardSet, GameState, GameTimer,
+    },
+    content::{ContentSelection, CustomContent},
+    daily::{DailyChallenge, DailyRecord},
+    endless::{WaveStarted, Waves},
+    game_over::{DeckedOut, GameLost, GameOutcome, GameOverRules, GameWon, Outcome},
+    game_shapes::{
+        element::{Element, ElementReactions, Reaction},
+        interaction::{ColorInteraction, ColorOutcome},
+        material::ShapeMaterial,
+        palette::{ColorOptions, Palette, Pattern},
+        stats::{PolygonStats, ShapeStats},
+        GameColor, GamePolygon, Shape,
+    },
+    input::GameAction,
+    juice::{HitStop, Shake},
+    level::{CurrentLevel, Level, LevelCleared},
+    locale::{Language, Locale, Localization, Localized},
+    operation::Operation,
+    particles::{BurstSettings, Particle, ParticlePreset, SpawnParticles},
+    pause::PauseState,
+    platform::Storage,
+    pointer::Pointer,
+    popup::{Popup, SpawnPopup},
+    replay::{Replay, ReplaySet},
+    rng::{GameRng, Seed},
+    save::{PendingRun, SaveGame, SaveRun},
+    score::{Score, ScoreChanged},
+    settings::Settings,
+    trail::{Trail, TrailPool},
+    tutorial::{
+        HudElement, StartTutorial, Tutorial, TutorialScript, TutorialStep, TutorialTarget,
+        TutorialTrigger,
+    },
+    AppState, GamePlugin, GamePlugins, GameplayEntity,
+};
@@ -0,0 +1,642 @@
+//! Recording runs and playing them back, for chasing physics and rule interactions that are hard
+//! to reproduce by hand. Every run is recorded from `GameState::Start` and written through
+//! `Storage` when it ends; `--replay <path>` plays one back in `AppState::Replay`.
+//!
+//! A replay is the table as it was at Start plus every `SpawnBody`, `DrawCard`, `PlayCard`,
+//! `Mulligan`, `ScryChoice`, `TutorChoice`, `DraftRule` and `OpponentPass` the run went through and
+//! every Play and Draw press, tagged with the frame they happened on, and each frame's length. The
+//! opponent's choices are among them, so it sits out playback. During playback the recorded events stand in for the live
+//! ones and each frame is given its recorded length, so the run plays out again without any input.
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+use bevy_xpbd_2d::prelude::LinearVelocity;
+use leafwing_input_manager::prelude::ActionState;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    args::LaunchArgs,
+    board::{layout::SelectedLayout, BodySpawned, SpawnBody, SpawnSet, SpawnSource},
+    cards::{
+        card::Card,
+        deck::{draw_card, draw_to_hand_size, DrawCard},
+        definition::CardId,
+        draft::{take_draft, DraftRule},
+        effect::{play_card, PlayCard},
+        hand::select_card,
+        mulligan::{take_mulligan, Mulligan},
+        opponent::{end_opponent_play, keep_opening_hand, pick_draft, take_turn, OpponentPass},
+        pass_device,
+        search::{take_scry, take_tutor, ScryChoice, TutorChoice},
+        skip_play, start_game,
+        zones::{ActivePlayer, PlayerId, ZoneKind, Zones},
+        GameState,
+    },
+    game_shapes::{element::Element, Shape},
+    input::GameAction,
+    level::{CurrentLevel, Level},
+    operation::Operation,
+    pause::PauseState,
+    platform::{self, Storage},
+    rng::{GameRng, Seed},
+    save::{PendingRun, RunSnapshot, SaveGame},
+    AppState,
+};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Replay {
+    pub seed: u64,
+    /// The table when the run reached `GameState::Start`.
+    pub start: SaveGame,
+    /// Seconds each running frame took, starting with the first frame of Start.
+    pub frames: Vec<f32>,
+    /// In the order they happened.
+    pub events: Vec<ReplayEvent>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ReplayEvent {
+    /// Index into `Replay::frames`.
+    pub frame: u32,
+    pub input: ReplayInput,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub enum ReplayInput {
+    /// The velocity is always set, including for bodies that were spawned with a random one.
+    SpawnBody {
+        shape: Shape,
+        transform: Transform,
+        velocity: Vec2,
+        /// Missing from replays recorded before chains were tracked.
+        #[serde(default)]
+        combo: u32,
+        /// Missing from replays recorded before shapes had elements.
+        #[serde(default)]
+        element: Option<Element>,
+        /// Missing from replays recorded before combines were credited.
+        #[serde(default)]
+        source: SpawnSource,
+    },
+    DrawCard {
+        owner: PlayerId,
+    },
+    /// Cards are found by where they sit in the active hand; `card` is only checked against.
+    PlayCard {
+        hand_index: usize,
+        card: Option<CardId>,
+        board_pos: Vec2,
+    },
+    DraftRule {
+        rule: Operation,
+    },
+    /// Cards sent back from the active hand, found the same way as for `PlayCard`.
+    Mulligan {
+        hand_indices: Vec<usize>,
+    },
+    /// How a scry put cards back, found by where they sat in the active library.
+    Scry {
+        top: Vec<usize>,
+        bottom: Vec<usize>,
+    },
+    /// The card a tutor took, found the same way as for `Scry`.
+    Tutor {
+        library_index: Option<usize>,
+    },
+    /// Starting the run or passing the device.
+    PressPlay,
+    /// Skipping a turn's play to draw a new hand.
+    PressDraw,
+    /// The opponent ending its play after dropping shapes.
+    OpponentPass,
+}
+
+/// Where recording and playback sit among the systems that send or read each kind of event.
+#[derive(SystemSet, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ReplaySet {
+    Input,
+    SpawnBody,
+    DrawCard,
+    PlayCard,
+    Mulligan,
+    Search,
+    Draft,
+    Opponent,
+}
+
+/// The run being recorded.
+#[derive(Resource)]
+pub struct Recorder(Replay);
+
+/// A replay read from `--replay`, waiting for `AppState::Replay`.
+#[derive(Resource)]
+struct PendingReplay(Replay);
+
+/// The replay being played back.
+#[derive(Resource)]
+pub struct Playback {
+    replay: Replay,
+    /// The frame being played, once the run has reached Start.
+    frame: Option<u32>,
+    /// Index of the first event not yet sent.
+    next_event: usize,
+}
+
+impl Playback {
+    /// The recorded events for the current frame that `pick` accepts.
+    fn due<'a, T>(
+        &'a self,
+        pick: impl Fn(&'a ReplayInput) -> Option<T> + 'a,
+    ) -> impl Iterator<Item = T> + 'a {
+        let frame = self.frame;
+        self.replay.events[self.next_event..]
+            .iter()
+            .take_while(move |event| Some(event.frame) == frame)
+            .filter_map(move |event| pick(&event.input))
+    }
+}
+
+pub struct ReplayPlugin;
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.configure_sets(
+            Update,
+            (
+                ReplaySet::Input
+                    .before(start_game)
+                    .before(pass_device)
+                    .before(skip_play),
+                ReplaySet::SpawnBody.in_set(SpawnSet),
+                ReplaySet::DrawCard
+                    .after(draw_to_hand_size)
+                    .before(draw_card),
+                ReplaySet::PlayCard
+                    .after(select_card)
+                    .after(take_turn)
+                    .before(play_card),
+                ReplaySet::Mulligan
+                    .after(keep_opening_hand)
+                    .before(take_mulligan),
+                ReplaySet::Search.before(take_scry).before(take_tutor),
+                ReplaySet::Draft.after(pick_draft).before(take_draft),
+                ReplaySet::Opponent
+                    .after(take_turn)
+                    .before(end_opponent_play),
+            ),
+        )
+        .add_systems(Startup, read_replay_arg)
+        .add_systems(OnEnter(AppState::Replay), start_playback)
+        .add_systems(OnEnter(GameState::Start), (start_recording, start_frames))
+        .add_systems(
+            Update,
+            (
+                (
+                    record_presses.in_set(ReplaySet::Input),
+                    record_bodies.after(SpawnSet),
+                    record_draws.in_set(ReplaySet::DrawCard),
+                    record_plays.in_set(ReplaySet::PlayCard),
+                    record_mulligans.in_set(ReplaySet::Mulligan),
+                    record_searches.in_set(ReplaySet::Search),
+                    record_drafts.in_set(ReplaySet::Draft),
+                    record_opponent_passes.in_set(ReplaySet::Opponent),
+                )
+                    .run_if(resource_exists::<Recorder>()),
+                (
+                    play_presses.in_set(ReplaySet::Input),
+                    play_bodies.in_set(ReplaySet::SpawnBody),
+                    play_draws.in_set(ReplaySet::DrawCard),
+                    play_plays.in_set(ReplaySet::PlayCard),
+                    play_mulligans.in_set(ReplaySet::Mulligan),
+                    play_searches.in_set(ReplaySet::Search),
+                    play_drafts.in_set(ReplaySet::Draft),
+                    play_opponent_passes.in_set(ReplaySet::Opponent),
+                )
+                    .run_if(playback_started),
+            )
+                .run_if(in_state(AppState::Playing)),
+        )
+        .add_systems(
+            Last,
+            (
+                record_frame.run_if(resource_exists::<Recorder>()),
+                step_playback.run_if(resource_exists::<Playback>()),
+            )
+                .run_if(in_state(AppState::Playing))
+                .run_if(in_state(PauseState::Running)),
+        )
+        .add_systems(OnExit(AppState::Playing), (save_replay, stop_playback));
+    }
+}
+
+fn read_replay_arg(mut cmd: Commands, args: Option<Res<LaunchArgs>>) {
+    let Some(path) = args.and_then(|args| args.replay.clone()) else {
+        return;
+    };
+    let replay = platform::read_file(&path)
+        .and_then(|text| ron::from_str::<Replay>(&text).map_err(|e| e.to_string()));
+    match replay {
+        Ok(replay) => cmd.insert_resource(PendingReplay(replay)),
+        Err(err) => error!("couldn't load replay {path}: {err}"),
+    }
+}
+
+/// Sets the replay's run up the way the Continue button does, then hands over to `Playing`.
+fn start_playback(mut cmd: Commands, pending: Option<Res<PendingReplay>>) {
+    let Some(pending) = pending else {
+        cmd.insert_resource(NextState(Some(AppState::Menu)));
+        return;
+    };
+    let replay = pending.0.clone();
+    cmd.remove_resource::<PendingReplay>();
+
+    info!(
+        "playing back replay (seed {}, {} frames)",
+        replay.seed,
+        replay.frames.len()
+    );
+    let seed = Seed(replay.seed);
+    cmd.insert_resource(replay.start.mode);
+    cmd.insert_resource(seed);
+    cmd.insert_resource(GameRng::from_seed(seed));
+    cmd.insert_resource(SelectedLayout(replay.start.layout.clone()));
+    cmd.insert_resource(CurrentLevel(Level::new(replay.start.level)));
+    cmd.insert_resource(PendingRun(replay.start.clone()));
+    cmd.insert_resource(Playback {
+        replay,
+        frame: None,
+        next_event: 0,
+    });
+    cmd.insert_resource(NextState(Some(AppState::Playing)));
+}
+
+fn start_recording(mut cmd: Commands, snapshot: RunSnapshot, playback: Option<Res<Playback>>) {
+    if playback.is_some() {
+        return;
+    }
+    let start = snapshot.capture();
+    cmd.insert_resource(Recorder(Replay {
+        seed: start.seed,
+        start,
+        frames: Vec::new(),
+        events: Vec::new(),
+    }));
+}
+
+/// The live events are only replaced from Start on, so the restored table still gets dealt.
+fn playback_started(playback: Option<Res<Playback>>) -> bool {
+    playback.is_some_and(|playback| playback.frame.is_some())
+}
+
+fn start_frames(mut playback: Option<ResMut<Playback>>) {
+    if let Some(playback) = playback.as_mut() {
+        playback.frame = Some(0);
+    }
+}
+
+impl Recorder {
+    fn push(&mut self, input: ReplayInput) {
+        let frame = self.0.frames.len() as u32;
+        self.0.events.push(ReplayEvent { frame, input });
+    }
+}
+
+fn record_presses(
+    mut recorder: ResMut<Recorder>,
+    actions: Res<ActionState<GameAction>>,
+    state: Res<State<GameState>>,
+) {
+    match state.get() {
+        GameState::Start | GameState::PassDevice if actions.just_pressed(GameAction::Play) => {
+            recorder.push(ReplayInput::PressPlay);
+        },
+        GameState::Play if actions.just_pressed(GameAction::Draw) => {
+            recorder.push(ReplayInput::PressDraw);
+        },
+        _ => {},
+    }
+}
+
+fn record_bodies(mut recorder: ResMut<Recorder>, mut reader: EventReader<BodySpawned>) {
+    for BodySpawned(body) in reader.read() {
+        recorder.push(ReplayInput::SpawnBody {
+            shape: body.shape,
+            transform: body.transform,
+            velocity: body.velocity.map_or(Vec2::ZERO, |velocity| velocity.0),
+            combo: body.combo,
+            element: body.element,
+            source: body.source,
+        });
+    }
+}
+
+fn record_draws(mut recorder: ResMut<Recorder>, mut reader: EventReader<DrawCard>) {
+    for event in reader.read() {
+        recorder.push(ReplayInput::DrawCard { owner: event.owner });
+    }
+}
+
+fn record_plays(
+    mut recorder: ResMut<Recorder>,
+    mut reader: EventReader<PlayCard>,
+    zones: Res<Zones>,
+    active: Res<ActivePlayer>,
+    q_children: Query<&Children>,
+    q_cards: Query<&Card>,
+) {
+    let hand = zones
+        .get(active.0, ZoneKind::Hand)
+        .and_then(|hand| q_children.get(hand).ok());
+    for event in reader.read() {
+        let Some(hand_index) = hand.and_then(|hand| hand.iter().position(|&e| e == event.card))
+        else {
+            warn!("record_plays: played card isn't in the active hand, leaving it out");
+            continue;
+        };
+        recorder.push(ReplayInput::PlayCard {
+            hand_index,
+            card: q_cards
+                .get(event.card)
+                .ok()
+                .and_then(|card| card.definition.clone()),
+            board_pos: event.board_pos,
+        });
+    }
+}
+
+fn record_mulligans(
+    mut recorder: ResMut<Recorder>,
+    mut reader: EventReader<Mulligan>,
+    zones: Res<Zones>,
+    active: Res<ActivePlayer>,
+    q_children: Query<&Children>,
+) {
+    let hand = zones
+        .get(active.0, ZoneKind::Hand)
+        .and_then(|hand| q_children.get(hand).ok());
+    for event in reader.read() {
+        let hand_indices = event
+            .cards
+            .iter()
+            .filter_map(|card| hand.and_then(|hand| hand.iter().position(|e| e == card)))
+            .collect();
+        recorder.push(ReplayInput::Mulligan { hand_indices });
+    }
+}
+
+fn record_searches(
+    mut recorder: ResMut<Recorder>,
+    mut scry: EventReader<ScryChoice>,
+    mut tutor: EventReader<TutorChoice>,
+    zones: Res<Zones>,
+    active: Res<ActivePlayer>,
+    q_children: Query<&Children>,
+) {
+    let library = zones
+        .get(active.0, ZoneKind::Library)
+        .and_then(|library| q_children.get(library).ok());
+    let index = |card: &Entity| library.and_then(|library| library.iter().position(|e| e == card));
+    for event in scry.read() {
+        recorder.push(ReplayInput::Scry {
+            top: event.top.iter().filter_map(index).collect(),
+            bottom: event.bottom.iter().filter_map(index).collect(),
+        });
+    }
+    for event in tutor.read() {
+        recorder.push(ReplayInput::Tutor {
+            library_index: event.card.as_ref().and_then(index),
+        });
+    }
+}
+
+fn record_drafts(mut recorder: ResMut<Recorder>, mut reader: EventReader<DraftRule>) {
+    for event in reader.read() {
+        recorder.push(ReplayInput::DraftRule {
+            rule: event.rule.clone(),
+        });
+    }
+}
+
+fn record_opponent_passes(mut recorder: ResMut<Recorder>, mut reader: EventReader<OpponentPass>) {
+    for _ in reader.read() {
+        recorder.push(ReplayInput::OpponentPass);
+    }
+}
+
+fn record_frame(mut recorder: ResMut<Recorder>, time: Res<Time<Real>>) {
+    recorder.0.frames.push(time.delta_seconds());
+}
+
+fn play_presses(playback: Res<Playback>, mut actions: ResMut<ActionState<GameAction>>) {
+    let play = playback
+        .due(|input| matches!(input, ReplayInput::PressPlay).then_some(()))
+        .count()
+        > 0;
+    let draw = playback
+        .due(|input| matches!(input, ReplayInput::PressDraw).then_some(()))
+        .count()
+        > 0;
+    // NOTE: releasing on frames without a recorded press keeps the keyboard out of the run
+    for (action, pressed) in [(GameAction::Play, play), (GameAction::Draw, draw)] {
+        if pressed {
+            actions.press(action);
+        } else {
+            actions.release(action);
+        }
+    }
+}
+
+fn play_bodies(playback: Res<Playback>, mut events: ResMut<Events<SpawnBody>>) {
+    events.clear();
+    for body in playback.due(|input| match input {
+        ReplayInput::SpawnBody {
+            shape,
+            transform,
+            velocity,
+            combo,
+            element,
+            source,
+        } => Some(SpawnBody {
+            shape: *shape,
+            transform: *transform,
+            velocity: Some(LinearVelocity(*velocity)),
+            combo: *combo,
+            element: *element,
+            source: *source,
+        }),
+        _ => None,
+    }) {
+        events.send(body);
+    }
+}
+
+fn play_draws(playback: Res<Playback>, mut events: ResMut<Events<DrawCard>>) {
+    events.clear();
+    for draw in playback.due(|input| match input {
+        ReplayInput::DrawCard { owner } => Some(DrawCard { owner: *owner }),
+        _ => None,
+    }) {
+        events.send(draw);
+    }
+}
+
+fn play_plays(
+    playback: Res<Playback>,
+    mut events: ResMut<Events<PlayCard>>,
+    zones: Res<Zones>,
+    active: Res<ActivePlayer>,
+    q_children: Query<&Children>,
+    q_cards: Query<&Card>,
+) {
+    events.clear();
+    let hand = zones
+        .get(active.0, ZoneKind::Hand)
+        .and_then(|hand| q_children.get(hand).ok());
+    for (hand_index, card, board_pos) in playback.due(|input| match input {
+        ReplayInput::PlayCard {
+            hand_index,
+            card,
+            board_pos,
+        } => Some((*hand_index, card, *board_pos)),
+        _ => None,
+    }) {
+        let Some(&entity) = hand.and_then(|hand| hand.get(hand_index)) else {
+            warn!("play_plays: the active hand has no card {hand_index}, the replay has drifted");
+            continue;
+        };
+        let found = q_cards.get(entity).ok().and_then(|c| c.definition.as_ref());
+        if found != card.as_ref() {
+            warn!("play_plays: expected {card:?} at {hand_index}, found {found:?}");
+        }
+        events.send(PlayCard {
+            card: entity,
+            board_pos,
+        });
+    }
+}
+
+fn play_mulligans(
+    playback: Res<Playback>,
+    mut events: ResMut<Events<Mulligan>>,
+    zones: Res<Zones>,
+    active: Res<ActivePlayer>,
+    q_children: Query<&Children>,
+) {
+    events.clear();
+    let hand = zones
+        .get(active.0, ZoneKind::Hand)
+        .and_then(|hand| q_children.get(hand).ok());
+    for hand_indices in playback.due(|input| match input {
+        ReplayInput::Mulligan { hand_indices } => Some(hand_indices),
+        _ => None,
+    }) {
+        let cards = hand_indices
+            .iter()
+            .filter_map(|&i| hand.and_then(|hand| hand.get(i)).copied())
+            .collect();
+        events.send(Mulligan { cards });
+    }
+}
+
+fn play_searches(
+    playback: Res<Playback>,
+    mut scry: ResMut<Events<ScryChoice>>,
+    mut tutor: ResMut<Events<TutorChoice>>,
+    zones: Res<Zones>,
+    active: Res<ActivePlayer>,
+    q_children: Query<&Children>,
+) {
+    scry.clear();
+    tutor.clear();
+    let library = zones
+        .get(active.0, ZoneKind::Library)
+        .and_then(|library| q_children.get(library).ok());
+    let card = |i: &usize| library.and_then(|library| library.get(*i)).copied();
+    for input in playback.due(|input| match input {
+        ReplayInput::Scry { .. } | ReplayInput::Tutor { .. } => Some(input),
+        _ => None,
+    }) {
+        match input {
+            ReplayInput::Scry { top, bottom } => scry.send(ScryChoice {
+                top: top.iter().filter_map(card).collect(),
+                bottom: bottom.iter().filter_map(card).collect(),
+            }),
+            ReplayInput::Tutor { library_index } => tutor.send(TutorChoice {
+                card: library_index.as_ref().and_then(card),
+            }),
+            _ => {},
+        }
+    }
+}
+
+fn play_drafts(playback: Res<Playback>, mut events: ResMut<Events<DraftRule>>) {
+    events.clear();
+    for draft in playback.due(|input| match input {
+        ReplayInput::DraftRule { rule } => Some(DraftRule { rule: rule.clone() }),
+        _ => None,
+    }) {
+        events.send(draft);
+    }
+}
+
+fn play_opponent_passes(playback: Res<Playback>, mut events: ResMut<Events<OpponentPass>>) {
+    events.clear();
+    for _ in playback.due(|input| matches!(input, ReplayInput::OpponentPass).then_some(())) {
+        events.send(OpponentPass);
+    }
+}
+
+/// Moves on to the next frame and gives it its recorded length. The first frame of Start is set
+/// up while leaving Setup, since time has already been advanced by the time `OnEnter` runs.
+fn step_playback(
+    mut cmd: Commands,
+    mut playback: ResMut<Playback>,
+    mut strategy: ResMut<TimeUpdateStrategy>,
+    next_game_state: Res<NextState<GameState>>,
+) {
+    let next = match playback.frame {
+        Some(frame) => {
+            let next = frame + 1;
+            playback.frame = Some(next);
+            let done = playback.replay.events[playback.next_event..]
+                .iter()
+                .take_while(|event| event.frame < next)
+                .count();
+            playback.next_event += done;
+            next
+        },
+        None if next_game_state.0 == Some(GameState::Start) => 0,
+        None => return,
+    };
+
+    match playback.replay.frames.get(next as usize) {
+        Some(&seconds) => {
+            *strategy =
+                TimeUpdateStrategy::ManualDuration(platform::Duration::from_secs_f32(seconds))
+        },
+        None => {
+            info!("replay finished");
+            *strategy = TimeUpdateStrategy::Automatic;
+            cmd.remove_resource::<Playback>();
+        },
+    }
+}
+
+fn save_replay(mut cmd: Commands, recorder: Option<Res<Recorder>>, storage: Res<Storage>) {
+    let Some(recorder) = recorder else {
+        return;
+    };
+    cmd.remove_resource::<Recorder>();
+
+    let key = format!("replay-{}.replay.ron", platform::unix_millis());
+    let saved = ron::ser::to_string(&recorder.0)
+        .map_err(|e| e.to_string())
+        .and_then(|ron| storage.save(&key, &ron).map_err(|e| e.to_string()));
+    match saved {
+        Ok(()) => info!("saved replay to {}", storage.location(&key)),
+        Err(err) => error!("couldn't save replay: {err}"),
+    }
+}
+
+fn stop_playback(mut cmd: Commands, mut strategy: ResMut<TimeUpdateStrategy>) {
+    *strategy = TimeUpdateStrategy::Automatic;
+    cmd.remove_resource::<Playback>();
+}
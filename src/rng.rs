@@ -0,0 +1,50 @@
+//! Randomness that should be reproducible from a seed. `--seed` fixes the seed for every run;
+//! otherwise each run gets a fresh one, logged so an interesting run can be replayed.
+
+use bevy::prelude::*;
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{args::LaunchArgs, platform, AppState};
+
+/// The seed `GameRng` was last seeded with.
+#[derive(Resource, Clone, Copy, Debug, Deref)]
+pub struct Seed(pub u64);
+
+/// Shared generator for gameplay randomness, reseeded from `Seed` between runs.
+#[derive(Resource, Deref, DerefMut)]
+pub struct GameRng(pub StdRng);
+
+impl GameRng {
+    pub fn from_seed(seed: Seed) -> Self {
+        Self(StdRng::seed_from_u64(seed.0))
+    }
+}
+
+pub struct RngPlugin;
+impl Plugin for RngPlugin {
+    fn build(&self, app: &mut App) {
+        let seed = Seed(
+            app.world
+                .get_resource::<LaunchArgs>()
+                .map(LaunchArgs::run_seed)
+                .unwrap_or_else(platform::entropy_seed),
+        );
+
+        app.insert_resource(seed)
+            .insert_resource(GameRng::from_seed(seed))
+            .add_systems(OnEnter(AppState::Playing), log_seed)
+            .add_systems(OnExit(AppState::Playing), reseed);
+    }
+}
+
+fn log_seed(seed: Res<Seed>) {
+    info!("run seed: {}", seed.0);
+}
+
+/// Keeps `--seed` runs identical to each other; other runs move on to a new seed.
+fn reseed(mut seed: ResMut<Seed>, mut rng: ResMut<GameRng>, args: Option<Res<LaunchArgs>>) {
+    if args.and_then(|args| args.seed).is_none() {
+        *seed = Seed(platform::entropy_seed());
+    }
+    *rng = GameRng::from_seed(*seed);
+}
@@ -0,0 +1,358 @@
+//! Saving a run and picking it back up later. Quitting to the menu from the pause menu saves the
+//! run, and the main menu's Continue button restores it. A run that ends is forgotten.
+//!
+//! Cards are saved zone by zone as `CardId`s, so a restored run deals the same cards into the
+//! same places. Where each card sat on screen and any animation in flight aren't kept.
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_xpbd_2d::prelude::LinearVelocity;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    board::{
+        config,
+        layout::{ActiveLayout, SelectedLayout},
+        IsOnBoard, SpawnBody, SpawnSource,
+    },
+    cards::{
+        card::{spawn_card, Card, CardTravel, SpawnCard},
+        definition::CardId,
+        goals::Goals,
+        phase::{EndPhase, PhaseSet},
+        rules::Rule,
+        zones::{ActivePlayer, GameMode, Owner, PlayerId, ZoneKind, Zones},
+        CardSet, GameState, GameTimer,
+    },
+    daily::DailyChallenge,
+    game_shapes::{element::Element, Shape},
+    goal::Goal,
+    level::{CurrentLevel, Level},
+    operation::Operation,
+    platform::{Duration, Storage},
+    rng::{GameRng, Seed},
+    score::Score,
+    utils::ThrottledWarn,
+    AppState,
+};
+
+const SAVE_KEY: &str = "run.ron";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SaveGame {
+    pub seed: u64,
+    pub mode: GameMode,
+    pub active_player: PlayerId,
+    /// One per seat, indexed by `PlayerId`.
+    pub seats: Vec<SavedSeat>,
+    pub rules: Vec<Operation>,
+    pub score: Score,
+    /// Seconds of the run's `GameTimer` already used up.
+    pub elapsed: f32,
+    pub shapes: Vec<SavedShape>,
+    /// The name of the `BoardLayout`. Empty in saves made before there were layouts, which
+    /// play on the first one.
+    #[serde(default)]
+    pub layout: String,
+    /// The `Level` number. Saves made before levels start on the first one.
+    #[serde(default = "first_level")]
+    pub level: u32,
+    /// Set while the run is a `DailyChallenge` whose score still counts. Saves made before daily
+    /// runs were kept play on as normal runs.
+    #[serde(default)]
+    pub daily: Option<DailyChallenge>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct SavedSeat {
+    /// Top of the library first.
+    pub library: Vec<CardId>,
+    pub hand: Vec<CardId>,
+    pub discard: Vec<CardId>,
+    /// Missing from runs saved before there was an exhaust pile.
+    #[serde(default)]
+    pub exhaust: Vec<CardId>,
+    pub goals: Vec<Goal>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct SavedShape {
+    pub shape: Shape,
+    pub position: Vec2,
+    pub rotation: f32,
+    pub velocity: Vec2,
+    /// Relative to `board::config::SHAPE_SCALE`. Missing from saves made before shapes could
+    /// shrink.
+    #[serde(default = "full_size")]
+    pub scale: f32,
+    /// Missing from saves made before shapes had elements.
+    #[serde(default)]
+    pub element: Option<Element>,
+}
+
+fn full_size() -> f32 {
+    1.
+}
+
+fn first_level() -> u32 {
+    1
+}
+
+impl SaveGame {
+    /// The saved run, if there is one that still parses.
+    pub fn load(storage: &Storage) -> Option<Self> {
+        let text = storage.load(SAVE_KEY).filter(|text| !text.is_empty())?;
+        ron::from_str(&text)
+            .map_err(|err| warn!("ignoring saved run {}: {err}", storage.location(SAVE_KEY)))
+            .ok()
+    }
+}
+
+/// Sets up a run to pick `save` back up, as the Continue button does. `restore_run` deals its
+/// cards once `AppState::Playing` starts.
+pub fn continue_run(cmd: &mut Commands, save: SaveGame) {
+    let seed = Seed(save.seed);
+    cmd.insert_resource(save.mode);
+    cmd.insert_resource(seed);
+    cmd.insert_resource(GameRng::from_seed(seed));
+    cmd.insert_resource(SelectedLayout(save.layout.clone()));
+    cmd.insert_resource(CurrentLevel(Level::new(save.level)));
+    // NOTE: its seed is the run's, and only its first level is scored, see `record_daily_score`
+    if let Some(daily) = save.daily.clone() {
+        cmd.insert_resource(daily);
+    }
+    cmd.insert_resource(PendingRun(save));
+    cmd.insert_resource(NextState(Some(AppState::Playing)));
+}
+
+/// A saved run to restore once `AppState::Playing` starts. Inserted by the Continue button and
+/// removed once the run is back on the table.
+#[derive(Resource, Deref)]
+pub struct PendingRun(pub SaveGame);
+
+/// Saves the run in progress. Ignored while the decks are still being dealt or once the run is
+/// over.
+#[derive(Event, Clone, Copy, Debug, Default)]
+pub struct SaveRun;
+
+pub struct SavePlugin;
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SaveRun>()
+            .add_systems(
+                Update,
+                (
+                    save_run.run_if(in_state(AppState::Playing)),
+                    restore_run
+                        .in_set(PhaseSet::Setup)
+                        .in_set(CardSet::Transfer)
+                        .before(spawn_card)
+                        .run_if(resource_exists::<PendingRun>()),
+                ),
+            )
+            .add_systems(OnEnter(GameState::Scoring), forget_run)
+            .add_systems(OnExit(AppState::Playing), drop_pending_run);
+    }
+}
+
+/// Everything a `SaveGame` is taken from.
+#[derive(SystemParam)]
+pub struct RunSnapshot<'w, 's> {
+    zones: Res<'w, Zones>,
+    mode: Res<'w, GameMode>,
+    active: Res<'w, ActivePlayer>,
+    seed: Res<'w, Seed>,
+    score: Res<'w, Score>,
+    game_timer: Res<'w, GameTimer>,
+    layout: Res<'w, ActiveLayout>,
+    level: Res<'w, CurrentLevel>,
+    daily: Option<Res<'w, DailyChallenge>>,
+    q_children: Query<'w, 's, &'static Children>,
+    q_cards: Query<'w, 's, &'static Card>,
+    q_travel: Query<'w, 's, (&'static Card, &'static CardTravel)>,
+    q_rules: Query<'w, 's, &'static Rule>,
+    q_goals: Query<'w, 's, (&'static Owner, &'static Goals)>,
+    q_shapes: Query<
+        'w,
+        's,
+        (
+            &'static Shape,
+            &'static Transform,
+            &'static LinearVelocity,
+            Option<&'static Element>,
+        ),
+        With<IsOnBoard>,
+    >,
+}
+
+impl RunSnapshot<'_, '_> {
+    /// The zone's cards, counting those still flying to it as already there.
+    fn zone_cards(&self, owner: PlayerId, kind: ZoneKind) -> Vec<CardId> {
+        let Some(zone) = self.zones.get(owner, kind) else {
+            return Vec::new();
+        };
+        let mut cards: Vec<CardId> = self
+            .q_children
+            .get(zone)
+            .into_iter()
+            .flat_map(|children| children.iter())
+            .filter_map(|&card| self.q_cards.get(card).ok()?.definition.clone())
+            .collect();
+        for (card, travel) in self.q_travel.iter().filter(|(_, t)| t.zone == zone) {
+            let Some(id) = card.definition.clone() else {
+                continue;
+            };
+            match travel.index {
+                Some(i) => cards.insert(i.min(cards.len()), id),
+                None => cards.push(id),
+            }
+        }
+        cards
+    }
+
+    pub fn capture(&self) -> SaveGame {
+        let seats = self
+            .mode
+            .seats()
+            .map(|owner| SavedSeat {
+                library: self.zone_cards(owner, ZoneKind::Library),
+                hand: self.zone_cards(owner, ZoneKind::Hand),
+                discard: self.zone_cards(owner, ZoneKind::Discard),
+                exhaust: self.zone_cards(owner, ZoneKind::Exhaust),
+                goals: self
+                    .q_goals
+                    .iter()
+                    .find(|(goal_owner, _)| goal_owner.0 == owner)
+                    .map(|(_, goals)| goals.to_vec())
+                    .unwrap_or_default(),
+            })
+            .collect();
+
+        SaveGame {
+            seed: self.seed.0,
+            mode: *self.mode,
+            active_player: self.active.0,
+            seats,
+            rules: self
+                .q_rules
+                .get_single()
+                .map(|rule| rule.0.clone())
+                .unwrap_or_default(),
+            score: self.score.clone(),
+            elapsed: self.game_timer.timer.elapsed_secs(),
+            shapes: self
+                .q_shapes
+                .iter()
+                .map(|(shape, transform, velocity, element)| SavedShape {
+                    shape: *shape,
+                    position: transform.translation.truncate(),
+                    rotation: transform.rotation.to_euler(EulerRot::XYZ).2,
+                    velocity: velocity.0,
+                    scale: transform.scale.x / config::SHAPE_SCALE,
+                    element: element.copied(),
+                })
+                .collect(),
+            layout: self.layout.name.clone(),
+            level: self.level.number,
+            daily: self.daily.as_deref().cloned(),
+        }
+    }
+}
+
+pub fn save_run(
+    mut reader: EventReader<SaveRun>,
+    state: Res<State<GameState>>,
+    snapshot: RunSnapshot,
+    storage: Res<Storage>,
+) {
+    if reader.read().count() == 0 {
+        return;
+    }
+    if matches!(state.get(), GameState::Setup | GameState::Scoring) {
+        return;
+    }
+
+    let saved = ron::ser::to_string_pretty(&snapshot.capture(), default())
+        .map_err(|e| e.to_string())
+        .and_then(|ron| storage.save(SAVE_KEY, &ron).map_err(|e| e.to_string()));
+    match saved {
+        Ok(()) => info!("saved run to {}", storage.location(SAVE_KEY)),
+        Err(err) => error!("couldn't save run: {err}"),
+    }
+}
+
+/// Deals the saved cards into their zones and puts the board back, in place of the usual Setup.
+/// The rules and goals are restored by `spawn_rules` and `spawn_goals`.
+fn restore_run(
+    mut cmd: Commands,
+    pending: Res<PendingRun>,
+    zones: Res<Zones>,
+    mut score: ResMut<Score>,
+    mut game_timer: ResMut<GameTimer>,
+    mut active: ResMut<ActivePlayer>,
+    mut spawn_card: EventWriter<SpawnCard>,
+    mut spawn_body: EventWriter<SpawnBody>,
+    mut end_phase: EventWriter<EndPhase>,
+    mut missing_zone: Local<ThrottledWarn>,
+) {
+    let mut deals = Vec::new();
+    for (i, seat) in pending.seats.iter().enumerate() {
+        let owner = PlayerId(i as u8);
+        for (kind, cards, face_up) in [
+            (ZoneKind::Library, &seat.library, false),
+            (ZoneKind::Hand, &seat.hand, true),
+            (ZoneKind::Discard, &seat.discard, false),
+            (ZoneKind::Exhaust, &seat.exhaust, false),
+        ] {
+            // NOTE: zones are spawned on entering Playing, so wait rather than lose the cards
+            let Some(zone) = zones.get(owner, kind) else {
+                missing_zone.warn(&format!(
+                    "restore_run: player {owner:?} has no {kind:?} zone yet"
+                ));
+                return;
+            };
+            deals.push((zone, cards, face_up));
+        }
+    }
+
+    for (zone, cards, face_up) in deals {
+        for card in cards {
+            spawn_card.send(SpawnCard {
+                zone_id: zone,
+                card: card.clone(),
+                face_up,
+                index: None,
+            });
+        }
+    }
+    for saved in &pending.shapes {
+        spawn_body.send(SpawnBody {
+            shape: saved.shape,
+            transform: Transform::from_translation(saved.position.extend(0.))
+                .with_rotation(Quat::from_rotation_z(saved.rotation))
+                .with_scale(Vec3::splat(saved.scale)),
+            velocity: Some(LinearVelocity(saved.velocity)),
+            combo: 0,
+            element: saved.element,
+            source: SpawnSource::Board,
+        });
+    }
+    *score = pending.score.clone();
+    game_timer
+        .timer
+        .set_elapsed(Duration::from_secs_f32(pending.elapsed));
+    active.0 = pending.active_player;
+
+    info!("restored saved run (seed {})", pending.seed);
+    cmd.remove_resource::<PendingRun>();
+    end_phase.send(EndPhase);
+}
+
+fn forget_run(storage: Res<Storage>) {
+    if let Err(err) = storage.remove(SAVE_KEY) {
+        error!("couldn't remove saved run: {err}");
+    }
+}
+
+fn drop_pending_run(mut cmd: Commands) {
+    cmd.remove_resource::<PendingRun>();
+}
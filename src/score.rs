@@ -0,0 +1,83 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{board::ShapesCombined, game_shapes::Shape, AppState};
+
+/// Points per side of the shape a combine produces.
+const POINTS_PER_SIDE: u32 = 10;
+
+#[derive(Resource, Clone, Debug, Serialize, Deserialize)]
+pub struct Score {
+    pub score: u32,
+    pub base_score: u32,
+    pub goal_status: Vec<bool>,
+    pub cards_played: u32,
+    /// Running total awarded for combines during the run.
+    pub combine_points: u32,
+}
+impl Score {
+    pub fn reset(&mut self) {
+        self.score = 0;
+        self.base_score = 0;
+        self.goal_status = vec![false, false, false];
+        self.cards_played = 0;
+        self.combine_points = 0;
+    }
+}
+impl Default for Score {
+    fn default() -> Self {
+        Self {
+            score: 0,
+            base_score: 0,
+            goal_status: vec![false, false, false],
+            cards_played: 0,
+            combine_points: 0,
+        }
+    }
+}
+
+/// Sent whenever points are awarded during a run. `total` is the new running total.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct ScoreChanged {
+    pub total: u32,
+    pub delta: u32,
+}
+
+pub struct ScorePlugin;
+impl Plugin for ScorePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Score>()
+            .add_event::<ScoreChanged>()
+            .add_systems(Update, award_combines.run_if(in_state(AppState::Playing)));
+    }
+}
+
+/// Bigger shapes are worth more, and a combine that had to settle a color fight counts double.
+/// Combines in a chain are worth this times their depth.
+pub fn combine_points(inputs: [Shape; 2], result: Shape) -> u32 {
+    let points = result.polygon.vertices() as u32 * POINTS_PER_SIDE;
+    if inputs[0].color != inputs[1].color {
+        points * 2
+    } else {
+        points
+    }
+}
+
+fn award_combines(
+    mut reader: EventReader<ShapesCombined>,
+    mut score: ResMut<Score>,
+    mut writer: EventWriter<ScoreChanged>,
+) {
+    let delta: u32 = reader
+        .read()
+        .map(|combined| combine_points(combined.inputs, combined.result) * combined.depth)
+        .sum();
+    if delta == 0 {
+        return;
+    }
+    score.combine_points += delta;
+    writer.send(ScoreChanged {
+        total: score.combine_points,
+        delta,
+    });
+}
@@ -0,0 +1,144 @@
+//! Player settings that survive restarts, saved as RON through `Storage` whenever they change
+//! and read back while assets load. Fields missing from an older file keep their defaults.
+
+use bevy::{
+    prelude::*,
+    window::{PresentMode, PrimaryWindow},
+};
+use leafwing_input_manager::prelude::InputMap;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    args::LaunchArgs,
+    audio::Volume,
+    game_shapes::palette::ColorOptions,
+    input::{add_missing_bindings, GameAction},
+    locale::Language,
+    platform::Storage,
+    AppState,
+};
+
+const SETTINGS_KEY: &str = "settings.ron";
+
+#[derive(Resource, Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct Settings {
+    pub volume: Volume,
+    /// Picked on the pause menu.
+    pub language: Language,
+    /// The color blind palette and shape patterns, picked on the pause menu.
+    pub colors: ColorOptions,
+    /// Window size on native. `--windowed` takes precedence.
+    pub resolution: Vec2,
+    pub vsync: bool,
+    /// Keyboard and mouse bindings, changed on the controls screen.
+    pub input_map: InputMap<GameAction>,
+    /// Set once the tutorial has been played or left. Settings saved before there was a tutorial
+    /// count as having seen it.
+    #[serde(default = "tutorial_seen")]
+    pub tutorial_done: bool,
+}
+
+fn tutorial_seen() -> bool {
+    true
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            volume: Volume::default(),
+            language: Language::default(),
+            colors: ColorOptions::default(),
+            resolution: Vec2::new(1920., 1080.),
+            vsync: true,
+            input_map: GameAction::default_input_map(),
+            tutorial_done: false,
+        }
+    }
+}
+
+pub struct SettingsPlugin;
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Settings>()
+            .add_systems(OnEnter(AppState::Loading), load_settings)
+            .add_systems(
+                Update,
+                ((sync_volume, sync_language, sync_colors), save_settings)
+                    .chain()
+                    .run_if(not(in_state(AppState::Loading))),
+            );
+    }
+}
+
+fn load_settings(
+    mut settings: ResMut<Settings>,
+    mut volume: ResMut<Volume>,
+    mut language: ResMut<Language>,
+    mut colors: ResMut<ColorOptions>,
+    mut q_window: Query<&mut Window, With<PrimaryWindow>>,
+    storage: Res<Storage>,
+    args: Option<Res<LaunchArgs>>,
+) {
+    if let Some(text) = storage.load(SETTINGS_KEY) {
+        match ron::from_str(&text) {
+            Ok(loaded) => {
+                *settings = loaded;
+                add_missing_bindings(&mut settings.input_map);
+            },
+            Err(err) => warn!(
+                "couldn't read {}, using the default settings: {err}",
+                storage.location(SETTINGS_KEY)
+            ),
+        }
+    }
+    *volume = settings.volume;
+    language.clone_from(&settings.language);
+    *colors = settings.colors;
+
+    let Ok(mut window) = q_window.get_single_mut() else {
+        return;
+    };
+    window.present_mode = if settings.vsync {
+        PresentMode::AutoVsync
+    } else {
+        PresentMode::AutoNoVsync
+    };
+    // NOTE: the web build's canvas is sized by the page
+    if cfg!(not(target_arch = "wasm32")) && args.is_none_or(|args| args.windowed.is_none()) {
+        window
+            .resolution
+            .set(settings.resolution.x, settings.resolution.y);
+    }
+}
+
+fn sync_volume(volume: Res<Volume>, mut settings: ResMut<Settings>) {
+    if volume.is_changed() && settings.volume != *volume {
+        settings.volume = *volume;
+    }
+}
+
+fn sync_language(language: Res<Language>, mut settings: ResMut<Settings>) {
+    if language.is_changed() && settings.language != *language {
+        settings.language.clone_from(&language);
+    }
+}
+
+fn sync_colors(colors: Res<ColorOptions>, mut settings: ResMut<Settings>) {
+    if colors.is_changed() && settings.colors != *colors {
+        settings.colors = *colors;
+    }
+}
+
+fn save_settings(settings: Res<Settings>, storage: Res<Storage>, mut seen: Local<bool>) {
+    // NOTE: the first run sees the settings that were just loaded as changed
+    if !std::mem::replace(&mut *seen, true) || !settings.is_changed() {
+        return;
+    }
+    let saved = ron::ser::to_string_pretty(&*settings, default())
+        .map_err(|e| e.to_string())
+        .and_then(|ron| storage.save(SETTINGS_KEY, &ron).map_err(|e| e.to_string()));
+    if let Err(err) = saved {
+        error!("couldn't save settings: {err}");
+    }
+}
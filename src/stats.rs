@@ -0,0 +1,230 @@
+//! Numbers about the game. `RunStats` counts what happens during a run for the summary shown
+//! next to the scoreboard, and each finished run is added to the `LifetimeStats` kept in
+//! `stats.ron`. Every few seconds a line about the board and cards is logged too.
+
+use bevy::{prelude::*, utils::HashMap};
+use bevy_xpbd_2d::prelude::{CollisionStarted, LinearVelocity};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    board::{
+        layout::BoardWall, transmute::BoardTransformed, BodySpawned, IsOnBoard, ShapesCombined,
+    },
+    cards::{card::Card, GameState},
+    game_shapes::{GamePolygon, Shape},
+    pause::PauseState,
+    platform::Storage,
+    score::Score,
+    AppState,
+};
+
+const LOG_INTERVAL: f32 = 5.;
+const STATS_KEY: &str = "stats.ron";
+
+/// What happened in the current run. Reset when a run starts.
+#[derive(Resource, Default, Clone, Debug)]
+pub struct RunStats {
+    /// Shapes that came onto the board other than by a combine, by polygon.
+    pub spawned: HashMap<GamePolygon, u32>,
+    /// Shapes made by combines, by polygon.
+    pub merged: HashMap<GamePolygon, u32>,
+    pub cards_played: u32,
+    /// The deepest chain of combines.
+    pub longest_combo: u32,
+    pub wall_hits: u32,
+    /// The speeds shapes hit the walls with, added up.
+    pub wall_damage: f32,
+    /// Seconds spent in each phase, paused time left out.
+    pub phase_time: HashMap<GameState, f32>,
+}
+
+impl RunStats {
+    pub fn total_spawned(&self) -> u32 {
+        self.spawned.values().sum()
+    }
+
+    pub fn total_merged(&self) -> u32 {
+        self.merged.values().sum()
+    }
+
+    pub fn play_time(&self) -> f32 {
+        self.phase_time.values().sum()
+    }
+}
+
+/// Every finished run added up, saved between sessions.
+#[derive(Resource, Serialize, Deserialize, Default, Clone, Debug)]
+#[serde(default)]
+pub struct LifetimeStats {
+    pub runs: u32,
+    pub spawned: HashMap<GamePolygon, u32>,
+    pub merged: HashMap<GamePolygon, u32>,
+    pub cards_played: u32,
+    pub longest_combo: u32,
+    pub wall_hits: u32,
+    pub wall_damage: f32,
+    /// Seconds.
+    pub play_time: f32,
+}
+
+impl LifetimeStats {
+    pub fn add(&mut self, run: &RunStats) {
+        self.runs += 1;
+        for (polygon, count) in run.spawned.iter() {
+            *self.spawned.entry(*polygon).or_default() += count;
+        }
+        for (polygon, count) in run.merged.iter() {
+            *self.merged.entry(*polygon).or_default() += count;
+        }
+        self.cards_played += run.cards_played;
+        self.longest_combo = self.longest_combo.max(run.longest_combo);
+        self.wall_hits += run.wall_hits;
+        self.wall_damage += run.wall_damage;
+        self.play_time += run.play_time();
+    }
+
+    pub fn total_merged(&self) -> u32 {
+        self.merged.values().sum()
+    }
+}
+
+#[derive(Resource, Deref, DerefMut)]
+pub struct StatsTick(Timer);
+
+/// Logs a summary of the board and card counts every few seconds, so bug reports carry a rough
+/// picture of how loaded the game was.
+pub struct StatsPlugin;
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(StatsTick(Timer::from_seconds(
+            LOG_INTERVAL,
+            TimerMode::Repeating,
+        )))
+        .init_resource::<RunStats>()
+        .init_resource::<LifetimeStats>()
+        .add_systems(OnEnter(AppState::Loading), load_lifetime_stats)
+        .add_systems(OnEnter(AppState::Playing), reset_run_stats)
+        .add_systems(
+            Update,
+            (
+                (log_stats, log_board_transforms),
+                (
+                    count_shapes,
+                    count_wall_hits,
+                    count_cards,
+                    time_phases.run_if(in_state(PauseState::Running)),
+                )
+                    .run_if(not(in_state(GameState::Scoring))),
+            )
+                .run_if(in_state(AppState::Playing)),
+        )
+        .add_systems(OnEnter(GameState::Scoring), record_run);
+    }
+}
+
+fn load_lifetime_stats(mut lifetime: ResMut<LifetimeStats>, storage: Res<Storage>) {
+    let Some(text) = storage.load(STATS_KEY) else {
+        return;
+    };
+    match ron::from_str(&text) {
+        Ok(loaded) => *lifetime = loaded,
+        Err(err) => warn!(
+            "couldn't read {}, starting the lifetime stats over: {err}",
+            storage.location(STATS_KEY)
+        ),
+    }
+}
+
+fn reset_run_stats(mut stats: ResMut<RunStats>) {
+    *stats = RunStats::default();
+}
+
+fn count_shapes(
+    mut spawned: EventReader<BodySpawned>,
+    mut combined: EventReader<ShapesCombined>,
+    mut stats: ResMut<RunStats>,
+) {
+    // NOTE: a combine's result is spawned too, it's counted as merged instead
+    for BodySpawned(body) in spawned.read().filter(|BodySpawned(body)| body.combo == 0) {
+        *stats.spawned.entry(body.shape.polygon).or_default() += 1;
+    }
+    for event in combined.read() {
+        *stats.merged.entry(event.result.polygon).or_default() += 1;
+        stats.longest_combo = stats.longest_combo.max(event.depth);
+    }
+}
+
+fn count_wall_hits(
+    mut started: EventReader<CollisionStarted>,
+    q_walls: Query<(), With<BoardWall>>,
+    q_shapes: Query<&LinearVelocity, (With<Shape>, With<IsOnBoard>)>,
+    mut stats: ResMut<RunStats>,
+) {
+    for CollisionStarted(e1, e2) in started.read() {
+        let shape = if q_walls.contains(*e1) {
+            *e2
+        } else if q_walls.contains(*e2) {
+            *e1
+        } else {
+            continue;
+        };
+        if let Ok(velocity) = q_shapes.get(shape) {
+            stats.wall_hits += 1;
+            stats.wall_damage += velocity.length();
+        }
+    }
+}
+
+fn count_cards(score: Res<Score>, mut stats: ResMut<RunStats>) {
+    if score.is_changed() && stats.cards_played != score.cards_played {
+        stats.cards_played = score.cards_played;
+    }
+}
+
+fn time_phases(time: Res<Time>, state: Res<State<GameState>>, mut stats: ResMut<RunStats>) {
+    *stats.phase_time.entry(state.get().clone()).or_default() += time.delta_seconds();
+}
+
+/// Adds the run that just ended to the lifetime stats, and saves them.
+pub fn record_run(run: Res<RunStats>, mut lifetime: ResMut<LifetimeStats>, storage: Res<Storage>) {
+    lifetime.add(&run);
+    let result = ron::ser::to_string_pretty(&*lifetime, default())
+        .map_err(|e| e.to_string())
+        .and_then(|ron| storage.save(STATS_KEY, &ron).map_err(|e| e.to_string()));
+    if let Err(err) = result {
+        error!("couldn't save the lifetime stats: {err}");
+    }
+}
+
+fn log_stats(
+    time: Res<Time>,
+    mut tick: ResMut<StatsTick>,
+    mut contacts: Local<usize>,
+    mut started_reader: EventReader<CollisionStarted>,
+    q_shapes: Query<(), With<IsOnBoard>>,
+    q_cards: Query<(), With<Card>>,
+) {
+    *contacts += started_reader.read().count();
+
+    if !tick.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    info!(
+        "{} shapes on board, {} contacts in the last {}s, {} cards",
+        q_shapes.iter().len(),
+        *contacts,
+        LOG_INTERVAL,
+        q_cards.iter().len(),
+    );
+    *contacts = 0;
+}
+
+fn log_board_transforms(mut reader: EventReader<BoardTransformed>) {
+    for event in reader.read() {
+        info!(
+            "{} {:?}s transformed into {:?}s",
+            event.count, event.from, event.to
+        );
+    }
+}
@@ -0,0 +1,222 @@
+//! Motion trails behind fast shapes. Every shape on the board keeps a short history of where it
+//! has been while it moves faster than `TRAIL_SPEED`, and a ribbon drawn through those points
+//! narrows and fades toward its tail. Ribbon meshes are rewritten in place every frame and go back
+//! to the `TrailPool` when their trail runs out, so trails coming and going don't add and drop
+//! meshes all the time.
+
+use std::collections::VecDeque;
+
+use bevy::{
+    prelude::*,
+    render::{
+        mesh::{Mesh, VertexAttributeValues},
+        render_resource::PrimitiveTopology,
+        view::NoFrustumCulling,
+    },
+    sprite::MaterialMesh2dBundle,
+    utils::HashMap,
+};
+use bevy_xpbd_2d::prelude::{LinearVelocity, Position};
+
+use crate::{
+    board::{self, IsOnBoard},
+    game_shapes::{config::POLYGON_RADIUS, palette::ColorOptions, Shape},
+    AppState, GameplayEntity,
+};
+
+/// Shapes slower than this, in pixels per second, leave no new points.
+const TRAIL_SPEED: f32 = 0.8 * board::config::MAX_SPEED;
+/// Seconds a point stays on the trail.
+const TRAIL_LIFETIME: f32 = 0.5;
+/// How far a shape moves before its trail gets a new point.
+const TRAIL_SPACING: f32 = 4.;
+const TRAIL_POINTS: usize = 32;
+/// The ribbon's width at the shape, relative to the shape's diameter.
+const TRAIL_WIDTH: f32 = 0.6;
+const TRAIL_ALPHA: f32 = 0.5;
+/// Under the shapes, over the board.
+const TRAIL_Z: f32 = -1.;
+
+/// Where the shape has been lately, newest first, with each point's age in seconds.
+#[derive(Component, Default, Debug)]
+pub struct Trail {
+    points: VecDeque<(Vec2, f32)>,
+}
+
+/// Ribbon meshes not in use, and the ribbon each trailing shape has.
+#[derive(Resource, Default)]
+pub struct TrailPool {
+    free: Vec<Handle<Mesh>>,
+    in_use: HashMap<Entity, (Entity, Handle<Mesh>)>,
+    material: Handle<ColorMaterial>,
+}
+
+impl TrailPool {
+    fn release(&mut self, cmd: &mut Commands, shape: Entity) {
+        if let Some((ribbon, mesh)) = self.in_use.remove(&shape) {
+            if let Some(ribbon) = cmd.get_entity(ribbon) {
+                ribbon.despawn_recursive();
+            }
+            self.free.push(mesh);
+        }
+    }
+}
+
+pub struct TrailPlugin;
+impl Plugin for TrailPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TrailPool>()
+            .add_systems(Startup, setup_trail_material)
+            .add_systems(
+                Update,
+                (add_trails, record_trails, draw_trails, release_trails)
+                    .chain()
+                    .run_if(in_state(AppState::Playing)),
+            )
+            .add_systems(OnExit(AppState::Playing), reset_trails);
+    }
+}
+
+fn setup_trail_material(mut pool: ResMut<TrailPool>, mut materials: ResMut<Assets<ColorMaterial>>) {
+    // NOTE: the ribbons carry their colors per vertex, so one white material serves them all
+    pool.material = materials.add(ColorMaterial::from(Color::WHITE));
+}
+
+fn add_trails(mut cmd: Commands, q_added: Query<Entity, (Added<IsOnBoard>, With<Shape>)>) {
+    for entity in q_added.iter() {
+        cmd.entity(entity).insert(Trail::default());
+    }
+}
+
+fn record_trails(time: Res<Time>, mut q_trails: Query<(&mut Trail, &Position, &LinearVelocity)>) {
+    let dt = time.delta_seconds();
+    for (mut trail, position, velocity) in q_trails.iter_mut() {
+        for (_, age) in trail.points.iter_mut() {
+            *age += dt;
+        }
+        while trail
+            .points
+            .back()
+            .is_some_and(|&(_, age)| age > TRAIL_LIFETIME)
+        {
+            trail.points.pop_back();
+        }
+        if velocity.0.length_squared() < TRAIL_SPEED.powi(2) {
+            continue;
+        }
+        let moved = trail
+            .points
+            .front()
+            .map_or(f32::INFINITY, |&(point, _)| point.distance(position.0));
+        if moved >= TRAIL_SPACING {
+            trail.points.push_front((position.0, 0.));
+            trail.points.truncate(TRAIL_POINTS);
+        }
+    }
+}
+
+/// Gives each trail with at least two points a ribbon, and rewrites its mesh from the points.
+fn draw_trails(
+    mut cmd: Commands,
+    q_trails: Query<(Entity, &Trail, &Shape, &Position, &Transform)>,
+    mut pool: ResMut<TrailPool>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    colors: Res<ColorOptions>,
+) {
+    for (entity, trail, shape, position, transform) in q_trails.iter() {
+        if trail.points.len() < 2 {
+            pool.release(&mut cmd, entity);
+            continue;
+        }
+        let mesh = match pool.in_use.get(&entity) {
+            Some((_, mesh)) => mesh.clone(),
+            None => {
+                let mesh = pool
+                    .free
+                    .pop()
+                    .unwrap_or_else(|| meshes.add(Mesh::new(PrimitiveTopology::TriangleStrip)));
+                let ribbon = cmd
+                    .spawn((
+                        MaterialMesh2dBundle {
+                            mesh: mesh.clone().into(),
+                            material: pool.material.clone(),
+                            transform: Transform::from_xyz(0., 0., TRAIL_Z),
+                            ..default()
+                        },
+                        // NOTE: bounds are only worked out once, and the ribbon keeps changing
+                        NoFrustumCulling,
+                        GameplayEntity,
+                    ))
+                    .id();
+                pool.in_use.insert(entity, (ribbon, mesh.clone()));
+                mesh
+            },
+        };
+        let Some(mesh) = meshes.get_mut(&mesh) else {
+            continue;
+        };
+        let half_width = POLYGON_RADIUS * transform.scale.x * TRAIL_WIDTH;
+        write_ribbon(
+            mesh,
+            position.0,
+            &trail.points,
+            half_width,
+            colors.color(shape.color),
+        );
+    }
+}
+
+/// Lays a strip through `head` and the trail's points, reusing the mesh's buffers.
+fn write_ribbon(
+    mesh: &mut Mesh,
+    head: Vec2,
+    points: &VecDeque<(Vec2, f32)>,
+    half_width: f32,
+    color: Color,
+) {
+    let mut positions = match mesh.remove_attribute(Mesh::ATTRIBUTE_POSITION) {
+        Some(VertexAttributeValues::Float32x3(positions)) => positions,
+        _ => Vec::new(),
+    };
+    let mut colors = match mesh.remove_attribute(Mesh::ATTRIBUTE_COLOR) {
+        Some(VertexAttributeValues::Float32x4(colors)) => colors,
+        _ => Vec::new(),
+    };
+    positions.clear();
+    colors.clear();
+
+    let path: Vec<(Vec2, f32)> = std::iter::once((head, 0.))
+        .chain(points.iter().copied())
+        .collect();
+    for (i, &(point, age)) in path.iter().enumerate() {
+        let ahead = path[i.saturating_sub(1)].0;
+        let behind = path[(i + 1).min(path.len() - 1)].0;
+        let side = (ahead - behind).normalize_or_zero().perp();
+        let left = 1. - age / TRAIL_LIFETIME;
+        let offset = side * half_width * left;
+        for vertex in [point + offset, point - offset] {
+            positions.push([vertex.x, vertex.y, 0.]);
+            colors.push(color.with_a(TRAIL_ALPHA * left).as_linear_rgba_f32());
+        }
+    }
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+}
+
+/// Shapes leaving the board take their ribbon with them.
+fn release_trails(
+    mut cmd: Commands,
+    mut removed: RemovedComponents<Trail>,
+    mut pool: ResMut<TrailPool>,
+) {
+    for entity in removed.read() {
+        pool.release(&mut cmd, entity);
+    }
+}
+
+fn reset_trails(mut pool: ResMut<TrailPool>) {
+    // NOTE: the ribbons are gameplay entities, already on their way out
+    let pool = &mut *pool;
+    pool.free
+        .extend(pool.in_use.drain().map(|(_, (_, mesh))| mesh));
+}
@@ -0,0 +1,252 @@
+//! The tutorial: a solo run walked through step by step, following the script in
+//! `assets/tutorial/intro.tutorial.ron`. Each step shows a line of text, may point at a part of the
+//! HUD, the board or the table (the overlay dims everything else), lets through only the actions
+//! it expects, and moves on when its `TutorialTrigger` happens. It starts on its own the first
+//! time the game is launched, and `Settings::tutorial_done` remembers it was seen.
+
+use std::io;
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+    utils::BoxedFuture,
+};
+use leafwing_input_manager::{plugin::InputManagerSystem, prelude::ActionState, Actionlike};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    board::ShapesCombined,
+    cards::{
+        deck::DrawCard,
+        effect::PlayCard,
+        zones::{GameMode, ZoneKind},
+        GameState,
+    },
+    input::GameAction,
+    level::{CurrentLevel, Level},
+    loading::TutorialAssets,
+    settings::Settings,
+    AppState,
+};
+
+/// Parts of the HUD a step can point at.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HudElement {
+    Timer,
+    Score,
+    Rules,
+    Energy,
+}
+
+/// What a step points at. Everything else is dimmed.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+pub enum TutorialTarget {
+    /// Nothing is dimmed.
+    #[default]
+    None,
+    Hud(HudElement),
+    /// A rectangle of the board, in world units around `center`.
+    Board {
+        center: Vec2,
+        half_size: Vec2,
+    },
+    /// The player's zone of this kind on the table, `half_size` in table units.
+    Zone {
+        kind: ZoneKind,
+        half_size: Vec2,
+    },
+}
+
+/// What moves the tutorial on to the next step.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub enum TutorialTrigger {
+    Action(GameAction),
+    DrawCard,
+    PlayCard,
+    Combine,
+    Phase(GameState),
+    /// Seconds after the step starts.
+    Wait(f32),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TutorialStep {
+    /// The key of the step's text in the locale files.
+    pub text: String,
+    #[serde(default)]
+    pub target: TutorialTarget,
+    /// The actions that get through during the step. `Pause` always does, so the tutorial can be
+    /// left, and so do the menu actions, like the mouse on buttons.
+    #[serde(default)]
+    pub allow: Vec<GameAction>,
+    pub advance: TutorialTrigger,
+}
+
+#[derive(Asset, TypePath, Serialize, Deserialize, Clone, Debug)]
+pub struct TutorialScript {
+    pub steps: Vec<TutorialStep>,
+}
+
+/// The tutorial in progress. Present from the start of the tutorial run until its last step is
+/// done or the run is left.
+#[derive(Resource, Clone, Debug)]
+pub struct Tutorial {
+    pub steps: Vec<TutorialStep>,
+    pub current: usize,
+    /// Seconds since the current step started.
+    pub elapsed: f32,
+}
+
+impl Tutorial {
+    pub fn step(&self) -> Option<&TutorialStep> {
+        self.steps.get(self.current)
+    }
+}
+
+/// Starts the tutorial run from the menu.
+#[derive(Event, Clone, Copy, Debug, Default)]
+pub struct StartTutorial;
+
+#[derive(Default)]
+pub struct TutorialScriptLoader;
+
+impl AssetLoader for TutorialScriptLoader {
+    type Asset = TutorialScript;
+    type Settings = ();
+    type Error = io::Error;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<TutorialScript, io::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            ron::de::from_bytes(&bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["tutorial.ron"]
+    }
+}
+
+pub struct TutorialPlugin;
+impl Plugin for TutorialPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<TutorialScript>()
+            .init_asset_loader::<TutorialScriptLoader>()
+            .add_event::<StartTutorial>()
+            .add_systems(OnEnter(AppState::Menu), start_on_first_launch)
+            .add_systems(Update, start_tutorial.run_if(in_state(AppState::Menu)))
+            .add_systems(
+                PreUpdate,
+                block_actions
+                    .after(InputManagerSystem::Update)
+                    .run_if(resource_exists::<Tutorial>()),
+            )
+            .add_systems(
+                Update,
+                advance_tutorial
+                    .run_if(in_state(AppState::Playing))
+                    .run_if(resource_exists::<Tutorial>()),
+            )
+            .add_systems(OnExit(AppState::Playing), end_tutorial);
+    }
+}
+
+fn start_on_first_launch(settings: Res<Settings>, mut writer: EventWriter<StartTutorial>) {
+    if !settings.tutorial_done {
+        writer.send(StartTutorial);
+    }
+}
+
+fn start_tutorial(
+    mut cmd: Commands,
+    mut reader: EventReader<StartTutorial>,
+    handles: Res<TutorialAssets>,
+    scripts: Res<Assets<TutorialScript>>,
+) {
+    if reader.read().last().is_none() {
+        return;
+    }
+    let Some(script) = scripts.get(&handles.script) else {
+        warn!("the tutorial script isn't loaded, not starting the tutorial");
+        return;
+    };
+    info!("starting the tutorial");
+    cmd.insert_resource(Tutorial {
+        steps: script.steps.clone(),
+        current: 0,
+        elapsed: 0.,
+    });
+    cmd.insert_resource(GameMode::Solo);
+    cmd.insert_resource(CurrentLevel(Level::new(1)));
+    cmd.insert_resource(NextState(Some(AppState::Playing)));
+}
+
+/// Runs after leafwing has read the inputs, so the rest of the frame never sees the actions the
+/// step doesn't expect.
+fn block_actions(tutorial: Res<Tutorial>, mut actions: ResMut<ActionState<GameAction>>) {
+    let Some(step) = tutorial.step() else {
+        return;
+    };
+    for action in GameAction::variants() {
+        let always = action == GameAction::Pause || action.is_menu();
+        if !always && !step.allow.contains(&action) && actions.pressed(action) {
+            actions.consume(action);
+        }
+    }
+}
+
+fn advance_tutorial(
+    mut tutorial: ResMut<Tutorial>,
+    time: Res<Time>,
+    actions: Res<ActionState<GameAction>>,
+    state: Res<State<GameState>>,
+    mut draws: EventReader<DrawCard>,
+    mut plays: EventReader<PlayCard>,
+    mut combines: EventReader<ShapesCombined>,
+    mut settings: ResMut<Settings>,
+    mut cmd: Commands,
+) {
+    tutorial.elapsed += time.delta_seconds();
+    let drawn = draws.read().count() > 0;
+    let played = plays.read().count() > 0;
+    let combined = combines.read().count() > 0;
+    let Some(step) = tutorial.step() else {
+        return;
+    };
+    let done = match &step.advance {
+        TutorialTrigger::Action(action) => actions.just_pressed(*action),
+        TutorialTrigger::DrawCard => drawn,
+        TutorialTrigger::PlayCard => played,
+        TutorialTrigger::Combine => combined,
+        TutorialTrigger::Phase(phase) => state.get() == phase,
+        TutorialTrigger::Wait(seconds) => tutorial.elapsed >= *seconds,
+    };
+    if !done {
+        return;
+    }
+    tutorial.current += 1;
+    tutorial.elapsed = 0.;
+    if tutorial.current >= tutorial.steps.len() {
+        info!("tutorial finished");
+        settings.tutorial_done = true;
+        cmd.remove_resource::<Tutorial>();
+    }
+}
+
+/// Leaving the run leaves the tutorial too, and counts as having seen it.
+fn end_tutorial(
+    mut cmd: Commands,
+    tutorial: Option<Res<Tutorial>>,
+    mut settings: ResMut<Settings>,
+) {
+    if tutorial.is_some() {
+        settings.tutorial_done = true;
+        cmd.remove_resource::<Tutorial>();
+    }
+}
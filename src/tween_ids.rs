@@ -0,0 +1,13 @@
+//! The `user_data` every tween completes with, in one place so no two systems reading
+//! `TweenCompleted` share an id. 0 is left for tweens nothing waits on.
+
+/// Hands a card back to `Layout` once its tween completes.
+pub const RELEASE_TWEEN_DRIVER: u64 = 1;
+/// A shape's shrink or merge, despawning it.
+pub const DESPAWN_SHAPE: u64 = 2;
+/// A popup's fade, despawning it.
+pub const DESPAWN_POPUP: u64 = 3;
+/// The end of a card flip's first half, when the faces are swapped.
+pub const FLIP_MIDPOINT: u64 = 4;
+/// The end of a card flip.
+pub const FLIP_DONE: u64 = 5;
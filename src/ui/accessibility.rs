@@ -0,0 +1,225 @@
+//! Screen readers and keyboard-only menus. The menu actions move bevy_a11y's `Focus` between the
+//! buttons on screen, top to bottom and then left to right (or by `FocusOrder`), staying inside the
+//! newest `FocusScope` while one is open, and `MenuSelect` presses the focused button the way a
+//! click would. The focused button shows as hovered.
+//!
+//! AccessKit reads the `AccessibilityNode`s: buttons are named after their (localized) text, and
+//! cards on the table after their definition, with the cost and rules text as the description. A
+//! card the hand focus lands on takes the `Focus` too, so it's read out as the player moves along
+//! the hand.
+
+use bevy::{
+    a11y::{
+        accesskit::{NodeBuilder, Role},
+        AccessibilityNode, Focus,
+    },
+    prelude::*,
+    ui::UiSystem,
+};
+use leafwing_input_manager::{plugin::InputManagerSystem, prelude::ActionState};
+
+use crate::{
+    cards::{card::Card, definition::CardDefinitions, focus::Focused},
+    input::GameAction,
+    locale::Localization,
+};
+
+/// Overrides where a button comes in the focus order: lower first, then by position.
+#[derive(Component, Clone, Copy, Default, Debug)]
+pub struct FocusOrder(pub i32);
+
+/// Keeps the focus on the buttons under it while it's open, like a modal screen.
+#[derive(Component, Debug)]
+pub struct FocusScope;
+
+pub struct AccessibilityPlugin;
+impl Plugin for AccessibilityPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            (move_menu_focus, press_focused_button)
+                .chain()
+                .after(UiSystem::Focus)
+                .after(InputManagerSystem::Update),
+        )
+        .add_systems(Update, (describe_cards, focus_card))
+        .add_systems(Last, name_buttons);
+    }
+}
+
+/// The buttons the focus can move between, in order.
+fn focus_order(
+    q_buttons: &Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &ViewVisibility,
+            Option<&FocusOrder>,
+        ),
+        With<Button>,
+    >,
+    q_scopes: &Query<Entity, With<FocusScope>>,
+    q_parents: &Query<&Parent>,
+) -> Vec<Entity> {
+    // NOTE: the newest scope is the one on top, entity ids only grow while the app runs
+    let scope = q_scopes.iter().max_by_key(|entity| entity.index());
+    let mut buttons: Vec<_> = q_buttons
+        .iter()
+        .filter(|(.., visibility, _)| visibility.get())
+        .filter(|(entity, ..)| {
+            scope.is_none_or(|scope| q_parents.iter_ancestors(*entity).any(|e| e == scope))
+        })
+        .map(|(entity, transform, _, order)| {
+            let position = transform.translation().truncate();
+            (order.copied().unwrap_or_default().0, position, entity)
+        })
+        .collect();
+    buttons.sort_by(|(a_order, a, _), (b_order, b, _)| {
+        a_order
+            .cmp(b_order)
+            .then(a.y.total_cmp(&b.y))
+            .then(a.x.total_cmp(&b.x))
+    });
+    buttons.into_iter().map(|(.., entity)| entity).collect()
+}
+
+fn move_menu_focus(
+    actions: Res<ActionState<GameAction>>,
+    mut focus: ResMut<Focus>,
+    q_buttons: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &ViewVisibility,
+            Option<&FocusOrder>,
+        ),
+        With<Button>,
+    >,
+    q_scopes: Query<Entity, With<FocusScope>>,
+    q_parents: Query<&Parent>,
+    mut q_interactions: Query<&mut Interaction>,
+) {
+    let step: isize = match (
+        actions.just_pressed(GameAction::MenuNext),
+        actions.just_pressed(GameAction::MenuPrevious),
+    ) {
+        (true, false) => 1,
+        (false, true) => -1,
+        _ => {
+            // NOTE: bevy's focus system unhovers the button every frame the mouse isn't on it
+            if let Some(mut interaction) = focus.0.and_then(|e| q_interactions.get_mut(e).ok()) {
+                if *interaction == Interaction::None {
+                    *interaction = Interaction::Hovered;
+                }
+            }
+            return;
+        },
+    };
+    let order = focus_order(&q_buttons, &q_scopes, &q_parents);
+    if order.is_empty() {
+        return;
+    }
+    let count = order.len() as isize;
+    let next = match focus.0.and_then(|e| order.iter().position(|&b| b == e)) {
+        Some(index) => (index as isize + step).rem_euclid(count),
+        None if step > 0 => 0,
+        None => count - 1,
+    };
+    if let Some(mut interaction) = focus.0.and_then(|e| q_interactions.get_mut(e).ok()) {
+        if *interaction == Interaction::Hovered {
+            *interaction = Interaction::None;
+        }
+    }
+    let button = order[next as usize];
+    focus.0 = Some(button);
+    if let Ok(mut interaction) = q_interactions.get_mut(button) {
+        *interaction = Interaction::Hovered;
+    }
+}
+
+/// Presses the focused button for a frame, so the screens' own `Changed<Interaction>` systems
+/// handle it like a click.
+fn press_focused_button(
+    actions: Res<ActionState<GameAction>>,
+    focus: Res<Focus>,
+    mut q_interactions: Query<&mut Interaction, With<Button>>,
+    mut pressed: Local<Option<Entity>>,
+) {
+    if let Some(mut interaction) = pressed.take().and_then(|e| q_interactions.get_mut(e).ok()) {
+        if *interaction == Interaction::Pressed {
+            *interaction = Interaction::Hovered;
+        }
+    }
+    if !actions.just_pressed(GameAction::MenuSelect) {
+        return;
+    }
+    let Some(button) = focus.0 else {
+        return;
+    };
+    if let Ok(mut interaction) = q_interactions.get_mut(button) {
+        *interaction = Interaction::Pressed;
+        *pressed = Some(button);
+    }
+}
+
+/// Names every button after the text in it. Runs last, after `Localized` text is filled in and
+/// after bevy_ui names new buttons from the text they were spawned with.
+fn name_buttons(
+    mut cmd: Commands,
+    q_buttons: Query<(Entity, &Children), With<Button>>,
+    q_texts: Query<Ref<Text>>,
+) {
+    for (entity, children) in q_buttons.iter() {
+        let texts: Vec<_> = children
+            .iter()
+            .filter_map(|&child| q_texts.get(child).ok())
+            .collect();
+        if !texts.iter().any(|text| text.is_changed()) {
+            continue;
+        }
+        let name: Vec<&str> = texts
+            .iter()
+            .flat_map(|text| text.sections.iter())
+            .map(|section| section.value.trim())
+            .filter(|value| !value.is_empty())
+            .collect();
+        let mut node = NodeBuilder::new(Role::Button);
+        node.set_name(name.join(" "));
+        cmd.entity(entity).insert(AccessibilityNode(node));
+    }
+}
+
+/// A face-up card is read as its name, then what it costs and does. A face-down one is only a
+/// card.
+fn describe_cards(
+    mut cmd: Commands,
+    q_cards: Query<(Entity, Ref<Card>)>,
+    definitions: Res<CardDefinitions>,
+    loc: Localization,
+) {
+    for (entity, card) in q_cards.iter() {
+        if !card.is_changed() && !loc.is_changed() {
+            continue;
+        }
+        let definition = card.definition.as_ref().and_then(|id| definitions.get(id));
+        let mut node = NodeBuilder::new(Role::ListItem);
+        match definition.filter(|_| card.face_up) {
+            Some(definition) => {
+                node.set_name(loc.card_name(definition));
+                node.set_description(format!(
+                    "{} {}",
+                    loc.format("a11y.card_cost", &[("cost", card.cost.to_string())]),
+                    loc.card_text(definition)
+                ));
+            },
+            None => node.set_name(loc.get("a11y.card_hidden")),
+        }
+        cmd.entity(entity).insert(AccessibilityNode(node));
+    }
+}
+
+fn focus_card(mut focus: ResMut<Focus>, q_focused: Query<Entity, Added<Focused>>) {
+    if let Some(card) = q_focused.iter().last() {
+        focus.0 = Some(card);
+    }
+}
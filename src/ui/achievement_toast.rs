@@ -0,0 +1,122 @@
+//! A toast in the top right corner for each achievement unlocked. Toasts stack up under each other
+//! and fade out after a few seconds, wherever the game is.
+
+use bevy::prelude::*;
+
+use crate::{achievements::AchievementUnlocked, loading::FontAssets};
+
+/// Seconds a toast stays up, fade included.
+const TOAST_DURATION: f32 = 4.;
+const TOAST_FADE: f32 = 0.6;
+const TOAST_COLOR: Color = Color::rgba(0.12, 0.1, 0.2, 0.9);
+const TITLE_COLOR: Color = Color::rgb(1., 0.85, 0.35);
+
+#[derive(Component)]
+pub struct ToastStack;
+
+#[derive(Component, Deref, DerefMut)]
+pub struct AchievementToast(Timer);
+
+pub struct AchievementToastPlugin;
+impl Plugin for AchievementToastPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_toast_stack)
+            .add_systems(Update, (spawn_toasts, fade_toasts).chain());
+    }
+}
+
+fn spawn_toast_stack(mut cmd: Commands) {
+    cmd.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(20.),
+                right: Val::Px(20.),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(10.),
+                ..default()
+            },
+            z_index: ZIndex::Global(100),
+            ..default()
+        },
+        ToastStack,
+    ));
+}
+
+fn spawn_toasts(
+    mut cmd: Commands,
+    mut reader: EventReader<AchievementUnlocked>,
+    q_stack: Query<Entity, With<ToastStack>>,
+    fonts: Option<Res<FontAssets>>,
+) {
+    let (Ok(stack), Some(fonts)) = (q_stack.get_single(), fonts) else {
+        reader.clear();
+        return;
+    };
+    for unlocked in reader.read() {
+        let toast = cmd
+            .spawn((
+                NodeBundle {
+                    background_color: TOAST_COLOR.into(),
+                    style: Style {
+                        padding: UiRect::all(Val::Px(14.)),
+                        flex_direction: FlexDirection::Column,
+                        row_gap: Val::Px(4.),
+                        ..default()
+                    },
+                    ..default()
+                },
+                AchievementToast(Timer::from_seconds(TOAST_DURATION, TimerMode::Once)),
+            ))
+            .with_children(|parent| {
+                for (value, font_size, color) in [
+                    ("Achievement unlocked", 16., TITLE_COLOR),
+                    (unlocked.name.as_str(), 26., Color::WHITE),
+                    (unlocked.description.as_str(), 18., Color::GRAY),
+                ] {
+                    parent.spawn(TextBundle::from_section(
+                        value,
+                        TextStyle {
+                            font: fonts.fira.clone(),
+                            font_size,
+                            color,
+                        },
+                    ));
+                }
+            })
+            .id();
+        cmd.entity(stack).add_child(toast);
+    }
+}
+
+fn fade_toasts(
+    mut cmd: Commands,
+    time: Res<Time>,
+    mut q_toasts: Query<(
+        Entity,
+        &mut AchievementToast,
+        &mut BackgroundColor,
+        &Children,
+    )>,
+    mut q_text: Query<&mut Text>,
+) {
+    for (entity, mut toast, mut background, children) in q_toasts.iter_mut() {
+        if toast.tick(time.delta()).finished() {
+            cmd.entity(entity).despawn_recursive();
+            continue;
+        }
+        let left = toast.remaining_secs();
+        if left > TOAST_FADE {
+            continue;
+        }
+        let alpha = left / TOAST_FADE;
+        background.0.set_a(TOAST_COLOR.a() * alpha);
+        for &child in children.iter() {
+            if let Ok(mut text) = q_text.get_mut(child) {
+                for section in text.sections.iter_mut() {
+                    section.style.color.set_a(alpha);
+                }
+            }
+        }
+    }
+}
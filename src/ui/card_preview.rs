@@ -0,0 +1,104 @@
+//! A card drawn as UI, for screens that show cards outside of the table: its name, cost and
+//! rarity, and its rules text with keywords highlighted.
+
+use bevy::prelude::*;
+
+use crate::{
+    cards::{
+        definition::{CardDefinition, Rarity},
+        keyword::KeywordRegistry,
+    },
+    loading::FontAssets,
+    locale::Localization,
+};
+
+pub const PREVIEW_WIDTH: f32 = 180.;
+pub const PREVIEW_HEIGHT: f32 = 240.;
+
+const PREVIEW_COLOR: Color = Color::rgb(0.12, 0.12, 0.16);
+const COST_COLOR: Color = Color::rgb(0.2, 0.3, 0.8);
+
+pub fn rarity_color(rarity: Rarity) -> Color {
+    match rarity {
+        Rarity::Common => Color::GRAY,
+        Rarity::Uncommon => Color::rgb(0.1, 0.4, 0.8),
+        Rarity::Rare => Color::rgb(0.8, 0.5, 0.),
+    }
+}
+
+/// Spawns a preview of `definition` under `parent`, with `marker` on the preview's root node.
+pub fn spawn_card_preview(
+    parent: &mut ChildBuilder,
+    definition: &CardDefinition,
+    fonts: &FontAssets,
+    registry: &KeywordRegistry,
+    loc: &Localization,
+    marker: impl Bundle,
+) {
+    let style = |font_size: f32, color: Color| TextStyle {
+        font: fonts.fira.clone_weak(),
+        font_size,
+        color,
+    };
+    parent
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Px(PREVIEW_WIDTH),
+                    height: Val::Px(PREVIEW_HEIGHT),
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(6.),
+                    padding: UiRect::all(Val::Px(8.)),
+                    border: UiRect::all(Val::Px(3.)),
+                    ..default()
+                },
+                background_color: BackgroundColor(PREVIEW_COLOR),
+                border_color: BorderColor(rarity_color(definition.rarity)),
+                ..default()
+            },
+            marker,
+        ))
+        .with_children(|preview| {
+            preview
+                .spawn(NodeBundle {
+                    style: Style {
+                        justify_content: JustifyContent::SpaceBetween,
+                        align_items: AlignItems::Center,
+                        column_gap: Val::Px(6.),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|header| {
+                    header.spawn(TextBundle::from_section(
+                        loc.card_name(definition),
+                        style(18., Color::WHITE),
+                    ));
+                    header
+                        .spawn(NodeBundle {
+                            style: Style {
+                                min_width: Val::Px(26.),
+                                height: Val::Px(26.),
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                ..default()
+                            },
+                            background_color: BackgroundColor(COST_COLOR),
+                            ..default()
+                        })
+                        .with_children(|cost| {
+                            cost.spawn(TextBundle::from_section(
+                                definition.cost.to_string(),
+                                style(18., Color::WHITE),
+                            ));
+                        });
+                });
+            preview.spawn(TextBundle::from_section(
+                format!("{:?}", definition.rarity),
+                style(14., rarity_color(definition.rarity)),
+            ));
+            preview.spawn(TextBundle::from_sections(
+                registry.highlight(&loc.card_text(definition), &style(14., Color::WHITE)),
+            ));
+        });
+}
@@ -0,0 +1,169 @@
+use bevy::prelude::*;
+
+use super::{
+    card_preview::spawn_card_preview,
+    main_menu::{HOVER_BUTTON_COLOR, NORMAL_BUTTON_COLOR, PRESS_BUTTON_COLOR},
+};
+use crate::{
+    cards::{
+        collection::{reward_offers, Collection},
+        definition::{CardDefinitions, CardId},
+        keyword::KeywordRegistry,
+    },
+    loading::FontAssets,
+    locale::Localization,
+    rng::GameRng,
+    AppState,
+};
+
+#[derive(Component)]
+pub struct CardReward;
+
+/// Adds this card to the `Collection`.
+#[derive(Component)]
+pub struct RewardButton(CardId);
+
+#[derive(Component)]
+pub struct SkipRewardButton;
+
+pub struct CardRewardPlugin;
+impl Plugin for CardRewardPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::CardReward), spawn_card_reward)
+            .add_systems(
+                Update,
+                (press_reward, press_skip).run_if(in_state(AppState::CardReward)),
+            )
+            .add_systems(OnExit(AppState::CardReward), despawn_card_reward);
+    }
+}
+
+fn reward_button(
+    parent: &mut ChildBuilder,
+    marker: impl Bundle,
+    content: impl FnOnce(&mut ChildBuilder),
+) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    padding: UiRect::all(Val::Px(10.)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: BackgroundColor::from(NORMAL_BUTTON_COLOR),
+                ..default()
+            },
+            marker,
+        ))
+        .with_children(content);
+}
+
+pub fn spawn_card_reward(
+    mut cmd: Commands,
+    fonts: Res<FontAssets>,
+    collection: Res<Collection>,
+    mut definitions: ResMut<CardDefinitions>,
+    registry: Res<KeywordRegistry>,
+    loc: Localization,
+    mut rng: ResMut<GameRng>,
+) {
+    let offers = reward_offers(&mut definitions, &collection, &mut **rng);
+    let style = |font_size: f32, color: Color| TextStyle {
+        font: fonts.fira.clone_weak(),
+        font_size,
+        color,
+    };
+
+    cmd.spawn((
+        NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(40.),
+                ..default()
+            },
+            ..default()
+        },
+        CardReward,
+    ))
+    .with_children(|parent| {
+        parent.spawn(TextBundle::from_section(
+            "Level cleared! Pick a card",
+            style(64., Color::WHITE),
+        ));
+        parent
+            .spawn(NodeBundle {
+                style: Style {
+                    column_gap: Val::Px(40.),
+                    ..default()
+                },
+                ..default()
+            })
+            .with_children(|row| {
+                for id in offers {
+                    let Some(definition) = definitions.get(&id) else {
+                        continue;
+                    };
+                    reward_button(row, RewardButton(id), |button| {
+                        spawn_card_preview(button, definition, &fonts, &registry, &loc, ());
+                    });
+                }
+            });
+        reward_button(parent, SkipRewardButton, |button| {
+            button.spawn(TextBundle::from_section("Skip", style(32., Color::BLACK)));
+        });
+    });
+}
+pub fn despawn_card_reward(mut cmd: Commands, q_screen: Query<Entity, With<CardReward>>) {
+    for entity in q_screen.iter() {
+        cmd.entity(entity).despawn_recursive();
+    }
+}
+pub fn press_reward(
+    mut cmd: Commands,
+    mut q_button: Query<(&Interaction, &mut BackgroundColor, &RewardButton), Changed<Interaction>>,
+    mut collection: ResMut<Collection>,
+) {
+    for (interaction, mut color, button) in q_button.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = BackgroundColor::from(PRESS_BUTTON_COLOR);
+                collection.add(button.0.clone());
+                cmd.insert_resource(NextState(Some(AppState::DeckBuilder)));
+            },
+            Interaction::Hovered => {
+                *color = BackgroundColor::from(HOVER_BUTTON_COLOR);
+            },
+            Interaction::None => {
+                *color = BackgroundColor::from(NORMAL_BUTTON_COLOR);
+            },
+        }
+    }
+}
+pub fn press_skip(
+    mut cmd: Commands,
+    mut q_button: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<SkipRewardButton>),
+    >,
+) {
+    if let Ok((interaction, mut color)) = q_button.get_single_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = BackgroundColor::from(PRESS_BUTTON_COLOR);
+                cmd.insert_resource(NextState(Some(AppState::DeckBuilder)));
+            },
+            Interaction::Hovered => {
+                *color = BackgroundColor::from(HOVER_BUTTON_COLOR);
+            },
+            Interaction::None => {
+                *color = BackgroundColor::from(NORMAL_BUTTON_COLOR);
+            },
+        }
+    }
+}
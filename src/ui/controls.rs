@@ -0,0 +1,269 @@
+use bevy::prelude::*;
+use leafwing_input_manager::Actionlike;
+
+use super::main_menu::{HOVER_BUTTON_COLOR, NORMAL_BUTTON_COLOR, PRESS_BUTTON_COLOR};
+use crate::{
+    input::{binding_label, rebind_key, GameAction},
+    loading::FontAssets,
+    locale::{Localization, Localized},
+    settings::Settings,
+    AppState,
+};
+
+#[derive(Component)]
+pub struct ControlsScreen;
+
+/// Waits for the next key and binds it to this action.
+#[derive(Component)]
+pub struct RebindButton(GameAction);
+
+#[derive(Component)]
+pub struct RebindLabel(GameAction);
+
+#[derive(Component)]
+pub struct ResetControlsButton;
+
+#[derive(Component)]
+pub struct BackButton;
+
+/// The action waiting for a key, if any.
+#[derive(Resource, Default)]
+pub struct Rebinding(Option<GameAction>);
+
+pub struct ControlsPlugin;
+impl Plugin for ControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Rebinding>()
+            .add_systems(OnEnter(AppState::Controls), spawn_controls)
+            .add_systems(
+                Update,
+                (
+                    (press_rebind, press_reset, press_back, capture_key),
+                    update_rebind_labels,
+                )
+                    .chain()
+                    .run_if(in_state(AppState::Controls)),
+            )
+            .add_systems(OnExit(AppState::Controls), despawn_controls);
+    }
+}
+
+fn menu_button(parent: &mut ChildBuilder, fonts: &FontAssets, key: &str, marker: impl Bundle) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    width: Val::Px(400.),
+                    height: Val::Px(60.),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: BackgroundColor::from(NORMAL_BUTTON_COLOR),
+                ..default()
+            },
+            marker,
+        ))
+        .with_children(|button| {
+            button.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: fonts.fira.clone_weak(),
+                        font_size: 28.0,
+                        color: Color::BLACK,
+                    },
+                ),
+                Localized::new(key),
+            ));
+        });
+}
+
+fn rebind_text(
+    action: GameAction,
+    settings: &Settings,
+    rebinding: &Rebinding,
+    loc: &Localization,
+) -> String {
+    if rebinding.0 == Some(action) {
+        format!("{}: {}", action.label(), loc.get("controls.press_key"))
+    } else {
+        format!(
+            "{}: {}",
+            action.label(),
+            binding_label(&settings.input_map, action)
+        )
+    }
+}
+
+pub fn spawn_controls(
+    mut cmd: Commands,
+    fonts: Res<FontAssets>,
+    settings: Res<Settings>,
+    rebinding: Res<Rebinding>,
+    loc: Localization,
+) {
+    cmd.spawn((
+        NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(10.),
+                ..default()
+            },
+            ..default()
+        },
+        ControlsScreen,
+    ))
+    .with_children(|parent: &mut ChildBuilder| {
+        parent.spawn((
+            TextBundle::from_section(
+                "",
+                TextStyle {
+                    font: fonts.fira.clone(),
+                    font_size: 64.0,
+                    color: Color::WHITE,
+                },
+            ),
+            Localized::new("controls.title"),
+        ));
+        for action in GameAction::variants().filter(|action| action.rebindable()) {
+            parent
+                .spawn((
+                    ButtonBundle {
+                        style: Style {
+                            width: Val::Px(500.),
+                            height: Val::Px(44.),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        background_color: BackgroundColor::from(NORMAL_BUTTON_COLOR),
+                        ..default()
+                    },
+                    RebindButton(action),
+                ))
+                .with_children(|button| {
+                    button.spawn((
+                        TextBundle::from_section(
+                            rebind_text(action, &settings, &rebinding, &loc),
+                            TextStyle {
+                                font: fonts.fira.clone_weak(),
+                                font_size: 24.0,
+                                color: Color::BLACK,
+                            },
+                        ),
+                        RebindLabel(action),
+                    ));
+                });
+        }
+        menu_button(parent, &fonts, "controls.reset", ResetControlsButton);
+        menu_button(parent, &fonts, "controls.back", BackButton);
+    });
+}
+pub fn despawn_controls(
+    mut cmd: Commands,
+    q_screen: Query<Entity, With<ControlsScreen>>,
+    mut rebinding: ResMut<Rebinding>,
+) {
+    for entity in q_screen.iter() {
+        cmd.entity(entity).despawn_recursive();
+    }
+    rebinding.0 = None;
+}
+pub fn press_rebind(
+    mut q_button: Query<(&Interaction, &mut BackgroundColor, &RebindButton), Changed<Interaction>>,
+    mut rebinding: ResMut<Rebinding>,
+) {
+    for (interaction, mut color, button) in q_button.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = BackgroundColor::from(PRESS_BUTTON_COLOR);
+                rebinding.0 = Some(button.0);
+            },
+            Interaction::Hovered => {
+                *color = BackgroundColor::from(HOVER_BUTTON_COLOR);
+            },
+            Interaction::None => {
+                *color = BackgroundColor::from(NORMAL_BUTTON_COLOR);
+            },
+        }
+    }
+}
+/// Binds the first key pressed to the waiting action, in place of its other keys.
+pub fn capture_key(
+    keys: Res<Input<KeyCode>>,
+    mut rebinding: ResMut<Rebinding>,
+    mut settings: ResMut<Settings>,
+) {
+    let Some(action) = rebinding.0 else {
+        return;
+    };
+    let Some(&key) = keys.get_just_pressed().next() else {
+        return;
+    };
+    rebind_key(&mut settings.input_map, action, key);
+    rebinding.0 = None;
+}
+pub fn press_reset(
+    mut q_button: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<ResetControlsButton>),
+    >,
+    mut settings: ResMut<Settings>,
+    mut rebinding: ResMut<Rebinding>,
+) {
+    if let Ok((interaction, mut color)) = q_button.get_single_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = BackgroundColor::from(PRESS_BUTTON_COLOR);
+                settings.input_map = GameAction::default_input_map();
+                rebinding.0 = None;
+            },
+            Interaction::Hovered => {
+                *color = BackgroundColor::from(HOVER_BUTTON_COLOR);
+            },
+            Interaction::None => {
+                *color = BackgroundColor::from(NORMAL_BUTTON_COLOR);
+            },
+        }
+    }
+}
+pub fn press_back(
+    mut cmd: Commands,
+    mut q_button: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<BackButton>),
+    >,
+) {
+    if let Ok((interaction, mut color)) = q_button.get_single_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = BackgroundColor::from(PRESS_BUTTON_COLOR);
+                cmd.insert_resource(NextState(Some(AppState::Menu)));
+            },
+            Interaction::Hovered => {
+                *color = BackgroundColor::from(HOVER_BUTTON_COLOR);
+            },
+            Interaction::None => {
+                *color = BackgroundColor::from(NORMAL_BUTTON_COLOR);
+            },
+        }
+    }
+}
+fn update_rebind_labels(
+    settings: Res<Settings>,
+    rebinding: Res<Rebinding>,
+    loc: Localization,
+    mut q_labels: Query<(&mut Text, &RebindLabel)>,
+) {
+    if !settings.is_changed() && !rebinding.is_changed() && !loc.is_changed() {
+        return;
+    }
+    for (mut text, label) in q_labels.iter_mut() {
+        text.sections[0].value = rebind_text(label.0, &settings, &rebinding, &loc);
+    }
+}
@@ -0,0 +1,266 @@
+use bevy::prelude::*;
+
+use super::main_menu::{HOVER_BUTTON_COLOR, NORMAL_BUTTON_COLOR, PRESS_BUTTON_COLOR};
+use crate::{
+    cards::{
+        collection::{Collection, Decklist, MAX_COPIES, MAX_DECK_SIZE, MIN_DECK_SIZE},
+        definition::{CardDefinitions, CardId},
+    },
+    loading::FontAssets,
+    AppState,
+};
+
+const DISABLED_BUTTON_COLOR: Color = Color::rgb(0.4, 0.4, 0.4);
+
+#[derive(Component)]
+pub struct DeckBuilder;
+
+/// Puts a copy of the card in the `Decklist`, or takes one out.
+#[derive(Component)]
+pub enum DeckButton {
+    Add(CardId),
+    Remove(CardId),
+}
+
+/// The card's name and how many copies of it are in the deck.
+#[derive(Component)]
+pub struct CardCountText(CardId);
+
+#[derive(Component)]
+pub struct DeckSizeText;
+
+/// Moves on to the level once the deck is within the size limits.
+#[derive(Component)]
+pub struct DoneButton;
+
+pub struct DeckBuilderPlugin;
+impl Plugin for DeckBuilderPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::DeckBuilder), spawn_deck_builder)
+            .add_systems(
+                Update,
+                (press_deck_buttons, update_deck_texts, press_done)
+                    .chain()
+                    .run_if(in_state(AppState::DeckBuilder)),
+            )
+            .add_systems(OnExit(AppState::DeckBuilder), despawn_deck_builder);
+    }
+}
+
+fn text_style(fonts: &FontAssets, font_size: f32, color: Color) -> TextStyle {
+    TextStyle {
+        font: fonts.fira.clone_weak(),
+        font_size,
+        color,
+    }
+}
+
+fn small_button(parent: &mut ChildBuilder, fonts: &FontAssets, label: &str, marker: impl Bundle) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    width: Val::Px(40.),
+                    height: Val::Px(40.),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: BackgroundColor::from(NORMAL_BUTTON_COLOR),
+                ..default()
+            },
+            marker,
+        ))
+        .with_children(|button| {
+            button.spawn(TextBundle::from_section(
+                label,
+                text_style(fonts, 32., Color::BLACK),
+            ));
+        });
+}
+
+pub fn spawn_deck_builder(
+    mut cmd: Commands,
+    fonts: Res<FontAssets>,
+    collection: Res<Collection>,
+    definitions: Res<CardDefinitions>,
+    mut decklist: ResMut<Decklist>,
+) {
+    // NOTE: a first deck starts out playable, so "Done" works straight away
+    if decklist.0.is_empty() {
+        decklist.fill_from(&collection);
+    }
+
+    cmd.spawn((
+        NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(20.),
+                ..default()
+            },
+            ..default()
+        },
+        DeckBuilder,
+    ))
+    .with_children(|parent| {
+        parent.spawn(TextBundle::from_section(
+            "Build your deck",
+            text_style(&fonts, 64., Color::WHITE),
+        ));
+        parent.spawn((
+            TextBundle::from_section("", text_style(&fonts, 32., Color::WHITE)),
+            DeckSizeText,
+        ));
+        parent
+            .spawn(NodeBundle {
+                style: Style {
+                    width: Val::Percent(90.),
+                    flex_wrap: FlexWrap::Wrap,
+                    justify_content: JustifyContent::Center,
+                    column_gap: Val::Px(30.),
+                    row_gap: Val::Px(10.),
+                    ..default()
+                },
+                ..default()
+            })
+            .with_children(|grid| {
+                for id in collection.0.iter() {
+                    grid.spawn(NodeBundle {
+                        style: Style {
+                            width: Val::Px(420.),
+                            align_items: AlignItems::Center,
+                            column_gap: Val::Px(10.),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|row| {
+                        small_button(row, &fonts, "-", DeckButton::Remove(id.clone()));
+                        small_button(row, &fonts, "+", DeckButton::Add(id.clone()));
+                        let name = definitions.get(id).map_or(&id.0, |d| &d.name);
+                        row.spawn((
+                            TextBundle::from_sections([
+                                TextSection::new(
+                                    name.clone(),
+                                    text_style(&fonts, 20., Color::WHITE),
+                                ),
+                                TextSection::new("", text_style(&fonts, 20., Color::GOLD)),
+                            ]),
+                            CardCountText(id.clone()),
+                        ));
+                    });
+                }
+            });
+        parent
+            .spawn((
+                ButtonBundle {
+                    style: Style {
+                        width: Val::Px(400.),
+                        height: Val::Px(80.),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    background_color: BackgroundColor::from(NORMAL_BUTTON_COLOR),
+                    ..default()
+                },
+                DoneButton,
+            ))
+            .with_children(|button| {
+                button.spawn(TextBundle::from_section(
+                    "Done",
+                    text_style(&fonts, 32., Color::BLACK),
+                ));
+            });
+    });
+}
+pub fn despawn_deck_builder(mut cmd: Commands, q_screen: Query<Entity, With<DeckBuilder>>) {
+    for entity in q_screen.iter() {
+        cmd.entity(entity).despawn_recursive();
+    }
+}
+pub fn press_deck_buttons(
+    mut q_button: Query<(&Interaction, &mut BackgroundColor, &DeckButton), Changed<Interaction>>,
+    mut decklist: ResMut<Decklist>,
+) {
+    for (interaction, mut color, button) in q_button.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = BackgroundColor::from(PRESS_BUTTON_COLOR);
+                match button {
+                    DeckButton::Add(id) => decklist.add(id),
+                    DeckButton::Remove(id) => decklist.remove(id),
+                };
+            },
+            Interaction::Hovered => {
+                *color = BackgroundColor::from(HOVER_BUTTON_COLOR);
+            },
+            Interaction::None => {
+                *color = BackgroundColor::from(NORMAL_BUTTON_COLOR);
+            },
+        }
+    }
+}
+pub fn update_deck_texts(
+    decklist: Res<Decklist>,
+    mut q_counts: Query<(&mut Text, &CardCountText), Without<DeckSizeText>>,
+    mut q_size: Query<&mut Text, With<DeckSizeText>>,
+    mut q_done: Query<(&Interaction, &mut BackgroundColor), With<DoneButton>>,
+) {
+    // NOTE: the texts are spawned empty, so they're filled in on the first frame too
+    if !decklist.is_changed() && q_size.iter().all(|text| !text.sections[0].value.is_empty()) {
+        return;
+    }
+    for (mut text, CardCountText(id)) in q_counts.iter_mut() {
+        let count = decklist.count(id);
+        text.sections[1].value = if count > 0 {
+            format!("  x{count}/{MAX_COPIES}")
+        } else {
+            String::new()
+        };
+    }
+    for mut text in q_size.iter_mut() {
+        text.sections[0].value = format!(
+            "{} cards ({MIN_DECK_SIZE} to {MAX_DECK_SIZE})",
+            decklist.0.len()
+        );
+    }
+    for (interaction, mut color) in q_done.iter_mut() {
+        if !decklist.is_legal() {
+            *color = BackgroundColor::from(DISABLED_BUTTON_COLOR);
+        } else if *interaction == Interaction::None {
+            *color = BackgroundColor::from(NORMAL_BUTTON_COLOR);
+        }
+    }
+}
+pub fn press_done(
+    mut cmd: Commands,
+    decklist: Res<Decklist>,
+    mut q_button: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<DoneButton>),
+    >,
+) {
+    if let Ok((interaction, mut color)) = q_button.get_single_mut() {
+        if !decklist.is_legal() {
+            *color = BackgroundColor::from(DISABLED_BUTTON_COLOR);
+            return;
+        }
+        match *interaction {
+            Interaction::Pressed => {
+                *color = BackgroundColor::from(PRESS_BUTTON_COLOR);
+                cmd.insert_resource(NextState(Some(AppState::LevelSelect)));
+            },
+            Interaction::Hovered => {
+                *color = BackgroundColor::from(HOVER_BUTTON_COLOR);
+            },
+            Interaction::None => {
+                *color = BackgroundColor::from(NORMAL_BUTTON_COLOR);
+            },
+        }
+    }
+}
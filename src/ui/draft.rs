@@ -0,0 +1,142 @@
+use bevy::prelude::*;
+
+use super::main_menu::{HOVER_BUTTON_COLOR, NORMAL_BUTTON_COLOR, PRESS_BUTTON_COLOR};
+use crate::{
+    cards::{
+        draft::{offer_rules, DraftOffer, DraftRule},
+        opponent::opponent_turn,
+        zones::ActivePlayer,
+        GameState,
+    },
+    loading::FontAssets,
+};
+
+/// Covers the table while a rule is being drafted.
+#[derive(Component)]
+pub struct DraftScreen;
+
+/// Picks the offered rule at this index.
+#[derive(Component)]
+pub struct DraftButton(usize);
+
+pub struct DraftUIPlugin;
+impl Plugin for DraftUIPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(GameState::Draft),
+            spawn_draft_screen
+                .after(offer_rules)
+                .run_if(not(opponent_turn)),
+        )
+        .add_systems(
+            Update,
+            press_draft_button.run_if(in_state(GameState::Draft)),
+        )
+        .add_systems(OnExit(GameState::Draft), despawn_draft_screen);
+    }
+}
+
+pub fn spawn_draft_screen(
+    mut cmd: Commands,
+    fonts: Res<FontAssets>,
+    offer: Res<DraftOffer>,
+    active: Res<ActivePlayer>,
+) {
+    cmd.spawn((
+        NodeBundle {
+            background_color: Color::rgba(0., 0., 0., 0.6).into(),
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(40.),
+                ..default()
+            },
+            z_index: ZIndex::Global(10),
+            ..default()
+        },
+        DraftScreen,
+    ))
+    .with_children(|parent: &mut ChildBuilder| {
+        parent.spawn(TextBundle {
+            text: Text {
+                sections: vec![TextSection::new(
+                    format!("Player {}, draft a rule", active.0 .0 + 1),
+                    TextStyle {
+                        font: fonts.fira.clone(),
+                        font_size: 64.0,
+                        color: Color::WHITE,
+                    },
+                )],
+                alignment: TextAlignment::Center,
+                ..default()
+            },
+            ..default()
+        });
+        parent
+            .spawn(NodeBundle {
+                style: Style {
+                    column_gap: Val::Px(40.),
+                    ..default()
+                },
+                ..default()
+            })
+            .with_children(|row| {
+                for (i, operation) in offer.iter().enumerate() {
+                    row.spawn((
+                        ButtonBundle {
+                            style: Style {
+                                width: Val::Px(280.),
+                                height: Val::Px(120.),
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                ..default()
+                            },
+                            background_color: BackgroundColor::from(NORMAL_BUTTON_COLOR),
+                            ..default()
+                        },
+                        DraftButton(i),
+                    ))
+                    .with_children(|button| {
+                        button.spawn(TextBundle::from_section(
+                            format!("{operation:?}"),
+                            TextStyle {
+                                font: fonts.fira.clone_weak(),
+                                font_size: 28.0,
+                                color: Color::BLACK,
+                            },
+                        ));
+                    });
+                }
+            });
+    });
+}
+pub fn press_draft_button(
+    mut q_button: Query<(&Interaction, &mut BackgroundColor, &DraftButton), Changed<Interaction>>,
+    offer: Res<DraftOffer>,
+    mut draft: EventWriter<DraftRule>,
+) {
+    for (interaction, mut color, button) in q_button.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = BackgroundColor::from(PRESS_BUTTON_COLOR);
+                if let Some(rule) = offer.get(button.0) {
+                    draft.send(DraftRule { rule: rule.clone() });
+                }
+            },
+            Interaction::Hovered => {
+                *color = BackgroundColor::from(HOVER_BUTTON_COLOR);
+            },
+            Interaction::None => {
+                *color = BackgroundColor::from(NORMAL_BUTTON_COLOR);
+            },
+        }
+    }
+}
+pub fn despawn_draft_screen(mut cmd: Commands, q_screen: Query<Entity, With<DraftScreen>>) {
+    for entity in q_screen.iter() {
+        cmd.entity(entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,123 @@
+//! An arrow under the score pointing the way gravity drifts while it's shifted, with the seconds
+//! the shift has left. Hidden while gravity pulls toward the center as usual.
+
+use bevy::prelude::*;
+
+use crate::{
+    board::gravity::{BoardGravity, GravityChanged},
+    loading::{FontAssets, TextureAssets},
+    AppState, GameplayEntity,
+};
+
+const ARROW_SIZE: f32 = 40.;
+const SHIFTED_COLOR: Color = Color::rgb(0.75, 0.5, 1.);
+
+#[derive(Component)]
+pub struct GravityIndicator;
+
+#[derive(Component)]
+pub struct GravityArrow;
+
+#[derive(Component)]
+pub struct GravityText;
+
+pub struct GravityIndicatorPlugin;
+impl Plugin for GravityIndicatorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::Playing), spawn_gravity_indicator)
+            .add_systems(
+                Update,
+                (
+                    turn_gravity_arrow.run_if(on_event::<GravityChanged>()),
+                    update_gravity_text,
+                )
+                    .chain()
+                    .run_if(in_state(AppState::Playing)),
+            );
+    }
+}
+
+fn spawn_gravity_indicator(
+    mut cmd: Commands,
+    fonts: Res<FontAssets>,
+    textures: Res<TextureAssets>,
+) {
+    cmd.spawn((
+        NodeBundle {
+            style: Style {
+                top: Val::Px(80.),
+                right: Val::Px(30.),
+                position_type: PositionType::Absolute,
+                align_items: AlignItems::Center,
+                column_gap: Val::Px(10.),
+                ..default()
+            },
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        GravityIndicator,
+        GameplayEntity,
+    ))
+    .with_children(|parent| {
+        parent.spawn((
+            TextBundle::from_section(
+                "",
+                TextStyle {
+                    font: fonts.fira.clone(),
+                    font_size: 24.,
+                    color: SHIFTED_COLOR,
+                },
+            ),
+            GravityText,
+        ));
+        // NOTE: the symbol points right, and is turned to point along "down"
+        parent.spawn((
+            ImageBundle {
+                style: Style {
+                    width: Val::Px(ARROW_SIZE),
+                    height: Val::Px(ARROW_SIZE),
+                    ..default()
+                },
+                image: UiImage::new(textures.gt.clone()),
+                background_color: SHIFTED_COLOR.into(),
+                ..default()
+            },
+            GravityArrow,
+        ));
+    });
+}
+
+/// Shows the indicator while gravity is shifted, with the arrow along the drift. UI space points y
+/// down, so the drift's y is flipped.
+fn turn_gravity_arrow(
+    gravity: Res<BoardGravity>,
+    mut q_indicator: Query<&mut Visibility, With<GravityIndicator>>,
+    mut q_arrow: Query<&mut Transform, With<GravityArrow>>,
+) {
+    if let Ok(mut visibility) = q_indicator.get_single_mut() {
+        *visibility = if gravity.timer.is_some() {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+    if let Ok(mut transform) = q_arrow.get_single_mut() {
+        let down = gravity.down();
+        transform.rotation = Quat::from_rotation_z((-down.y).atan2(down.x));
+    }
+}
+
+fn update_gravity_text(
+    gravity: Res<BoardGravity>,
+    mut q_text: Query<&mut Text, With<GravityText>>,
+) {
+    if gravity.timer.is_none() {
+        return;
+    }
+    if let Ok(mut text) = q_text.get_single_mut() {
+        let label = format!("Gravity {:.0}s", gravity.remaining_secs().ceil());
+        if text.sections[0].value != label {
+            text.sections[0].value = label;
+        }
+    }
+}
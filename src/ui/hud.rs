@@ -1,34 +1,89 @@
+use std::time::Duration;
+
 use bevy::prelude::*;
+use bevy_tweening::{Animator, EaseFunction, Lens, Tween};
 
-use super::StartText;
+use super::{score_ui::CountUpLens, StartText};
 use crate::{
-    cards::GameTimer,
-    loading::{FontAssets, TextureAssets},
-    AppState,
+    board::RuleApplied,
+    cards::{
+        energy::{CardRejected, Energy},
+        opponent::Opponent,
+        rules::Rule,
+        zones::{ActivePlayer, GameMode},
+        GameTimer,
+    },
+    endless::Waves,
+    game_shapes::element::ElementReactions,
+    input::{binding_label, GameAction},
+    level::CurrentLevel,
+    loading::FontAssets,
+    score::ScoreChanged,
+    settings::Settings,
+    AppState, GameplayEntity,
 };
 pub const NORMAL_BUTTON_COLOR: Color = Color::rgb(1., 1., 1.);
 pub const HOVER_BUTTON_COLOR: Color = Color::rgb(0.9, 0.9, 0.9);
 pub const PRESS_BUTTON_COLOR: Color = Color::rgb(0.5, 0.5, 0.5);
 
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Component)]
 pub struct HUD;
 #[derive(Component)]
 pub struct TimerText;
+#[derive(Component)]
+pub struct ScoreText;
+/// Lists the operations in the `Rule`, newest first, one section each after the title.
+#[derive(Component)]
+pub struct RulesText;
+
+/// What the elements do when they meet, from the `ElementReactions`. Beside the rules.
+#[derive(Component)]
+pub struct ReactionsText;
+
+/// Whose turn it is, in two-player games. Empty when playing alone.
+#[derive(Component)]
+pub struct TurnText;
+
+/// Shows the `Energy` left this turn, and shakes when a card costs more.
+#[derive(Component)]
+pub struct EnergyText;
+
+const ENERGY_LEFT: f32 = 30.;
+/// Pixels the energy display moves either way when it shakes.
+const ENERGY_SHAKE: f32 = 12.;
+const OPPONENT_TURN_COLOR: Color = Color::rgb(1., 0.45, 0.35);
+const RULE_COLOR: Color = Color::WHITE;
+const APPLIED_RULE_COLOR: Color = Color::GOLD;
+/// Seconds a rule stays lit after a contact used it.
+const APPLIED_RULE_FLASH: f32 = 0.4;
 
 pub struct HUDPlugin;
 
 impl Plugin for HUDPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(AppState::Playing), (spawn_hud))
+        app.add_systems(OnEnter(AppState::Playing), spawn_hud)
             .add_systems(
                 Update,
-                (update_timer_text).run_if(in_state(AppState::Playing)),
-            )
-            .add_systems(OnExit(AppState::Playing), (despawn_hud));
+                (
+                    update_timer_text,
+                    update_score_text,
+                    update_turn_text,
+                    (update_rules_text, flash_applied_rule).chain(),
+                    update_reactions_text,
+                    (update_energy_text, shake_energy_text),
+                )
+                    .run_if(in_state(AppState::Playing)),
+            );
     }
 }
-pub fn spawn_hud(mut cmd: Commands, fonts: Res<FontAssets>) {
-    let hud = cmd
+pub fn spawn_hud(
+    mut cmd: Commands,
+    fonts: Res<FontAssets>,
+    settings: Res<Settings>,
+    level: Res<CurrentLevel>,
+) {
+    let _hud = cmd
         .spawn((
             NodeBundle {
                 style: Style {
@@ -43,6 +98,7 @@ pub fn spawn_hud(mut cmd: Commands, fonts: Res<FontAssets>) {
                 ..default()
             },
             HUD,
+            GameplayEntity,
         ))
         .with_children(|parent: &mut ChildBuilder| {
             //Title
@@ -83,6 +139,100 @@ pub fn spawn_hud(mut cmd: Commands, fonts: Res<FontAssets>) {
                 },
                 TimerText,
             ));
+            parent.spawn((
+                TextBundle {
+                    style: Style {
+                        top: Val::Px(30.),
+                        position_type: PositionType::Absolute,
+                        ..default()
+                    },
+                    text: Text::from_section(
+                        "",
+                        TextStyle {
+                            font: fonts.fira.clone(),
+                            font_size: 32.0,
+                            color: Color::WHITE,
+                        },
+                    ),
+                    ..default()
+                },
+                TurnText,
+            ));
+            parent.spawn((
+                TextBundle {
+                    style: Style {
+                        bottom: Val::Px(30.),
+                        left: Val::Px(ENERGY_LEFT),
+                        position_type: PositionType::Absolute,
+                        ..default()
+                    },
+                    text: Text::from_sections([
+                        TextSection::new(
+                            "Energy: ",
+                            TextStyle {
+                                font: fonts.fira.clone(),
+                                font_size: 32.0,
+                                color: Color::WHITE,
+                            },
+                        ),
+                        TextSection::new(
+                            "",
+                            TextStyle {
+                                font: fonts.fira.clone(),
+                                font_size: 32.0,
+                                color: Color::GOLD,
+                            },
+                        ),
+                    ]),
+                    ..default()
+                },
+                EnergyText,
+            ));
+            parent.spawn((
+                TextBundle {
+                    style: Style {
+                        top: Val::Px(30.),
+                        right: Val::Px(30.),
+                        position_type: PositionType::Absolute,
+
+                        ..default()
+                    },
+                    text: Text {
+                        sections: vec![
+                            TextSection::new(
+                                "Score: ",
+                                TextStyle {
+                                    font: fonts.fira.clone(),
+                                    font_size: 32.0,
+                                    color: Color::WHITE,
+                                },
+                            ),
+                            TextSection::new(
+                                "0",
+                                TextStyle {
+                                    font: fonts.fira.clone(),
+                                    font_size: 32.0,
+                                    color: Color::WHITE,
+                                },
+                            ),
+                            TextSection::new(
+                                format!(" / {} (level {})", level.target_score, level.number),
+                                TextStyle {
+                                    font: fonts.fira.clone(),
+                                    font_size: 32.0,
+                                    color: Color::WHITE,
+                                },
+                            ),
+                        ],
+                        alignment: TextAlignment::Center,
+
+                        ..default()
+                    },
+
+                    ..default()
+                },
+                ScoreText,
+            ));
             parent.spawn((TextBundle {
                 style: Style {
                     bottom: Val::Px(150.),
@@ -107,37 +257,73 @@ pub fn spawn_hud(mut cmd: Commands, fonts: Res<FontAssets>) {
 
                 ..default()
             },));
-            parent.spawn((TextBundle {
-                style: Style {
-                    bottom: Val::Px(150.),
-                    left: Val::Px(150.),
-                    position_type: PositionType::Absolute,
+            parent.spawn((
+                TextBundle {
+                    style: Style {
+                        bottom: Val::Px(150.),
+                        left: Val::Px(150.),
+                        position_type: PositionType::Absolute,
+
+                        ..default()
+                    },
+                    text: Text {
+                        sections: vec![
+                            TextSection::new(
+                                "Rules",
+                                TextStyle {
+                                    font: fonts.fira.clone(),
+                                    font_size: 32.0,
+                                    color: Color::WHITE,
+                                },
+                            ),
+                            TextSection::new(
+                                "",
+                                TextStyle {
+                                    font: fonts.fira.clone(),
+                                    font_size: 20.0,
+                                    color: Color::WHITE,
+                                },
+                            ),
+                        ],
+                        alignment: TextAlignment::Left,
+
+                        ..default()
+                    },
 
                     ..default()
                 },
-                text: Text {
-                    sections: vec![TextSection::new(
-                        "Rules",
+                RulesText,
+            ));
+            parent.spawn((
+                TextBundle {
+                    style: Style {
+                        bottom: Val::Px(150.),
+                        left: Val::Px(400.),
+                        position_type: PositionType::Absolute,
+                        ..default()
+                    },
+                    text: Text::from_section(
+                        "",
                         TextStyle {
                             font: fonts.fira.clone(),
-                            font_size: 32.0,
+                            font_size: 20.0,
                             color: Color::WHITE,
                         },
-                    )],
-                    alignment: TextAlignment::Center,
-
+                    ),
                     ..default()
                 },
-
-                ..default()
-            },));
+                ReactionsText,
+            ));
 
             parent.spawn((
                 TextBundle {
                     style: Style { ..default() },
                     text: Text {
                         sections: vec![TextSection::new(
-                            "Press Space to Start",
+                            format!(
+                                "Press {} to Start",
+                                binding_label(&settings.input_map, GameAction::Play)
+                            ),
                             TextStyle {
                                 font: fonts.fira.clone(),
                                 font_size: 32.0,
@@ -156,16 +342,199 @@ pub fn spawn_hud(mut cmd: Commands, fonts: Res<FontAssets>) {
         })
         .id();
 }
-pub fn despawn_hud(mut cmd: Commands, menu_q: Query<Entity, With<HUD>>) {
-    cmd.entity(menu_q.single()).despawn_recursive();
-}
+/// Counts down the time left, or in endless mode up the time survived, with the wave.
 pub fn update_timer_text(
-    mut cmd: Commands,
     game_timer: Res<GameTimer>,
-    mut q_text: Query<(&mut Text), (With<TimerText>)>,
+    mode: Res<GameMode>,
+    waves: Res<Waves>,
+    mut q_text: Query<&mut Text, With<TimerText>>,
 ) {
     if let Ok(mut text) = q_text.get_single_mut() {
-        text.sections[1].value =
-            (120 - game_timer.timer.elapsed_secs().round() as u32).to_string() + "s";
+        text.sections[1].value = if *mode == GameMode::Endless {
+            format!(
+                "{}s (wave {})",
+                game_timer.timer.elapsed_secs().floor(),
+                waves.number
+            )
+        } else {
+            format!("{}s", game_timer.timer.remaining_secs().round())
+        };
+    }
+}
+pub fn update_turn_text(
+    mode: Res<GameMode>,
+    active: Res<ActivePlayer>,
+    opponent: Option<Res<Opponent>>,
+    mut q_text: Query<&mut Text, With<TurnText>>,
+) {
+    let Ok(mut text) = q_text.get_single_mut() else {
+        return;
+    };
+    let (label, color) = match (*mode, opponent) {
+        (GameMode::Solo | GameMode::Endless, _) => (String::new(), Color::WHITE),
+        (_, Some(opponent)) if opponent.seat == active.0 => {
+            ("Opponent's turn".to_string(), OPPONENT_TURN_COLOR)
+        },
+        (_, Some(_)) => ("Your turn".to_string(), Color::WHITE),
+        (_, None) => (format!("Player {}'s turn", active.0 .0 + 1), Color::WHITE),
+    };
+    let section = &mut text.sections[0];
+    if section.value != label || section.style.color != color {
+        section.value = label;
+        section.style.color = color;
+    }
+}
+/// Counts the score up to its new total whenever points are awarded.
+pub fn update_score_text(
+    mut cmd: Commands,
+    mut reader: EventReader<ScoreChanged>,
+    q_text: Query<Entity, With<ScoreText>>,
+) {
+    let Some(changed) = reader.read().last() else {
+        return;
+    };
+    if let Ok(entity) = q_text.get_single() {
+        let tween = Tween::new(
+            EaseFunction::QuadraticOut,
+            Duration::from_millis(300),
+            CountUpLens {
+                start: changed.total - changed.delta,
+                end: changed.total,
+            },
+        );
+        cmd.entity(entity).insert(Animator::new(tween));
+    }
+}
+pub fn update_rules_text(
+    q_rule: Query<&Rule, Changed<Rule>>,
+    mut q_text: Query<&mut Text, With<RulesText>>,
+) {
+    let (Ok(rule), Ok(mut text)) = (q_rule.get_single(), q_text.get_single_mut()) else {
+        return;
+    };
+    let style = text.sections[1].style.clone();
+    text.sections.truncate(1);
+    text.sections
+        .extend(rule.iter().map(|operation| TextSection {
+            value: format!("\n{operation:?}"),
+            style: TextStyle {
+                color: RULE_COLOR,
+                ..style.clone()
+            },
+        }));
+    // NOTE: an empty section keeps the style around for the next rebuild
+    if rule.is_empty() {
+        text.sections.push(TextSection::new("", style));
+    }
+}
+/// Lists each reaction as its elements, in their colors, and what they make. Empty when shapes
+/// never spawn with an element.
+pub fn update_reactions_text(
+    reactions: Res<ElementReactions>,
+    mut q_text: Query<(&mut Text, Ref<ReactionsText>)>,
+) {
+    let Ok((mut text, marker)) = q_text.get_single_mut() else {
+        return;
+    };
+    if !marker.is_added() && !reactions.is_changed() {
+        return;
+    }
+    let style = text.sections[0].style.clone();
+    let mut pairs: Vec<_> = reactions.reactions.iter().collect();
+    // NOTE: the table is a map, so sort it to keep the lines in a stable order
+    pairs.sort_by_key(|((first, second), _)| (*first as u8, *second as u8));
+    text.sections = if reactions.element_chance > 0. && !pairs.is_empty() {
+        std::iter::once(("Reactions".to_string(), RULE_COLOR))
+            .chain(pairs.into_iter().flat_map(|(&(first, second), reaction)| {
+                [
+                    (format!("\n{}", first.name()), first.color()),
+                    (" + ".to_string(), RULE_COLOR),
+                    (second.name().to_string(), second.color()),
+                    (format!(": {}", reaction.name()), RULE_COLOR),
+                ]
+            }))
+            .map(|(value, color)| TextSection {
+                value,
+                style: TextStyle {
+                    color,
+                    ..style.clone()
+                },
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+    // NOTE: an empty section keeps the style around for the next rebuild
+    if text.sections.is_empty() {
+        text.sections.push(TextSection::new("", style));
+    }
+}
+/// Lights up the rules contacts just used, then lets them fade back.
+pub fn flash_applied_rule(
+    time: Res<Time>,
+    mut reader: EventReader<RuleApplied>,
+    mut q_text: Query<&mut Text, With<RulesText>>,
+    mut flash: Local<Timer>,
+) {
+    let Ok(mut text) = q_text.get_single_mut() else {
+        reader.clear();
+        return;
+    };
+    let mut lit = false;
+    for applied in reader.read() {
+        if let Some(section) = text.sections.get_mut(1 + applied.rule_index) {
+            section.style.color = APPLIED_RULE_COLOR;
+            lit = true;
+        }
+    }
+    if lit {
+        *flash = Timer::from_seconds(APPLIED_RULE_FLASH, TimerMode::Once);
+    } else if flash.tick(time.delta()).just_finished() {
+        for section in text.sections.iter_mut().skip(1) {
+            section.style.color = RULE_COLOR;
+        }
+    }
+}
+pub fn update_energy_text(energy: Res<Energy>, mut q_text: Query<&mut Text, With<EnergyText>>) {
+    if let Ok(mut text) = q_text.get_single_mut() {
+        // NOTE: the text is spawned after the energy last changed, so check every frame
+        let value = format!("{}/{}", energy.current, energy.max);
+        if text.sections[1].value != value {
+            text.sections[1].value = value;
+        }
+    }
+}
+
+/// Rocks the energy display side to side, dying down.
+struct ShakeLens {
+    left: f32,
+    amplitude: f32,
+}
+
+impl Lens<Style> for ShakeLens {
+    fn lerp(&mut self, target: &mut Style, ratio: f32) {
+        let offset = (ratio * 4. * std::f32::consts::TAU).sin() * self.amplitude * (1. - ratio);
+        target.left = Val::Px(self.left + offset);
+    }
+}
+
+pub fn shake_energy_text(
+    mut cmd: Commands,
+    mut reader: EventReader<CardRejected>,
+    q_text: Query<Entity, With<EnergyText>>,
+) {
+    if reader.read().last().is_none() {
+        return;
+    }
+    if let Ok(entity) = q_text.get_single() {
+        let tween = Tween::new(
+            EaseFunction::QuadraticOut,
+            Duration::from_millis(400),
+            ShakeLens {
+                left: ENERGY_LEFT,
+                amplitude: ENERGY_SHAKE,
+            },
+        );
+        cmd.entity(entity).insert(Animator::new(tween));
     }
 }
@@ -0,0 +1,212 @@
+//! Clicking a shape on the board outlines it and shows what it is in a corner panel: its polygon
+//! and color, how fast it's going and the rules it's part of. Clicking empty board, or `Cancel`,
+//! lets it go. Cards take the click first, so a shape under the hand can't be picked.
+
+use bevy::{prelude::*, sprite::Mesh2dHandle};
+use bevy_xpbd_2d::prelude::{LinearVelocity, SpatialQuery, SpatialQueryFilter};
+use leafwing_input_manager::prelude::ActionState;
+
+use super::PileBrowserOpen;
+use crate::{
+    board::{IsOnBoard, Layer},
+    cards::{
+        hand::{select_card, Hand},
+        rules::Rule,
+    },
+    game_shapes::{get_polygon_mesh, Shape, ShapeAssets},
+    input::GameAction,
+    loading::FontAssets,
+    operation::Operation,
+    pointer::Pointer,
+    AppState, GameplayEntity,
+};
+
+const OUTLINE_COLOR: Color = Color::WHITE;
+/// The outline's size relative to the shape.
+const OUTLINE_SCALE: f32 = 1.25;
+
+/// The shape the panel describes. At most one shape has it.
+#[derive(Component)]
+pub struct Inspected;
+
+/// Drawn behind the inspected shape, as its child.
+#[derive(Component)]
+pub struct InspectOutline;
+
+#[derive(Component)]
+pub struct InspectPanel;
+
+#[derive(Component)]
+pub struct InspectText;
+
+#[derive(Resource)]
+struct OutlineMaterial(Handle<ColorMaterial>);
+
+pub struct InspectPlugin;
+impl Plugin for InspectPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, add_outline_material)
+            .add_systems(OnEnter(AppState::Playing), spawn_inspect_panel)
+            .add_systems(
+                Update,
+                (
+                    pick_shape
+                        .after(select_card)
+                        .run_if(not(resource_exists::<PileBrowserOpen>())),
+                    update_inspect_panel,
+                )
+                    .chain()
+                    .run_if(in_state(AppState::Playing)),
+            );
+    }
+}
+
+fn add_outline_material(mut cmd: Commands, mut materials: ResMut<Assets<ColorMaterial>>) {
+    cmd.insert_resource(OutlineMaterial(
+        materials.add(ColorMaterial::from(OUTLINE_COLOR)),
+    ));
+}
+
+fn spawn_inspect_panel(mut cmd: Commands, fonts: Res<FontAssets>) {
+    cmd.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                left: Val::Px(20.),
+                bottom: Val::Px(20.),
+                padding: UiRect::all(Val::Px(10.)),
+                ..default()
+            },
+            background_color: BackgroundColor(Color::rgba(0., 0., 0., 0.7)),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        InspectPanel,
+        GameplayEntity,
+    ))
+    .with_children(|panel| {
+        panel.spawn((
+            TextBundle::from_section(
+                "",
+                TextStyle {
+                    font: fonts.fira.clone(),
+                    font_size: 20.,
+                    color: Color::WHITE,
+                },
+            ),
+            InspectText,
+        ));
+    });
+}
+
+fn pick_shape(
+    mut cmd: Commands,
+    actions: Res<ActionState<GameAction>>,
+    pointer: Res<Pointer>,
+    spatial: SpatialQuery,
+    q_hands: Query<&Hand>,
+    q_shapes: Query<&Shape, With<IsOnBoard>>,
+    q_inspected: Query<Entity, With<Inspected>>,
+    q_outlines: Query<Entity, With<InspectOutline>>,
+    shape_assets: Res<ShapeAssets>,
+    outline: Res<OutlineMaterial>,
+) {
+    let clicked = actions.just_pressed(GameAction::Select);
+    if !clicked && !actions.just_pressed(GameAction::Cancel) {
+        return;
+    }
+    if q_hands
+        .iter()
+        .any(|hand| hand.selected.is_some() || hand.hovered.is_some())
+    {
+        return;
+    }
+    let picked = pointer.over_board().filter(|_| clicked).and_then(|pos| {
+        let filter = SpatialQueryFilter::new().with_masks([Layer::Shape]);
+        spatial
+            .point_intersections(pos, filter)
+            .into_iter()
+            .find_map(|entity| Some((entity, q_shapes.get(entity).ok()?)))
+    });
+
+    for entity in q_inspected.iter() {
+        cmd.entity(entity).remove::<Inspected>();
+    }
+    for entity in q_outlines.iter() {
+        cmd.entity(entity).despawn_recursive();
+    }
+    let Some((entity, shape)) = picked else {
+        return;
+    };
+    cmd.entity(entity)
+        .insert(Inspected)
+        .with_children(|parent| {
+            parent.spawn((
+                ColorMesh2dBundle {
+                    mesh: get_polygon_mesh(&shape.polygon, &shape_assets).into(),
+                    material: outline.0.clone(),
+                    transform: Transform::from_xyz(0., 0., -0.5).with_scale(Vec3::new(
+                        OUTLINE_SCALE,
+                        OUTLINE_SCALE,
+                        1.,
+                    )),
+                    ..default()
+                },
+                InspectOutline,
+            ));
+        });
+}
+
+/// Whether `operation` does anything when `shape` touches something.
+fn applies_to(operation: &Operation, shape: &Shape) -> bool {
+    match operation {
+        Operation::Add(s1, s2) | Operation::Sub(s1, s2) => s1 == shape || s2 == shape,
+        Operation::Mul(s, _) | Operation::Div(s) => s == shape,
+        _ => false,
+    }
+}
+
+fn update_inspect_panel(
+    q_inspected: Query<(&Shape, &LinearVelocity), (With<Inspected>, With<IsOnBoard>)>,
+    mut q_outlines: Query<(&Parent, &mut Mesh2dHandle), With<InspectOutline>>,
+    q_rule: Query<&Rule>,
+    shape_assets: Res<ShapeAssets>,
+    mut q_panel: Query<&mut Visibility, With<InspectPanel>>,
+    mut q_text: Query<&mut Text, With<InspectText>>,
+) {
+    let (Ok(mut visibility), Ok(mut text)) = (q_panel.get_single_mut(), q_text.get_single_mut())
+    else {
+        return;
+    };
+    // NOTE: a shape that left the board, or combined away, just hides the panel
+    let Ok((shape, velocity)) = q_inspected.get_single() else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+    *visibility = Visibility::Inherited;
+
+    // NOTE: transmutes change the polygon under the outline
+    for (parent, mut mesh) in q_outlines.iter_mut() {
+        if q_inspected.contains(parent.get()) {
+            let polygon_mesh = get_polygon_mesh(&shape.polygon, &shape_assets);
+            if mesh.0 != polygon_mesh {
+                mesh.0 = polygon_mesh;
+            }
+        }
+    }
+
+    let rules: String = q_rule
+        .iter()
+        .flat_map(|rule| rule.iter())
+        .filter(|operation| applies_to(operation, shape))
+        .map(|operation| format!("\n{operation:?}"))
+        .collect();
+    text.sections[0].value = format!(
+        "{:?} {:?}\nVelocity: ({:.0}, {:.0})\nRules:{}",
+        shape.color,
+        shape.polygon,
+        velocity.x,
+        velocity.y,
+        if rules.is_empty() { " none" } else { &rules },
+    );
+}
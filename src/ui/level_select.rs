@@ -0,0 +1,149 @@
+use bevy::prelude::*;
+
+use super::main_menu::{HOVER_BUTTON_COLOR, NORMAL_BUTTON_COLOR, PRESS_BUTTON_COLOR};
+use crate::{
+    board::layout::{BoardLayout, SelectedLayout},
+    level::CurrentLevel,
+    loading::{FontAssets, LayoutAssets},
+    AppState,
+};
+
+#[derive(Component)]
+pub struct LevelSelect;
+
+/// Starts the run on the layout with this name.
+#[derive(Component)]
+pub struct LevelButton(String);
+
+#[derive(Component)]
+pub struct BackButton;
+
+pub struct LevelSelectPlugin;
+impl Plugin for LevelSelectPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::LevelSelect), spawn_level_select)
+            .add_systems(
+                Update,
+                (press_level, press_back).run_if(in_state(AppState::LevelSelect)),
+            )
+            .add_systems(OnExit(AppState::LevelSelect), despawn_level_select);
+    }
+}
+
+fn menu_button(parent: &mut ChildBuilder, fonts: &FontAssets, label: &str, marker: impl Bundle) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    width: Val::Px(400.),
+                    height: Val::Px(80.),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: BackgroundColor::from(NORMAL_BUTTON_COLOR),
+                ..default()
+            },
+            marker,
+        ))
+        .with_children(|button| {
+            button.spawn(TextBundle::from_section(
+                label,
+                TextStyle {
+                    font: fonts.fira.clone_weak(),
+                    font_size: 32.0,
+                    color: Color::BLACK,
+                },
+            ));
+        });
+}
+
+pub fn spawn_level_select(
+    mut cmd: Commands,
+    fonts: Res<FontAssets>,
+    handles: Res<LayoutAssets>,
+    layouts: Res<Assets<BoardLayout>>,
+    level: Res<CurrentLevel>,
+) {
+    cmd.spawn((
+        NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(20.),
+                ..default()
+            },
+            ..default()
+        },
+        LevelSelect,
+    ))
+    .with_children(|parent: &mut ChildBuilder| {
+        parent.spawn(TextBundle::from_section(
+            format!("Level {}: pick a board", level.number),
+            TextStyle {
+                font: fonts.fira.clone(),
+                font_size: 64.0,
+                color: Color::WHITE,
+            },
+        ));
+        for layout in handles.layouts.iter().filter_map(|h| layouts.get(h)) {
+            menu_button(
+                parent,
+                &fonts,
+                &layout.name,
+                LevelButton(layout.name.clone()),
+            );
+        }
+        menu_button(parent, &fonts, "Back", BackButton);
+    });
+}
+pub fn despawn_level_select(mut cmd: Commands, q_screen: Query<Entity, With<LevelSelect>>) {
+    for entity in q_screen.iter() {
+        cmd.entity(entity).despawn_recursive();
+    }
+}
+pub fn press_level(
+    mut cmd: Commands,
+    mut q_button: Query<(&Interaction, &mut BackgroundColor, &LevelButton), Changed<Interaction>>,
+) {
+    for (interaction, mut color, button) in q_button.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = BackgroundColor::from(PRESS_BUTTON_COLOR);
+                cmd.insert_resource(SelectedLayout(button.0.clone()));
+                cmd.insert_resource(NextState(Some(AppState::Playing)));
+            },
+            Interaction::Hovered => {
+                *color = BackgroundColor::from(HOVER_BUTTON_COLOR);
+            },
+            Interaction::None => {
+                *color = BackgroundColor::from(NORMAL_BUTTON_COLOR);
+            },
+        }
+    }
+}
+pub fn press_back(
+    mut cmd: Commands,
+    mut q_button: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<BackButton>),
+    >,
+) {
+    if let Ok((interaction, mut color)) = q_button.get_single_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = BackgroundColor::from(PRESS_BUTTON_COLOR);
+                cmd.insert_resource(NextState(Some(AppState::Menu)));
+            },
+            Interaction::Hovered => {
+                *color = BackgroundColor::from(HOVER_BUTTON_COLOR);
+            },
+            Interaction::None => {
+                *color = BackgroundColor::from(NORMAL_BUTTON_COLOR);
+            },
+        }
+    }
+}
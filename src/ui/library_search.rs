@@ -0,0 +1,337 @@
+//! The screen for a scry or a tutor, over the table while the played card resolves. A scry shows
+//! the top of the library left to right, top first, with buttons to move each card along or send
+//! it under the library. A tutor shows every matching card; clicking one takes it.
+
+use bevy::prelude::*;
+
+use super::{
+    card_preview::spawn_card_preview,
+    main_menu::{HOVER_BUTTON_COLOR, NORMAL_BUTTON_COLOR, PRESS_BUTTON_COLOR},
+};
+use crate::{
+    cards::{
+        card::Card,
+        definition::CardDefinitions,
+        keyword::KeywordRegistry,
+        search::{LibrarySearch, ScryChoice, SearchKind, TutorChoice},
+    },
+    loading::FontAssets,
+    locale::Localization,
+    AppState, GameplayEntity,
+};
+
+const BOTTOM_COLOR: Color = Color::rgba(0.7, 0.2, 0.2, 0.6);
+
+#[derive(Component)]
+pub struct LibrarySearchScreen;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ScryAction {
+    /// One place towards the top.
+    Earlier,
+    /// One place towards the bottom.
+    Later,
+    ToggleBottom,
+}
+
+#[derive(Component)]
+pub struct ScryButton {
+    card: Entity,
+    action: ScryAction,
+}
+
+#[derive(Component)]
+pub struct TutorButton {
+    card: Entity,
+}
+
+/// Ends the search: confirms a scry, or takes nothing from a tutor.
+#[derive(Component)]
+pub struct ConfirmSearchButton;
+
+pub struct LibrarySearchPlugin;
+impl Plugin for LibrarySearchPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                (
+                    press_scry_buttons,
+                    press_tutor_buttons,
+                    press_confirm_search,
+                )
+                    .run_if(resource_exists::<LibrarySearch>()),
+                show_library_search.run_if(resource_exists_and_changed::<LibrarySearch>()),
+                despawn_library_search.run_if(resource_removed::<LibrarySearch>()),
+            )
+                .chain()
+                .run_if(in_state(AppState::Playing)),
+        );
+    }
+}
+
+fn button(
+    parent: &mut ChildBuilder,
+    marker: impl Bundle,
+    width: f32,
+    label: &str,
+    font: &Handle<Font>,
+) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    width: Val::Px(width),
+                    height: Val::Px(40.),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: BackgroundColor::from(NORMAL_BUTTON_COLOR),
+                ..default()
+            },
+            marker,
+        ))
+        .with_children(|button| {
+            button.spawn(TextBundle::from_section(
+                label,
+                TextStyle {
+                    font: font.clone_weak(),
+                    font_size: 22.,
+                    color: Color::BLACK,
+                },
+            ));
+        });
+}
+
+/// Respawns the screen to match the search, or leaves it gone once the choice is made.
+fn show_library_search(
+    mut cmd: Commands,
+    search: Res<LibrarySearch>,
+    q_screen: Query<Entity, With<LibrarySearchScreen>>,
+    q_cards: Query<&Card>,
+    definitions: Res<CardDefinitions>,
+    registry: Res<KeywordRegistry>,
+    loc: Localization,
+    fonts: Res<FontAssets>,
+) {
+    for entity in q_screen.iter() {
+        cmd.entity(entity).despawn_recursive();
+    }
+    if search.chosen() {
+        return;
+    }
+    let (title, confirm) = match &search.kind {
+        SearchKind::Scry => (
+            "Scry: the leftmost card goes back on top".to_string(),
+            "Done",
+        ),
+        SearchKind::Tutor(_) if search.cards.is_empty() => (
+            "Tutor: no card in your library matches".to_string(),
+            "Close",
+        ),
+        SearchKind::Tutor(filter) => (
+            format!("Tutor: choose {} to put in your hand", filter.describe()),
+            "Take nothing",
+        ),
+    };
+    let font = &fonts.fira;
+
+    cmd.spawn((
+        NodeBundle {
+            background_color: Color::rgba(0., 0., 0., 0.8).into(),
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(20.),
+                ..default()
+            },
+            z_index: ZIndex::Global(20),
+            ..default()
+        },
+        LibrarySearchScreen,
+        GameplayEntity,
+    ))
+    .with_children(|parent| {
+        parent.spawn(TextBundle::from_section(
+            title,
+            TextStyle {
+                font: font.clone_weak(),
+                font_size: 40.,
+                color: Color::WHITE,
+            },
+        ));
+        parent
+            .spawn(NodeBundle {
+                style: Style {
+                    width: Val::Percent(80.),
+                    flex_wrap: FlexWrap::Wrap,
+                    justify_content: JustifyContent::Center,
+                    column_gap: Val::Px(16.),
+                    row_gap: Val::Px(16.),
+                    ..default()
+                },
+                ..default()
+            })
+            .with_children(|row| {
+                for &card in search.cards.iter() {
+                    let Some(definition) = q_cards
+                        .get(card)
+                        .ok()
+                        .and_then(|card| definitions.get(card.definition.as_ref()?))
+                    else {
+                        continue;
+                    };
+                    if search.kind != SearchKind::Scry {
+                        row.spawn((
+                            ButtonBundle {
+                                style: Style {
+                                    padding: UiRect::all(Val::Px(10.)),
+                                    ..default()
+                                },
+                                background_color: BackgroundColor::from(NORMAL_BUTTON_COLOR),
+                                ..default()
+                            },
+                            TutorButton { card },
+                        ))
+                        .with_children(|button| {
+                            spawn_card_preview(button, definition, &fonts, &registry, &loc, ());
+                        });
+                        continue;
+                    }
+                    let bottom = search.bottom.contains(&card);
+                    row.spawn(NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Column,
+                            align_items: AlignItems::Center,
+                            row_gap: Val::Px(8.),
+                            padding: UiRect::all(Val::Px(8.)),
+                            ..default()
+                        },
+                        background_color: if bottom {
+                            BackgroundColor(BOTTOM_COLOR)
+                        } else {
+                            BackgroundColor(Color::NONE)
+                        },
+                        ..default()
+                    })
+                    .with_children(|column| {
+                        spawn_card_preview(column, definition, &fonts, &registry, &loc, ());
+                        column
+                            .spawn(NodeBundle {
+                                style: Style {
+                                    column_gap: Val::Px(6.),
+                                    ..default()
+                                },
+                                ..default()
+                            })
+                            .with_children(|buttons| {
+                                let actions = [
+                                    (ScryAction::Earlier, 40., "<"),
+                                    (
+                                        ScryAction::ToggleBottom,
+                                        100.,
+                                        if bottom { "Top" } else { "Bottom" },
+                                    ),
+                                    (ScryAction::Later, 40., ">"),
+                                ];
+                                for (action, width, label) in actions {
+                                    button(
+                                        buttons,
+                                        ScryButton { card, action },
+                                        width,
+                                        label,
+                                        font,
+                                    );
+                                }
+                            });
+                    });
+                }
+            });
+        button(parent, ConfirmSearchButton, 220., confirm, font);
+    });
+}
+
+fn despawn_library_search(mut cmd: Commands, q_screen: Query<Entity, With<LibrarySearchScreen>>) {
+    for entity in q_screen.iter() {
+        cmd.entity(entity).despawn_recursive();
+    }
+}
+
+fn press_scry_buttons(
+    mut q_buttons: Query<(&Interaction, &mut BackgroundColor, &ScryButton), Changed<Interaction>>,
+    mut search: ResMut<LibrarySearch>,
+) {
+    for (interaction, mut color, button) in q_buttons.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = BackgroundColor::from(PRESS_BUTTON_COLOR);
+                match button.action {
+                    ScryAction::Earlier => search.move_card(button.card, -1),
+                    ScryAction::Later => search.move_card(button.card, 1),
+                    ScryAction::ToggleBottom => search.toggle_bottom(button.card),
+                }
+            },
+            Interaction::Hovered => {
+                *color = BackgroundColor::from(HOVER_BUTTON_COLOR);
+            },
+            Interaction::None => {
+                *color = BackgroundColor::from(NORMAL_BUTTON_COLOR);
+            },
+        }
+    }
+}
+
+fn press_tutor_buttons(
+    mut q_buttons: Query<(&Interaction, &mut BackgroundColor, &TutorButton), Changed<Interaction>>,
+    mut choice: EventWriter<TutorChoice>,
+) {
+    for (interaction, mut color, button) in q_buttons.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = BackgroundColor::from(PRESS_BUTTON_COLOR);
+                choice.send(TutorChoice {
+                    card: Some(button.card),
+                });
+            },
+            Interaction::Hovered => {
+                *color = BackgroundColor::from(HOVER_BUTTON_COLOR);
+            },
+            Interaction::None => {
+                *color = BackgroundColor::from(NORMAL_BUTTON_COLOR);
+            },
+        }
+    }
+}
+
+fn press_confirm_search(
+    mut q_button: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<ConfirmSearchButton>),
+    >,
+    search: Res<LibrarySearch>,
+    mut scry: EventWriter<ScryChoice>,
+    mut tutor: EventWriter<TutorChoice>,
+) {
+    if let Ok((interaction, mut color)) = q_button.get_single_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = BackgroundColor::from(PRESS_BUTTON_COLOR);
+                match search.kind {
+                    SearchKind::Scry => scry.send(search.scry_choice()),
+                    SearchKind::Tutor(_) => tutor.send(TutorChoice { card: None }),
+                }
+            },
+            Interaction::Hovered => {
+                *color = BackgroundColor::from(HOVER_BUTTON_COLOR);
+            },
+            Interaction::None => {
+                *color = BackgroundColor::from(NORMAL_BUTTON_COLOR);
+            },
+        }
+    }
+}
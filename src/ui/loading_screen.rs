@@ -0,0 +1,168 @@
+//! The loading screen, with a bar and a count for each group of assets, and the error screen
+//! listing the assets that failed to load. Both use the default font, since the game's own may
+//! be one of them.
+
+use bevy::prelude::*;
+
+use crate::{loading::LoadingProgress, AppState};
+
+const SCREEN_COLOR: Color = Color::rgb(0.08, 0.07, 0.12);
+const BAR_WIDTH: f32 = 600.;
+const BAR_COLOR: Color = Color::rgb(0.25, 0.22, 0.35);
+const FILL_COLOR: Color = Color::rgb(0.6, 0.5, 1.);
+const ERROR_COLOR: Color = Color::rgb(1., 0.45, 0.4);
+
+#[derive(Component)]
+pub struct LoadingScreen;
+
+#[derive(Component)]
+pub struct LoadingBarFill;
+
+#[derive(Component)]
+pub struct LoadingCounts;
+
+#[derive(Component)]
+pub struct LoadFailedScreen;
+
+#[derive(Component)]
+pub struct FailedList;
+
+pub struct LoadingScreenPlugin;
+impl Plugin for LoadingScreenPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::Loading), spawn_loading_screen)
+            .add_systems(
+                Update,
+                update_loading_screen.run_if(in_state(AppState::Loading)),
+            )
+            .add_systems(OnExit(AppState::Loading), despawn_loading_screen)
+            .add_systems(OnEnter(AppState::LoadFailed), spawn_load_failed_screen)
+            .add_systems(
+                Update,
+                update_failed_list.run_if(in_state(AppState::LoadFailed)),
+            );
+    }
+}
+
+fn full_screen() -> NodeBundle {
+    NodeBundle {
+        background_color: SCREEN_COLOR.into(),
+        style: Style {
+            width: Val::Percent(100.),
+            height: Val::Percent(100.),
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(24.),
+            ..default()
+        },
+        ..default()
+    }
+}
+
+fn text(value: impl Into<String>, font_size: f32, color: Color) -> TextBundle {
+    TextBundle::from_section(
+        value,
+        TextStyle {
+            font_size,
+            color,
+            ..default()
+        },
+    )
+    .with_text_alignment(TextAlignment::Center)
+}
+
+fn spawn_loading_screen(mut cmd: Commands) {
+    cmd.spawn((full_screen(), LoadingScreen))
+        .with_children(|parent| {
+            parent.spawn(text("Loading", 60., Color::WHITE));
+            parent
+                .spawn(NodeBundle {
+                    background_color: BAR_COLOR.into(),
+                    style: Style {
+                        width: Val::Px(BAR_WIDTH),
+                        height: Val::Px(24.),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn((
+                        NodeBundle {
+                            background_color: FILL_COLOR.into(),
+                            style: Style {
+                                width: Val::Percent(0.),
+                                height: Val::Percent(100.),
+                                ..default()
+                            },
+                            ..default()
+                        },
+                        LoadingBarFill,
+                    ));
+                });
+            parent.spawn((text("", 24., Color::GRAY), LoadingCounts));
+        });
+}
+
+fn update_loading_screen(
+    progress: Res<LoadingProgress>,
+    mut q_fill: Query<&mut Style, With<LoadingBarFill>>,
+    mut q_counts: Query<&mut Text, With<LoadingCounts>>,
+) {
+    if !progress.is_changed() {
+        return;
+    }
+    for mut style in q_fill.iter_mut() {
+        style.width = Val::Percent(100. * progress.fraction());
+    }
+    let (meshes_done, meshes_total) = progress.meshes;
+    let counts = progress
+        .groups
+        .iter()
+        .map(|group| format!("{} {}/{}", group.label, group.loaded, group.total()))
+        .chain(std::iter::once(format!(
+            "meshes {meshes_done}/{meshes_total}"
+        )))
+        .collect::<Vec<_>>()
+        .join("   ");
+    for mut text in q_counts.iter_mut() {
+        text.sections[0].value = counts.clone();
+    }
+}
+
+fn despawn_loading_screen(mut cmd: Commands, q_screen: Query<Entity, With<LoadingScreen>>) {
+    for entity in q_screen.iter() {
+        cmd.entity(entity).despawn_recursive();
+    }
+}
+
+fn spawn_load_failed_screen(mut cmd: Commands) {
+    cmd.spawn((full_screen(), LoadFailedScreen))
+        .with_children(|parent| {
+            parent.spawn(text("Some game files couldn't be loaded", 48., ERROR_COLOR));
+            parent.spawn((text("", 24., Color::WHITE), FailedList));
+            parent.spawn(text(
+                "Check that the assets folder is complete, then restart the game.",
+                24.,
+                Color::GRAY,
+            ));
+        });
+}
+
+/// The failures are still being found while the screen goes up, so the list follows them.
+fn update_failed_list(
+    progress: Res<LoadingProgress>,
+    mut q_list: Query<&mut Text, With<FailedList>>,
+) {
+    if !progress.is_changed() {
+        return;
+    }
+    let list = if progress.failed.is_empty() {
+        "(unknown asset)".to_string()
+    } else {
+        progress.failed.join("\n")
+    };
+    for mut text in q_list.iter_mut() {
+        text.sections[0].value = list.clone();
+    }
+}
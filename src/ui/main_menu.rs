@@ -1,7 +1,17 @@
 use bevy::prelude::*;
+use strum::IntoEnumIterator;
 
 use crate::{
-    loading::{FontAssets, SoundAssets, TextureAssets},
+    board::layout::{BoardLayout, SelectedLayout},
+    cards::zones::GameMode,
+    content::{ContentKind, ContentSelection, CustomContent},
+    daily::{self, DailyChallenge, DailyRecord},
+    level::{CurrentLevel, Level},
+    loading::{FontAssets, LayoutAssets, TextureAssets},
+    locale::{Localization, Localized},
+    platform::Storage,
+    rng::{GameRng, Seed},
+    save::{continue_run, SaveGame},
     AppState,
 };
 pub const NORMAL_BUTTON_COLOR: Color = Color::rgb(1., 1., 1.);
@@ -10,37 +20,70 @@ pub const PRESS_BUTTON_COLOR: Color = Color::rgb(0.5, 0.5, 0.5);
 
 #[derive(Component)]
 pub struct MainMenu;
+/// Only shown when there's a saved run.
+#[derive(Component)]
+pub struct ContinueButton;
 #[derive(Component)]
 pub struct PlayButton;
+/// Starts today's `DailyChallenge`, and shows the day's best score.
+#[derive(Component)]
+pub struct DailyButton;
+#[derive(Component)]
+pub struct HotseatButton;
+#[derive(Component)]
+pub struct EndlessButton;
+#[derive(Component)]
+pub struct VersusButton;
 #[derive(Component)]
 pub struct InstructionButton;
+#[derive(Component)]
+pub struct ControlsButton;
+/// Cycles between the built-in option and the custom content of one kind.
+#[derive(Component)]
+pub struct ContentButton(ContentKind);
+#[derive(Component)]
+pub struct ContentLabel(ContentKind);
 
 pub struct MainMenuPlugin;
 
 impl Plugin for MainMenuPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(AppState::Menu), (spawn_main_menu))
+        app.add_systems(OnEnter(AppState::Menu), spawn_main_menu)
             .add_systems(
                 Update,
-                (press_instruction, press_play).run_if(in_state(AppState::Menu)),
+                (
+                    press_instruction,
+                    press_continue,
+                    press_play,
+                    press_daily,
+                    press_hotseat,
+                    press_endless,
+                    press_versus,
+                    press_controls,
+                    press_content,
+                    update_content_labels,
+                )
+                    .run_if(in_state(AppState::Menu)),
             )
-            .add_systems(OnExit(AppState::Menu), (despawn_main_menu));
+            .add_systems(OnExit(AppState::Menu), despawn_main_menu);
     }
 }
 pub fn spawn_main_menu(
     mut cmd: Commands,
     fonts: Res<FontAssets>,
     textures: Res<TextureAssets>,
-    sound: Res<SoundAssets>,
+    content: Res<CustomContent>,
+    selection: Res<ContentSelection>,
+    storage: Res<Storage>,
+    daily_record: Res<DailyRecord>,
+    loc: Localization,
 ) {
-    cmd.spawn(AudioBundle {
-        source: sound.bg_music.clone(),
-        settings: PlaybackSettings {
-            mode: bevy::audio::PlaybackMode::Loop,
-            ..default()
-        },
-    });
-    let main_menu = cmd
+    let has_save = SaveGame::load(&storage).is_some();
+    let daily_best = match daily_record.best(daily::today()) {
+        Some(best) => loc.format("menu.daily_best", &[("score", best.to_string())]),
+        None => loc.get("menu.daily_unplayed"),
+    };
+    let _main_menu = cmd
         .spawn((
             NodeBundle {
                 background_color: Color::rgb(153. / 255., 173. / 255., 211. / 255.).into(),
@@ -50,7 +93,8 @@ pub fn spawn_main_menu(
                     flex_direction: FlexDirection::Column,
                     justify_content: JustifyContent::Center,
                     align_items: AlignItems::Center,
-                    row_gap: Val::Px(40.),
+                    // NOTE: sized for every button, Continue included, to fit a 1080p window
+                    row_gap: Val::Px(24.),
                     ..default()
                 },
                 ..default()
@@ -59,44 +103,206 @@ pub fn spawn_main_menu(
         ))
         .with_children(|parent: &mut ChildBuilder| {
             //Title
-            parent.spawn(
-                (TextBundle {
-                    style: Style {
-                        padding: UiRect {
-                            bottom: Val::Px(100.),
+            parent.spawn(TextBundle {
+                style: Style {
+                    padding: UiRect {
+                        bottom: Val::Px(100.),
+                        ..default()
+                    },
+                    ..default()
+                },
+                text: Text {
+                    sections: vec![
+                        TextSection::new(
+                            "Shape",
+                            TextStyle {
+                                font: fonts.fira.clone(),
+                                font_size: 100.0,
+                                color: Color::WHITE,
+                            },
+                        ),
+                        TextSection::new(
+                            "craft",
+                            TextStyle {
+                                font: fonts.fira.clone(),
+                                font_size: 100.0,
+                                color: Color::WHITE,
+                            },
+                        ),
+                    ],
+                    alignment: TextAlignment::Center,
+
+                    ..default()
+                },
+
+                ..default()
+            });
+
+            //Continue Button
+            if has_save {
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                width: Val::Px(200.),
+                                height: Val::Px(80.0),
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                ..default()
+                            },
+
+                            background_color: BackgroundColor::from(NORMAL_BUTTON_COLOR),
+                            ..default()
+                        },
+                        ContinueButton,
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn((
+                            TextBundle {
+                                text: Text {
+                                    sections: vec![TextSection::new(
+                                        "",
+                                        TextStyle {
+                                            font: fonts.fira.clone_weak(),
+                                            font_size: 32.0,
+                                            color: Color::BLACK,
+                                        },
+                                    )],
+                                    alignment: TextAlignment::Center,
+                                    ..default()
+                                },
+                                ..default()
+                            },
+                            Localized::new("menu.continue"),
+                        ));
+                    });
+            }
+
+            //Play Button
+            parent
+                .spawn((
+                    ButtonBundle {
+                        style: Style {
+                            width: Val::Px(200.),
+                            height: Val::Px(80.0),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
                             ..default()
                         },
+
+                        background_color: BackgroundColor::from(NORMAL_BUTTON_COLOR),
                         ..default()
                     },
-                    text: Text {
-                        sections: vec![
-                            TextSection::new(
-                                "Shape",
-                                TextStyle {
-                                    font: fonts.fira.clone(),
-                                    font_size: 100.0,
-                                    color: Color::WHITE,
-                                },
-                            ),
-                            TextSection::new(
-                                "craft",
-                                TextStyle {
-                                    font: fonts.fira.clone(),
-                                    font_size: 100.0,
-                                    color: Color::WHITE,
-                                },
-                            ),
-                        ],
-                        alignment: TextAlignment::Center,
+                    PlayButton,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        TextBundle {
+                            text: Text {
+                                sections: vec![TextSection::new(
+                                    "",
+                                    TextStyle {
+                                        font: fonts.fira.clone_weak(),
+                                        font_size: 32.0,
+                                        color: Color::BLACK,
+                                    },
+                                )],
+                                alignment: TextAlignment::Center,
+                                ..default()
+                            },
+                            ..default()
+                        },
+                        Localized::new("menu.play"),
+                    ));
+                });
+
+            //Daily Button
+            parent
+                .spawn((
+                    ButtonBundle {
+                        style: Style {
+                            width: Val::Px(200.),
+                            height: Val::Px(80.0),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
 
+                        background_color: BackgroundColor::from(NORMAL_BUTTON_COLOR),
                         ..default()
                     },
+                    DailyButton,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        TextBundle {
+                            text: Text {
+                                sections: vec![
+                                    TextSection::new(
+                                        "",
+                                        TextStyle {
+                                            font: fonts.fira.clone_weak(),
+                                            font_size: 32.0,
+                                            color: Color::BLACK,
+                                        },
+                                    ),
+                                    TextSection::new(
+                                        format!("\n{daily_best}"),
+                                        TextStyle {
+                                            font: fonts.fira.clone_weak(),
+                                            font_size: 18.0,
+                                            color: Color::DARK_GRAY,
+                                        },
+                                    ),
+                                ],
+                                alignment: TextAlignment::Center,
+                                ..default()
+                            },
+                            ..default()
+                        },
+                        Localized::new("menu.daily"),
+                    ));
+                });
 
-                    ..default()
-                }),
-            );
+            //Hotseat Button
+            parent
+                .spawn((
+                    ButtonBundle {
+                        style: Style {
+                            width: Val::Px(200.),
+                            height: Val::Px(80.0),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
 
-            //Play Button
+                        background_color: BackgroundColor::from(NORMAL_BUTTON_COLOR),
+                        ..default()
+                    },
+                    HotseatButton,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        TextBundle {
+                            text: Text {
+                                sections: vec![TextSection::new(
+                                    "",
+                                    TextStyle {
+                                        font: fonts.fira.clone_weak(),
+                                        font_size: 32.0,
+                                        color: Color::BLACK,
+                                    },
+                                )],
+                                alignment: TextAlignment::Center,
+                                ..default()
+                            },
+                            ..default()
+                        },
+                        Localized::new("menu.hotseat"),
+                    ));
+                });
+
+            //Versus Button
             parent
                 .spawn((
                     ButtonBundle {
@@ -111,25 +317,198 @@ pub fn spawn_main_menu(
                         background_color: BackgroundColor::from(NORMAL_BUTTON_COLOR),
                         ..default()
                     },
-                    PlayButton,
+                    VersusButton,
                 ))
                 .with_children(|parent| {
-                    parent.spawn(TextBundle {
-                        text: Text {
-                            sections: vec![TextSection::new(
-                                "Play",
-                                TextStyle {
-                                    font: fonts.fira.clone_weak(),
-                                    font_size: 32.0,
-                                    color: Color::BLACK,
-                                },
-                            )],
-                            alignment: TextAlignment::Center,
+                    parent.spawn((
+                        TextBundle {
+                            text: Text {
+                                sections: vec![TextSection::new(
+                                    "",
+                                    TextStyle {
+                                        font: fonts.fira.clone_weak(),
+                                        font_size: 32.0,
+                                        color: Color::BLACK,
+                                    },
+                                )],
+                                alignment: TextAlignment::Center,
+                                ..default()
+                            },
+                            ..default()
+                        },
+                        Localized::new("menu.versus"),
+                    ));
+                });
+
+            //Endless Button
+            parent
+                .spawn((
+                    ButtonBundle {
+                        style: Style {
+                            width: Val::Px(200.),
+                            height: Val::Px(80.0),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
                             ..default()
                         },
+
+                        background_color: BackgroundColor::from(NORMAL_BUTTON_COLOR),
                         ..default()
-                    });
+                    },
+                    EndlessButton,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        TextBundle {
+                            text: Text {
+                                sections: vec![TextSection::new(
+                                    "",
+                                    TextStyle {
+                                        font: fonts.fira.clone_weak(),
+                                        font_size: 32.0,
+                                        color: Color::BLACK,
+                                    },
+                                )],
+                                alignment: TextAlignment::Center,
+                                ..default()
+                            },
+                            ..default()
+                        },
+                        Localized::new("menu.endless"),
+                    ));
+                });
+
+            //Controls Button
+            parent
+                .spawn((
+                    ButtonBundle {
+                        style: Style {
+                            width: Val::Px(200.),
+                            height: Val::Px(80.0),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+
+                        background_color: BackgroundColor::from(NORMAL_BUTTON_COLOR),
+                        ..default()
+                    },
+                    ControlsButton,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        TextBundle {
+                            text: Text {
+                                sections: vec![TextSection::new(
+                                    "",
+                                    TextStyle {
+                                        font: fonts.fira.clone_weak(),
+                                        font_size: 32.0,
+                                        color: Color::BLACK,
+                                    },
+                                )],
+                                alignment: TextAlignment::Center,
+                                ..default()
+                            },
+                            ..default()
+                        },
+                        Localized::new("menu.controls"),
+                    ));
+                });
+
+            //Custom Content Buttons
+            // NOTE: in a wrapping row, so every kind fits under the mode buttons at 1080p
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        max_width: Val::Percent(90.),
+                        flex_wrap: FlexWrap::Wrap,
+                        justify_content: JustifyContent::Center,
+                        column_gap: Val::Px(16.),
+                        row_gap: Val::Px(16.),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for kind in ContentKind::iter() {
+                        if content.len(kind) == 0 {
+                            continue;
+                        }
+                        parent
+                            .spawn((
+                                ButtonBundle {
+                                    style: Style {
+                                        width: Val::Px(340.),
+                                        height: Val::Px(50.0),
+                                        justify_content: JustifyContent::Center,
+                                        align_items: AlignItems::Center,
+                                        ..default()
+                                    },
+
+                                    background_color: BackgroundColor::from(NORMAL_BUTTON_COLOR),
+                                    ..default()
+                                },
+                                ContentButton(kind),
+                            ))
+                            .with_children(|parent| {
+                                parent.spawn((
+                                    TextBundle {
+                                        text: Text {
+                                            sections: vec![TextSection::new(
+                                                content_label(kind, &content, &selection, &loc),
+                                                TextStyle {
+                                                    font: fonts.fira.clone_weak(),
+                                                    font_size: 24.0,
+                                                    color: Color::BLACK,
+                                                },
+                                            )],
+                                            alignment: TextAlignment::Center,
+                                            ..default()
+                                        },
+                                        ..default()
+                                    },
+                                    ContentLabel(kind),
+                                ));
+                            });
+                    }
+                });
+
+            //Content Problems
+            if !content.problems.is_empty() {
+                let mut sections = vec![TextSection::new(
+                    format!("{}\n", loc.get("menu.content_problems")),
+                    TextStyle {
+                        font: fonts.fira.clone_weak(),
+                        font_size: 20.0,
+                        color: Color::WHITE,
+                    },
+                )];
+                sections.extend(content.problems.iter().map(|problem| {
+                    TextSection::new(
+                        format!("{}: {}\n", problem.path, problem.message),
+                        TextStyle {
+                            font: fonts.fira.clone_weak(),
+                            font_size: 16.0,
+                            color: Color::rgb(0.6, 0., 0.),
+                        },
+                    )
+                }));
+                parent.spawn(TextBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        bottom: Val::Px(20.),
+                        max_width: Val::Percent(60.),
+                        ..default()
+                    },
+                    text: Text {
+                        sections,
+                        alignment: TextAlignment::Left,
+                        ..default()
+                    },
+                    ..default()
                 });
+            }
 
             // //Instructions Button
             // parent
@@ -220,6 +599,54 @@ pub fn spawn_main_menu(
 pub fn despawn_main_menu(mut cmd: Commands, menu_q: Query<Entity, With<MainMenu>>) {
     cmd.entity(menu_q.single()).despawn_recursive();
 }
+pub fn press_continue(
+    mut cmd: Commands,
+    mut q_button: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<ContinueButton>),
+    >,
+    storage: Res<Storage>,
+) {
+    if let Ok((interaction, mut color)) = q_button.get_single_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = BackgroundColor::from(PRESS_BUTTON_COLOR);
+                let Some(save) = SaveGame::load(&storage) else {
+                    return;
+                };
+                continue_run(&mut cmd, save);
+            },
+            Interaction::Hovered => {
+                *color = BackgroundColor::from(HOVER_BUTTON_COLOR);
+            },
+            Interaction::None => {
+                *color = BackgroundColor::from(NORMAL_BUTTON_COLOR);
+            },
+        }
+    }
+}
+pub fn press_controls(
+    mut cmd: Commands,
+    mut q_button: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<ControlsButton>),
+    >,
+) {
+    if let Ok((interaction, mut color)) = q_button.get_single_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = BackgroundColor::from(PRESS_BUTTON_COLOR);
+                cmd.insert_resource(NextState(Some(AppState::Controls)));
+            },
+            Interaction::Hovered => {
+                *color = BackgroundColor::from(HOVER_BUTTON_COLOR);
+            },
+            Interaction::None => {
+                *color = BackgroundColor::from(NORMAL_BUTTON_COLOR);
+            },
+        }
+    }
+}
 pub fn press_play(
     mut cmd: Commands,
     mut q_button: Query<
@@ -231,6 +658,46 @@ pub fn press_play(
         match *interaction {
             Interaction::Pressed => {
                 *color = BackgroundColor::from(PRESS_BUTTON_COLOR);
+                cmd.insert_resource(GameMode::Solo);
+                cmd.insert_resource(NextState(Some(AppState::LevelSelect)));
+            },
+            Interaction::Hovered => {
+                *color = BackgroundColor::from(HOVER_BUTTON_COLOR);
+            },
+            Interaction::None => {
+                *color = BackgroundColor::from(NORMAL_BUTTON_COLOR);
+            },
+        }
+    }
+}
+/// Sets up today's challenge and goes straight to the board, skipping the level select.
+pub fn press_daily(
+    mut cmd: Commands,
+    mut q_button: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<DailyButton>),
+    >,
+    layout_assets: Res<LayoutAssets>,
+    layouts: Res<Assets<BoardLayout>>,
+) {
+    if let Ok((interaction, mut color)) = q_button.get_single_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = BackgroundColor::from(PRESS_BUTTON_COLOR);
+                let names: Vec<String> = layout_assets
+                    .layouts
+                    .iter()
+                    .filter_map(|handle| Some(layouts.get(handle)?.name.clone()))
+                    .collect();
+                let challenge = DailyChallenge::new(daily::today(), &names);
+                info!("daily challenge for {}", daily::date_label(challenge.day));
+                let seed = Seed(challenge.seed);
+                cmd.insert_resource(GameMode::Solo);
+                cmd.insert_resource(seed);
+                cmd.insert_resource(GameRng::from_seed(seed));
+                cmd.insert_resource(SelectedLayout(challenge.layout.clone()));
+                cmd.insert_resource(CurrentLevel(Level::new(1)));
+                cmd.insert_resource(challenge);
                 cmd.insert_resource(NextState(Some(AppState::Playing)));
             },
             Interaction::Hovered => {
@@ -242,6 +709,75 @@ pub fn press_play(
         }
     }
 }
+pub fn press_hotseat(
+    mut cmd: Commands,
+    mut q_button: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<HotseatButton>),
+    >,
+) {
+    if let Ok((interaction, mut color)) = q_button.get_single_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = BackgroundColor::from(PRESS_BUTTON_COLOR);
+                cmd.insert_resource(GameMode::Hotseat);
+                cmd.insert_resource(NextState(Some(AppState::LevelSelect)));
+            },
+            Interaction::Hovered => {
+                *color = BackgroundColor::from(HOVER_BUTTON_COLOR);
+            },
+            Interaction::None => {
+                *color = BackgroundColor::from(NORMAL_BUTTON_COLOR);
+            },
+        }
+    }
+}
+pub fn press_versus(
+    mut cmd: Commands,
+    mut q_button: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<VersusButton>),
+    >,
+) {
+    if let Ok((interaction, mut color)) = q_button.get_single_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = BackgroundColor::from(PRESS_BUTTON_COLOR);
+                cmd.insert_resource(GameMode::Versus);
+                cmd.insert_resource(NextState(Some(AppState::LevelSelect)));
+            },
+            Interaction::Hovered => {
+                *color = BackgroundColor::from(HOVER_BUTTON_COLOR);
+            },
+            Interaction::None => {
+                *color = BackgroundColor::from(NORMAL_BUTTON_COLOR);
+            },
+        }
+    }
+}
+pub fn press_endless(
+    mut cmd: Commands,
+    mut q_button: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<EndlessButton>),
+    >,
+) {
+    if let Ok((interaction, mut color)) = q_button.get_single_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = BackgroundColor::from(PRESS_BUTTON_COLOR);
+                cmd.insert_resource(GameMode::Endless);
+                cmd.insert_resource(NextState(Some(AppState::LevelSelect)));
+            },
+            Interaction::Hovered => {
+                *color = BackgroundColor::from(HOVER_BUTTON_COLOR);
+            },
+            Interaction::None => {
+                *color = BackgroundColor::from(NORMAL_BUTTON_COLOR);
+            },
+        }
+    }
+}
 pub fn press_instruction(
     mut cmd: Commands,
     mut q_button: Query<
@@ -264,3 +800,67 @@ pub fn press_instruction(
         }
     }
 }
+fn content_label(
+    kind: ContentKind,
+    content: &CustomContent,
+    selection: &ContentSelection,
+    loc: &Localization,
+) -> String {
+    let prefix = loc.get(match kind {
+        ContentKind::Deck => "menu.content_deck",
+        ContentKind::Rules => "menu.content_rules",
+        ContentKind::Shapes => "menu.content_shapes",
+        ContentKind::Fight => "menu.content_fight",
+        ContentKind::Difficulty => "menu.content_difficulty",
+    });
+    match selection
+        .get(kind)
+        .and_then(|index| content.name(kind, index))
+    {
+        Some(name) => loc.format(
+            "menu.content_custom",
+            &[("kind", prefix), ("name", name.to_string())],
+        ),
+        None => match kind {
+            ContentKind::Deck | ContentKind::Rules | ContentKind::Shapes => {
+                loc.format("menu.content_random", &[("kind", prefix)])
+            },
+            ContentKind::Fight | ContentKind::Difficulty => {
+                loc.format("menu.content_default", &[("kind", prefix)])
+            },
+        },
+    }
+}
+pub fn press_content(
+    mut q_button: Query<(&Interaction, &mut BackgroundColor, &ContentButton), Changed<Interaction>>,
+    content: Res<CustomContent>,
+    mut selection: ResMut<ContentSelection>,
+) {
+    for (interaction, mut color, button) in q_button.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = BackgroundColor::from(PRESS_BUTTON_COLOR);
+                selection.cycle(button.0, content.len(button.0));
+            },
+            Interaction::Hovered => {
+                *color = BackgroundColor::from(HOVER_BUTTON_COLOR);
+            },
+            Interaction::None => {
+                *color = BackgroundColor::from(NORMAL_BUTTON_COLOR);
+            },
+        }
+    }
+}
+pub fn update_content_labels(
+    content: Res<CustomContent>,
+    selection: Res<ContentSelection>,
+    loc: Localization,
+    mut q_label: Query<(&mut Text, &ContentLabel)>,
+) {
+    if !selection.is_changed() && !loc.is_changed() {
+        return;
+    }
+    for (mut text, label) in q_label.iter_mut() {
+        text.sections[0].value = content_label(label.0, &content, &selection, &loc);
+    }
+}
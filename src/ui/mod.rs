@@ -1,16 +1,65 @@
+mod accessibility;
+mod achievement_toast;
+mod card_preview;
+mod card_reward;
+mod controls;
+mod deck_builder;
+mod draft;
+mod gravity_indicator;
 mod hud;
+mod inspect;
+mod level_select;
+mod library_search;
+mod loading_screen;
 mod main_menu;
+mod mulligan;
+mod pass_device;
+mod pause_menu;
+mod pile_browser;
+mod run_summary;
 mod score_ui;
+mod status_bar;
+mod tooltip;
+mod tutorial_overlay;
 use bevy::prelude::*;
 
-use self::{hud::HUDPlugin, main_menu::MainMenuPlugin, score_ui::ScoreUIPlugin};
+pub use self::pile_browser::PileBrowserOpen;
+use self::{
+    accessibility::AccessibilityPlugin, achievement_toast::AchievementToastPlugin,
+    card_reward::CardRewardPlugin, controls::ControlsPlugin, deck_builder::DeckBuilderPlugin,
+    draft::DraftUIPlugin, gravity_indicator::GravityIndicatorPlugin, hud::HUDPlugin,
+    inspect::InspectPlugin, level_select::LevelSelectPlugin, library_search::LibrarySearchPlugin,
+    loading_screen::LoadingScreenPlugin, main_menu::MainMenuPlugin, mulligan::MulliganUIPlugin,
+    pass_device::PassDeviceUIPlugin, pause_menu::PauseMenuPlugin, pile_browser::PileBrowserPlugin,
+    run_summary::RunSummaryPlugin, score_ui::ScoreUIPlugin, status_bar::StatusBarPlugin,
+    tooltip::TooltipPlugin, tutorial_overlay::TutorialOverlayPlugin,
+};
 
 pub struct UIPlugin;
 impl Plugin for UIPlugin {
     fn build(&self, app: &mut App) {
+        app.add_plugins(LoadingScreenPlugin);
         app.add_plugins(MainMenuPlugin);
+        app.add_plugins(LevelSelectPlugin);
+        app.add_plugins(CardRewardPlugin);
+        app.add_plugins(DeckBuilderPlugin);
+        app.add_plugins(ControlsPlugin);
         app.add_plugins(HUDPlugin);
         app.add_plugins(ScoreUIPlugin);
+        app.add_plugins(RunSummaryPlugin);
+        app.add_plugins(PassDeviceUIPlugin);
+        app.add_plugins(DraftUIPlugin);
+        app.add_plugins(MulliganUIPlugin);
+        app.add_plugins(PauseMenuPlugin);
+        app.add_plugins(InspectPlugin);
+        app.add_plugins(TooltipPlugin);
+        app.add_plugins(PileBrowserPlugin);
+        app.add_plugins(LibrarySearchPlugin);
+        app.add_plugins(StatusBarPlugin);
+        app.add_plugins(GravityIndicatorPlugin);
+        app.add_plugins(AchievementToastPlugin);
+        app.add_plugins(TutorialOverlayPlugin);
+        app.add_plugins(AccessibilityPlugin);
     }
 }
 #[derive(Component)]
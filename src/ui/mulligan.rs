@@ -0,0 +1,148 @@
+use bevy::prelude::*;
+
+use super::main_menu::{HOVER_BUTTON_COLOR, NORMAL_BUTTON_COLOR, PRESS_BUTTON_COLOR};
+use crate::{
+    cards::{
+        mulligan::{Mulligan, MulliganSelection},
+        opponent::opponent_turn,
+        zones::ActivePlayer,
+        GameState,
+    },
+    loading::FontAssets,
+};
+
+/// Sits above the table, leaving the hand free to click, while the opening hand is chosen.
+#[derive(Component)]
+pub struct MulliganScreen;
+
+/// Confirms the marked cards, or keeps the hand if there are none.
+#[derive(Component)]
+pub struct ConfirmMulliganButton;
+
+#[derive(Component)]
+pub struct ConfirmMulliganText;
+
+pub struct MulliganUIPlugin;
+impl Plugin for MulliganUIPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(GameState::Mulligan),
+            spawn_mulligan_screen.run_if(not(opponent_turn)),
+        )
+        .add_systems(
+            Update,
+            (update_mulligan_screen, press_confirm_mulligan).run_if(in_state(GameState::Mulligan)),
+        )
+        .add_systems(OnExit(GameState::Mulligan), despawn_mulligan_screen);
+    }
+}
+
+pub fn spawn_mulligan_screen(mut cmd: Commands, fonts: Res<FontAssets>, active: Res<ActivePlayer>) {
+    cmd.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                top: Val::Px(40.),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(20.),
+                ..default()
+            },
+            z_index: ZIndex::Global(10),
+            ..default()
+        },
+        MulliganScreen,
+    ))
+    .with_children(|parent| {
+        parent.spawn(TextBundle::from_section(
+            format!("Player {}, click cards to redraw them", active.0 .0 + 1),
+            TextStyle {
+                font: fonts.fira.clone(),
+                font_size: 48.0,
+                color: Color::WHITE,
+            },
+        ));
+        parent
+            .spawn((
+                ButtonBundle {
+                    style: Style {
+                        width: Val::Px(280.),
+                        height: Val::Px(70.),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    background_color: BackgroundColor::from(NORMAL_BUTTON_COLOR),
+                    ..default()
+                },
+                ConfirmMulliganButton,
+            ))
+            .with_children(|button| {
+                button.spawn((
+                    TextBundle::from_section(
+                        "Keep hand",
+                        TextStyle {
+                            font: fonts.fira.clone_weak(),
+                            font_size: 32.0,
+                            color: Color::BLACK,
+                        },
+                    ),
+                    ConfirmMulliganText,
+                ));
+            });
+    });
+}
+/// Names what the button will do, and hides the screen once the cards are being redrawn.
+pub fn update_mulligan_screen(
+    selection: Option<Res<MulliganSelection>>,
+    mut q_text: Query<&mut Text, With<ConfirmMulliganText>>,
+    mut q_screen: Query<&mut Visibility, With<MulliganScreen>>,
+) {
+    let Some(selection) = selection.filter(|s| s.is_changed()) else {
+        return;
+    };
+    for mut text in q_text.iter_mut() {
+        text.sections[0].value = match selection.cards.len() {
+            0 => "Keep hand".to_string(),
+            n => format!("Redraw {n}"),
+        };
+    }
+    if selection.redraws.is_some() {
+        for mut visibility in q_screen.iter_mut() {
+            *visibility = Visibility::Hidden;
+        }
+    }
+}
+pub fn press_confirm_mulligan(
+    mut q_button: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<ConfirmMulliganButton>),
+    >,
+    selection: Option<Res<MulliganSelection>>,
+    mut mulligan: EventWriter<Mulligan>,
+) {
+    if let Ok((interaction, mut color)) = q_button.get_single_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = BackgroundColor::from(PRESS_BUTTON_COLOR);
+                if let Some(selection) = selection {
+                    mulligan.send(Mulligan {
+                        cards: selection.cards.clone(),
+                    });
+                }
+            },
+            Interaction::Hovered => {
+                *color = BackgroundColor::from(HOVER_BUTTON_COLOR);
+            },
+            Interaction::None => {
+                *color = BackgroundColor::from(NORMAL_BUTTON_COLOR);
+            },
+        }
+    }
+}
+pub fn despawn_mulligan_screen(mut cmd: Commands, q_screen: Query<Entity, With<MulliganScreen>>) {
+    for entity in q_screen.iter() {
+        cmd.entity(entity).despawn_recursive();
+    }
+}
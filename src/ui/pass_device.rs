@@ -0,0 +1,95 @@
+use bevy::prelude::*;
+
+use crate::{
+    cards::{
+        end_turn,
+        zones::{ActivePlayer, GameMode, Winner},
+        GameState,
+    },
+    input::{binding_label, GameAction},
+    loading::FontAssets,
+    settings::Settings,
+};
+
+/// Covers the table between hotseat turns so the next player's hand isn't shown to the last one.
+#[derive(Component)]
+pub struct PassDeviceScreen;
+
+pub struct PassDeviceUIPlugin;
+impl Plugin for PassDeviceUIPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(GameState::PassDevice),
+            spawn_pass_device
+                .after(end_turn)
+                .run_if(resource_equals(GameMode::Hotseat))
+                .run_if(|winner: Res<Winner>| winner.is_none()),
+        )
+        .add_systems(OnExit(GameState::PassDevice), despawn_pass_device);
+    }
+}
+
+pub fn spawn_pass_device(
+    mut cmd: Commands,
+    fonts: Res<FontAssets>,
+    active: Res<ActivePlayer>,
+    settings: Res<Settings>,
+) {
+    cmd.spawn((
+        NodeBundle {
+            background_color: Color::rgb(153. / 255., 173. / 255., 211. / 255.).into(),
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(40.),
+                ..default()
+            },
+            z_index: ZIndex::Global(10),
+            ..default()
+        },
+        PassDeviceScreen,
+    ))
+    .with_children(|parent: &mut ChildBuilder| {
+        parent.spawn(TextBundle {
+            text: Text {
+                sections: vec![TextSection::new(
+                    format!("Player {}, take the device", active.0 .0 + 1),
+                    TextStyle {
+                        font: fonts.fira.clone(),
+                        font_size: 64.0,
+                        color: Color::WHITE,
+                    },
+                )],
+                alignment: TextAlignment::Center,
+                ..default()
+            },
+            ..default()
+        });
+        parent.spawn(TextBundle {
+            text: Text {
+                sections: vec![TextSection::new(
+                    format!(
+                        "Press {} to Start",
+                        binding_label(&settings.input_map, GameAction::Play)
+                    ),
+                    TextStyle {
+                        font: fonts.fira.clone(),
+                        font_size: 32.0,
+                        color: Color::WHITE,
+                    },
+                )],
+                alignment: TextAlignment::Center,
+                ..default()
+            },
+            ..default()
+        });
+    });
+}
+pub fn despawn_pass_device(mut cmd: Commands, q_screen: Query<Entity, With<PassDeviceScreen>>) {
+    for entity in q_screen.iter() {
+        cmd.entity(entity).despawn_recursive();
+    }
+}
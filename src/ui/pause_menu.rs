@@ -0,0 +1,422 @@
+use bevy::prelude::*;
+
+use super::{
+    accessibility::FocusScope,
+    main_menu::{HOVER_BUTTON_COLOR, NORMAL_BUTTON_COLOR, PRESS_BUTTON_COLOR},
+};
+use crate::{
+    audio::{Volume, VolumeSlider},
+    game_shapes::palette::{ColorOptions, Palette},
+    input::{binding_label, GameAction},
+    loading::FontAssets,
+    locale::{Language, Localization, Localized},
+    pause::PauseState,
+    settings::Settings,
+};
+
+#[derive(Component)]
+pub struct PauseMenu;
+
+/// Moves a volume slider by `steps` when pressed.
+#[derive(Component)]
+pub struct VolumeButton {
+    slider: VolumeSlider,
+    steps: i32,
+}
+
+#[derive(Component)]
+pub struct VolumeLabel(VolumeSlider);
+
+/// Moves to the language `steps` further along the list when pressed.
+#[derive(Component)]
+pub struct LanguageButton(i32);
+
+#[derive(Component)]
+pub struct LanguageLabel;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ColorSetting {
+    Palette,
+    Patterns,
+}
+
+/// Moves to the palette `steps` further along, or turns the patterns on or off, when pressed.
+#[derive(Component)]
+pub struct ColorButton {
+    setting: ColorSetting,
+    steps: i32,
+}
+
+#[derive(Component)]
+pub struct ColorLabel(ColorSetting);
+
+pub struct PauseMenuPlugin;
+impl Plugin for PauseMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(PauseState::Paused), spawn_pause_menu)
+            .add_systems(
+                Update,
+                (
+                    (press_volume, press_language, press_colors),
+                    (
+                        update_volume_labels,
+                        update_language_label,
+                        update_color_labels,
+                    ),
+                )
+                    .chain()
+                    .run_if(in_state(PauseState::Paused)),
+            )
+            .add_systems(OnExit(PauseState::Paused), despawn_pause_menu);
+    }
+}
+
+fn volume_label(slider: VolumeSlider, volume: &Volume, loc: &Localization) -> String {
+    loc.format(
+        "pause.volume",
+        &[
+            ("slider", loc.get(&format!("volume.{slider:?}"))),
+            ("percent", (volume.get(slider) * 100.).round().to_string()),
+        ],
+    )
+}
+
+fn language_label(loc: &Localization) -> String {
+    loc.format("pause.language", &[("language", loc.language_name())])
+}
+
+fn color_label(setting: ColorSetting, colors: &ColorOptions, loc: &Localization) -> String {
+    match setting {
+        ColorSetting::Palette => loc.format(
+            "pause.palette",
+            &[("palette", loc.get(&format!("palette.{:?}", colors.palette)))],
+        ),
+        ColorSetting::Patterns => loc.format(
+            "pause.patterns",
+            &[(
+                "state",
+                loc.get(if colors.patterns {
+                    "pause.on"
+                } else {
+                    "pause.off"
+                }),
+            )],
+        ),
+    }
+}
+
+pub fn spawn_pause_menu(
+    mut cmd: Commands,
+    fonts: Res<FontAssets>,
+    volume: Res<Volume>,
+    colors: Res<ColorOptions>,
+    settings: Res<Settings>,
+    loc: Localization,
+) {
+    let input_map = &settings.input_map;
+    cmd.spawn((
+        NodeBundle {
+            background_color: Color::rgba(0., 0., 0., 0.6).into(),
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(40.),
+                ..default()
+            },
+            z_index: ZIndex::Global(20),
+            ..default()
+        },
+        PauseMenu,
+        FocusScope,
+    ))
+    .with_children(|parent: &mut ChildBuilder| {
+        for (localized, font_size) in [
+            (Localized::new("pause.title"), 64.0),
+            (
+                Localized::new("pause.resume")
+                    .with("key", binding_label(input_map, GameAction::Pause)),
+                32.0,
+            ),
+            (
+                Localized::new("pause.quit")
+                    .with("key", binding_label(input_map, GameAction::QuitToMenu)),
+                32.0,
+            ),
+        ] {
+            parent.spawn((
+                TextBundle {
+                    text: Text {
+                        sections: vec![TextSection::new(
+                            "",
+                            TextStyle {
+                                font: fonts.fira.clone(),
+                                font_size,
+                                color: Color::WHITE,
+                            },
+                        )],
+                        alignment: TextAlignment::Center,
+                        ..default()
+                    },
+                    ..default()
+                },
+                localized,
+            ));
+        }
+
+        for slider in VolumeSlider::ALL {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        align_items: AlignItems::Center,
+                        column_gap: Val::Px(20.),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|row| {
+                    spawn_volume_button(row, &fonts, "-", slider, -1);
+                    row.spawn((
+                        TextBundle {
+                            text: Text::from_section(
+                                volume_label(slider, &volume, &loc),
+                                TextStyle {
+                                    font: fonts.fira.clone_weak(),
+                                    font_size: 28.0,
+                                    color: Color::WHITE,
+                                },
+                            ),
+                            style: Style {
+                                width: Val::Px(220.),
+                                ..default()
+                            },
+                            ..default()
+                        },
+                        VolumeLabel(slider),
+                    ));
+                    spawn_volume_button(row, &fonts, "+", slider, 1);
+                });
+        }
+
+        spawn_option_row(
+            parent,
+            &fonts,
+            language_label(&loc),
+            LanguageLabel,
+            (LanguageButton(-1), LanguageButton(1)),
+        );
+        for setting in [ColorSetting::Palette, ColorSetting::Patterns] {
+            spawn_option_row(
+                parent,
+                &fonts,
+                color_label(setting, &colors, &loc),
+                ColorLabel(setting),
+                (
+                    ColorButton { setting, steps: -1 },
+                    ColorButton { setting, steps: 1 },
+                ),
+            );
+        }
+    });
+}
+/// A label between a button going back and one going forward through the options.
+fn spawn_option_row(
+    parent: &mut ChildBuilder,
+    fonts: &FontAssets,
+    text: String,
+    label: impl Bundle,
+    (previous, next): (impl Bundle, impl Bundle),
+) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                align_items: AlignItems::Center,
+                column_gap: Val::Px(20.),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|row| {
+            spawn_small_button(row, fonts, "<", previous);
+            row.spawn((
+                TextBundle {
+                    text: Text::from_section(
+                        text,
+                        TextStyle {
+                            font: fonts.fira.clone_weak(),
+                            font_size: 28.0,
+                            color: Color::WHITE,
+                        },
+                    ),
+                    style: Style {
+                        width: Val::Px(320.),
+                        ..default()
+                    },
+                    ..default()
+                },
+                label,
+            ));
+            spawn_small_button(row, fonts, ">", next);
+        });
+}
+fn spawn_volume_button(
+    row: &mut ChildBuilder,
+    fonts: &FontAssets,
+    text: &str,
+    slider: VolumeSlider,
+    steps: i32,
+) {
+    spawn_small_button(row, fonts, text, VolumeButton { slider, steps });
+}
+fn spawn_small_button(row: &mut ChildBuilder, fonts: &FontAssets, text: &str, marker: impl Bundle) {
+    row.spawn((
+        ButtonBundle {
+            style: Style {
+                width: Val::Px(48.),
+                height: Val::Px(48.),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            background_color: BackgroundColor::from(NORMAL_BUTTON_COLOR),
+            ..default()
+        },
+        marker,
+    ))
+    .with_children(|button| {
+        button.spawn(TextBundle::from_section(
+            text,
+            TextStyle {
+                font: fonts.fira.clone_weak(),
+                font_size: 32.0,
+                color: Color::BLACK,
+            },
+        ));
+    });
+}
+pub fn press_volume(
+    mut q_button: Query<(&Interaction, &mut BackgroundColor, &VolumeButton), Changed<Interaction>>,
+    mut volume: ResMut<Volume>,
+) {
+    for (interaction, mut color, button) in q_button.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = BackgroundColor::from(PRESS_BUTTON_COLOR);
+                volume.step(button.slider, button.steps);
+            },
+            Interaction::Hovered => {
+                *color = BackgroundColor::from(HOVER_BUTTON_COLOR);
+            },
+            Interaction::None => {
+                *color = BackgroundColor::from(NORMAL_BUTTON_COLOR);
+            },
+        }
+    }
+}
+pub fn press_language(
+    mut q_button: Query<
+        (&Interaction, &mut BackgroundColor, &LanguageButton),
+        Changed<Interaction>,
+    >,
+    loc: Localization,
+    mut language: ResMut<Language>,
+) {
+    for (interaction, mut color, button) in q_button.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = BackgroundColor::from(PRESS_BUTTON_COLOR);
+                let codes: Vec<&str> = loc
+                    .languages()
+                    .into_iter()
+                    .map(|locale| locale.code.as_str())
+                    .collect();
+                if codes.is_empty() {
+                    continue;
+                }
+                let current = codes.iter().position(|code| *code == language.0);
+                let next = current.map_or(0, |i| {
+                    (i as i32 + button.0).rem_euclid(codes.len() as i32) as usize
+                });
+                language.0 = codes[next].to_string();
+            },
+            Interaction::Hovered => {
+                *color = BackgroundColor::from(HOVER_BUTTON_COLOR);
+            },
+            Interaction::None => {
+                *color = BackgroundColor::from(NORMAL_BUTTON_COLOR);
+            },
+        }
+    }
+}
+pub fn press_colors(
+    mut q_button: Query<(&Interaction, &mut BackgroundColor, &ColorButton), Changed<Interaction>>,
+    mut colors: ResMut<ColorOptions>,
+) {
+    for (interaction, mut color, button) in q_button.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = BackgroundColor::from(PRESS_BUTTON_COLOR);
+                match button.setting {
+                    ColorSetting::Palette => {
+                        let count = Palette::ALL.len() as i32;
+                        let current = Palette::ALL
+                            .iter()
+                            .position(|palette| *palette == colors.palette)
+                            .unwrap_or(0) as i32;
+                        colors.palette =
+                            Palette::ALL[(current + button.steps).rem_euclid(count) as usize];
+                    },
+                    ColorSetting::Patterns => colors.patterns = !colors.patterns,
+                }
+            },
+            Interaction::Hovered => {
+                *color = BackgroundColor::from(HOVER_BUTTON_COLOR);
+            },
+            Interaction::None => {
+                *color = BackgroundColor::from(NORMAL_BUTTON_COLOR);
+            },
+        }
+    }
+}
+pub fn update_volume_labels(
+    volume: Res<Volume>,
+    loc: Localization,
+    mut q_label: Query<(&mut Text, &VolumeLabel)>,
+) {
+    if !volume.is_changed() && !loc.is_changed() {
+        return;
+    }
+    for (mut text, label) in q_label.iter_mut() {
+        text.sections[0].value = volume_label(label.0, &volume, &loc);
+    }
+}
+pub fn update_language_label(
+    loc: Localization,
+    mut q_label: Query<&mut Text, With<LanguageLabel>>,
+) {
+    if !loc.is_changed() {
+        return;
+    }
+    for mut text in q_label.iter_mut() {
+        text.sections[0].value = language_label(&loc);
+    }
+}
+pub fn update_color_labels(
+    colors: Res<ColorOptions>,
+    loc: Localization,
+    mut q_label: Query<(&mut Text, &ColorLabel)>,
+) {
+    if !colors.is_changed() && !loc.is_changed() {
+        return;
+    }
+    for (mut text, label) in q_label.iter_mut() {
+        text.sections[0].value = color_label(label.0, &colors, &loc);
+    }
+}
+pub fn despawn_pause_menu(mut cmd: Commands, q_menu: Query<Entity, With<PauseMenu>>) {
+    for entity in q_menu.iter() {
+        cmd.entity(entity).despawn_recursive();
+    }
+}
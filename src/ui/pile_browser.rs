@@ -0,0 +1,279 @@
+//! Clicking the discard of the player at the device lists every card in it over the table, top
+//! card first, even during the opponent's turn. Clicking their library does the same without
+//! giving its order away: the cards are sorted by name. The list scrolls with the mouse wheel, and
+//! Escape or the Close button puts it away. While it's open the hand and the board don't take
+//! clicks.
+
+use bevy::{
+    input::mouse::{MouseScrollUnit, MouseWheel},
+    prelude::*,
+};
+use leafwing_input_manager::prelude::ActionState;
+
+use super::{
+    card_preview::spawn_card_preview,
+    main_menu::{HOVER_BUTTON_COLOR, NORMAL_BUTTON_COLOR, PRESS_BUTTON_COLOR},
+};
+use crate::{
+    cards::{
+        card::Card,
+        definition::{CardDefinition, CardDefinitions},
+        hand::{select_card, Hand},
+        keyword::KeywordRegistry,
+        search::LibrarySearch,
+        zones::{ActivePlayer, GameMode, ZoneKind, Zones},
+    },
+    input::GameAction,
+    loading::FontAssets,
+    locale::Localization,
+    pointer::Pointer,
+    AppState, GameplayEntity,
+};
+
+/// Pixels scrolled per line of the mouse wheel.
+const SCROLL_LINE: f32 = 40.;
+
+/// Present only while the browser is open.
+#[derive(Resource, Debug)]
+pub struct PileBrowserOpen;
+
+#[derive(Component)]
+pub struct PileBrowser;
+
+/// The grid of cards, moved up and down inside its clipped panel.
+#[derive(Component, Default)]
+pub struct PileBrowserList {
+    offset: f32,
+}
+
+#[derive(Component)]
+pub struct ClosePileButton;
+
+pub struct PileBrowserPlugin;
+impl Plugin for PileBrowserPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                open_pile_browser
+                    .after(select_card)
+                    .run_if(not(resource_exists::<PileBrowserOpen>()))
+                    .run_if(not(resource_exists::<LibrarySearch>())),
+                (close_pile_browser, press_close_pile, scroll_pile_browser)
+                    .run_if(resource_exists::<PileBrowserOpen>()),
+            )
+                .run_if(in_state(AppState::Playing)),
+        )
+        .add_systems(OnExit(AppState::Playing), forget_browsed_pile);
+    }
+}
+
+/// Whether `pos`, in the card camera's world, is on the pile at `zone`.
+fn on_pile(pos: Vec2, zone: &GlobalTransform) -> bool {
+    // NOTE: the same size as a card, piles are no wider than the card on top
+    let offset = (pos - zone.translation().truncate()).abs();
+    offset.x <= 70. && offset.y <= 95.
+}
+
+fn open_pile_browser(
+    mut cmd: Commands,
+    actions: Res<ActionState<GameAction>>,
+    pointer: Res<Pointer>,
+    zones: Res<Zones>,
+    mode: Res<GameMode>,
+    active: Res<ActivePlayer>,
+    q_hands: Query<&Hand>,
+    q_zones: Query<(&GlobalTransform, Option<&Children>), Without<Card>>,
+    q_cards: Query<&Card>,
+    definitions: Res<CardDefinitions>,
+    registry: Res<KeywordRegistry>,
+    loc: Localization,
+    fonts: Res<FontAssets>,
+) {
+    if !actions.just_pressed(GameAction::Select) {
+        return;
+    }
+    let Some(pos) = pointer.cards else {
+        return;
+    };
+    if q_hands
+        .iter()
+        .any(|hand| hand.selected.is_some() || hand.hovered.is_some())
+    {
+        return;
+    }
+    let Some((kind, children)) =
+        [ZoneKind::Discard, ZoneKind::Library]
+            .into_iter()
+            .find_map(|kind| {
+                let (zone_g, children) =
+                    q_zones.get(zones.get(mode.viewer(active.0), kind)?).ok()?;
+                on_pile(pos, zone_g).then_some((kind, children))
+            })
+    else {
+        return;
+    };
+
+    let mut cards: Vec<&CardDefinition> = children
+        .into_iter()
+        .flat_map(|children| children.iter())
+        .filter_map(|&child| definitions.get(q_cards.get(child).ok()?.definition.as_ref()?))
+        .collect();
+    let title = match kind {
+        ZoneKind::Library => {
+            cards.sort_by(|a, b| a.name.cmp(&b.name));
+            format!("Library: {} cards, in no particular order", cards.len())
+        },
+        _ => format!("Discard: {} cards, top first", cards.len()),
+    };
+    let style = |font_size: f32, color: Color| TextStyle {
+        font: fonts.fira.clone_weak(),
+        font_size,
+        color,
+    };
+
+    cmd.insert_resource(PileBrowserOpen);
+    cmd.spawn((
+        NodeBundle {
+            background_color: Color::rgba(0., 0., 0., 0.8).into(),
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(20.),
+                ..default()
+            },
+            z_index: ZIndex::Global(20),
+            ..default()
+        },
+        PileBrowser,
+        GameplayEntity,
+    ))
+    .with_children(|parent| {
+        parent.spawn(TextBundle::from_section(title, style(40., Color::WHITE)));
+        parent
+            .spawn(NodeBundle {
+                style: Style {
+                    width: Val::Percent(80.),
+                    height: Val::Percent(65.),
+                    overflow: Overflow::clip_y(),
+                    ..default()
+                },
+                ..default()
+            })
+            .with_children(|panel| {
+                panel
+                    .spawn((
+                        NodeBundle {
+                            style: Style {
+                                position_type: PositionType::Absolute,
+                                width: Val::Percent(100.),
+                                flex_wrap: FlexWrap::Wrap,
+                                justify_content: JustifyContent::Center,
+                                column_gap: Val::Px(16.),
+                                row_gap: Val::Px(16.),
+                                ..default()
+                            },
+                            ..default()
+                        },
+                        PileBrowserList::default(),
+                    ))
+                    .with_children(|list| {
+                        for definition in cards {
+                            spawn_card_preview(list, definition, &fonts, &registry, &loc, ());
+                        }
+                    });
+            });
+        parent
+            .spawn((
+                ButtonBundle {
+                    style: Style {
+                        width: Val::Px(200.),
+                        height: Val::Px(60.),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    background_color: BackgroundColor::from(NORMAL_BUTTON_COLOR),
+                    ..default()
+                },
+                ClosePileButton,
+            ))
+            .with_children(|button| {
+                button.spawn(TextBundle::from_section("Close", style(28., Color::BLACK)));
+            });
+    });
+}
+
+fn despawn_pile_browser(cmd: &mut Commands, q_browser: &Query<Entity, With<PileBrowser>>) {
+    cmd.remove_resource::<PileBrowserOpen>();
+    for entity in q_browser.iter() {
+        cmd.entity(entity).despawn_recursive();
+    }
+}
+
+fn close_pile_browser(
+    mut cmd: Commands,
+    actions: Res<ActionState<GameAction>>,
+    q_browser: Query<Entity, With<PileBrowser>>,
+) {
+    if actions.just_pressed(GameAction::Pause) || actions.just_pressed(GameAction::Cancel) {
+        despawn_pile_browser(&mut cmd, &q_browser);
+    }
+}
+
+fn press_close_pile(
+    mut cmd: Commands,
+    mut q_button: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<ClosePileButton>),
+    >,
+    q_browser: Query<Entity, With<PileBrowser>>,
+) {
+    if let Ok((interaction, mut color)) = q_button.get_single_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = BackgroundColor::from(PRESS_BUTTON_COLOR);
+                despawn_pile_browser(&mut cmd, &q_browser);
+            },
+            Interaction::Hovered => {
+                *color = BackgroundColor::from(HOVER_BUTTON_COLOR);
+            },
+            Interaction::None => {
+                *color = BackgroundColor::from(NORMAL_BUTTON_COLOR);
+            },
+        }
+    }
+}
+
+fn scroll_pile_browser(
+    mut wheel: EventReader<MouseWheel>,
+    mut q_list: Query<(&mut PileBrowserList, &mut Style, &Parent, &Node)>,
+    q_panel: Query<&Node, Without<PileBrowserList>>,
+) {
+    let scrolled: f32 = wheel
+        .read()
+        .map(|event| match event.unit {
+            MouseScrollUnit::Line => event.y * SCROLL_LINE,
+            MouseScrollUnit::Pixel => event.y,
+        })
+        .sum();
+    if scrolled == 0. {
+        return;
+    }
+    for (mut list, mut style, parent, node) in q_list.iter_mut() {
+        let Ok(panel) = q_panel.get(parent.get()) else {
+            continue;
+        };
+        let max_scroll = (node.size().y - panel.size().y).max(0.);
+        list.offset = (list.offset + scrolled).clamp(-max_scroll, 0.);
+        style.top = Val::Px(list.offset);
+    }
+}
+
+fn forget_browsed_pile(mut cmd: Commands) {
+    cmd.remove_resource::<PileBrowserOpen>();
+}
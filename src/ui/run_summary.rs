@@ -0,0 +1,130 @@
+//! The run summary beside the scoreboard: the `RunStats` of the run that just ended, and a line of
+//! lifetime totals under it.
+
+use bevy::prelude::*;
+use strum::IntoEnumIterator;
+
+use crate::{
+    cards::GameState,
+    game_over::run_decided,
+    game_shapes::GamePolygon,
+    loading::FontAssets,
+    stats::{record_run, LifetimeStats, RunStats},
+    AppState,
+};
+
+const PANEL_COLOR: Color = Color::rgba(0.1, 0.1, 0.15, 0.85);
+
+#[derive(Component)]
+pub struct RunSummary;
+
+pub struct RunSummaryPlugin;
+impl Plugin for RunSummaryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(GameState::Scoring),
+            spawn_run_summary.after(record_run).run_if(not(run_decided)),
+        )
+        .add_systems(OnEnter(AppState::Won), spawn_run_summary)
+        .add_systems(OnEnter(AppState::Lost), spawn_run_summary)
+        // NOTE: like the scoreboard, gone with the run or its end screen
+        .add_systems(OnExit(AppState::Playing), despawn_run_summary)
+        .add_systems(OnExit(AppState::Won), despawn_run_summary)
+        .add_systems(OnExit(AppState::Lost), despawn_run_summary);
+    }
+}
+
+fn summary_lines(run: &RunStats, lifetime: &LifetimeStats) -> Vec<String> {
+    let mut lines = vec![
+        format!(
+            "Shapes: {} spawned, {} merged",
+            run.total_spawned(),
+            run.total_merged()
+        ),
+        format!("Cards played: {}", run.cards_played),
+        format!("Longest combo: {}", run.longest_combo),
+        format!(
+            "Wall hits: {} ({:.0} damage)",
+            run.wall_hits, run.wall_damage
+        ),
+    ];
+    lines.extend(GamePolygon::iter().filter_map(|polygon| {
+        let spawned = run.spawned.get(&polygon).copied().unwrap_or(0);
+        let merged = run.merged.get(&polygon).copied().unwrap_or(0);
+        (spawned + merged > 0).then(|| format!("  {polygon:?}: {spawned} spawned, {merged} merged"))
+    }));
+
+    let mut phases: Vec<_> = run
+        .phase_time
+        .iter()
+        .filter(|(_, &seconds)| seconds >= 1.)
+        .collect();
+    phases.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+    lines.push(format!("Time: {:.0}s", run.play_time()));
+    lines.extend(
+        phases
+            .into_iter()
+            .map(|(phase, seconds)| format!("  {phase:?}: {seconds:.0}s")),
+    );
+
+    lines.push(String::new());
+    lines.push(format!(
+        "Lifetime: {} runs, {} shapes merged, {} cards played, best combo {}",
+        lifetime.runs,
+        lifetime.total_merged(),
+        lifetime.cards_played,
+        lifetime.longest_combo
+    ));
+    lines
+}
+
+fn spawn_run_summary(
+    mut cmd: Commands,
+    run: Res<RunStats>,
+    lifetime: Res<LifetimeStats>,
+    fonts: Res<FontAssets>,
+) {
+    cmd.spawn((
+        NodeBundle {
+            background_color: PANEL_COLOR.into(),
+            style: Style {
+                position_type: PositionType::Absolute,
+                top: Val::Percent(15.),
+                left: Val::Percent(70.),
+                width: Val::Percent(28.),
+                padding: UiRect::all(Val::Px(20.)),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(6.),
+                ..default()
+            },
+            ..default()
+        },
+        RunSummary,
+    ))
+    .with_children(|parent| {
+        parent.spawn(TextBundle::from_section(
+            "Run Summary",
+            TextStyle {
+                font: fonts.fira.clone(),
+                font_size: 28.,
+                color: Color::WHITE,
+            },
+        ));
+        for line in summary_lines(&run, &lifetime) {
+            parent.spawn(TextBundle::from_section(
+                line,
+                TextStyle {
+                    font: fonts.fira.clone(),
+                    font_size: 18.,
+                    color: Color::WHITE,
+                },
+            ));
+        }
+    });
+}
+
+fn despawn_run_summary(mut cmd: Commands, q_summary: Query<Entity, With<RunSummary>>) {
+    for entity in q_summary.iter() {
+        cmd.entity(entity).despawn_recursive();
+    }
+}
@@ -8,16 +8,38 @@ use bevy_tweening::{
 use super::hud::{HOVER_BUTTON_COLOR, NORMAL_BUTTON_COLOR, PRESS_BUTTON_COLOR};
 use crate::{
     board::IsOnBoard,
-    cards::{goals::Goals, hand::TransformLens, GameState, Score},
+    cards::{
+        goals::Goals,
+        zones::{ActivePlayer, GameMode, Owner, PlayerId, Winner},
+        GameState, GameTimer,
+    },
+    daily::record_daily_score,
+    endless::Waves,
+    game_over::{run_decided, GameOutcome, Outcome},
     game_shapes::Shape,
+    level::CurrentLevel,
     loading::FontAssets,
+    score::Score,
     AppState,
 };
 #[derive(Component)]
 pub struct MainMenuButton;
+/// Moves on to the next level. Only shown once a level is won or cleared.
+#[derive(Component)]
+pub struct NextLevelButton;
 #[derive(Component)]
 pub struct Scoreboard;
 
+/// What the scoreboard shows, tallied on entering `GameState::Scoring` while the board is still up,
+/// so it outlasts the run when a decided run moves on to `AppState::Won` / `AppState::Lost`.
+#[derive(Resource, Default)]
+pub struct FinalScore {
+    pub title: String,
+    pub score: Score,
+    /// Whether the run was won or cleared, so the next level opens.
+    pub next_level: bool,
+}
+
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub struct CountUpLens {
     /// Start value.
@@ -35,44 +57,99 @@ impl Lens<Text> for CountUpLens {
 pub struct ScoreUIPlugin;
 impl Plugin for ScoreUIPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(GameState::Scoring), (spawn_scoreboard))
-            .add_systems(Update, (press_menu).run_if(in_state(GameState::Scoring)))
+        app.init_resource::<FinalScore>()
+            .add_systems(
+                OnEnter(GameState::Scoring),
+                (
+                    tally_score.before(record_daily_score),
+                    spawn_scoreboard.after(tally_score).run_if(not(run_decided)),
+                ),
+            )
+            .add_systems(OnEnter(AppState::Won), spawn_scoreboard)
+            .add_systems(OnEnter(AppState::Lost), spawn_scoreboard)
+            .add_systems(
+                Update,
+                (press_menu, press_next_level).run_if(
+                    in_state(GameState::Scoring)
+                        .or_else(in_state(AppState::Won))
+                        .or_else(in_state(AppState::Lost)),
+                ),
+            )
             .add_systems(Update, component_animator_system::<Text>)
-            .add_systems(OnExit(GameState::Scoring), despawn_scoreboard);
+            // NOTE: `Scoring` only ends with the run, which may be heading for `Won` or `Lost`
+            // with its scoreboard still to come
+            .add_systems(OnExit(AppState::Playing), despawn_scoreboard)
+            .add_systems(OnExit(AppState::Won), despawn_scoreboard)
+            .add_systems(OnExit(AppState::Lost), despawn_scoreboard);
     }
 }
 
-pub fn spawn_scoreboard(
-    mut cmd: Commands,
-    q_goals: Query<&Goals>,
+pub fn tally_score(
+    q_goals: Query<(&Goals, &Owner)>,
     q_shapes: Query<&Shape, With<IsOnBoard>>,
-    fonts: Res<FontAssets>,
     mut score: ResMut<Score>,
+    mut final_score: ResMut<FinalScore>,
+    mode: Res<GameMode>,
+    active: Res<ActivePlayer>,
+    winner: Res<Winner>,
+    outcome: Res<GameOutcome>,
+    level: Res<CurrentLevel>,
+    game_timer: Res<GameTimer>,
+    waves: Res<Waves>,
 ) {
-    for shape in q_shapes.iter() {
+    for _shape in q_shapes.iter() {
         score.base_score += 1;
     }
     score.score = score.base_score;
 
-    for (i, goal) in q_goals.single().iter().enumerate() {
-        let mut lhs = 0;
-        let mut rhs = 0;
-        for shape in q_shapes.iter() {
-            if goal.s1 == *shape {
-                lhs += 1;
-            }
-            if goal.s2 == *shape {
-                rhs += 1;
-            }
-        }
-
-        if lhs > rhs {
+    // NOTE: in hotseat the winner's goals are scored, or the last player's if time ran out;
+    // against the opponent, the player's own
+    let player = winner.unwrap_or(mode.viewer(active.0));
+    let goals = q_goals
+        .iter()
+        .find(|(_, owner)| owner.0 == player)
+        .map(|(goals, _)| goals);
+    for (i, goal) in goals.into_iter().flat_map(|g| g.iter()).enumerate() {
+        if goal.is_met(q_shapes.iter()) {
             score.goal_status[i] = true;
             score.score *= 2;
         }
     }
+    let survived = game_timer.timer.elapsed_secs() as u32;
+    let title = match (*mode, **winner, **outcome) {
+        (GameMode::Endless, ..) => {
+            format!("You held out for {survived}s, into wave {}!", waves.number)
+        },
+        (_, _, Some(Outcome::Lost)) => "The board overflowed!".to_string(),
+        (_, _, Some(Outcome::Cleared)) => format!("Level {} cleared!", level.number),
+        (GameMode::Solo, _, Some(Outcome::Won)) => "You made the target shape!".to_string(),
+        (GameMode::Solo, ..) => "Great Job!".to_string(),
+        (GameMode::Hotseat, Some(player), _) => format!("Player {} wins!", player.0 + 1),
+        (GameMode::Hotseat | GameMode::Versus, None, _) => "Time's up!".to_string(),
+        (GameMode::Versus, Some(PlayerId(0)), _) => "You beat the opponent!".to_string(),
+        (GameMode::Versus, Some(_), _) => "The opponent wins!".to_string(),
+    };
     score.score += score.cards_played * 100;
-    let scoreboard = cmd
+    score.score += score.combine_points;
+    // NOTE: in endless mode surviving is all that counts
+    if *mode == GameMode::Endless {
+        score.score = survived;
+    }
+    *final_score = FinalScore {
+        title,
+        score: score.clone(),
+        next_level: matches!(**outcome, Some(Outcome::Won | Outcome::Cleared)),
+    };
+}
+
+pub fn spawn_scoreboard(
+    mut cmd: Commands,
+    fonts: Res<FontAssets>,
+    final_score: Res<FinalScore>,
+    level: Res<CurrentLevel>,
+) {
+    let score = &final_score.score;
+    let _scoreboard = cmd
         .spawn((
             NodeBundle {
                 background_color: Color::rgb(153. / 255., 173. / 255., 211. / 255.).into(),
@@ -103,7 +180,7 @@ pub fn spawn_scoreboard(
                 },
                 text: Text {
                     sections: vec![TextSection::new(
-                        "Great Job!",
+                        final_score.title.clone(),
                         TextStyle {
                             font: fonts.fira.clone(),
                             font_size: 32.0,
@@ -315,12 +392,41 @@ pub fn spawn_scoreboard(
                         ..default()
                     });
                 });
+            if final_score.next_level {
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                width: Val::Px(200.),
+                                height: Val::Px(80.0),
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                ..default()
+                            },
+
+                            background_color: BackgroundColor::from(NORMAL_BUTTON_COLOR),
+                            ..default()
+                        },
+                        NextLevelButton,
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn(TextBundle::from_section(
+                            format!("Level {}", level.number + 1),
+                            TextStyle {
+                                font: fonts.fira.clone_weak(),
+                                font_size: 32.0,
+                                color: Color::BLACK,
+                            },
+                        ));
+                    });
+            }
         })
         .id();
 }
 pub fn despawn_scoreboard(mut cmd: Commands, score_q: Query<Entity, With<Scoreboard>>) {
-    println!("DESPAWN SCORE");
-    cmd.entity(score_q.single()).despawn_recursive();
+    for entity in score_q.iter() {
+        cmd.entity(entity).despawn_recursive();
+    }
 }
 pub fn press_menu(
     mut cmd: Commands,
@@ -344,3 +450,27 @@ pub fn press_menu(
         }
     }
 }
+pub fn press_next_level(
+    mut cmd: Commands,
+    mut q_button: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<NextLevelButton>),
+    >,
+    level: Res<CurrentLevel>,
+) {
+    if let Ok((interaction, mut color)) = q_button.get_single_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = BackgroundColor::from(PRESS_BUTTON_COLOR);
+                cmd.insert_resource(CurrentLevel(level.next()));
+                cmd.insert_resource(NextState(Some(AppState::CardReward)));
+            },
+            Interaction::Hovered => {
+                *color = BackgroundColor::from(HOVER_BUTTON_COLOR);
+            },
+            Interaction::None => {
+                *color = BackgroundColor::from(NORMAL_BUTTON_COLOR);
+            },
+        }
+    }
+}
@@ -0,0 +1,118 @@
+//! The board statuses running, under the timer: an icon in the status's color and the seconds it
+//! has left.
+
+use bevy::prelude::*;
+
+use crate::{
+    board::status::{BoardStatus, StatusKind},
+    loading::FontAssets,
+    AppState, GameplayEntity,
+};
+
+const ICON_SIZE: f32 = 28.;
+
+#[derive(Component)]
+pub struct StatusBar;
+
+/// The time left on the status of this kind.
+#[derive(Component)]
+pub struct StatusTimerText(StatusKind);
+
+pub struct StatusBarPlugin;
+impl Plugin for StatusBarPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::Playing), spawn_status_bar)
+            .add_systems(
+                Update,
+                update_status_bar.run_if(in_state(AppState::Playing)),
+            );
+    }
+}
+
+fn spawn_status_bar(mut cmd: Commands) {
+    cmd.spawn((
+        NodeBundle {
+            style: Style {
+                top: Val::Px(80.),
+                left: Val::Px(30.),
+                position_type: PositionType::Absolute,
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(8.),
+                ..default()
+            },
+            ..default()
+        },
+        StatusBar,
+        GameplayEntity,
+    ));
+}
+
+/// Rebuilds the bar when a status starts or wears off, and counts the rest down.
+fn update_status_bar(
+    mut cmd: Commands,
+    status: Res<BoardStatus>,
+    q_bar: Query<Entity, With<StatusBar>>,
+    mut q_timers: Query<(&StatusTimerText, &mut Text)>,
+    fonts: Res<FontAssets>,
+) {
+    let Ok(bar) = q_bar.get_single() else {
+        return;
+    };
+    let shown = q_timers.iter().count() == status.iter().count()
+        && q_timers
+            .iter()
+            .all(|(timer, _)| status.iter().any(|active| active.kind == timer.0));
+    if shown {
+        for (timer, mut text) in q_timers.iter_mut() {
+            let Some(active) = status.iter().find(|active| active.kind == timer.0) else {
+                continue;
+            };
+            let label = format!(
+                "{} {:.0}s",
+                timer.0.name(),
+                active.timer.remaining_secs().ceil()
+            );
+            if text.sections[0].value != label {
+                text.sections[0].value = label;
+            }
+        }
+        return;
+    }
+
+    cmd.entity(bar).despawn_descendants();
+    cmd.entity(bar).with_children(|parent| {
+        for active in status.iter() {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        align_items: AlignItems::Center,
+                        column_gap: Val::Px(10.),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|row| {
+                    row.spawn(NodeBundle {
+                        style: Style {
+                            width: Val::Px(ICON_SIZE),
+                            height: Val::Px(ICON_SIZE),
+                            ..default()
+                        },
+                        background_color: active.kind.color().into(),
+                        ..default()
+                    });
+                    row.spawn((
+                        TextBundle::from_section(
+                            active.kind.name(),
+                            TextStyle {
+                                font: fonts.fira.clone(),
+                                font_size: 24.,
+                                color: active.kind.color(),
+                            },
+                        ),
+                        StatusTimerText(active.kind),
+                    ));
+                });
+        }
+    });
+}
@@ -0,0 +1,210 @@
+//! A tooltip for the card hovered in the hand (or focused with a gamepad): its name and full
+//! rules text, with keywords highlighted, and a smaller box under it explaining each keyword the
+//! card has. It follows the mouse, and sits above the hand otherwise.
+
+use bevy::{prelude::*, window::PrimaryWindow};
+
+use crate::{
+    cards::{
+        card::Card,
+        definition::CardDefinitions,
+        hand::{select_card, Hand},
+        keyword::KeywordRegistry,
+    },
+    loading::FontAssets,
+    locale::Localization,
+    AppState, GameplayEntity,
+};
+
+const TOOLTIP_WIDTH: f32 = 320.;
+/// Pixels between the cursor and the tooltip's corner.
+const CURSOR_OFFSET: f32 = 24.;
+
+#[derive(Component)]
+pub struct CardTooltip;
+
+#[derive(Component)]
+struct TooltipText;
+
+/// Holds one box per keyword on the card.
+#[derive(Component)]
+struct KeywordTooltips;
+
+pub struct TooltipPlugin;
+impl Plugin for TooltipPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::Playing), spawn_tooltip)
+            .add_systems(
+                Update,
+                (update_tooltip, position_tooltip)
+                    .chain()
+                    .after(select_card)
+                    .run_if(in_state(AppState::Playing)),
+            );
+    }
+}
+
+fn spawn_tooltip(mut cmd: Commands) {
+    cmd.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Px(TOOLTIP_WIDTH),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(6.),
+                ..default()
+            },
+            visibility: Visibility::Hidden,
+            z_index: ZIndex::Global(50),
+            ..default()
+        },
+        CardTooltip,
+        GameplayEntity,
+    ))
+    .with_children(|tooltip| {
+        tooltip
+            .spawn(NodeBundle {
+                style: Style {
+                    padding: UiRect::all(Val::Px(10.)),
+                    ..default()
+                },
+                background_color: BackgroundColor(Color::rgba(0., 0., 0., 0.85)),
+                ..default()
+            })
+            .with_children(|panel| {
+                panel.spawn((TextBundle::default(), TooltipText));
+            });
+        tooltip.spawn((
+            NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(4.),
+                    ..default()
+                },
+                ..default()
+            },
+            KeywordTooltips,
+        ));
+    });
+}
+
+fn update_tooltip(
+    mut cmd: Commands,
+    q_hands: Query<&Hand>,
+    q_cards: Query<&Card>,
+    definitions: Res<CardDefinitions>,
+    registry: Res<KeywordRegistry>,
+    fonts: Res<FontAssets>,
+    loc: Localization,
+    mut q_tooltip: Query<&mut Visibility, With<CardTooltip>>,
+    mut q_text: Query<&mut Text, With<TooltipText>>,
+    q_keywords: Query<Entity, With<KeywordTooltips>>,
+    mut shown: Local<Option<Entity>>,
+) {
+    // NOTE: no tooltip while dragging, the card is in the way of the board
+    let hovered = q_hands
+        .iter()
+        .find(|hand| hand.selected.is_none() && hand.hovered.is_some())
+        .and_then(|hand| hand.hovered);
+    let definition = hovered
+        .and_then(|entity| q_cards.get(entity).ok())
+        .and_then(|card| definitions.get(card.definition.as_ref()?));
+    let (Ok(mut visibility), Ok(mut text), Ok(keywords_e)) = (
+        q_tooltip.get_single_mut(),
+        q_text.get_single_mut(),
+        q_keywords.get_single(),
+    ) else {
+        return;
+    };
+    let Some(definition) = definition else {
+        *visibility = Visibility::Hidden;
+        *shown = None;
+        return;
+    };
+    *visibility = Visibility::Inherited;
+    if *shown == hovered && !loc.is_changed() {
+        return;
+    }
+    *shown = hovered;
+
+    let style = TextStyle {
+        font: fonts.fira.clone(),
+        font_size: 20.,
+        color: Color::WHITE,
+    };
+    text.sections = vec![TextSection::new(
+        format!("{}\n", loc.card_name(definition)),
+        TextStyle {
+            font_size: 26.,
+            color: Color::GOLD,
+            ..style.clone()
+        },
+    )];
+    text.sections
+        .extend(registry.highlight(&loc.card_text(definition), &style));
+
+    cmd.entity(keywords_e).despawn_descendants();
+    cmd.entity(keywords_e).with_children(|list| {
+        for info in definition.keywords.iter().filter_map(|k| registry.get(*k)) {
+            list.spawn(NodeBundle {
+                style: Style {
+                    padding: UiRect::all(Val::Px(8.)),
+                    ..default()
+                },
+                background_color: BackgroundColor(Color::rgba(0.1, 0.1, 0.15, 0.85)),
+                ..default()
+            })
+            .with_children(|panel| {
+                panel.spawn(TextBundle::from_sections([
+                    TextSection::new(
+                        format!("{}\n", info.name),
+                        TextStyle {
+                            color: info.color,
+                            ..style.clone()
+                        },
+                    ),
+                    TextSection::new(
+                        loc.keyword_description(info),
+                        TextStyle {
+                            font_size: 16.,
+                            ..style.clone()
+                        },
+                    ),
+                ]));
+            });
+        }
+    });
+}
+
+fn position_tooltip(
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    mut q_tooltip: Query<(&mut Style, &Visibility), With<CardTooltip>>,
+) {
+    let Ok((mut style, visibility)) = q_tooltip.get_single_mut() else {
+        return;
+    };
+    if *visibility == Visibility::Hidden {
+        return;
+    }
+    let Ok(window) = q_window.get_single() else {
+        return;
+    };
+    // NOTE: the tooltip opens away from the closer edge so it stays on screen
+    match window.cursor_position() {
+        Some(cursor) => {
+            let left = if cursor.x + CURSOR_OFFSET + TOOLTIP_WIDTH > window.width() {
+                cursor.x - CURSOR_OFFSET - TOOLTIP_WIDTH
+            } else {
+                cursor.x + CURSOR_OFFSET
+            };
+            style.left = Val::Px(left.max(0.));
+            style.top = Val::Auto;
+            style.bottom = Val::Px(window.height() - cursor.y + CURSOR_OFFSET);
+        },
+        None => {
+            style.left = Val::Px((window.width() - TOOLTIP_WIDTH) / 2.);
+            style.top = Val::Auto;
+            style.bottom = Val::Px(300.);
+        },
+    }
+}
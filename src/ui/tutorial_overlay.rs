@@ -0,0 +1,240 @@
+//! The tutorial's overlay: the current step's text in a panel at the top, and four dark panels
+//! framing the step's target so everything else is dimmed. The dimmed panels also keep the
+//! mouse off the buttons under them.
+
+use bevy::{prelude::*, ui::FocusPolicy, window::PrimaryWindow};
+use leafwing_input_manager::Actionlike;
+
+use super::hud::{EnergyText, RulesText, ScoreText, TimerText};
+use crate::{
+    camera::{BoardCamera, CardCamera},
+    cards::zones::{ActivePlayer, Zones},
+    input::{binding_label, GameAction},
+    loading::FontAssets,
+    locale::Localization,
+    settings::Settings,
+    tutorial::{HudElement, Tutorial, TutorialTarget},
+};
+
+const DIM_COLOR: Color = Color::rgba(0., 0., 0., 0.6);
+const PANEL_COLOR: Color = Color::rgba(0.1, 0.1, 0.15, 0.9);
+/// Room left around the target, in pixels.
+const TARGET_MARGIN: f32 = 12.;
+
+#[derive(Component)]
+pub struct TutorialOverlay;
+
+/// One of the four panels around the target: above, below, left and right of it.
+#[derive(Component)]
+pub struct TutorialDim(usize);
+
+#[derive(Component)]
+pub struct TutorialText;
+
+pub struct TutorialOverlayPlugin;
+impl Plugin for TutorialOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                spawn_overlay.run_if(resource_added::<Tutorial>()),
+                despawn_overlay.run_if(resource_removed::<Tutorial>()),
+                (update_text, update_dims).run_if(resource_exists::<Tutorial>()),
+            )
+                .chain(),
+        );
+    }
+}
+
+fn spawn_overlay(mut cmd: Commands, fonts: Res<FontAssets>) {
+    cmd.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                ..default()
+            },
+            focus_policy: FocusPolicy::Pass,
+            z_index: ZIndex::Global(50),
+            ..default()
+        },
+        TutorialOverlay,
+    ))
+    .with_children(|parent| {
+        for i in 0..4 {
+            parent.spawn((
+                NodeBundle {
+                    background_color: DIM_COLOR.into(),
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        display: Display::None,
+                        ..default()
+                    },
+                    focus_policy: FocusPolicy::Block,
+                    ..default()
+                },
+                TutorialDim(i),
+            ));
+        }
+        parent
+            .spawn(NodeBundle {
+                background_color: PANEL_COLOR.into(),
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Percent(12.),
+                    left: Val::Percent(25.),
+                    width: Val::Percent(50.),
+                    padding: UiRect::all(Val::Px(20.)),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                ..default()
+            })
+            .with_children(|parent| {
+                parent.spawn((
+                    TextBundle::from_section(
+                        "",
+                        TextStyle {
+                            font: fonts.fira.clone(),
+                            font_size: 28.,
+                            color: Color::WHITE,
+                        },
+                    )
+                    .with_text_alignment(TextAlignment::Center),
+                    TutorialText,
+                ));
+            });
+    });
+}
+
+fn despawn_overlay(mut cmd: Commands, q_overlay: Query<Entity, With<TutorialOverlay>>) {
+    for entity in q_overlay.iter() {
+        cmd.entity(entity).despawn_recursive();
+    }
+}
+
+/// The step's text in the current language, with every `{Action}` replaced by what the action is
+/// bound to.
+fn update_text(
+    tutorial: Res<Tutorial>,
+    settings: Res<Settings>,
+    loc: Localization,
+    mut q_text: Query<&mut Text, With<TutorialText>>,
+) {
+    if !tutorial.is_changed() && !loc.is_changed() {
+        return;
+    }
+    let Some(step) = tutorial.step() else {
+        return;
+    };
+    let text = GameAction::variants().fold(loc.get(&step.text), |text, action| {
+        text.replace(
+            &format!("{{{action:?}}}"),
+            &binding_label(&settings.input_map, action),
+        )
+    });
+    for mut current in q_text.iter_mut() {
+        current.sections[0].value.clone_from(&text);
+    }
+}
+
+/// Where the step's target is on screen, in logical pixels, y down.
+fn target_rect(
+    target: TutorialTarget,
+    q_hud: &Query<(
+        &Node,
+        &GlobalTransform,
+        AnyOf<(&TimerText, &ScoreText, &RulesText, &EnergyText)>,
+    )>,
+    q_board_camera: &Query<(&Camera, &GlobalTransform), (With<BoardCamera>, Without<CardCamera>)>,
+    q_card_camera: &Query<(&Camera, &GlobalTransform), (With<CardCamera>, Without<BoardCamera>)>,
+    q_transforms: &Query<&GlobalTransform>,
+    zones: &Zones,
+    active: &ActivePlayer,
+) -> Option<Rect> {
+    let project = |camera: (&Camera, &GlobalTransform), center: Vec2, half_size: Vec2| {
+        let (camera, transform) = camera;
+        let a = camera.world_to_viewport(transform, (center - half_size).extend(0.))?;
+        let b = camera.world_to_viewport(transform, (center + half_size).extend(0.))?;
+        Some(Rect::from_corners(a, b))
+    };
+    match target {
+        TutorialTarget::None => None,
+        TutorialTarget::Hud(element) => q_hud
+            .iter()
+            .find(|(.., (timer, score, rules, energy))| match element {
+                HudElement::Timer => timer.is_some(),
+                HudElement::Score => score.is_some(),
+                HudElement::Rules => rules.is_some(),
+                HudElement::Energy => energy.is_some(),
+            })
+            .map(|(node, transform, _)| {
+                Rect::from_center_size(transform.translation().truncate(), node.size())
+            }),
+        TutorialTarget::Board { center, half_size } => {
+            project(q_board_camera.get_single().ok()?, center, half_size)
+        },
+        TutorialTarget::Zone { kind, half_size } => {
+            let zone = zones.get(active.0, kind)?;
+            let center = q_transforms.get(zone).ok()?.translation().truncate();
+            project(q_card_camera.get_single().ok()?, center, half_size)
+        },
+    }
+}
+
+fn update_dims(
+    tutorial: Res<Tutorial>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    q_hud: Query<(
+        &Node,
+        &GlobalTransform,
+        AnyOf<(&TimerText, &ScoreText, &RulesText, &EnergyText)>,
+    )>,
+    q_board_camera: Query<(&Camera, &GlobalTransform), (With<BoardCamera>, Without<CardCamera>)>,
+    q_card_camera: Query<(&Camera, &GlobalTransform), (With<CardCamera>, Without<BoardCamera>)>,
+    q_transforms: Query<&GlobalTransform>,
+    zones: Res<Zones>,
+    active: Res<ActivePlayer>,
+    mut q_dims: Query<(&TutorialDim, &mut Style)>,
+) {
+    let Ok(window) = q_window.get_single() else {
+        return;
+    };
+    let rect = tutorial.step().and_then(|step| {
+        target_rect(
+            step.target,
+            &q_hud,
+            &q_board_camera,
+            &q_card_camera,
+            &q_transforms,
+            &zones,
+            &active,
+        )
+    });
+    let screen = Rect::new(0., 0., window.width(), window.height());
+    for (dim, mut style) in q_dims.iter_mut() {
+        let Some(rect) = rect else {
+            style.display = Display::None;
+            continue;
+        };
+        let hole = rect.inset(TARGET_MARGIN).intersect(screen);
+        let (min, max) = match dim.0 {
+            0 => (screen.min, Vec2::new(screen.max.x, hole.min.y)),
+            1 => (Vec2::new(screen.min.x, hole.max.y), screen.max),
+            2 => (
+                Vec2::new(screen.min.x, hole.min.y),
+                Vec2::new(hole.min.x, hole.max.y),
+            ),
+            _ => (
+                Vec2::new(hole.max.x, hole.min.y),
+                Vec2::new(screen.max.x, hole.max.y),
+            ),
+        };
+        style.display = Display::Flex;
+        style.left = Val::Px(min.x);
+        style.top = Val::Px(min.y);
+        style.width = Val::Px((max.x - min.x).max(0.));
+        style.height = Val::Px((max.y - min.y).max(0.));
+    }
+}
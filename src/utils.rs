@@ -1,7 +1,43 @@
 use core::{iter::Sum, ops::Div};
 use std::f32::consts::PI;
 
-use bevy::prelude::{Transform, Vec2, Vec3};
+use bevy::{
+    log::warn,
+    prelude::{Transform, Vec2, Vec3},
+};
+
+use crate::platform::{Duration, Instant};
+
+/// Warns about a precondition a system gives up on, logging the first occurrence right away and
+/// then at most once every few seconds. Keep one per call site in a `Local`.
+#[derive(Default)]
+pub struct ThrottledWarn {
+    last: Option<Instant>,
+    suppressed: u32,
+}
+
+impl ThrottledWarn {
+    const INTERVAL: Duration = Duration::from_secs(10);
+
+    pub fn warn(&mut self, message: &str) {
+        let now = Instant::now();
+        if self
+            .last
+            .is_some_and(|last| now.duration_since(last) < Self::INTERVAL)
+        {
+            self.suppressed += 1;
+            return;
+        }
+
+        if self.suppressed > 0 {
+            warn!("{message} (repeated {} times)", self.suppressed);
+        } else {
+            warn!("{message}");
+        }
+        self.last = Some(now);
+        self.suppressed = 0;
+    }
+}
 
 pub fn calculate_rotated_bounds(
     transform: &Transform,
@@ -67,7 +103,7 @@ pub fn average<T>(items: &[T]) -> T
 where
     T: Sum + Div<f32, Output = T> + Clone + Default,
 {
-    if items.len() == 0 {
+    if items.is_empty() {
         T::default()
     } else {
         items.iter().cloned().sum::<T>() / (items.len() as f32)
@@ -85,6 +121,16 @@ pub fn point_in_board(x: f32, y: f32, size: Vec2, center: Vec2) -> bool {
     min_x <= x && x <= max_x && min_y <= y && y <= max_y
 }
 
-pub fn vec3_to_vec2(v: Vec3) -> Vec2 {
-    Vec2::new(v.x, v.y)
+/// The outline of a star with `points` points, alternating between `outer` and `inner` radius and
+/// starting from the top like `regular_polygon_vertices`.
+pub fn star_vertices(points: usize, outer: f32, inner: f32) -> Vec<Vec2> {
+    let angle_increment = PI / points as f32;
+
+    (0..2 * points)
+        .map(|i| {
+            let angle = i as f32 * angle_increment;
+            let radius = if i % 2 == 0 { outer } else { inner };
+            Vec2::new(radius * angle.sin(), radius * angle.cos())
+        })
+        .collect()
 }
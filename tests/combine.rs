@@ -0,0 +1,90 @@
+use bevy::{ecs::system::RunSystemOnce, prelude::*};
+use bevy_xpbd_2d::prelude::{CollisionEnded, CollisionStarted, LinearVelocity, Position};
+use shapecraft::{
+    board::{rebuild_rule_lookup, shape_collisions},
+    cards::energy::{bank_combine_energy, COMBINE_ENERGY},
+    prelude::*,
+};
+
+const RED_TRIANGLE: Shape = Shape {
+    polygon: GamePolygon::Triangle,
+    color: GameColor::Red,
+};
+
+/// Just enough of the board for `shape_collisions`, with two red triangles overlapping and a rule
+/// adding them.
+fn board() -> (World, [Entity; 2]) {
+    let mut world = World::new();
+    world.init_resource::<Events<CollisionStarted>>();
+    world.init_resource::<Events<CollisionEnded>>();
+    world.init_resource::<Events<SpawnBody>>();
+    world.init_resource::<Events<ShapesCombined>>();
+    world.init_resource::<Events<ShapesSplit>>();
+    world.init_resource::<Events<RuleApplied>>();
+    world.init_resource::<ProcessedContacts>();
+    world.init_resource::<CombinedShapes>();
+    world.init_resource::<RuleLookup>();
+    world.init_resource::<ColorInteraction>();
+    world.init_resource::<RuleResolution>();
+    world.init_resource::<CardDefinitions>();
+    world.insert_resource(GameRng::from_seed(Seed(0)));
+    world.spawn(Rule(vec![Operation::Add(RED_TRIANGLE, RED_TRIANGLE)]));
+    world.run_system_once(rebuild_rule_lookup);
+
+    let shapes = [0., 10.].map(|x| {
+        world
+            .spawn((
+                RED_TRIANGLE,
+                Position::from_xy(x, 0.),
+                Transform::from_xyz(x, 0., 0.),
+                LinearVelocity::ZERO,
+                IsOnBoard,
+            ))
+            .id()
+    });
+    (world, shapes)
+}
+
+#[test]
+fn persistent_overlap_spawns_once() {
+    let (mut world, [a, b]) = board();
+    let mut schedule = Schedule::default();
+    schedule.add_systems(shape_collisions);
+
+    // NOTE: xpbd reports an overlap for as long as it lasts, and in either order
+    for _ in 0..10 {
+        world.resource_mut::<CombinedShapes>().clear();
+        world.send_event_batch([CollisionStarted(a, b), CollisionStarted(b, a)]);
+        schedule.run(&mut world);
+    }
+
+    let spawns = world.resource::<Events<SpawnBody>>();
+    assert_eq!(spawns.len(), 1);
+    let spawned = spawns.iter_current_update_events().next().unwrap();
+    assert_eq!(spawned.shape.polygon, GamePolygon::Hexagon);
+    assert_eq!(world.resource::<Events<ShapesCombined>>().len(), 1);
+    assert_eq!(world.resource::<Events<RuleApplied>>().len(), 1);
+}
+
+#[test]
+fn combine_is_credited_to_the_cards_owner() {
+    let (mut world, [a, b]) = board();
+    world.entity_mut(b).insert(SpawnSource::Card(PlayerId(1)));
+    world.init_resource::<EnergyBank>();
+    let mut schedule = Schedule::default();
+    schedule.add_systems((shape_collisions, bank_combine_energy).chain());
+
+    world.send_event(CollisionStarted(a, b));
+    schedule.run(&mut world);
+
+    let combined = world.resource::<Events<ShapesCombined>>();
+    let combined = combined.iter_current_update_events().next().unwrap();
+    assert_eq!(combined.credit, Some(PlayerId(1)));
+    // the result keeps the credit, so a chain it starts is credited too
+    let spawned = world.resource::<Events<SpawnBody>>();
+    let spawned = spawned.iter_current_update_events().next().unwrap();
+    assert_eq!(spawned.source, SpawnSource::Card(PlayerId(1)));
+    let bank = world.resource::<EnergyBank>();
+    assert_eq!(bank.get(&PlayerId(1)), Some(&COMBINE_ENERGY));
+    assert_eq!(bank.get(&PlayerId(0)), None);
+}
@@ -0,0 +1,106 @@
+//! A headless game for integration tests: no window and no GPU, but the real assets.
+
+// NOTE: each test crate builds this module, and none of them use all of it
+#![allow(dead_code)]
+
+use std::{sync::Once, thread, time::Duration};
+
+use bevy::{
+    asset::AssetMetaCheck,
+    prelude::*,
+    render::{settings::WgpuSettings, RenderPlugin},
+    window::ExitCondition,
+    winit::WinitPlugin,
+};
+use bevy_xpbd_2d::prelude::PhysicsPlugins;
+use shapecraft::prelude::*;
+
+/// Frames to wait for the assets before giving up, a millisecond apart.
+const LOADING_FRAMES: usize = 10_000;
+
+static SANDBOX: Once = Once::new();
+
+/// Points `Storage` at a folder of its own, so the stats, daily scores and saves of the runs tests
+/// play stay out of the player's. The tutorial counts as seen there, or reaching the menu would
+/// start it.
+fn sandbox_storage() {
+    let data = std::env::temp_dir().join("shapecraft-tests");
+    std::env::set_var("APPDATA", &data);
+    std::env::set_var("XDG_DATA_HOME", &data);
+    let settings = Settings {
+        tutorial_done: true,
+        ..default()
+    };
+    Storage::default()
+        .save("settings.ron", &ron::to_string(&settings).unwrap())
+        .unwrap();
+}
+
+/// The game as `main` builds it, without the window, rendering and sound.
+pub fn headless_app(args: LaunchArgs) -> App {
+    SANDBOX.call_once(sandbox_storage);
+    let mut app = App::new();
+    app.insert_resource(AssetMetaCheck::Never)
+        .insert_resource(args)
+        .add_plugins((
+            DefaultPlugins
+                .set(RenderPlugin {
+                    render_creation: WgpuSettings {
+                        backends: None,
+                        ..default()
+                    }
+                    .into(),
+                })
+                .set(WindowPlugin {
+                    primary_window: None,
+                    exit_condition: ExitCondition::DontExit,
+                    ..default()
+                })
+                .disable::<WinitPlugin>(),
+            PhysicsPlugins::default(),
+            GamePlugins::default().headless(),
+        ));
+    // NOTE: `App::run` would do this, tests update the app by hand instead
+    app.finish();
+    app.cleanup();
+    app
+}
+
+/// Options for a seeded run that skips the menus.
+pub fn playing_args(seed: u64) -> LaunchArgs {
+    LaunchArgs {
+        seed: Some(seed),
+        skip_to: Some(AppState::Playing),
+        ..default()
+    }
+}
+
+/// A headless game that skips the menus, updated until the run has started.
+pub fn playing_app(seed: u64) -> App {
+    let mut app = headless_app(playing_args(seed));
+    start_playing(&mut app);
+    app
+}
+
+/// Updates a game built from `playing_args` until its assets are in and the run has started.
+pub fn start_playing(app: &mut App) {
+    update_until(app, |world| {
+        *world.resource::<State<AppState>>().get() == AppState::Playing
+    });
+}
+
+/// Updates `app` until `done`, panicking if that takes longer than the assets should.
+pub fn update_until(app: &mut App, done: impl Fn(&World) -> bool) {
+    for _ in 0..LOADING_FRAMES {
+        app.update();
+        if done(&app.world) {
+            return;
+        }
+        // NOTE: assets load on other threads, in real time
+        thread::sleep(Duration::from_millis(1));
+    }
+    panic!(
+        "still in {:?} after {LOADING_FRAMES} frames",
+        app.world.resource::<State<AppState>>().get()
+    );
+}
@@ -0,0 +1,113 @@
+mod common;
+
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+use shapecraft::{
+    board::config,
+    content::ContentKind,
+    game_shapes::interaction::{ColorInteraction, ColorOutcome},
+    prelude::*,
+};
+use strum::IntoEnumIterator;
+
+/// Where the test spawner drops its shapes, away from anything else that spawns.
+const SPAWNER_OFFSET: Vec2 = Vec2::new(123., 45.);
+
+fn every_kind_selected() -> ContentSelection {
+    ContentSelection {
+        deck: Some(0),
+        rules: Some(0),
+        shapes: Some(0),
+        fight: Some(0),
+        difficulty: Some(0),
+    }
+}
+
+// NOTE: `cargo test` runs in the crate root, so the game finds the repo's `mods/` at startup
+#[test]
+fn the_example_mods_load_at_startup() {
+    let mut app = common::headless_app(LaunchArgs::default());
+    app.update();
+
+    let content = app.world.resource::<CustomContent>();
+    assert!(
+        content.problems.is_empty(),
+        "{:?}",
+        content
+            .problems
+            .iter()
+            .map(|problem| format!("{}: {}", problem.path, problem.message))
+            .collect::<Vec<_>>()
+    );
+    for kind in ContentKind::iter() {
+        assert_eq!(content.len(kind), 1, "one example {kind:?}");
+    }
+}
+
+#[test]
+fn selected_content_is_played_and_put_back() {
+    let mut app = common::headless_app(common::playing_args(51));
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        50,
+    )))
+    .insert_resource(every_kind_selected());
+    common::start_playing(&mut app);
+
+    // example.difficulty.ron: "Relaxed"
+    let level = app.world.resource::<CurrentLevel>();
+    assert_eq!(level.target_score, 150);
+    assert_eq!(level.time_limit, 180.);
+    // example.fight.ron: "Peace Talks", with the pairs it leaves out kept
+    let interaction = app.world.resource::<ColorInteraction>();
+    assert_eq!(
+        interaction.outcome(GameColor::Red, GameColor::Green),
+        ColorOutcome::Merge(GameColor::Blue)
+    );
+    assert_eq!(
+        interaction.outcome(GameColor::Green, GameColor::Blue),
+        ColorOutcome::Lose
+    );
+
+    // example.shapes.ron: "Mostly Triangles", in place of a table of red squares
+    app.world.spawn(Spawner::from(&SpawnerDef {
+        position: SPAWNER_OFFSET,
+        half_size: Vec2::ZERO,
+        rate: RateCurve(vec![(0., 1000.)]),
+        shapes: vec![WeightedShape {
+            polygon: GamePolygon::Square,
+            color: Some(GameColor::Red),
+            element: None,
+            weight: 1,
+        }],
+    }));
+    app.world.insert_resource(NextState(Some(GameState::Play)));
+    app.update();
+    app.update();
+    let events = app.world.resource::<Events<SpawnBody>>();
+    let dropped: Vec<Shape> = events
+        .get_reader()
+        .read(events)
+        .filter(|spawn| spawn.transform.translation.truncate() == config::CENTER + SPAWNER_OFFSET)
+        .map(|spawn| spawn.shape)
+        .collect();
+    assert!(!dropped.is_empty());
+    assert!(dropped
+        .iter()
+        .any(|shape| shape.polygon == GamePolygon::Triangle));
+    assert!(!dropped.contains(&Shape {
+        polygon: GamePolygon::Square,
+        color: GameColor::Red,
+    }));
+
+    // NOTE: the fight table only lasts for the run
+    app.world.insert_resource(NextState(Some(AppState::Menu)));
+    app.update();
+    app.update();
+    assert_eq!(
+        app.world
+            .resource::<ColorInteraction>()
+            .outcome(GameColor::Red, GameColor::Green),
+        ColorOutcome::Lose
+    );
+}
@@ -0,0 +1,60 @@
+mod common;
+
+use std::time::Duration;
+
+use bevy::{ecs::system::RunSystemOnce, prelude::*, time::TimeUpdateStrategy};
+use shapecraft::{
+    prelude::*,
+    save::{continue_run, RunSnapshot},
+};
+
+const DAY: u64 = 19_000;
+
+/// A daily run saved from the table, as the pause menu would.
+fn saved_daily(challenge: &DailyChallenge) -> SaveGame {
+    let mut app = common::headless_app(common::playing_args(challenge.seed));
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        50,
+    )))
+    .insert_resource(challenge.clone());
+    common::start_playing(&mut app);
+    common::update_until(&mut app, |world| {
+        *world.resource::<State<GameState>>().get() == GameState::Start
+    });
+    let save = app
+        .world
+        .run_system_once(|snapshot: RunSnapshot| snapshot.capture());
+    // NOTE: through the file format, like a real save
+    ron::from_str(&ron::to_string(&save).unwrap()).unwrap()
+}
+
+#[test]
+fn a_continued_daily_run_still_counts() {
+    let challenge = DailyChallenge::new(DAY, &[]);
+    let save = saved_daily(&challenge);
+    assert_eq!(save.daily.as_ref(), Some(&challenge));
+    assert_eq!(save.seed, challenge.seed);
+
+    let mut app = common::headless_app(LaunchArgs::default());
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        50,
+    )));
+    common::update_until(&mut app, |world| {
+        *world.resource::<State<AppState>>().get() == AppState::Menu
+    });
+    app.world
+        .run_system_once(move |mut cmd: Commands| continue_run(&mut cmd, save.clone()));
+    common::update_until(&mut app, |world| {
+        *world.resource::<State<GameState>>().get() == GameState::Start
+    });
+    assert_eq!(app.world.get_resource::<DailyChallenge>(), Some(&challenge));
+    assert_eq!(app.world.resource::<Seed>().0, challenge.seed);
+
+    // the first time it's scored counts, and the rest of the run plays on as a normal one
+    app.world
+        .insert_resource(NextState(Some(GameState::Scoring)));
+    app.update();
+    app.update();
+    assert!(app.world.resource::<DailyRecord>().best(DAY).is_some());
+    assert!(!app.world.contains_resource::<DailyChallenge>());
+}
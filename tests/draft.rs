@@ -0,0 +1,20 @@
+use bevy::{ecs::system::RunSystemOnce, prelude::*};
+use shapecraft::{
+    cards::draft::{offer_rules, DraftOffer, DRAFT_SIZE},
+    prelude::*,
+};
+
+fn offer(seed: u64) -> Vec<Operation> {
+    let mut world = World::new();
+    world.insert_resource(GameRng::from_seed(Seed(seed)));
+    world.run_system_once(offer_rules);
+    world.resource::<DraftOffer>().0.clone()
+}
+
+#[test]
+fn the_seed_picks_the_offer() {
+    let first = offer(9);
+    assert_eq!(first.len(), DRAFT_SIZE);
+    assert_eq!(offer(9), first);
+    assert!((10..20).any(|seed| offer(seed) != first));
+}
@@ -0,0 +1,59 @@
+mod common;
+
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+use shapecraft::{cards::card::Flipping, prelude::*};
+
+/// A seeded run waiting in `Start`, and the top card of player 0's library, face down.
+fn app() -> (App, Entity) {
+    let mut app = common::headless_app(common::playing_args(61));
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        50,
+    )));
+    common::start_playing(&mut app);
+    common::update_until(&mut app, |world| {
+        *world.resource::<State<GameState>>().get() == GameState::Start
+    });
+    let library = app
+        .world
+        .resource::<Zones>()
+        .get(PlayerId(0), ZoneKind::Library)
+        .unwrap();
+    let card = app.world.get::<Children>(library).unwrap()[0];
+    // NOTE: cards are dealt face down, flipping as they're drawn
+    assert!(!app.world.get::<Card>(card).unwrap().face_up);
+    (app, card)
+}
+
+/// Updates until `card` has no flip left to make, and returns whether its front is showing.
+fn settle(app: &mut App, card: Entity) -> bool {
+    common::update_until(app, |world| world.get::<Flipping>(card).is_none());
+    let world = &app.world;
+    let card = world.get::<Card>(card).unwrap();
+    let front = world.get::<Visibility>(card.front).unwrap();
+    let back = world.get::<Visibility>(card.back).unwrap();
+    assert_eq!(*front == Visibility::Visible, card.face_up);
+    assert_eq!(*back == Visibility::Visible, !card.face_up);
+    card.face_up
+}
+
+#[test]
+fn a_flip_asked_for_mid_flip_is_made_after_it() {
+    let (mut app, card) = app();
+    app.world.send_event(FlipCard { card });
+    app.update();
+    app.update();
+    assert!(app.world.get::<Flipping>(card).is_some());
+
+    app.world.send_event(FlipCard { card });
+    assert!(!settle(&mut app, card), "flipped twice, back face down");
+}
+
+#[test]
+fn flips_asked_for_on_one_frame_are_all_made() {
+    let (mut app, card) = app();
+    app.world
+        .send_event_batch([FlipCard { card }, FlipCard { card }]);
+    assert!(!settle(&mut app, card), "flipped twice, back face down");
+}
@@ -0,0 +1,108 @@
+mod common;
+
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+use shapecraft::{board::config, prelude::*};
+
+const OCTAGON: Shape = Shape {
+    polygon: GamePolygon::Octagon,
+    color: GameColor::Red,
+};
+
+fn app(seed: u64) -> App {
+    let mut app = common::headless_app(common::playing_args(seed));
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        50,
+    )));
+    common::start_playing(&mut app);
+    // NOTE: the run ends from `Start` on, events sent during `Setup` would expire unread
+    common::update_until(&mut app, |world| {
+        *world.resource::<State<GameState>>().get() == GameState::Start
+    });
+    app
+}
+
+fn board_shapes(world: &mut World) -> usize {
+    world
+        .query_filtered::<(), With<IsOnBoard>>()
+        .iter(world)
+        .count()
+}
+
+#[test]
+fn an_overflowing_board_ends_in_lost() {
+    let mut app = app(31);
+    app.world.resource_mut::<GameOverRules>().max_shapes = 0;
+    app.world.send_event(SpawnBody {
+        shape: OCTAGON,
+        transform: Transform::from_translation(config::CENTER.extend(0.)),
+        velocity: None,
+        combo: 0,
+        element: None,
+        source: SpawnSource::Board,
+    });
+    common::update_until(&mut app, |world| {
+        *world.resource::<State<AppState>>().get() == AppState::Lost
+    });
+
+    // NOTE: the board is torn down before the end screen
+    assert_eq!(board_shapes(&mut app.world), 0);
+    assert!(app.world.resource::<GameOutcome>().is_none());
+}
+
+#[test]
+fn the_target_shape_ends_in_won() {
+    let mut app = app(32);
+    app.world.send_event(ShapesCombined {
+        inputs: [OCTAGON; 2],
+        result: OCTAGON,
+        position: config::CENTER,
+        depth: 1,
+        credit: Some(PlayerId(0)),
+    });
+    common::update_until(&mut app, |world| {
+        *world.resource::<State<AppState>>().get() == AppState::Won
+    });
+    assert_eq!(board_shapes(&mut app.world), 0);
+}
+
+#[test]
+fn one_players_empty_discard_hands_the_other_the_win() {
+    let mut app = common::headless_app(common::playing_args(33));
+    app.insert_resource(GameMode::Hotseat)
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+            50,
+        )));
+    common::start_playing(&mut app);
+    // NOTE: the run waits in `Start` once the libraries are dealt
+    common::update_until(&mut app, |world| {
+        *world.resource::<State<GameState>>().get() == GameState::Start
+    });
+
+    // player 0 has a card in their discard pile, player 1 has none
+    let zones = app.world.resource::<Zones>();
+    let library = zones.get(PlayerId(0), ZoneKind::Library).unwrap();
+    let discard = zones.get(PlayerId(0), ZoneKind::Discard).unwrap();
+    let card = app.world.get::<Children>(library).unwrap()[0];
+    app.world.entity_mut(discard).push_children(&[card]);
+
+    // NOTE: player 1 first, so their empty discard can't hide player 0's shuffle
+    app.world.send_event_batch([
+        ShuffleDiscard { owner: PlayerId(1) },
+        ShuffleDiscard { owner: PlayerId(0) },
+    ]);
+    app.update();
+    let events = app.world.resource::<Events<ZoneTransfer>>();
+    assert!(events.get_reader().read(events).any(|transfer| {
+        transfer.card == card && transfer.from == discard && transfer.to == library
+    }));
+    app.update();
+    assert_eq!(**app.world.resource::<Winner>(), Some(PlayerId(0)));
+
+    // NOTE: the winner is cleared again as the run leaves `AppState::Playing`
+
+    common::update_until(&mut app, |world| {
+        *world.resource::<State<AppState>>().get() == AppState::Won
+    });
+}
@@ -0,0 +1,85 @@
+mod common;
+
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy, utils::HashSet};
+use rand::Rng;
+use shapecraft::{
+    board::config,
+    game_shapes::{config::MAX_SHAPE_MATERIALS, AnimatedMaterial},
+    prelude::*,
+};
+
+const FRAME: Duration = Duration::from_millis(50);
+/// Five minutes of play.
+const FRAMES: usize = 5 * 60 * 20;
+/// Shapes dropped on the board every second, each fading out through a pooled material copy.
+const SHAPES_PER_SECOND: usize = 10;
+const SHAPE_LIFETIME: f32 = 4.;
+
+/// Gives every new shape a short `Lifetime`, so the board keeps turning over.
+fn expire_new_shapes(
+    mut cmd: Commands,
+    q_new: Query<Entity, (Added<IsOnBoard>, Without<Lifetime>)>,
+) {
+    for entity in q_new.iter() {
+        cmd.entity(entity).insert(Lifetime::new(SHAPE_LIFETIME));
+    }
+}
+
+#[test]
+#[ignore = "long soak; run with --ignored"]
+fn shape_materials_stay_bounded() {
+    let mut app = common::headless_app(common::playing_args(13));
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(FRAME))
+        .add_systems(Update, expire_new_shapes);
+    common::start_playing(&mut app);
+
+    let mut rng = GameRng::from_seed(Seed(13));
+    let mut faded = HashSet::new();
+    let mut peak = 0;
+    for frame in 0..FRAMES {
+        if frame % (1000 / FRAME.as_millis() as usize) == 0 {
+            app.world.send_event_batch((0..SHAPES_PER_SECOND).map(|_| {
+                SpawnBody {
+                    shape: Shape::random_shape_with(&mut rng.0),
+                    transform: Transform::from_translation(
+                        (config::CENTER
+                            + Vec2::new(
+                                rng.0.gen_range(-200.0..200.),
+                                rng.0.gen_range(-200.0..200.),
+                            ))
+                        .extend(0.),
+                    ),
+                    velocity: None,
+                    combo: 0,
+                    element: None,
+                    source: SpawnSource::Board,
+                }
+            }));
+        }
+        // NOTE: the run would end after two minutes, the soak plays on
+        app.world.resource_mut::<GameTimer>().timer.reset();
+        app.update();
+
+        let materials = app.world.resource::<Assets<ShapeMaterial>>().len();
+        assert!(
+            materials <= MAX_SHAPE_MATERIALS,
+            "{materials} shape materials after {frame} frames"
+        );
+        peak = peak.max(materials);
+        faded.extend(
+            app.world
+                .query_filtered::<Entity, With<AnimatedMaterial>>()
+                .iter(&app.world),
+        );
+    }
+
+    // every shape took a pooled copy as it faded, far more than the materials ever alive
+    assert!(
+        faded.len() > 2 * MAX_SHAPE_MATERIALS,
+        "{} faded",
+        faded.len()
+    );
+    assert!(peak < MAX_SHAPE_MATERIALS);
+}
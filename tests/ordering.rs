@@ -0,0 +1,128 @@
+mod common;
+
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+use bevy_xpbd_2d::{prelude::LinearVelocity, PhysicsSchedule};
+use shapecraft::{
+    board::{config, shape_collisions, CollisionSet, SpawnSet},
+    prelude::*,
+};
+
+/// The sets that ran this frame, in the order they ran. Cleared at the start of every frame.
+#[derive(Resource, Default)]
+struct OrderLog(Vec<&'static str>);
+
+/// A `SpawnBody` for a test system to send from inside one of the sets.
+#[derive(Resource, Default)]
+struct Pending {
+    before_spawn: Option<SpawnBody>,
+    in_collisions: Option<SpawnBody>,
+}
+
+fn log(label: &'static str) -> impl FnMut(ResMut<OrderLog>) {
+    move |mut log: ResMut<OrderLog>| log.0.push(label)
+}
+
+fn ordered_app() -> App {
+    let mut app = common::headless_app(common::playing_args(3));
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+        1. / 60.,
+    )))
+    .init_resource::<OrderLog>()
+    .init_resource::<Pending>()
+    .add_systems(First, |mut log: ResMut<OrderLog>| log.0.clear())
+    .add_systems(
+        Update,
+        (
+            log("transfer").in_set(CardSet::Transfer),
+            log("layout").in_set(CardSet::Layout),
+            log("spawn").in_set(SpawnSet),
+            send_pending(|pending| &mut pending.before_spawn).before(SpawnSet),
+        ),
+    )
+    .add_systems(
+        PhysicsSchedule,
+        (
+            log("collisions"),
+            // NOTE: xpbd's schedule refuses systems with no order between them
+            send_pending(|pending| &mut pending.in_collisions).after(shape_collisions),
+        )
+            .in_set(CollisionSet),
+    );
+    common::start_playing(&mut app);
+    app
+}
+
+fn send_pending(
+    slot: fn(&mut Pending) -> &mut Option<SpawnBody>,
+) -> impl FnMut(ResMut<Pending>, EventWriter<SpawnBody>) {
+    move |mut pending: ResMut<Pending>, mut writer: EventWriter<SpawnBody>| {
+        if let Some(event) = slot(&mut pending).take() {
+            writer.send(event);
+        }
+    }
+}
+
+fn marker_body(x: f32) -> SpawnBody {
+    SpawnBody {
+        shape: Shape {
+            polygon: GamePolygon::Heptagon,
+            color: GameColor::Blue,
+        },
+        transform: Transform::from_translation((config::CENTER + Vec2::new(x, 0.)).extend(0.)),
+        velocity: Some(LinearVelocity::ZERO),
+        combo: 0,
+        element: None,
+        source: SpawnSource::Board,
+    }
+}
+
+fn has_body_at(app: &mut App, x: f32) -> bool {
+    let at = config::CENTER + Vec2::new(x, 0.);
+    app.world
+        .query_filtered::<&Transform, With<IsOnBoard>>()
+        .iter(&app.world)
+        .any(|transform| transform.translation.truncate().distance(at) < 1.)
+}
+
+#[test]
+fn sets_run_in_order_every_frame() {
+    let mut app = ordered_app();
+    for _ in 0..60 {
+        app.update();
+        let log = &app.world.resource::<OrderLog>().0;
+        let position = |label| log.iter().position(|l| *l == label).unwrap();
+
+        assert_eq!(log.iter().filter(|l| **l != "collisions").count(), 3);
+        assert!(position("transfer") < position("layout"), "{log:?}");
+        // NOTE: xpbd steps in `PostUpdate`, after everything in `Update`
+        assert!(
+            log.iter()
+                .skip(position("spawn"))
+                .skip_while(|l| **l != "collisions")
+                .all(|l| *l == "collisions"),
+            "{log:?}"
+        );
+    }
+    let steps = app.world.resource::<OrderLog>().0.len() - 3;
+    assert!(steps >= 1, "no physics step ran");
+}
+
+#[test]
+fn spawn_latency() {
+    let mut app = ordered_app();
+
+    // sent in `Update` ahead of `SpawnSet`: a body the same frame
+    app.world.resource_mut::<Pending>().before_spawn = Some(marker_body(-60.));
+    app.update();
+    assert!(has_body_at(&mut app, -60.));
+
+    // sent by a physics step, like a combine's result: a body at the start of the next frame
+    app.world.resource_mut::<Pending>().in_collisions = Some(marker_body(60.));
+    app.update();
+    assert!(app.world.resource::<Pending>().in_collisions.is_none());
+    assert!(!has_body_at(&mut app, 60.));
+    app.update();
+    assert!(has_body_at(&mut app, 60.));
+}
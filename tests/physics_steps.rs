@@ -0,0 +1,127 @@
+mod common;
+
+use std::time::Duration;
+
+use bevy::{ecs::event::ManualEventReader, prelude::*, time::TimeUpdateStrategy};
+use bevy_xpbd_2d::{
+    prelude::{LinearVelocity, Physics},
+    PhysicsSchedule,
+};
+use shapecraft::{
+    board::{config, shape_collisions, AwaitNoCollision, CollisionSet, Layer},
+    level::CurrentLevel,
+    prelude::*,
+};
+
+const FRAME: f64 = 1. / 60.;
+const STEPS_PER_FRAME: usize = 4;
+const BLUE_PENTAGON: Shape = Shape {
+    polygon: GamePolygon::Pentagon,
+    color: GameColor::Blue,
+};
+/// Where each pair meets, relative to the board's center. Clear of the default layout's obstacles
+/// and far enough apart that no pair reaches another.
+const MEETING_POINTS: [Vec2; 4] = [
+    Vec2::new(-120., -110.),
+    Vec2::new(120., -110.),
+    Vec2::new(-120., 110.),
+    Vec2::new(120., 110.),
+];
+
+/// Physics steps run this frame.
+#[derive(Resource, Default)]
+struct Steps(usize);
+
+fn count_step(mut steps: ResMut<Steps>) {
+    steps.0 += 1;
+}
+
+fn pair(meeting_point: Vec2) -> [SpawnBody; 2] {
+    [-1., 1.].map(|side| SpawnBody {
+        shape: BLUE_PENTAGON,
+        transform: Transform::from_translation(
+            (config::CENTER + meeting_point + Vec2::new(side * 30., 0.)).extend(0.),
+        ),
+        velocity: Some(LinearVelocity(Vec2::new(-side * 80., 0.))),
+        combo: 0,
+        element: None,
+        source: SpawnSource::Board,
+    })
+}
+
+#[test]
+fn contacts_are_handled_once_at_four_steps_per_frame() {
+    let mut app = common::headless_app(common::playing_args(7));
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+        FRAME,
+    )))
+    .insert_resource(Time::new_with(Physics::fixed_hz(
+        STEPS_PER_FRAME as f64 / FRAME,
+    )))
+    .init_resource::<Steps>()
+    .add_systems(
+        PhysicsSchedule,
+        count_step.in_set(CollisionSet).after(shape_collisions),
+    );
+    common::start_playing(&mut app);
+    // NOTE: four decagons clear the first level, which would end the run
+    app.world.resource_mut::<CurrentLevel>().0.target_score = u32::MAX;
+
+    // NOTE: the run waits in `Start` for a key, with the board live but no cards played
+    app.update();
+    let world = &mut app.world;
+    let mut q_rule = world.query::<&mut Rule>();
+    q_rule.single_mut(world).0 = vec![Operation::Add(BLUE_PENTAGON, BLUE_PENTAGON)];
+    let shapes: Vec<Entity> = world
+        .query_filtered::<Entity, With<IsOnBoard>>()
+        .iter(world)
+        .collect();
+    for shape in shapes {
+        world.entity_mut(shape).despawn_recursive();
+    }
+    world.send_event_batch(MEETING_POINTS.into_iter().flat_map(pair));
+    app.update();
+
+    // NOTE: fresh shapes only collide with walls until they're clear of each other, these
+    // pairs are meant to meet straight away
+    let world = &mut app.world;
+    let shapes: Vec<Entity> = world
+        .query_filtered::<Entity, With<IsOnBoard>>()
+        .iter(world)
+        .collect();
+    assert_eq!(shapes.len(), 2 * MEETING_POINTS.len());
+    for shape in shapes {
+        world
+            .entity_mut(shape)
+            .remove::<AwaitNoCollision>()
+            .insert(Layer::shape());
+    }
+
+    let mut applied = ManualEventReader::<RuleApplied>::default();
+    let mut combined = ManualEventReader::<ShapesCombined>::default();
+    let (mut applied_count, mut combined_count) = (0, 0);
+    for _ in 0..120 {
+        app.world.resource_mut::<Steps>().0 = 0;
+        app.update();
+        assert_eq!(app.world.resource::<Steps>().0, STEPS_PER_FRAME);
+        applied_count += applied
+            .read(app.world.resource::<Events<RuleApplied>>())
+            .count();
+        combined_count += combined
+            .read(app.world.resource::<Events<ShapesCombined>>())
+            .count();
+    }
+
+    assert_eq!(applied_count, MEETING_POINTS.len());
+    assert_eq!(combined_count, MEETING_POINTS.len());
+    let world = &mut app.world;
+    let results: Vec<Shape> = world
+        .query_filtered::<&Shape, With<IsOnBoard>>()
+        .iter(world)
+        .copied()
+        .collect();
+    assert_eq!(results.len(), MEETING_POINTS.len());
+    assert!(results
+        .iter()
+        .all(|shape| shape.polygon == BLUE_PENTAGON.polygon + BLUE_PENTAGON.polygon));
+}
@@ -0,0 +1,22 @@
+mod common;
+
+use bevy::prelude::*;
+use shapecraft::prelude::*;
+
+#[test]
+fn headless_game_reaches_the_menu() {
+    let mut app = common::headless_app(LaunchArgs::default());
+    common::update_until(&mut app, |world| {
+        *world.resource::<State<AppState>>().get() == AppState::Menu
+    });
+    for _ in 0..10 {
+        app.update();
+    }
+
+    // NOTE: `headless` leaves out the cameras and the UI, everything else is built
+    let world = &mut app.world;
+    assert_eq!(world.query::<&Camera>().iter(world).count(), 0);
+    assert_eq!(world.query::<&Node>().iter(world).count(), 0);
+    assert!(world.contains_resource::<CardDefinitions>());
+    assert!(world.contains_resource::<GameRng>());
+}
@@ -0,0 +1,117 @@
+mod common;
+
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+use shapecraft::{board::BodySpawned, cards::goals::Goals, prelude::*};
+
+/// The library a seeded run deals, top first, and its goals.
+fn dealt_run(seed: u64) -> (Vec<Operation>, String) {
+    dealt_run_in(GameMode::Solo, seed)
+}
+
+/// The library a seeded run in `mode` deals the first player, top first, and their goals.
+fn dealt_run_in(mode: GameMode, seed: u64) -> (Vec<Operation>, String) {
+    let mut app = common::headless_app(common::playing_args(seed));
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        50,
+    )))
+    .insert_resource(mode);
+    common::start_playing(&mut app);
+    // NOTE: the run waits in `Start` once the libraries are dealt
+    common::update_until(&mut app, |world| {
+        *world.resource::<State<GameState>>().get() == GameState::Start
+    });
+
+    let world = &mut app.world;
+    let library = world
+        .resource::<Zones>()
+        .get(PlayerId(0), ZoneKind::Library)
+        .unwrap();
+    let operations = world
+        .get::<Children>(library)
+        .unwrap()
+        .iter()
+        .map(|&card| world.get::<Card>(card).unwrap().operation.clone())
+        .collect();
+    let goals = world
+        .query::<(&Goals, &Owner)>()
+        .iter(world)
+        .filter(|(_, owner)| owner.0 == PlayerId(0))
+        .map(|(goals, _)| format!("{:?}", **goals))
+        .collect();
+    (operations, goals)
+}
+
+#[test]
+fn a_seed_deals_the_same_run() {
+    let (library, goals) = dealt_run(17);
+    assert!(!library.is_empty());
+    assert_eq!(dealt_run(17), (library.clone(), goals.clone()));
+    assert_ne!(dealt_run(18), (library, goals));
+}
+
+// NOTE: goals are drawn after the libraries, so they'd match if solo dealt a second library too
+#[test]
+fn a_solo_run_deals_one_library() {
+    let (solo_library, solo_goals) = dealt_run_in(GameMode::Solo, 19);
+    let (hotseat_library, hotseat_goals) = dealt_run_in(GameMode::Hotseat, 19);
+    assert_eq!(solo_library, hotseat_library);
+    assert_ne!(solo_goals, hotseat_goals);
+}
+
+/// Every body a seeded run spawns in its first frames of play, with where it's placed and how
+/// it's launched.
+fn spawned_board(seed: u64) -> Vec<(Shape, Vec3, Vec2)> {
+    let mut app = common::headless_app(common::playing_args(seed));
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        50,
+    )));
+    common::start_playing(&mut app);
+    common::update_until(&mut app, |world| {
+        *world.resource::<State<GameState>>().get() == GameState::Start
+    });
+    app.world.spawn(Spawner::from(&SpawnerDef {
+        position: Vec2::ZERO,
+        half_size: Vec2::splat(200.),
+        rate: RateCurve(vec![(0., 100.)]),
+        shapes: vec![
+            WeightedShape {
+                polygon: GamePolygon::Triangle,
+                color: None,
+                element: None,
+                weight: 2,
+            },
+            WeightedShape {
+                polygon: GamePolygon::Square,
+                color: None,
+                element: None,
+                weight: 1,
+            },
+        ],
+    }));
+    app.world.insert_resource(NextState(Some(GameState::Play)));
+
+    let mut reader = app.world.resource::<Events<BodySpawned>>().get_reader();
+    let mut spawned = Vec::new();
+    for _ in 0..10 {
+        app.update();
+        let events = app.world.resource::<Events<BodySpawned>>();
+        spawned.extend(reader.read(events).map(|BodySpawned(body)| {
+            (
+                body.shape,
+                body.transform.translation,
+                body.velocity.unwrap().0,
+            )
+        }));
+    }
+    spawned
+}
+
+#[test]
+fn a_seed_spawns_the_same_board() {
+    let board = spawned_board(23);
+    assert!(board.len() >= 20);
+    assert_eq!(spawned_board(23), board);
+    assert_ne!(spawned_board(24), board);
+}
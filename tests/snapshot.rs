@@ -0,0 +1,86 @@
+mod common;
+
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy, utils::HashMap};
+use bevy_xpbd_2d::prelude::{LinearVelocity, RigidBody};
+use shapecraft::{
+    board::{
+        config,
+        snapshot::{export_board, import_board, parse_scene},
+    },
+    prelude::*,
+};
+
+const SHAPES: usize = 12;
+
+/// How many of each shape are on the board.
+fn census(world: &mut World) -> HashMap<Shape, usize> {
+    let mut census = HashMap::new();
+    for shape in world
+        .query_filtered::<&Shape, With<IsOnBoard>>()
+        .iter(world)
+    {
+        *census.entry(*shape).or_default() += 1;
+    }
+    census
+}
+
+/// Every shape on the board with where it is and how it moves, in a stable order.
+fn bodies(world: &mut World) -> Vec<(Shape, Vec3, Vec2)> {
+    let mut bodies: Vec<_> = world
+        .query_filtered::<(&Shape, &Transform, &LinearVelocity), With<IsOnBoard>>()
+        .iter(world)
+        .map(|(shape, transform, velocity)| (*shape, transform.translation, velocity.0))
+        .collect();
+    bodies.sort_by(|(_, a, _), (_, b, _)| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+    bodies
+}
+
+fn app(seed: u64) -> App {
+    let mut app = common::headless_app(common::playing_args(seed));
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        50,
+    )));
+    common::start_playing(&mut app);
+    app
+}
+
+#[test]
+fn export_and_reload_keep_the_board() {
+    let mut saved = app(21);
+    let mut rng = GameRng::from_seed(Seed(21));
+    saved.world.send_event_batch((0..SHAPES).map(|i| SpawnBody {
+        shape: Shape::random_shape_with(&mut rng.0),
+        transform: Transform::from_translation(
+            (config::CENTER + Vec2::from_angle(i as f32).rotate(Vec2::X) * 150.).extend(0.),
+        ),
+        velocity: None,
+        combo: 0,
+        element: None,
+        source: SpawnSource::Board,
+    }));
+    for _ in 0..20 {
+        saved.update();
+    }
+    let ron = export_board(&mut saved.world).unwrap();
+    let expected_census = census(&mut saved.world);
+    let expected_bodies = bodies(&mut saved.world);
+    assert!(expected_bodies.len() >= SHAPES);
+
+    // NOTE: a different run, so the reloaded board can't match by accident
+    let mut loaded = app(22);
+    loaded.update();
+    let scene = parse_scene(&loaded.world, &ron).unwrap();
+    let count = import_board(&mut loaded.world, &scene).unwrap();
+    assert_eq!(count, expected_bodies.len());
+    assert_eq!(census(&mut loaded.world), expected_census);
+    assert_eq!(bodies(&mut loaded.world), expected_bodies);
+
+    // the loaded shapes get their bodies back on the next frame
+    loaded.update();
+    let mut q_bodies = loaded
+        .world
+        .query_filtered::<(), (With<IsOnBoard>, Without<RigidBody>)>();
+    assert_eq!(q_bodies.iter(&loaded.world).count(), 0);
+}
@@ -0,0 +1,55 @@
+mod common;
+
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+use shapecraft::prelude::*;
+
+/// Frames of play before leaving, enough for the decks to be dealt and shapes to spawn.
+const PLAY_FRAMES: usize = 200;
+/// Frames for the menu to settle after leaving.
+const SETTLE_FRAMES: usize = 10;
+
+fn go_to(app: &mut App, state: AppState, frames: usize) {
+    app.world.insert_resource(NextState(Some(state)));
+    for _ in 0..frames {
+        app.update();
+    }
+}
+
+/// Entities in the world, leaving out sounds. Without an audio device a sound never starts, so
+/// music that fades out when the track changes is never despawned.
+fn entity_count(world: &mut World) -> usize {
+    world
+        .query_filtered::<(), Without<SoundChannel>>()
+        .iter(world)
+        .count()
+}
+
+#[test]
+fn leaving_playing_despawns_the_run() {
+    let mut app = common::headless_app(common::playing_args(11));
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        50,
+    )));
+    common::start_playing(&mut app);
+
+    let mut counts = Vec::new();
+    for run in 0..2 {
+        if run > 0 {
+            go_to(&mut app, AppState::Playing, 1);
+        }
+        for _ in 0..PLAY_FRAMES {
+            app.update();
+        }
+        let playing = entity_count(&mut app.world);
+        go_to(&mut app, AppState::Menu, SETTLE_FRAMES);
+        let after = entity_count(&mut app.world);
+        assert!(after < playing, "{after} entities left of {playing}");
+        counts.push(after);
+    }
+    assert_eq!(counts[0], counts[1]);
+
+    let mut q_gameplay = app.world.query_filtered::<(), With<GameplayEntity>>();
+    assert_eq!(q_gameplay.iter(&app.world).count(), 0);
+}
@@ -0,0 +1,134 @@
+use std::f32::consts::FRAC_PI_6;
+
+use bevy::prelude::*;
+use shapecraft::{
+    cards::{card::CardTravel, transfer::apply_zone_transfers},
+    prelude::*,
+};
+
+const EPSILON: f32 = 1e-4;
+
+fn zone(world: &mut World, kind: ZoneKind, transform: Transform) -> Entity {
+    world
+        .spawn((kind, SpatialBundle::from_transform(transform)))
+        .id()
+}
+
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins((TransformPlugin, HierarchyPlugin))
+        .add_event::<ZoneTransfer>()
+        .add_event::<FlipCard>()
+        .add_systems(Update, apply_zone_transfers);
+    app
+}
+
+fn card(world: &mut World, transform: Transform, zone: Entity) -> Entity {
+    world
+        .spawn((
+            Card {
+                front: Entity::PLACEHOLDER,
+                back: Entity::PLACEHOLDER,
+                face_up: true,
+                operation: Operation::Inc(Shape::default()),
+                definition: None,
+                cost: 0,
+                keywords: Vec::new(),
+            },
+            SpatialBundle::from_transform(transform),
+        ))
+        .set_parent(zone)
+        .id()
+}
+
+#[test]
+fn transfer_keeps_global_transform() {
+    let mut app = app();
+
+    let hand = zone(
+        &mut app.world,
+        ZoneKind::Hand,
+        Transform::from_xyz(-120., -340., 2.)
+            .with_rotation(Quat::from_rotation_z(FRAC_PI_6))
+            .with_scale(Vec3::splat(1.5)),
+    );
+    let discard = zone(
+        &mut app.world,
+        ZoneKind::Discard,
+        Transform::from_xyz(500., -200., 1.).with_rotation(Quat::from_rotation_z(-0.3)),
+    );
+    let card = card(
+        &mut app.world,
+        Transform::from_xyz(80., 25., 0.5)
+            .with_rotation(Quat::from_rotation_z(-0.7))
+            .with_scale(Vec3::splat(0.8)),
+        hand,
+    );
+    app.update();
+    let before = *app.world.get::<GlobalTransform>(card).unwrap();
+
+    app.world.send_event(ZoneTransfer {
+        card,
+        from: hand,
+        to: discard,
+        reason: TransferReason::Discard,
+    });
+    app.update();
+
+    assert!(app.world.get::<Parent>(card).is_none());
+    let after = app.world.get::<GlobalTransform>(card).unwrap();
+    assert!(
+        after.affine().abs_diff_eq(before.affine(), EPSILON),
+        "{before:?} moved to {after:?}"
+    );
+}
+
+#[test]
+fn a_card_in_flight_is_sent_on_to_its_new_zone() {
+    let mut app = app();
+    let hand = zone(
+        &mut app.world,
+        ZoneKind::Hand,
+        Transform::from_xyz(-120., -340., 2.),
+    );
+    let discard = zone(
+        &mut app.world,
+        ZoneKind::Discard,
+        Transform::from_xyz(500., -200., 1.),
+    );
+    let library = zone(
+        &mut app.world,
+        ZoneKind::Library,
+        Transform::from_xyz(-500., -200., 1.),
+    );
+    let card = card(&mut app.world, Transform::from_xyz(80., 25., 0.5), hand);
+    app.update();
+
+    app.world.send_event(ZoneTransfer {
+        card,
+        from: hand,
+        to: discard,
+        reason: TransferReason::Discard,
+    });
+    app.update();
+    // NOTE: the flight itself isn't run here, so the card hangs where it left the hand
+    let before = *app.world.get::<GlobalTransform>(card).unwrap();
+
+    app.world.send_event(ZoneTransfer {
+        card,
+        from: discard,
+        to: library,
+        reason: TransferReason::Shuffle,
+    });
+    app.update();
+
+    assert!(app.world.get::<Parent>(card).is_none());
+    let travel = app.world.get::<CardTravel>(card).unwrap();
+    assert_eq!(travel.zone, library);
+    assert_eq!(travel.index, TransferReason::Shuffle.index());
+    let after = app.world.get::<GlobalTransform>(card).unwrap();
+    assert!(
+        after.affine().abs_diff_eq(before.affine(), EPSILON),
+        "{before:?} moved to {after:?}"
+    );
+}
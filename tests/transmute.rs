@@ -0,0 +1,107 @@
+mod common;
+
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+use shapecraft::{
+    board::{config, AwaitNoCollision, Layer},
+    prelude::*,
+};
+
+const SHAPES: usize = 6;
+const RED_TRIANGLE: Shape = Shape {
+    polygon: GamePolygon::Triangle,
+    color: GameColor::Red,
+};
+
+/// A run with nothing on the board but `SHAPES` settled red triangles.
+fn app() -> (App, Vec<Entity>) {
+    let mut app = common::headless_app(common::playing_args(41));
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        50,
+    )));
+    common::start_playing(&mut app);
+    // NOTE: the run waits in `Start` for a key, so nothing else spawns
+    app.update();
+    let world = &mut app.world;
+    let shapes: Vec<Entity> = world
+        .query_filtered::<Entity, With<IsOnBoard>>()
+        .iter(world)
+        .collect();
+    for shape in shapes {
+        world.entity_mut(shape).despawn_recursive();
+    }
+    world.send_event_batch((0..SHAPES).map(|i| SpawnBody {
+        shape: RED_TRIANGLE,
+        transform: Transform::from_translation(
+            (config::CENTER + Vec2::new(i as f32 * 80. - 200., 0.)).extend(0.),
+        ),
+        velocity: None,
+        combo: 0,
+        element: None,
+        source: SpawnSource::Board,
+    }));
+    app.update();
+
+    let world = &mut app.world;
+    let shapes: Vec<Entity> = world
+        .query_filtered::<Entity, With<IsOnBoard>>()
+        .iter(world)
+        .collect();
+    assert_eq!(shapes.len(), SHAPES);
+    for &shape in &shapes {
+        world
+            .entity_mut(shape)
+            .remove::<AwaitNoCollision>()
+            .insert(Layer::shape());
+    }
+    (app, shapes)
+}
+
+fn squares(world: &mut World) -> usize {
+    world
+        .query::<&Shape>()
+        .iter(world)
+        .filter(|shape| shape.polygon == GamePolygon::Square)
+        .count()
+}
+
+#[test]
+fn dragged_shapes_are_left_alone() {
+    let (mut app, shapes) = app();
+    app.world.entity_mut(shapes[0]).insert(Dragged);
+    app.world.send_event(TransmuteShapes {
+        from: GamePolygon::Triangle,
+        to: GamePolygon::Square,
+    });
+    app.update();
+
+    assert_eq!(squares(&mut app.world), SHAPES - 1);
+    assert_eq!(
+        app.world.get::<Shape>(shapes[0]).unwrap().polygon,
+        GamePolygon::Triangle
+    );
+}
+
+#[test]
+fn frozen_shapes_are_left_alone() {
+    let (mut app, _) = app();
+    app.world.send_event(ApplyStatus {
+        kind: StatusKind::Frozen,
+        duration: 10.,
+    });
+    app.update();
+    let world = &mut app.world;
+    assert_eq!(
+        world.query::<&Frozen>().iter(world).count(),
+        SHAPES,
+        "every shape freezes with the board"
+    );
+
+    app.world.send_event(TransmuteShapes {
+        from: GamePolygon::Triangle,
+        to: GamePolygon::Square,
+    });
+    app.update();
+    assert_eq!(squares(&mut app.world), 0);
+}
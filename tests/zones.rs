@@ -0,0 +1,107 @@
+mod common;
+
+use std::time::Duration;
+
+use bevy::{
+    prelude::*,
+    time::TimeUpdateStrategy,
+    utils::{HashMap, HashSet},
+};
+use shapecraft::prelude::*;
+
+const KINDS: [ZoneKind; 4] = [
+    ZoneKind::Library,
+    ZoneKind::Hand,
+    ZoneKind::Discard,
+    ZoneKind::Exhaust,
+];
+
+const FRAME: Duration = Duration::from_millis(50);
+/// Frames a phase that waits on the player is held for, long enough for the cards to land.
+const HOLD_FRAMES: usize = 20;
+/// Turns to play through, two for each player.
+const TURNS: u32 = 4;
+const MAX_FRAMES: usize = 5_000;
+
+/// Every card sitting in a zone, with the zone's owner and kind.
+fn zone_cards(world: &World) -> Vec<(Entity, PlayerId, ZoneKind)> {
+    let zones = world.resource::<Zones>();
+    let mut cards = Vec::new();
+    for seat in GameMode::Hotseat.seats() {
+        for kind in KINDS {
+            let zone = zones
+                .get(seat, kind)
+                .unwrap_or_else(|| panic!("player {seat:?} has no {kind:?}"));
+            let Some(children) = world.get::<Children>(zone) else {
+                continue;
+            };
+            cards.extend(
+                children
+                    .iter()
+                    .filter(|&&e| world.get::<Card>(e).is_some())
+                    .map(|&card| (card, seat, kind)),
+            );
+        }
+    }
+    cards
+}
+
+#[test]
+fn hotseat_cards_stay_with_their_owner() {
+    let mut app = common::headless_app(common::playing_args(5));
+    app.insert_resource(GameMode::Hotseat)
+        .insert_resource(TimeUpdateStrategy::ManualDuration(FRAME));
+    common::start_playing(&mut app);
+
+    // NOTE: cards don't carry an owner, so each belongs to whoever's pile it's first seen in
+    let mut owners = HashMap::new();
+    let mut filled = HashSet::new();
+    let mut players_seen = HashSet::new();
+    let mut phase = GameState::Setup;
+    let mut frames_in_phase = 0;
+    for _ in 0..MAX_FRAMES {
+        app.update();
+        for (card, seat, kind) in zone_cards(&app.world) {
+            let owner = *owners.entry(card).or_insert(seat);
+            assert_eq!(
+                owner, seat,
+                "{card:?} left {owner:?}'s piles for {seat:?}'s {kind:?}"
+            );
+            filled.insert((seat, kind));
+        }
+        players_seen.insert(app.world.resource::<ActivePlayer>().0);
+        if **app.world.resource::<TurnCount>() >= TURNS {
+            break;
+        }
+
+        let state = app.world.resource::<State<GameState>>().get().clone();
+        if state != phase {
+            phase = state;
+            frames_in_phase = 0;
+        }
+        frames_in_phase += 1;
+        // NOTE: stand in for the players wherever a phase waits on them
+        let waiting = matches!(
+            phase,
+            GameState::Start
+                | GameState::Mulligan
+                | GameState::Play
+                | GameState::Draft
+                | GameState::PassDevice
+        );
+        if waiting && frames_in_phase >= HOLD_FRAMES {
+            app.world.send_event(EndPhase);
+        }
+    }
+
+    assert!(**app.world.resource::<TurnCount>() >= TURNS);
+    for seat in GameMode::Hotseat.seats() {
+        assert!(players_seen.contains(&seat), "{seat:?} never had a turn");
+        for kind in [ZoneKind::Library, ZoneKind::Hand, ZoneKind::Discard] {
+            assert!(
+                filled.contains(&(seat, kind)),
+                "{seat:?}'s {kind:?} never held a card"
+            );
+        }
+    }
+}